@@ -1,9 +1,14 @@
-use std::time::Instant;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use bytes::Bytes;
 use eyre::{Result, WrapErr};
 use http::{
     header::{AUTHORIZATION, CONTENT_LENGTH},
+    request::Builder as RequestBuilder,
     Response,
 };
 use hyper::{
@@ -15,16 +20,31 @@ use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use leaky_bucket_lite::LeakyBucket;
 use tokio::time::Duration;
 
-use crate::{metrics::ClientMetrics, multipart::Multipart, ClientError, Site, MY_USER_AGENT};
+use crate::{
+    metrics::ClientMetrics, multipart::Multipart, webhook::WebhookConnector, ClientError, Site,
+    MY_USER_AGENT,
+};
 
 pub(crate) type InnerClient = HyperClient<HttpsConnector<HttpConnector<GaiResolver>>, Body>;
 
+type WebhookClient = HyperClient<HttpsConnector<WebhookConnector>, Body>;
+
+/// Ratelimiter of each webhook url along with the last time it was used
+type WebhookRatelimiters = HashMap<Box<str>, (Arc<LeakyBucket>, Instant)>;
+
+/// Webhook ratelimiters that have not been used for this long are removed
+const WEBHOOK_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
 pub struct Client {
     pub(crate) client: InnerClient,
     #[cfg(feature = "twitch")]
     twitch: bathbot_model::TwitchData,
     github_auth: Box<str>,
     ratelimiters: [LeakyBucket; 17],
+    /// Only connects to public addresses, see [`WebhookConnector`]
+    pub(crate) webhook_client: WebhookClient,
+    /// Webhooks are user-provided so each of them gets its own ratelimiter
+    webhook_ratelimiters: Mutex<WebhookRatelimiters>,
 }
 
 impl Client {
@@ -41,6 +61,7 @@ impl Client {
             .build();
 
         let client = HyperClient::builder().build(connector);
+        let webhook_client = Self::webhook_client(WebhookConnector::new());
 
         #[cfg(feature = "twitch")]
         let twitch = Self::get_twitch_token(&client, twitch_client_id, twitch_token)
@@ -64,12 +85,12 @@ impl Client {
             ratelimiter(5),  // Github
             ratelimiter(2),  // Huismetbenen
             ratelimiter(5),  // KittenRoleplay
-            ratelimiter(5),  // MissAnalyzer
             ratelimiter(2),  // Osekai
             ratelimiter(10), // OsuAvatar
             ratelimiter(10), // OsuBadge
             ratelimiter(2),  // OsuMapFile
             ratelimiter(10), // OsuMapsetCover
+            ratelimiter(5),  // OsuMapsetPreview
             LeakyBucket::builder() // OsuReplay, allows 6 per minute
                 .max(10)
                 .tokens(10)
@@ -86,16 +107,70 @@ impl Client {
         Ok(Self {
             client,
             ratelimiters,
+            webhook_client,
+            webhook_ratelimiters: Mutex::new(HashMap::new()),
             #[cfg(feature = "twitch")]
             twitch,
             github_auth,
         })
     }
 
+    pub(crate) fn webhook_client(connector: WebhookConnector) -> WebhookClient {
+        let connector = HttpsConnectorBuilder::new()
+            .with_webpki_roots()
+            .https_or_http()
+            .enable_http1()
+            .wrap_connector(connector);
+
+        HyperClient::builder().build(connector)
+    }
+
+    /// Wait for the ratelimiter of the site.
+    ///
+    /// Must not be used for [`Site::Webhook`], use `ratelimit_webhook` instead.
     pub(crate) async fn ratelimit(&self, site: Site) {
         self.ratelimiters[site as usize].acquire_one().await
     }
 
+    pub(crate) async fn ratelimit_webhook(&self, url: &str) {
+        let ratelimiter = {
+            let mut ratelimiters = self
+                .webhook_ratelimiters
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            let now = Instant::now();
+
+            if let Some((ratelimiter, last_used)) = ratelimiters.get_mut(url) {
+                *last_used = now;
+
+                Arc::clone(ratelimiter)
+            } else {
+                // An idle ratelimiter is refilled anyway so removing it
+                // keeps the map from growing with each url ever used
+                ratelimiters.retain(|_, (ratelimiter, last_used)| {
+                    Arc::strong_count(ratelimiter) > 1
+                        || now.duration_since(*last_used) < WEBHOOK_IDLE_TIMEOUT
+                });
+
+                let ratelimiter = Arc::new(
+                    LeakyBucket::builder()
+                        .max(2)
+                        .tokens(2)
+                        .refill_interval(Duration::from_millis(500))
+                        .refill_amount(1)
+                        .build(),
+                );
+
+                ratelimiters.insert(Box::from(url), (Arc::clone(&ratelimiter), now));
+
+                ratelimiter
+            }
+        };
+
+        ratelimiter.acquire_one().await
+    }
+
     pub(crate) async fn make_get_request(
         &self,
         url: impl AsRef<str>,
@@ -205,7 +280,9 @@ impl Client {
             .body(Body::from(json))
             .wrap_err("Failed to build POST json request")?;
 
-        self.ratelimit(site).await;
+        if site == Site::Webhook {
+            self.ratelimit_webhook(url).await;
+        }
 
         let (response, start) = self
             .send_request(req, site)
@@ -221,6 +298,65 @@ impl Client {
         bytes_res
     }
 
+    pub(crate) async fn make_twitch_request(
+        &self,
+        method: Method,
+        url: impl AsRef<str>,
+        json: Option<Vec<u8>>,
+    ) -> Result<Bytes, ClientError> {
+        let url = url.as_ref();
+        trace!("{method} twitch request to url {url}");
+
+        let req = Request::builder()
+            .method(method)
+            .uri(url)
+            .header(USER_AGENT, MY_USER_AGENT);
+
+        let mut req = self.twitch_auth(req)?;
+
+        let body = match json {
+            Some(json) => {
+                req = req
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(CONTENT_LENGTH, json.len());
+
+                Body::from(json)
+            }
+            None => Body::empty(),
+        };
+
+        let req = req.body(body).wrap_err("Failed to build twitch request")?;
+
+        let (response, start) = self
+            .send_request(req, Site::Twitch)
+            .await
+            .wrap_err("Failed to receive twitch response")?;
+
+        let status = response.status();
+        let bytes_res = Self::error_for_status(response, url).await;
+
+        let latency = start.elapsed();
+        ClientMetrics::observe(Site::Twitch, status, latency);
+
+        bytes_res
+    }
+
+    #[cfg(feature = "twitch")]
+    fn twitch_auth(&self, req: RequestBuilder) -> Result<RequestBuilder, ClientError> {
+        let req = req
+            .header("Client-ID", self.twitch.client_id.clone())
+            .header(AUTHORIZATION, format!("Bearer {}", self.twitch.oauth_token));
+
+        Ok(req)
+    }
+
+    #[cfg(not(feature = "twitch"))]
+    fn twitch_auth(&self, _: RequestBuilder) -> Result<RequestBuilder, ClientError> {
+        Err(ClientError::Report(eyre::Report::msg(
+            "twitch request without twitch feature",
+        )))
+    }
+
     pub(crate) async fn error_for_status(
         response: Response<Body>,
         url: &str,
@@ -239,15 +375,25 @@ impl Client {
         }
     }
 
+    /// Send the request after waiting for the site's ratelimiter.
+    ///
+    /// Requests to [`Site::Webhook`] must be ratelimited beforehand through
+    /// `ratelimit_webhook`.
     async fn send_request(
         &self,
         req: Request<Body>,
         site: Site,
     ) -> Result<(Response<Body>, Instant), HyperError> {
-        self.ratelimit(site).await;
+        let response_fut = match site {
+            Site::Webhook => self.webhook_client.request(req),
+            _ => {
+                self.ratelimit(site).await;
+
+                self.client.request(req)
+            }
+        };
 
         let start = Instant::now();
-        let response_fut = self.client.request(req);
 
         match response_fut.await {
             Ok(res) => Ok((res, start)),
@@ -9,6 +9,8 @@ pub enum ClientError {
     NotFound,
     #[error("status code 429 - ratelimited")]
     Ratelimited,
+    #[error("host does not resolve to a public address")]
+    NonPublicAddress,
     #[error(transparent)]
     Report(#[from] Report),
 }
@@ -9,7 +9,6 @@ mod discord;
 mod error;
 mod github;
 mod metrics;
-mod miss_analyzer;
 mod multipart;
 mod osekai;
 mod osu;
@@ -20,8 +19,9 @@ mod respektive;
 mod site;
 mod snipe;
 mod twitch;
+mod webhook;
 
 use self::site::Site;
-pub use self::{client::Client, error::ClientError};
+pub use self::{client::Client, error::ClientError, webhook::is_public_ip};
 
 static MY_USER_AGENT: &str = env!("CARGO_PKG_NAME");
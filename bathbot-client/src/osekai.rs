@@ -2,7 +2,8 @@ use bathbot_model::{
     OsekaiBadge, OsekaiBadgeOwner, OsekaiComment, OsekaiComments, OsekaiMap, OsekaiMaps,
     OsekaiMedal, OsekaiRanking, OsekaiRankingEntries,
 };
-use eyre::{Result, WrapErr};
+use bytes::Bytes;
+use eyre::{Report, Result, WrapErr};
 use itoa::Buffer as IntBuffer;
 
 use crate::{multipart::Multipart, site::Site, Client};
@@ -50,6 +51,12 @@ impl Client {
         })
     }
 
+    pub async fn get_osekai_medal_icon(&self, url: &str) -> Result<Bytes> {
+        self.make_get_request(url, Site::Osekai)
+            .await
+            .map_err(Report::new)
+    }
+
     pub async fn get_osekai_beatmaps(&self, medal_name: &str) -> Result<Vec<OsekaiMap>> {
         let url = "https://osekai.net/medals/api/beatmaps.php";
         let mut form = Multipart::new();
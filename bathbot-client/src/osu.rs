@@ -64,6 +64,15 @@ impl Client {
             .map_err(Report::new)
     }
 
+    /// Retrieve the ~10 second audio preview of a mapset in mp3 format
+    pub async fn get_mapset_preview(&self, mapset_id: u32) -> Result<Bytes> {
+        let url = format!("https://b.ppy.sh/preview/{mapset_id}.mp3");
+
+        self.make_get_request(&url, Site::OsuMapsetPreview)
+            .await
+            .map_err(Report::new)
+    }
+
     pub async fn get_map_file(&self, map_id: u32) -> Result<Bytes, ClientError> {
         let url = format!("{OSU_BASE}osu/{map_id}");
 
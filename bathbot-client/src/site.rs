@@ -6,18 +6,19 @@ pub enum Site {
     Github,
     Huismetbenen,
     KittenRoleplay,
-    MissAnalyzer,
     Osekai,
     OsuAvatar,
     OsuBadge,
     OsuMapFile,
     OsuMapsetCover,
+    OsuMapsetPreview,
     OsuReplay,
     OsuStats,
     OsuTrack,
     OsuWorld,
     Respektive,
     Twitch,
+    Webhook,
 }
 
 impl Site {
@@ -28,18 +29,19 @@ impl Site {
             Self::Github => "Github",
             Self::Huismetbenen => "Huismetbenen",
             Self::KittenRoleplay => "KittenRoleplay",
-            Self::MissAnalyzer => "MissAnalyzer",
             Self::Osekai => "Osekai",
             Self::OsuAvatar => "OsuAvatar",
             Self::OsuBadge => "OsuBadge",
             Self::OsuMapFile => "OsuMapFile",
             Self::OsuMapsetCover => "OsuMapsetCover",
+            Self::OsuMapsetPreview => "OsuMapsetPreview",
             Self::OsuReplay => "OsuReplay",
             Self::OsuStats => "OsuStats",
             Self::OsuTrack => "OsuTrack",
             Self::OsuWorld => "OsuWorld",
             Self::Respektive => "Respektive",
             Self::Twitch => "Twitch",
+            Self::Webhook => "Webhook",
         }
     }
 }
@@ -4,12 +4,17 @@ use std::{
     time::Duration,
 };
 
-use bathbot_model::{TwitchDataList, TwitchStream, TwitchUser, TwitchVideo};
+use bathbot_model::{
+    TwitchDataList, TwitchEventSubSubscription, TwitchPaginatedList, TwitchStream, TwitchUser,
+    TwitchVideo,
+};
 use bathbot_util::constants::{
-    TWITCH_STREAM_ENDPOINT, TWITCH_USERS_ENDPOINT, TWITCH_VIDEOS_ENDPOINT,
+    TWITCH_EVENTSUB_ENDPOINT, TWITCH_STREAM_ENDPOINT, TWITCH_USERS_ENDPOINT, TWITCH_VIDEOS_ENDPOINT,
 };
 use bytes::Bytes;
 use eyre::{Result, WrapErr};
+use hyper::Method;
+use serde::Serialize;
 use tokio::time::interval;
 
 use crate::{Client, ClientError, Site};
@@ -69,6 +74,103 @@ impl Client {
 }
 
 impl Client {
+    /// Retrieve all EventSub subscriptions of the app.
+    pub async fn get_twitch_eventsub_subscriptions(
+        &self,
+    ) -> Result<Vec<TwitchEventSubSubscription>> {
+        let mut subscriptions = Vec::new();
+        let mut cursor: Option<Box<str>> = None;
+
+        loop {
+            let data = cursor.as_deref().map(|cursor| ("after", cursor));
+
+            let bytes = self
+                .make_twitch_get_request(TWITCH_EVENTSUB_ENDPOINT, data)
+                .await?;
+
+            let mut page: TwitchPaginatedList<TwitchEventSubSubscription> =
+                serde_json::from_slice(&bytes).wrap_err_with(|| {
+                    let body = String::from_utf8_lossy(&bytes);
+
+                    format!("Failed to deserialize eventsub subscriptions: {body}")
+                })?;
+
+            subscriptions.append(&mut page.data);
+
+            match page.pagination.cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        Ok(subscriptions)
+    }
+
+    /// Subscribe to an EventSub event of a broadcaster which will be posted
+    /// to the given callback url.
+    ///
+    /// The secret is used by twitch to sign notifications.
+    pub async fn create_twitch_eventsub_subscription(
+        &self,
+        kind: &str,
+        user_id: u64,
+        callback: &str,
+        secret: &str,
+    ) -> Result<()> {
+        #[derive(Serialize)]
+        struct Body<'a> {
+            #[serde(rename = "type")]
+            kind: &'a str,
+            version: &'a str,
+            condition: Condition,
+            transport: Transport<'a>,
+        }
+
+        #[derive(Serialize)]
+        struct Condition {
+            broadcaster_user_id: String,
+        }
+
+        #[derive(Serialize)]
+        struct Transport<'a> {
+            method: &'a str,
+            callback: &'a str,
+            secret: &'a str,
+        }
+
+        let body = Body {
+            kind,
+            version: "1",
+            condition: Condition {
+                broadcaster_user_id: user_id.to_string(),
+            },
+            transport: Transport {
+                method: "webhook",
+                callback,
+                secret,
+            },
+        };
+
+        let json =
+            serde_json::to_vec(&body).wrap_err("Failed to serialize eventsub subscription")?;
+
+        self.make_twitch_request(Method::POST, TWITCH_EVENTSUB_ENDPOINT, Some(json))
+            .await
+            .wrap_err("Failed to create eventsub subscription")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_twitch_eventsub_subscription(&self, id: &str) -> Result<()> {
+        let url = format!("{TWITCH_EVENTSUB_ENDPOINT}?id={id}");
+
+        self.make_twitch_request(Method::DELETE, url, None)
+            .await
+            .wrap_err("Failed to delete eventsub subscription")?;
+
+        Ok(())
+    }
+
     async fn make_twitch_get_request<I, U, V>(
         &self,
         url: impl AsRef<str>,
@@ -0,0 +1,238 @@
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter, Result as FmtResult},
+    future::Future,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use hyper::{service::Service, Uri};
+use tokio::net::{lookup_host, TcpStream};
+
+use crate::{Client, ClientError, Site};
+
+impl Client {
+    /// Post a JSON payload to a user-provided webhook url.
+    ///
+    /// The url's host must resolve to a public address, otherwise
+    /// [`ClientError::NonPublicAddress`] is returned.
+    pub async fn post_webhook(&self, url: &str, json: Vec<u8>) -> Result<(), ClientError> {
+        match self.make_json_post_request(url, Site::Webhook, json).await {
+            Ok(_) => Ok(()),
+            Err(ClientError::Report(err))
+                if err.chain().any(<dyn StdError>::is::<NonPublicAddress>) =>
+            {
+                Err(ClientError::NonPublicAddress)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Whether the address is reachable from the public internet i.e. not a
+/// loopback, private, link-local, or otherwise reserved address.
+pub fn is_public_ip(addr: IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => is_public_ipv4(addr),
+        IpAddr::V6(addr) => is_public_ipv6(addr),
+    }
+}
+
+fn is_public_ipv4(addr: Ipv4Addr) -> bool {
+    let [a, b, ..] = addr.octets();
+
+    // 100.64.0.0/10 is reserved for carrier-grade NAT
+    let shared = a == 100 && (b & 0b1100_0000) == 64;
+
+    !(addr.is_loopback()
+        || addr.is_private()
+        || addr.is_link_local()
+        || addr.is_unspecified()
+        || addr.is_broadcast()
+        || addr.is_documentation()
+        || shared)
+}
+
+fn is_public_ipv6(addr: Ipv6Addr) -> bool {
+    if let Some(addr) = addr.to_ipv4_mapped() {
+        return is_public_ipv4(addr);
+    }
+
+    let first = addr.segments()[0];
+
+    // fc00::/7 are unique local and fe80::/10 are link-local addresses
+    let unique_local = (first & 0xfe00) == 0xfc00;
+    let link_local = (first & 0xffc0) == 0xfe80;
+
+    !(addr.is_loopback() || addr.is_unspecified() || unique_local || link_local)
+}
+
+/// The webhook host did not resolve to any public address.
+#[derive(Debug)]
+pub(crate) struct NonPublicAddress;
+
+impl Display for NonPublicAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("host does not resolve to a public address")
+    }
+}
+
+impl StdError for NonPublicAddress {}
+
+/// Connector for webhook requests.
+///
+/// Since webhook urls are user-provided, the host is resolved on each
+/// connection and only public addresses are connected to. This way neither
+/// a domain pointing to a private network nor one that was rebound after the
+/// url was validated can be used to reach internal services.
+#[derive(Clone)]
+pub(crate) struct WebhookConnector {
+    allow_private: bool,
+}
+
+impl WebhookConnector {
+    pub(crate) fn new() -> Self {
+        Self {
+            allow_private: false,
+        }
+    }
+
+    /// Connector that also connects to non-public addresses so that tests
+    /// can use a local listener.
+    #[cfg(test)]
+    pub(crate) fn allow_private() -> Self {
+        Self {
+            allow_private: true,
+        }
+    }
+}
+
+type BoxError = Box<dyn StdError + Send + Sync>;
+
+impl Service<Uri> for WebhookConnector {
+    type Response = TcpStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<TcpStream, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, uri: Uri) -> Self::Future {
+        let allow_private = self.allow_private;
+
+        Box::pin(async move {
+            let host = uri.host().ok_or("missing host in webhook url")?;
+
+            // Ipv6 hosts are wrapped in brackets
+            let host = host.trim_start_matches('[').trim_end_matches(']');
+
+            let port = match uri.port_u16() {
+                Some(port) => port,
+                None if uri.scheme_str() == Some("http") => 80,
+                None => 443,
+            };
+
+            let addrs: Vec<SocketAddr> = lookup_host((host, port))
+                .await?
+                .filter(|addr| allow_private || is_public_ip(addr.ip()))
+                .collect();
+
+            if addrs.is_empty() {
+                return Err(Box::new(NonPublicAddress) as BoxError);
+            }
+
+            let stream = TcpStream::connect(addrs.as_slice()).await?;
+            stream.set_nodelay(true)?;
+
+            Ok(stream)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    const JSON: &[u8] = br#"{"type":"test"}"#;
+
+    #[test]
+    fn public_ips() {
+        let public = ["1.1.1.1", "2606:4700:4700::1111"];
+
+        let private = [
+            "127.0.0.1",
+            "10.0.0.1",
+            "100.64.0.1",
+            "169.254.169.254",
+            "172.16.0.1",
+            "192.168.0.1",
+            "0.0.0.0",
+            "::1",
+            "::ffff:127.0.0.1",
+            "fc00::1",
+            "fe80::1",
+        ];
+
+        for addr in public {
+            assert!(is_public_ip(addr.parse().unwrap()), "{addr}");
+        }
+
+        for addr in private {
+            assert!(!is_public_ip(addr.parse().unwrap()), "{addr}");
+        }
+    }
+
+    // With the twitch feature, creating a client would request a twitch token
+    #[cfg(not(feature = "twitch"))]
+    #[tokio::test]
+    async fn post_to_local_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0; 1024];
+
+            while !request.ends_with(JSON) {
+                let n = stream.read(&mut buf).await.unwrap();
+                assert_ne!(n, 0, "connection closed before the body was received");
+                request.extend_from_slice(&buf[..n]);
+            }
+
+            let response = b"HTTP/1.1 204 No Content\r\ncontent-length: 0\r\n\r\n";
+            stream.write_all(response).await.unwrap();
+
+            String::from_utf8(request).unwrap()
+        });
+
+        let mut client = Client::new("").await.unwrap();
+        client.webhook_client = Client::webhook_client(WebhookConnector::allow_private());
+
+        client.post_webhook(&url, JSON.to_vec()).await.unwrap();
+
+        let request = server.await.unwrap();
+
+        assert!(request.starts_with("POST /webhook HTTP/1.1\r\n"));
+        assert!(request.contains("content-type: application/json\r\n"));
+    }
+
+    #[cfg(not(feature = "twitch"))]
+    #[tokio::test]
+    async fn reject_local_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/webhook", listener.local_addr().unwrap());
+
+        let client = Client::new("").await.unwrap();
+        let res = client.post_webhook(&url, JSON.to_vec()).await;
+
+        assert!(matches!(res, Err(ClientError::NonPublicAddress)), "{res:?}");
+    }
+}
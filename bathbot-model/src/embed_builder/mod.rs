@@ -44,3 +44,7 @@ pub use self::{settings::*, value::*};
 fn is_true(b: &bool) -> bool {
     *b
 }
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
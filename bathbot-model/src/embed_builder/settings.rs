@@ -142,7 +142,7 @@ pub struct SettingsButtons {
     #[serde(
         default = "SettingsButtons::default_miss_analyzer",
         with = "bool_as_u8",
-        skip_serializing_if = "super::is_true"
+        skip_serializing_if = "super::is_false"
     )]
     pub miss_analyzer: bool,
 }
@@ -156,8 +156,9 @@ impl SettingsButtons {
         true
     }
 
+    /// Analyzing a replay is comparatively expensive so it's opt-in
     fn default_miss_analyzer() -> bool {
-        true
+        false
     }
 }
 
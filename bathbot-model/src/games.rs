@@ -13,6 +13,87 @@ pub struct BgGameScore {
     pub score: i32,
 }
 
+/// Background game stats of a user within a guild's season
+pub struct BgGameSeasonScore {
+    pub discord_id: i64,
+    pub guesses: i32,
+    pub guess_time_ms: i64,
+    pub hints: i32,
+    pub best_streak: i32,
+}
+
+impl BgGameSeasonScore {
+    /// Average time in seconds until the user guessed correctly
+    pub fn avg_guess_time(&self) -> f32 {
+        if self.guesses == 0 {
+            return 0.0;
+        }
+
+        self.guess_time_ms as f32 / self.guesses as f32 / 1000.0
+    }
+}
+
+#[derive(Copy, Clone, Default, CommandOption, CreateOption)]
+pub enum BgSeasonColumn {
+    #[default]
+    #[option(name = "Correct guesses", value = "guesses")]
+    Guesses,
+    #[option(name = "Average guess time", value = "guess_time")]
+    GuessTime,
+    #[option(name = "Hints used", value = "hints")]
+    Hints,
+    #[option(name = "Best streak", value = "streak")]
+    Streak,
+}
+
+impl BgSeasonColumn {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Guesses => "Correct guesses",
+            Self::GuessTime => "Average guess time",
+            Self::Hints => "Hints used",
+            Self::Streak => "Best streak",
+        }
+    }
+}
+
+/// Outcome of a single guess in the daily challenge
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DailyGuess {
+    Wrong = 0,
+    Artist = 1,
+    Title = 2,
+}
+
+impl DailyGuess {
+    pub fn emote(self) -> &'static str {
+        match self {
+            Self::Wrong => "🟥",
+            Self::Artist => "🟨",
+            Self::Title => "🟩",
+        }
+    }
+}
+
+impl From<i16> for DailyGuess {
+    #[inline]
+    fn from(outcome: i16) -> Self {
+        match outcome {
+            1 => Self::Artist,
+            2 => Self::Title,
+            _ => Self::Wrong,
+        }
+    }
+}
+
+/// Daily challenge stats of a user
+pub struct DailyChallengeStats {
+    pub played: u32,
+    pub solved: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+}
+
 pub struct HlGameScore {
     pub discord_id: i64,
     pub highscore: i32,
@@ -22,6 +103,16 @@ pub struct HlGameScore {
 pub enum HlVersion {
     #[option(name = "Score PP", value = "score_pp")]
     ScorePp = 0,
+    #[option(name = "Map Stars", value = "map_stars")]
+    MapStars = 1,
+    #[option(name = "Global Rank", value = "global_rank")]
+    GlobalRank = 2,
+    #[option(name = "Playcount", value = "playcount")]
+    Playcount = 3,
+    #[option(name = "Medal Rarity", value = "medal_rarity")]
+    MedalRarity = 4,
+    #[option(name = "Mapset Favourites", value = "mapset_favourites")]
+    MapsetFavourites = 5,
 }
 
 bitflags::bitflags! {
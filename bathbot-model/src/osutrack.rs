@@ -1,15 +1,18 @@
 use std::fmt;
 
+use rkyv::{Archive, Deserialize, Serialize};
 use serde::de;
 use time::OffsetDateTime;
 
-use crate::deser::Datetime;
+use crate::{deser::Datetime, rkyv_util::time::DateTimeRkyv};
 
-#[derive(Clone)]
+#[derive(Archive, Clone, Deserialize, Serialize)]
 pub struct RankAccPeaks {
     pub rank: u32,
+    #[with(DateTimeRkyv)]
     pub rank_timestamp: OffsetDateTime,
     pub acc: f64,
+    #[with(DateTimeRkyv)]
     pub acc_timestamp: OffsetDateTime,
 }
 
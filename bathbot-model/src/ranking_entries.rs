@@ -6,8 +6,8 @@ use time::OffsetDateTime;
 use twilight_model::id::{marker::GuildMarker, Id};
 
 use crate::{
-    twilight_model::util::ImageHash, BgGameScore, HlGameScore, HlVersion, UserModeStatsColumn,
-    UserStatsColumn, UserStatsEntries, UserStatsEntry,
+    twilight_model::util::ImageHash, BgGameScore, BgGameSeasonScore, BgSeasonColumn, HlGameScore,
+    HlVersion, UserModeStatsColumn, UserStatsColumn, UserStatsEntries, UserStatsEntry,
 };
 
 pub struct RankingEntry<V> {
@@ -186,6 +186,11 @@ pub enum RankingKind {
         global: bool,
         scores: Vec<BgGameScore>,
     },
+    BgSeason {
+        season: i32,
+        column: BgSeasonColumn,
+        scores: Vec<BgGameSeasonScore>,
+    },
     Commands {
         bootup_time: OffsetDateTime,
     },
@@ -245,6 +250,14 @@ impl RankingKind {
 
                 EmbedHeader::Author(AuthorBuilder::new(text))
             }
+            Self::BgSeason { season, column, .. } => {
+                let text = format!(
+                    "Server leaderboard for season {season}: {column}",
+                    column = column.name()
+                );
+
+                EmbedHeader::Author(AuthorBuilder::new(text))
+            }
             Self::Commands { .. } => {
                 let text = "Most popular prefix commands:";
 
@@ -253,6 +266,13 @@ impl RankingKind {
             Self::HlScores { version, .. } => {
                 let text = match version {
                     HlVersion::ScorePp => "Server leaderboard for Higherlower (Score PP)",
+                    HlVersion::MapStars => "Server leaderboard for Higherlower (Map Stars)",
+                    HlVersion::GlobalRank => "Server leaderboard for Higherlower (Global Rank)",
+                    HlVersion::Playcount => "Server leaderboard for Higherlower (Playcount)",
+                    HlVersion::MedalRarity => "Server leaderboard for Higherlower (Medal Rarity)",
+                    HlVersion::MapsetFavourites => {
+                        "Server leaderboard for Higherlower (Mapset Favourites)"
+                    }
                 };
 
                 EmbedHeader::Author(AuthorBuilder::new(text))
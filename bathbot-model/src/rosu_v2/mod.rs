@@ -1,3 +1,4 @@
 pub mod grade;
 pub mod ranking;
+pub mod score;
 pub mod user;
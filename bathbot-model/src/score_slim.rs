@@ -1,14 +1,22 @@
 use bathbot_util::{ScoreExt, ScoreHasEndedAt, ScoreHasMode};
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_v2::{
     model::score::LegacyScoreStatistics,
     prelude::{GameMode, GameMods, Grade, Score},
 };
 use time::OffsetDateTime;
 
-#[derive(Clone)]
+use crate::{
+    rkyv_util::time::DateTimeRkyv,
+    rosu_v2::{grade::GradeRkyv, score::LegacyScoreStatistics as LegacyScoreStatisticsRkyv},
+};
+
+#[derive(Archive, Clone, Deserialize, Serialize)]
 pub struct ScoreSlim {
     pub accuracy: f32,
+    #[with(DateTimeRkyv)]
     pub ended_at: OffsetDateTime,
+    #[with(GradeRkyv)]
     pub grade: Grade,
     pub max_combo: u32,
     pub mode: GameMode,
@@ -19,6 +27,7 @@ pub struct ScoreSlim {
     /// Note that this is the *new* kind of score id
     pub score_id: u64,
     pub legacy_id: Option<u64>,
+    #[with(LegacyScoreStatisticsRkyv)]
     pub statistics: LegacyScoreStatistics,
 }
 
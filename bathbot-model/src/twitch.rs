@@ -53,6 +53,7 @@ pub struct TwitchUser {
 pub struct TwitchStream {
     #[serde(rename = "game_id", deserialize_with = "str_to_maybe_u64")]
     pub game_id: Option<u64>,
+    pub game_name: Box<str>,
     #[serde(rename = "id", deserialize_with = "str_to_u64")]
     pub stream_id: u64,
     // Gets modified inside the struct so required to keep as `String`
@@ -68,6 +69,7 @@ pub struct TwitchStream {
     pub live: bool,
     #[serde(with = "super::deser::datetime_rfc3339")]
     pub started_at: OffsetDateTime,
+    pub viewer_count: u32,
 }
 
 impl TwitchStream {
@@ -85,6 +87,47 @@ pub struct TwitchDataList<T> {
     pub data: Vec<T>,
 }
 
+#[derive(Deserialize)]
+pub struct TwitchPaginatedList<T> {
+    pub data: Vec<T>,
+    #[serde(default)]
+    pub pagination: TwitchPagination,
+}
+
+#[derive(Default, Deserialize)]
+pub struct TwitchPagination {
+    pub cursor: Option<Box<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwitchEventSubSubscription {
+    pub id: Box<str>,
+    pub status: Box<str>,
+    #[serde(rename = "type")]
+    pub kind: Box<str>,
+    pub condition: TwitchEventSubCondition,
+    pub transport: TwitchEventSubTransport,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwitchEventSubCondition {
+    #[serde(deserialize_with = "str_to_u64")]
+    pub broadcaster_user_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TwitchEventSubTransport {
+    pub method: Box<str>,
+    pub callback: Option<Box<str>>,
+}
+
+/// Stream status change received through a twitch EventSub notification
+#[derive(Copy, Clone, Debug)]
+pub enum TwitchStreamEvent {
+    Online { user_id: u64 },
+    Offline { user_id: u64 },
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TwitchVideo {
     #[serde(with = "super::deser::datetime_rfc3339")]
@@ -0,0 +1 @@
+DROP TABLE tracked_matches;
\ No newline at end of file
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS tracked_matches (
+    match_id      INT4 NOT NULL,
+    channel_id    INT8 NOT NULL,
+    -- last message that was sent into the channel and will be edited on updates
+    last_msg_id   INT8 NOT NULL,
+    -- latest match event that has been processed
+    last_event_id INT8 NOT NULL,
+    -- game that was still in progress when the match was last processed
+    last_game_id  INT8,
+    PRIMARY KEY (match_id, channel_id)
+);
@@ -0,0 +1 @@
+DROP TABLE tracked_osu_user_filters;
\ No newline at end of file
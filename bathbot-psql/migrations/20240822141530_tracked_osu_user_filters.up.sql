@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS tracked_osu_user_filters (
+    user_id    INT4 NOT NULL,
+    gamemode   INT2 NOT NULL,
+    channel_id INT8 NOT NULL,
+    -- query string in the same syntax as the `query` option of /top
+    criteria   VARCHAR(256) NOT NULL,
+    PRIMARY KEY (user_id, gamemode, channel_id),
+    FOREIGN KEY (user_id, gamemode) REFERENCES tracked_osu_users (user_id, gamemode) ON DELETE CASCADE
+);
@@ -0,0 +1,16 @@
+DELETE FROM tracked_osu_users WHERE kind <> 0;
+
+ALTER TABLE tracked_osu_user_filters
+    DROP CONSTRAINT tracked_osu_user_filters_user_id_gamemode_kind_fkey,
+    DROP CONSTRAINT tracked_osu_user_filters_pkey,
+    DROP COLUMN kind,
+    ADD PRIMARY KEY (user_id, gamemode, channel_id);
+
+ALTER TABLE tracked_osu_users
+    DROP CONSTRAINT tracked_osu_users_pkey,
+    DROP COLUMN kind,
+    DROP COLUMN last_score_id,
+    ADD PRIMARY KEY (user_id, gamemode);
+
+ALTER TABLE tracked_osu_user_filters
+    ADD FOREIGN KEY (user_id, gamemode) REFERENCES tracked_osu_users (user_id, gamemode) ON DELETE CASCADE;
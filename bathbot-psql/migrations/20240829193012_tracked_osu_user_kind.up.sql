@@ -0,0 +1,16 @@
+ALTER TABLE tracked_osu_user_filters
+    DROP CONSTRAINT tracked_osu_user_filters_user_id_gamemode_fkey,
+    DROP CONSTRAINT tracked_osu_user_filters_pkey;
+
+ALTER TABLE tracked_osu_users
+    DROP CONSTRAINT tracked_osu_users_pkey,
+    -- 0: top scores, 1: recent scores
+    ADD COLUMN kind INT2 NOT NULL DEFAULT 0,
+    -- id of the latest processed recent score
+    ADD COLUMN last_score_id INT8,
+    ADD PRIMARY KEY (user_id, gamemode, kind);
+
+ALTER TABLE tracked_osu_user_filters
+    ADD COLUMN kind INT2 NOT NULL DEFAULT 0,
+    ADD PRIMARY KEY (user_id, gamemode, kind, channel_id),
+    ADD FOREIGN KEY (user_id, gamemode, kind) REFERENCES tracked_osu_users (user_id, gamemode, kind) ON DELETE CASCADE;
@@ -0,0 +1 @@
+DROP TABLE tracked_osu_user_milestones;
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS tracked_osu_user_milestones (
+    user_id    INT4 NOT NULL,
+    gamemode   INT2 NOT NULL,
+    kind       INT2 NOT NULL,
+    channel_id INT8 NOT NULL,
+    -- bitflags of the milestone types the channel opted into
+    milestones INT2 NOT NULL,
+    PRIMARY KEY (user_id, gamemode, kind, channel_id),
+    FOREIGN KEY (user_id, gamemode, kind) REFERENCES tracked_osu_users (user_id, gamemode, kind) ON DELETE CASCADE
+);
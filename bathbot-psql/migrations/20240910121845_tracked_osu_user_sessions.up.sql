@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS tracked_osu_user_sessions (
+    user_id       INT4 NOT NULL,
+    gamemode      INT2 NOT NULL,
+    kind          INT2 NOT NULL,
+    channel_id    INT8 NOT NULL,
+    -- minutes of inactivity after which a session is closed
+    timeout       INT2 NOT NULL,
+    -- summary message of the current session, NULL if no session started yet
+    msg_id        INT8,
+    started_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_activity TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    start_pp      FLOAT4 NOT NULL DEFAULT 0,
+    start_rank    INT4 NOT NULL DEFAULT 0,
+    score_count   INT4 NOT NULL DEFAULT 0,
+    -- formatted lines of the latest plays within the session
+    plays         TEXT[] NOT NULL DEFAULT '{}',
+    PRIMARY KEY (user_id, gamemode, kind, channel_id),
+    FOREIGN KEY (user_id, gamemode, kind) REFERENCES tracked_osu_users (user_id, gamemode, kind) ON DELETE CASCADE
+);
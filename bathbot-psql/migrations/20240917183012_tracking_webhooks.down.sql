@@ -0,0 +1,3 @@
+DROP TABLE tracked_osu_user_webhooks;
+
+ALTER TABLE tracked_twitch_streams DROP COLUMN webhook_url;
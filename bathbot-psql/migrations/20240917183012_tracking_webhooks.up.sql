@@ -0,0 +1,12 @@
+ALTER TABLE tracked_twitch_streams ADD COLUMN webhook_url TEXT;
+
+CREATE TABLE IF NOT EXISTS tracked_osu_user_webhooks (
+    user_id     INT4 NOT NULL,
+    gamemode    INT2 NOT NULL,
+    kind        INT2 NOT NULL,
+    channel_id  INT8 NOT NULL,
+    -- notifications are sent to this url instead of the channel
+    webhook_url TEXT NOT NULL,
+    PRIMARY KEY (user_id, gamemode, kind, channel_id),
+    FOREIGN KEY (user_id, gamemode, kind) REFERENCES tracked_osu_users (user_id, gamemode, kind) ON DELETE CASCADE
+);
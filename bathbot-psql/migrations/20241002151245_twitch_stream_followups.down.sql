@@ -0,0 +1,3 @@
+DROP TABLE tracked_twitch_stream_messages;
+
+ALTER TABLE tracked_twitch_streams DROP COLUMN followup;
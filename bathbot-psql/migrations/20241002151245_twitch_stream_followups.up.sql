@@ -0,0 +1,18 @@
+-- whether live notifications are edited once the stream ends
+ALTER TABLE tracked_twitch_streams ADD COLUMN followup BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS tracked_twitch_stream_messages (
+    channel_id   INT8 NOT NULL,
+    user_id      INT8 NOT NULL,
+    msg_id       INT8 NOT NULL,
+    stream_id    INT8 NOT NULL,
+    started_at   TIMESTAMPTZ NOT NULL,
+    -- title and game at the time of most viewers
+    peak_viewers INT4 NOT NULL,
+    title        TEXT NOT NULL,
+    game_name    TEXT NOT NULL,
+    -- set once the stream went offline; the row is kept until the VOD is linked
+    ended_at     TIMESTAMPTZ,
+    PRIMARY KEY (channel_id, user_id),
+    FOREIGN KEY (channel_id, user_id) REFERENCES tracked_twitch_streams (channel_id, user_id) ON DELETE CASCADE
+);
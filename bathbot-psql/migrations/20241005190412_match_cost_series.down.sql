@@ -0,0 +1,3 @@
+DROP TABLE match_cost_series_matches;
+
+DROP TABLE match_cost_series;
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS match_cost_series (
+    guild_id   INT8 NOT NULL,
+    -- lowercase
+    name       VARCHAR(32) NOT NULL,
+    created_by INT8 NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guild_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS match_cost_series_matches (
+    guild_id INT8 NOT NULL,
+    name     VARCHAR(32) NOT NULL,
+    match_id INT4 NOT NULL,
+    warmups  INT2 NOT NULL DEFAULT 0,
+    added_by INT8 NOT NULL,
+    added_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guild_id, name, match_id),
+    FOREIGN KEY (guild_id, name) REFERENCES match_cost_series (guild_id, name) ON DELETE CASCADE
+);
@@ -0,0 +1,5 @@
+ALTER TABLE tracked_matches DROP COLUMN mappool_id;
+
+DROP TABLE mappool_slots;
+
+DROP TABLE mappools;
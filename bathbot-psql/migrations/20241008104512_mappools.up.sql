@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS mappools (
+    pool_id    SERIAL PRIMARY KEY,
+    guild_id   INT8 NOT NULL,
+    -- lowercase
+    name       VARCHAR(32) NOT NULL,
+    created_by INT8 NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (guild_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS mappool_slots (
+    pool_id INT4 NOT NULL REFERENCES mappools (pool_id) ON DELETE CASCADE,
+    -- uppercase e.g. NM1, HD2, TB
+    slot    VARCHAR(8) NOT NULL,
+    map_id  INT4 NOT NULL,
+    PRIMARY KEY (pool_id, slot)
+);
+
+ALTER TABLE tracked_matches ADD COLUMN mappool_id INT4 REFERENCES mappools (pool_id) ON DELETE SET NULL;
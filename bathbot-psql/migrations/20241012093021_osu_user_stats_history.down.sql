@@ -0,0 +1 @@
+DROP TABLE IF EXISTS osu_user_stats_history;
@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS osu_user_stats_history (
+    user_id      INT4 NOT NULL,
+    gamemode     INT2 NOT NULL,
+    pp           FLOAT4 NOT NULL,
+    global_rank  INT4 NOT NULL,
+    country_rank INT4 NOT NULL,
+    accuracy     FLOAT4 NOT NULL,
+    playcount    INT4 NOT NULL,
+    recorded_at  TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, gamemode, recorded_at)
+);
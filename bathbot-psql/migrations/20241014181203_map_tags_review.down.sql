@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS map_tags_history;
+
+ALTER TABLE map_tags DROP COLUMN reviewed_at;
@@ -0,0 +1,24 @@
+-- NULL if the mapset has not been tagged yet
+ALTER TABLE map_tags ADD COLUMN reviewed_at TIMESTAMPTZ;
+
+-- Mapsets with any tag must have been tagged at some point
+UPDATE
+  map_tags
+SET
+  reviewed_at = NOW()
+WHERE
+  farm OR streams OR alternate OR old OR meme OR hardname OR easy
+  OR hard OR tech OR weeb OR bluesky OR english OR kpop;
+
+-- Previous tags of mapsets so that changes can be undone.
+-- All changes of a transaction share the same timestamp.
+CREATE TABLE IF NOT EXISTS map_tags_history (
+    changed_by       INT8 NOT NULL,
+    changed_at       TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    mapset_id        INT4 NOT NULL,
+    -- bitflags of the previous tags
+    prev_tags        INT4 NOT NULL,
+    prev_reviewed_at TIMESTAMPTZ,
+    PRIMARY KEY (changed_by, changed_at, mapset_id),
+    FOREIGN KEY (mapset_id) REFERENCES map_tags (mapset_id) ON DELETE CASCADE
+);
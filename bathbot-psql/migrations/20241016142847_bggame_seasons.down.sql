@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS bggame_guild_scores;
+DROP TABLE IF EXISTS bggame_seasons;
@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS bggame_seasons (
+    guild_id   INT8 NOT NULL,
+    season     INT4 NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (guild_id, season)
+);
+
+CREATE TABLE IF NOT EXISTS bggame_guild_scores (
+    guild_id      INT8 NOT NULL,
+    season        INT4 NOT NULL,
+    discord_id    INT8 NOT NULL,
+    guesses       INT4 NOT NULL DEFAULT 0,
+    guess_time_ms INT8 NOT NULL DEFAULT 0,
+    hints         INT4 NOT NULL DEFAULT 0,
+    best_streak   INT4 NOT NULL DEFAULT 0,
+    PRIMARY KEY (guild_id, season, discord_id)
+);
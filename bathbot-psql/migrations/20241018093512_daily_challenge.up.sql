@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS daily_challenges (
+    day       DATE NOT NULL,
+    mapset_id INT4 NOT NULL,
+    PRIMARY KEY (day)
+);
+
+CREATE TABLE IF NOT EXISTS daily_challenge_attempts (
+    discord_id INT8 NOT NULL,
+    day        DATE NOT NULL,
+    -- 0: wrong, 1: correct artist, 2: correct title
+    outcomes   INT2[] NOT NULL DEFAULT '{}',
+    finished   BOOLEAN NOT NULL DEFAULT false,
+    PRIMARY KEY (discord_id, day)
+);
@@ -0,0 +1,3 @@
+DROP TABLE guild_default_skins;
+
+DROP TABLE guild_skins;
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS guild_skins (
+    skin_id      SERIAL PRIMARY KEY,
+    guild_id     INT8 NOT NULL,
+    name         VARCHAR(64) NOT NULL,
+    author       VARCHAR(64),
+    url          VARCHAR(256) NOT NULL,
+    ordr_skin_id INT4,
+    preview_url  VARCHAR(256),
+    added_by     INT8 NOT NULL,
+    added_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- names are unique per guild regardless of casing
+CREATE UNIQUE INDEX IF NOT EXISTS guild_skins_name_index ON guild_skins (guild_id, LOWER(name));
+
+CREATE TABLE IF NOT EXISTS guild_default_skins (
+    guild_id INT8 PRIMARY KEY,
+    skin_id  INT4 NOT NULL REFERENCES guild_skins (skin_id) ON DELETE CASCADE
+);
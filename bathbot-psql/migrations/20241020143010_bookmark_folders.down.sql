@@ -0,0 +1,5 @@
+ALTER TABLE user_map_bookmarks
+    DROP COLUMN note,
+    DROP COLUMN folder_id;
+
+DROP TABLE bookmark_folders;
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS bookmark_folders (
+    folder_id  SERIAL PRIMARY KEY,
+    user_id    INT8 NOT NULL,
+    -- lowercase
+    name       VARCHAR(32) NOT NULL,
+    shared     BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+ALTER TABLE user_map_bookmarks
+    ADD COLUMN folder_id INT4 REFERENCES bookmark_folders (folder_id) ON DELETE SET NULL,
+    ADD COLUMN note      VARCHAR(256);
@@ -0,0 +1,4 @@
+CREATE TABLE IF NOT EXISTS leaderboard_refresh_guilds (
+    guild_id   INT8 PRIMARY KEY,
+    last_shown TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
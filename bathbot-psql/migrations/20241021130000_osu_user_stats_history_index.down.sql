@@ -0,0 +1 @@
+DROP INDEX osu_user_stats_history_recorded_at_index;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS osu_user_stats_history_recorded_at_index ON osu_user_stats_history (recorded_at);
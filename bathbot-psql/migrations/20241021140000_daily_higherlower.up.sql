@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS daily_higherlower_attempts (
+    discord_id INT8 NOT NULL,
+    day        DATE NOT NULL,
+    score      INT4 NOT NULL DEFAULT 0,
+    finished   BOOLEAN NOT NULL DEFAULT false,
+    PRIMARY KEY (discord_id, day)
+);
@@ -0,0 +1,12 @@
+ALTER TABLE user_map_bookmarks
+    ADD COLUMN folder_id INT4 REFERENCES bookmark_folders (folder_id) ON DELETE SET NULL;
+
+UPDATE user_map_bookmarks AS bookmarks
+SET folder_id = (
+    SELECT MIN(folder_id)
+    FROM bookmark_folder_maps AS folder_maps
+    WHERE folder_maps.user_id = bookmarks.user_id
+        AND folder_maps.map_id = bookmarks.map_id
+);
+
+DROP TABLE IF EXISTS bookmark_folder_maps;
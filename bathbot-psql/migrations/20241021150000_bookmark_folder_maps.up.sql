@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS bookmark_folder_maps (
+    user_id   INT8 NOT NULL,
+    map_id    INT4 NOT NULL,
+    folder_id INT4 NOT NULL REFERENCES bookmark_folders (folder_id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, map_id, folder_id),
+    FOREIGN KEY (user_id, map_id) REFERENCES user_map_bookmarks (user_id, map_id) ON DELETE CASCADE
+);
+
+CREATE INDEX bookmark_folder_maps_folder_index ON bookmark_folder_maps (folder_id);
+
+INSERT INTO bookmark_folder_maps (user_id, map_id, folder_id)
+SELECT user_id, map_id, folder_id
+FROM user_map_bookmarks
+WHERE folder_id IS NOT NULL;
+
+ALTER TABLE user_map_bookmarks
+    DROP COLUMN folder_id;
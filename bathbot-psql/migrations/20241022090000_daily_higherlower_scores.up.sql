@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS daily_higherlower_scores (
+    day      DATE NOT NULL,
+    -- position within the day's chain of scores
+    idx      INT2 NOT NULL,
+    user_id  INT4 NOT NULL,
+    score_id INT8 NOT NULL,
+    PRIMARY KEY (day, idx)
+);
@@ -3,18 +3,25 @@ use futures::StreamExt;
 use twilight_model::id::{marker::UserMarker, Id};
 
 use crate::{
-    model::osu::MapBookmark,
+    model::osu::{BookmarkFolder, MapBookmark},
     util::{parse_genre, parse_language, parse_mode, parse_status},
     Database,
 };
 
 impl Database {
-    pub async fn select_user_bookmarks(&self, user_id: Id<UserMarker>) -> Result<Vec<MapBookmark>> {
+    /// If a folder is specified, only bookmarks of that folder are returned.
+    pub async fn select_user_bookmarks(
+        &self,
+        user_id: Id<UserMarker>,
+        folder_id: Option<u32>,
+    ) -> Result<Vec<MapBookmark>> {
         let query = sqlx::query!(
             r#"
 SELECT 
   bookmarks.insert_date, 
+  bookmarks.note, 
   maps.map_id, 
+  maps.checksum, 
   maps.mapset_id, 
   maps.user_id AS mapper_id, 
   maps.map_version, 
@@ -42,17 +49,32 @@ FROM
   (
     SELECT 
       map_id, 
-      insert_date 
+      insert_date, 
+      note 
     FROM 
       user_map_bookmarks 
     WHERE 
-      user_id = $1
+      user_id = $1 
+      AND (
+        $2 :: INT4 IS NULL 
+        OR EXISTS (
+          SELECT 
+            1 
+          FROM 
+            bookmark_folder_maps AS folder_maps 
+          WHERE 
+            folder_maps.user_id = user_map_bookmarks.user_id 
+            AND folder_maps.map_id = user_map_bookmarks.map_id 
+            AND folder_maps.folder_id = $2
+        )
+      )
   ) AS bookmarks 
   JOIN (
     SELECT 
       map_id, 
       mapset_id, 
       user_id, 
+      checksum, 
       map_version, 
       seconds_drain, 
       seconds_total, 
@@ -85,7 +107,8 @@ FROM
   ) AS mapsets ON maps.mapset_id = mapsets.mapset_id 
 ORDER BY 
   bookmarks.insert_date DESC"#,
-            user_id.get() as i64
+            user_id.get() as i64,
+            folder_id.map(|id| id as i32),
         );
 
         let mut rows = query.fetch(self);
@@ -96,7 +119,9 @@ ORDER BY
 
             let bookmark = MapBookmark {
                 insert_date: row.insert_date,
+                note: row.note.map(String::into_boxed_str),
                 map_id: row.map_id as u32,
+                checksum: row.checksum.into_boxed_str(),
                 mapset_id: row.mapset_id as u32,
                 mapper_id: row.mapper_id as u32,
                 creator_id: row.creator_id as u32,
@@ -165,4 +190,238 @@ WHERE
 
         Ok(())
     }
+
+    /// Bookmarks the maps and adds them to the folder. Maps can be in multiple
+    /// folders so already bookmarked maps stay in their other folders.
+    ///
+    /// Returns the amount of maps that were newly added to the folder.
+    pub async fn insert_user_bookmarks(
+        &self,
+        user_id: Id<UserMarker>,
+        map_ids: &[i32],
+        folder_id: u32,
+    ) -> Result<u64> {
+        let mut tx = self.begin().await.wrap_err("Failed to begin transaction")?;
+
+        let query = sqlx::query!(
+            r#"
+INSERT INTO user_map_bookmarks (user_id, map_id) 
+SELECT 
+  $1, 
+  map_id 
+FROM 
+  UNNEST($2 :: INT4[]) AS map_id ON CONFLICT (user_id, map_id) DO NOTHING"#,
+            user_id.get() as i64,
+            map_ids,
+        );
+
+        query
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to execute query")?;
+
+        let query = sqlx::query!(
+            r#"
+INSERT INTO bookmark_folder_maps (user_id, map_id, folder_id) 
+SELECT 
+  $1, 
+  map_id, 
+  $3 
+FROM 
+  UNNEST($2 :: INT4[]) AS map_id ON CONFLICT (user_id, map_id, folder_id) DO NOTHING"#,
+            user_id.get() as i64,
+            map_ids,
+            folder_id as i32,
+        );
+
+        let res = query
+            .execute(&mut *tx)
+            .await
+            .wrap_err("Failed to execute query")?;
+
+        tx.commit().await.wrap_err("Failed to commit transaction")?;
+
+        Ok(res.rows_affected())
+    }
+
+    pub async fn update_user_bookmark_note(
+        &self,
+        user_id: Id<UserMarker>,
+        map_id: u32,
+        note: Option<&str>,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE 
+  user_map_bookmarks 
+SET 
+  note = $3 
+WHERE 
+  user_id = $1 
+  AND map_id = $2"#,
+            user_id.get() as i64,
+            map_id as i32,
+            note,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("Failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn select_bookmark_folders(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<BookmarkFolder>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  folders.folder_id, 
+  folders.name, 
+  folders.shared, 
+  COUNT(folder_maps.map_id) AS "map_count!" 
+FROM 
+  bookmark_folders AS folders 
+  LEFT JOIN bookmark_folder_maps AS folder_maps ON folders.folder_id = folder_maps.folder_id 
+WHERE 
+  folders.user_id = $1 
+GROUP BY 
+  folders.folder_id 
+ORDER BY 
+  folders.name"#,
+            user_id.get() as i64,
+        );
+
+        let rows = query
+            .fetch_all(self)
+            .await
+            .wrap_err("Failed to fetch all")?;
+
+        let folders = rows
+            .into_iter()
+            .map(|row| BookmarkFolder {
+                folder_id: row.folder_id as u32,
+                user_id,
+                name: row.name.into_boxed_str(),
+                shared: row.shared,
+                map_count: row.map_count as usize,
+            })
+            .collect();
+
+        Ok(folders)
+    }
+
+    pub async fn select_bookmark_folder(
+        &self,
+        user_id: Id<UserMarker>,
+        name: &str,
+    ) -> Result<Option<BookmarkFolder>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  folders.folder_id, 
+  folders.name, 
+  folders.shared, 
+  COUNT(folder_maps.map_id) AS "map_count!" 
+FROM 
+  bookmark_folders AS folders 
+  LEFT JOIN bookmark_folder_maps AS folder_maps ON folders.folder_id = folder_maps.folder_id 
+WHERE 
+  folders.user_id = $1 
+  AND folders.name = $2 
+GROUP BY 
+  folders.folder_id"#,
+            user_id.get() as i64,
+            name,
+        );
+
+        let row_opt = query
+            .fetch_optional(self)
+            .await
+            .wrap_err("Failed to fetch optional")?;
+
+        let folder_opt = row_opt.map(|row| BookmarkFolder {
+            folder_id: row.folder_id as u32,
+            user_id,
+            name: row.name.into_boxed_str(),
+            shared: row.shared,
+            map_count: row.map_count as usize,
+        });
+
+        Ok(folder_opt)
+    }
+
+    /// Returns whether the folder was created i.e. it did not exist yet
+    pub async fn insert_bookmark_folder(
+        &self,
+        user_id: Id<UserMarker>,
+        name: &str,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO bookmark_folders (user_id, name) 
+VALUES 
+  ($1, $2) ON CONFLICT (user_id, name) DO NOTHING"#,
+            user_id.get() as i64,
+            name,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("Failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Bookmarks of the folder are kept, only their membership is removed.
+    ///
+    /// Returns whether the folder was deleted.
+    pub async fn delete_bookmark_folder(
+        &self,
+        user_id: Id<UserMarker>,
+        name: &str,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  bookmark_folders 
+WHERE 
+  user_id = $1 
+  AND name = $2"#,
+            user_id.get() as i64,
+            name,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("Failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    pub async fn update_bookmark_folder_shared(&self, folder_id: u32, shared: bool) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE 
+  bookmark_folders 
+SET 
+  shared = $2 
+WHERE 
+  folder_id = $1"#,
+            folder_id as i32,
+            shared,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("Failed to execute query")?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,211 @@
+use eyre::{Result, WrapErr};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    model::configs::{DbGuildSkin, GuildSkin, NewGuildSkin},
+    Database,
+};
+
+impl Database {
+    pub async fn select_guild_skins(&self, guild: Id<GuildMarker>) -> Result<Vec<GuildSkin>> {
+        let query = sqlx::query_as!(
+            DbGuildSkin,
+            r#"
+SELECT 
+  skins.skin_id, 
+  skins.name, 
+  skins.author, 
+  skins.url, 
+  skins.ordr_skin_id, 
+  skins.preview_url, 
+  skins.added_by, 
+  skins.added_at, 
+  (defaults.skin_id IS NOT NULL) AS "is_default!" 
+FROM 
+  guild_skins AS skins 
+  LEFT JOIN guild_default_skins AS defaults ON skins.skin_id = defaults.skin_id 
+WHERE 
+  skins.guild_id = $1 
+ORDER BY 
+  LOWER(skins.name)"#,
+            guild.get() as i64,
+        );
+
+        let rows = query
+            .fetch_all(self)
+            .await
+            .wrap_err("failed to fetch all")?;
+
+        Ok(rows.into_iter().map(GuildSkin::from).collect())
+    }
+
+    /// The name is matched case-insensitively.
+    pub async fn select_guild_skin(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+    ) -> Result<Option<GuildSkin>> {
+        let query = sqlx::query_as!(
+            DbGuildSkin,
+            r#"
+SELECT 
+  skins.skin_id, 
+  skins.name, 
+  skins.author, 
+  skins.url, 
+  skins.ordr_skin_id, 
+  skins.preview_url, 
+  skins.added_by, 
+  skins.added_at, 
+  (defaults.skin_id IS NOT NULL) AS "is_default!" 
+FROM 
+  guild_skins AS skins 
+  LEFT JOIN guild_default_skins AS defaults ON skins.skin_id = defaults.skin_id 
+WHERE 
+  skins.guild_id = $1 
+  AND LOWER(skins.name) = LOWER($2)"#,
+            guild.get() as i64,
+            name,
+        );
+
+        let row_opt = query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")?;
+
+        Ok(row_opt.map(GuildSkin::from))
+    }
+
+    pub async fn select_guild_default_skin(
+        &self,
+        guild: Id<GuildMarker>,
+    ) -> Result<Option<GuildSkin>> {
+        let query = sqlx::query_as!(
+            DbGuildSkin,
+            r#"
+SELECT 
+  skins.skin_id, 
+  skins.name, 
+  skins.author, 
+  skins.url, 
+  skins.ordr_skin_id, 
+  skins.preview_url, 
+  skins.added_by, 
+  skins.added_at, 
+  TRUE AS "is_default!" 
+FROM 
+  guild_default_skins AS defaults 
+  JOIN guild_skins AS skins ON defaults.skin_id = skins.skin_id 
+WHERE 
+  defaults.guild_id = $1"#,
+            guild.get() as i64,
+        );
+
+        let row_opt = query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")?;
+
+        Ok(row_opt.map(GuildSkin::from))
+    }
+
+    /// Returns whether the skin was added i.e. there was no skin with the
+    /// same name yet.
+    pub async fn insert_guild_skin(
+        &self,
+        guild: Id<GuildMarker>,
+        skin: &NewGuildSkin<'_>,
+        user: Id<UserMarker>,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO guild_skins (
+  guild_id, name, author, url, ordr_skin_id, 
+  preview_url, added_by
+) 
+VALUES 
+  ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (guild_id, LOWER(name)) DO NOTHING"#,
+            guild.get() as i64,
+            skin.name,
+            skin.author,
+            skin.url,
+            skin.ordr_skin_id.map(|id| id as i32),
+            skin.preview_url,
+            user.get() as i64,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Returns whether the skin was deleted
+    pub async fn delete_guild_skin(&self, skin_id: u32) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  guild_skins 
+WHERE 
+  skin_id = $1"#,
+            skin_id as i32,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// The skin must belong to the guild.
+    pub async fn upsert_guild_default_skin(
+        &self,
+        guild: Id<GuildMarker>,
+        skin_id: u32,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO guild_default_skins (guild_id, skin_id) 
+VALUES 
+  ($1, $2) ON CONFLICT (guild_id) DO 
+UPDATE 
+SET 
+  skin_id = $2"#,
+            guild.get() as i64,
+            skin_id as i32,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Returns whether the guild had a default skin
+    pub async fn delete_guild_default_skin(&self, guild: Id<GuildMarker>) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  guild_default_skins 
+WHERE 
+  guild_id = $1"#,
+            guild.get() as i64,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
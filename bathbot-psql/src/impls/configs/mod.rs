@@ -1,2 +1,3 @@
 pub mod guild;
+pub mod guild_skins;
 pub mod user;
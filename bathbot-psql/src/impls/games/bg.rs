@@ -1,11 +1,17 @@
 use std::mem;
 
-use bathbot_model::BgGameScore;
+use bathbot_model::{BgGameScore, BgGameSeasonScore, MapsetTags};
 use eyre::{Result, WrapErr};
+use futures::StreamExt;
 use rosu_v2::prelude::GameMode;
+use time::OffsetDateTime;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
 
 use crate::{
-    model::games::{DbBgGameScore, DbMapTagEntry, DbMapTagsParams},
+    model::games::{DbBgGameScore, DbBgGameSeasonScore, DbMapTag, DbMapTagEntry, DbMapTagsParams},
     Database,
 };
 
@@ -53,6 +59,158 @@ FROM
         Ok(unsafe { mem::transmute::<Vec<DbBgGameScore>, Vec<BgGameScore>>(scores) })
     }
 
+    /// Returns the current season of the guild and when it started.
+    ///
+    /// Guilds that never started a new season are in their first season.
+    pub async fn select_bggame_season(
+        &self,
+        guild: Id<GuildMarker>,
+    ) -> Result<(i32, Option<OffsetDateTime>)> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  season, 
+  started_at 
+FROM 
+  bggame_seasons 
+WHERE 
+  guild_id = $1 
+ORDER BY 
+  season DESC 
+LIMIT 
+  1"#,
+            guild.get() as i64
+        );
+
+        let row_opt = query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")?;
+
+        Ok(row_opt.map_or((1, None), |row| (row.season, Some(row.started_at))))
+    }
+
+    /// Starts a new season for the guild and returns its number.
+    pub async fn insert_bggame_season(&self, guild: Id<GuildMarker>) -> Result<i32> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO bggame_seasons (guild_id, season) 
+SELECT 
+  $1, 
+  COALESCE(MAX(season), 1) + 1 
+FROM 
+  bggame_seasons 
+WHERE 
+  guild_id = $1 RETURNING season"#,
+            guild.get() as i64
+        );
+
+        let row = query
+            .fetch_one(self)
+            .await
+            .wrap_err("failed to fetch one")?;
+
+        Ok(row.season)
+    }
+
+    /// Adds stats to the current season of the guild.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn increment_bggame_guild_scores(
+        &self,
+        guild: Id<GuildMarker>,
+        user_ids: &[i64],
+        guesses: &[i32],
+        guess_times_ms: &[i64],
+        hints: &[i32],
+        streaks: &[i32],
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO bggame_guild_scores (
+  guild_id, season, discord_id, guesses, 
+  guess_time_ms, hints, best_streak
+) 
+SELECT 
+  $1, 
+  (
+    SELECT 
+      COALESCE(MAX(season), 1) 
+    FROM 
+      bggame_seasons 
+    WHERE 
+      guild_id = $1
+  ), 
+  new.* 
+FROM 
+  UNNEST(
+    $2::INT8[], $3::INT4[], $4::INT8[], 
+    $5::INT4[], $6::INT4[]
+  ) AS new ON CONFLICT (guild_id, season, discord_id) DO 
+UPDATE 
+SET 
+  guesses = bggame_guild_scores.guesses + excluded.guesses, 
+  guess_time_ms = bggame_guild_scores.guess_time_ms + excluded.guess_time_ms, 
+  hints = bggame_guild_scores.hints + excluded.hints, 
+  best_streak = GREATEST(
+    bggame_guild_scores.best_streak, excluded.best_streak
+  )"#,
+            guild.get() as i64,
+            user_ids,
+            guesses,
+            guess_times_ms,
+            hints,
+            streaks,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn select_bggame_guild_scores(
+        &self,
+        guild: Id<GuildMarker>,
+        season: i32,
+    ) -> Result<Vec<BgGameSeasonScore>> {
+        let query = sqlx::query_as!(
+            DbBgGameSeasonScore,
+            r#"
+SELECT 
+  discord_id, 
+  guesses, 
+  guess_time_ms, 
+  hints, 
+  best_streak 
+FROM 
+  bggame_guild_scores 
+WHERE 
+  guild_id = $1 
+  AND season = $2"#,
+            guild.get() as i64,
+            season
+        );
+
+        let mut rows = query.fetch(self);
+        let mut scores = Vec::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            scores.push(BgGameSeasonScore {
+                discord_id: row.discord_id,
+                guesses: row.guesses,
+                guess_time_ms: row.guess_time_ms,
+                hints: row.hints,
+                best_streak: row.best_streak,
+            });
+        }
+
+        Ok(scores)
+    }
+
     pub async fn upsert_map_tag(
         &self,
         mapset_id: u32,
@@ -90,4 +248,221 @@ SET
             .await
             .wrap_err("failed to fetch all")
     }
+
+    pub async fn select_map_tag(&self, mapset_id: u32) -> Result<Option<DbMapTag>> {
+        let query = sqlx::query_as!(
+            DbMapTag,
+            r#"
+SELECT 
+  mapset_id, 
+  image_filename, 
+  gamemode, 
+  farm, 
+  streams, 
+  alternate, 
+  old, 
+  meme, 
+  hardname, 
+  easy, 
+  hard, 
+  tech, 
+  weeb, 
+  bluesky, 
+  english, 
+  kpop, 
+  reviewed_at 
+FROM 
+  map_tags 
+WHERE 
+  mapset_id = $1"#,
+            mapset_id as i32
+        );
+
+        query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")
+    }
+
+    pub async fn select_map_tags_by_mode(&self, mode: GameMode) -> Result<Vec<DbMapTag>> {
+        let query = sqlx::query_as!(
+            DbMapTag,
+            r#"
+SELECT 
+  mapset_id, 
+  image_filename, 
+  gamemode, 
+  farm, 
+  streams, 
+  alternate, 
+  old, 
+  meme, 
+  hardname, 
+  easy, 
+  hard, 
+  tech, 
+  weeb, 
+  bluesky, 
+  english, 
+  kpop, 
+  reviewed_at 
+FROM 
+  map_tags 
+WHERE 
+  gamemode = $1"#,
+            mode as i16
+        );
+
+        query.fetch_all(self).await.wrap_err("failed to fetch all")
+    }
+
+    /// Overwrites the tags of the given mapsets and marks them as reviewed.
+    ///
+    /// The previous tags are stored so that [`Database::revert_map_tags`] can
+    /// undo the change. Returns the amount of updated mapsets.
+    pub async fn update_map_tags(
+        &self,
+        changed_by: Id<UserMarker>,
+        entries: &[(u32, MapsetTags)],
+    ) -> Result<u64> {
+        let mut mapset_ids = Vec::with_capacity(entries.len());
+        let mut tags = Vec::with_capacity(entries.len());
+
+        for (mapset_id, mapset_tags) in entries {
+            mapset_ids.push(*mapset_id as i32);
+            tags.push(mapset_tags.bits() as i32);
+        }
+
+        let mut tx = self.begin().await.wrap_err("failed to begin transaction")?;
+
+        // Bit positions must match those of `MapsetTags`
+        let query = sqlx::query!(
+            r#"
+INSERT INTO map_tags_history (
+  changed_by, mapset_id, prev_tags, prev_reviewed_at
+) 
+SELECT 
+  $1, 
+  mapset_id, 
+  farm :: INT4 
+  | (streams :: INT4 << 1) 
+  | (alternate :: INT4 << 2) 
+  | (old :: INT4 << 3) 
+  | (meme :: INT4 << 4) 
+  | (hardname :: INT4 << 5) 
+  | (easy :: INT4 << 6) 
+  | (hard :: INT4 << 7) 
+  | (tech :: INT4 << 8) 
+  | (weeb :: INT4 << 9) 
+  | (bluesky :: INT4 << 10) 
+  | (english :: INT4 << 11) 
+  | (kpop :: INT4 << 12), 
+  reviewed_at 
+FROM 
+  map_tags 
+WHERE 
+  mapset_id = ANY($2)"#,
+            changed_by.get() as i64,
+            &mapset_ids,
+        );
+
+        query
+            .execute(&mut *tx)
+            .await
+            .wrap_err("failed to execute map_tags_history query")?;
+
+        let query = sqlx::query!(
+            r#"
+UPDATE 
+  map_tags 
+SET 
+  farm = tags & 1 > 0, 
+  streams = tags & 2 > 0, 
+  alternate = tags & 4 > 0, 
+  old = tags & 8 > 0, 
+  meme = tags & 16 > 0, 
+  hardname = tags & 32 > 0, 
+  easy = tags & 64 > 0, 
+  hard = tags & 128 > 0, 
+  tech = tags & 256 > 0, 
+  weeb = tags & 512 > 0, 
+  bluesky = tags & 1024 > 0, 
+  english = tags & 2048 > 0, 
+  kpop = tags & 4096 > 0, 
+  reviewed_at = NOW() 
+FROM 
+  UNNEST($1::INT4[], $2::INT4[]) AS new(id, tags) 
+WHERE 
+  mapset_id = new.id"#,
+            &mapset_ids,
+            &tags,
+        );
+
+        let updated = query
+            .execute(&mut *tx)
+            .await
+            .wrap_err("failed to execute map_tags query")?
+            .rows_affected();
+
+        tx.commit().await.wrap_err("failed to commit transaction")?;
+
+        Ok(updated)
+    }
+
+    /// Reverts the most recent tag change of the user.
+    ///
+    /// Returns the mapset ids whose tags were reverted.
+    pub async fn revert_map_tags(&self, changed_by: Id<UserMarker>) -> Result<Vec<u32>> {
+        let query = sqlx::query!(
+            r#"
+WITH prev AS (
+  DELETE FROM 
+    map_tags_history 
+  WHERE 
+    changed_by = $1 
+    AND changed_at = (
+      SELECT 
+        MAX(changed_at) 
+      FROM 
+        map_tags_history 
+      WHERE 
+        changed_by = $1
+    ) RETURNING mapset_id AS id, 
+    prev_tags AS tags, 
+    prev_reviewed_at
+) 
+UPDATE 
+  map_tags 
+SET 
+  farm = tags & 1 > 0, 
+  streams = tags & 2 > 0, 
+  alternate = tags & 4 > 0, 
+  old = tags & 8 > 0, 
+  meme = tags & 16 > 0, 
+  hardname = tags & 32 > 0, 
+  easy = tags & 64 > 0, 
+  hard = tags & 128 > 0, 
+  tech = tags & 256 > 0, 
+  weeb = tags & 512 > 0, 
+  bluesky = tags & 1024 > 0, 
+  english = tags & 2048 > 0, 
+  kpop = tags & 4096 > 0, 
+  reviewed_at = prev.prev_reviewed_at 
+FROM 
+  prev 
+WHERE 
+  mapset_id = prev.id RETURNING mapset_id"#,
+            changed_by.get() as i64,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut mapset_ids = Vec::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+            mapset_ids.push(row.mapset_id as u32);
+        }
+
+        Ok(mapset_ids)
+    }
 }
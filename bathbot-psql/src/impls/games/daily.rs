@@ -0,0 +1,311 @@
+use eyre::{Result, WrapErr};
+use time::Date;
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    model::games::{DbDailyChallengeAttempt, DbDailyHlAttempt, DbDailyHlScore},
+    Database,
+};
+
+impl Database {
+    pub async fn select_daily_challenge_mapset(&self, day: Date) -> Result<Option<i32>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  mapset_id 
+FROM 
+  daily_challenges 
+WHERE 
+  day = $1"#,
+            day
+        );
+
+        let row_opt = query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")?;
+
+        Ok(row_opt.map(|row| row.mapset_id))
+    }
+
+    /// Stores the mapset for the day unless another one has already been
+    /// stored. Returns the mapset id that is stored afterwards.
+    pub async fn insert_daily_challenge_mapset(&self, day: Date, mapset_id: i32) -> Result<i32> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO daily_challenges (day, mapset_id) 
+VALUES 
+  ($1, $2) ON CONFLICT (day) DO 
+UPDATE 
+SET 
+  mapset_id = daily_challenges.mapset_id RETURNING mapset_id"#,
+            day,
+            mapset_id
+        );
+
+        let row = query
+            .fetch_one(self)
+            .await
+            .wrap_err("failed to fetch one")?;
+
+        Ok(row.mapset_id)
+    }
+
+    pub async fn select_daily_challenge_attempt(
+        &self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<Option<DbDailyChallengeAttempt>> {
+        let query = sqlx::query_as!(
+            DbDailyChallengeAttempt,
+            r#"
+SELECT 
+  day, 
+  outcomes, 
+  finished 
+FROM 
+  daily_challenge_attempts 
+WHERE 
+  discord_id = $1 
+  AND day = $2"#,
+            user_id.get() as i64,
+            day
+        );
+
+        query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")
+    }
+
+    /// All finished attempts of a user, ordered by day.
+    pub async fn select_daily_challenge_attempts(
+        &self,
+        user_id: Id<UserMarker>,
+    ) -> Result<Vec<DbDailyChallengeAttempt>> {
+        let query = sqlx::query_as!(
+            DbDailyChallengeAttempt,
+            r#"
+SELECT 
+  day, 
+  outcomes, 
+  finished 
+FROM 
+  daily_challenge_attempts 
+WHERE 
+  discord_id = $1 
+  AND finished 
+ORDER BY 
+  day"#,
+            user_id.get() as i64
+        );
+
+        query.fetch_all(self).await.wrap_err("failed to fetch all")
+    }
+
+    /// Appends the outcome of a guess to the attempt, provided the stored
+    /// attempt is unfinished and still contains `prev_count` outcomes.
+    ///
+    /// Returns `None` if the attempt has been modified concurrently.
+    pub async fn append_daily_challenge_outcome(
+        &self,
+        user_id: Id<UserMarker>,
+        day: Date,
+        outcome: i16,
+        prev_count: i32,
+        finished: bool,
+    ) -> Result<Option<DbDailyChallengeAttempt>> {
+        let query = sqlx::query_as!(
+            DbDailyChallengeAttempt,
+            r#"
+INSERT INTO daily_challenge_attempts (
+  discord_id, day, outcomes, finished
+) 
+VALUES 
+  ($1, $2, ARRAY[$3 :: INT2], $5) ON CONFLICT (discord_id, day) DO 
+UPDATE 
+SET 
+  outcomes = ARRAY_APPEND(
+    daily_challenge_attempts.outcomes, 
+    $3 :: INT2
+  ), 
+  finished = $5 
+WHERE 
+  NOT daily_challenge_attempts.finished 
+  AND CARDINALITY(daily_challenge_attempts.outcomes) = $4 RETURNING day, 
+  outcomes, 
+  finished"#,
+            user_id.get() as i64,
+            day,
+            outcome,
+            prev_count,
+            finished
+        );
+
+        query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")
+    }
+
+    /// Marks the attempt as finished and returns it.
+    pub async fn finish_daily_challenge_attempt(
+        &self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<DbDailyChallengeAttempt> {
+        let query = sqlx::query_as!(
+            DbDailyChallengeAttempt,
+            r#"
+INSERT INTO daily_challenge_attempts (discord_id, day, finished) 
+VALUES 
+  ($1, $2, true) ON CONFLICT (discord_id, day) DO 
+UPDATE 
+SET 
+  finished = true RETURNING day, 
+  outcomes, 
+  finished"#,
+            user_id.get() as i64,
+            day
+        );
+
+        query.fetch_one(self).await.wrap_err("failed to fetch one")
+    }
+
+    /// Starts the higherlower attempt of the user for the day.
+    ///
+    /// Returns `false` if the user already started an attempt on that day.
+    pub async fn insert_daily_higherlower_attempt(
+        &self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO daily_higherlower_attempts (discord_id, day) 
+VALUES 
+  ($1, $2) ON CONFLICT (discord_id, day) DO NOTHING"#,
+            user_id.get() as i64,
+            day
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() == 1)
+    }
+
+    pub async fn select_daily_higherlower_attempt(
+        &self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<Option<DbDailyHlAttempt>> {
+        let query = sqlx::query_as!(
+            DbDailyHlAttempt,
+            r#"
+SELECT 
+  score, 
+  finished 
+FROM 
+  daily_higherlower_attempts 
+WHERE 
+  discord_id = $1 
+  AND day = $2"#,
+            user_id.get() as i64,
+            day
+        );
+
+        query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")
+    }
+
+    pub async fn update_daily_higherlower_attempt(
+        &self,
+        user_id: Id<UserMarker>,
+        day: Date,
+        score: i32,
+        finished: bool,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE 
+  daily_higherlower_attempts 
+SET 
+  score = $3, 
+  finished = $4 
+WHERE 
+  discord_id = $1 
+  AND day = $2 
+  AND NOT finished"#,
+            user_id.get() as i64,
+            day,
+            score,
+            finished
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn select_daily_higherlower_score(
+        &self,
+        day: Date,
+        idx: i16,
+    ) -> Result<Option<DbDailyHlScore>> {
+        let query = sqlx::query_as!(
+            DbDailyHlScore,
+            r#"
+SELECT 
+  user_id, 
+  score_id 
+FROM 
+  daily_higherlower_scores 
+WHERE 
+  day = $1 
+  AND idx = $2"#,
+            day,
+            idx
+        );
+
+        query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")
+    }
+
+    /// Stores the score at the index of the day's chain unless another one
+    /// has already been stored. Returns the score that is stored afterwards.
+    pub async fn insert_daily_higherlower_score(
+        &self,
+        day: Date,
+        idx: i16,
+        user_id: i32,
+        score_id: i64,
+    ) -> Result<DbDailyHlScore> {
+        let query = sqlx::query_as!(
+            DbDailyHlScore,
+            r#"
+INSERT INTO daily_higherlower_scores (day, idx, user_id, score_id) 
+VALUES 
+  ($1, $2, $3, $4) ON CONFLICT (day, idx) DO 
+UPDATE 
+SET 
+  user_id = daily_higherlower_scores.user_id RETURNING user_id, 
+  score_id"#,
+            day,
+            idx,
+            user_id,
+            score_id
+        );
+
+        query.fetch_one(self).await.wrap_err("failed to fetch one")
+    }
+}
@@ -1,2 +1,3 @@
 mod bg;
+mod daily;
 mod hl;
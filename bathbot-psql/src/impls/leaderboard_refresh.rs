@@ -0,0 +1,70 @@
+use eyre::{Result, WrapErr};
+use time::OffsetDateTime;
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::database::Database;
+
+impl Database {
+    pub async fn upsert_leaderboard_refresh_guild(&self, guild: Id<GuildMarker>) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO leaderboard_refresh_guilds (guild_id, last_shown) 
+VALUES 
+  ($1, NOW()) ON CONFLICT (guild_id) DO 
+UPDATE 
+SET 
+  last_shown = NOW()"#,
+            guild.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Removes guilds that were not shown since `active_since` and returns the
+    /// remaining ones, most recently shown first.
+    pub async fn select_leaderboard_refresh_guilds(
+        &self,
+        active_since: OffsetDateTime,
+    ) -> Result<Vec<Id<GuildMarker>>> {
+        let delete_query = sqlx::query!(
+            r#"
+DELETE FROM 
+  leaderboard_refresh_guilds 
+WHERE 
+  last_shown < $1"#,
+            active_since,
+        );
+
+        delete_query
+            .execute(self)
+            .await
+            .wrap_err("failed to delete inactive guilds")?;
+
+        let select_query = sqlx::query!(
+            r#"
+SELECT 
+  guild_id 
+FROM 
+  leaderboard_refresh_guilds 
+ORDER BY 
+  last_shown DESC"#
+        );
+
+        let rows = select_query
+            .fetch_all(self)
+            .await
+            .wrap_err("failed to fetch all")?;
+
+        let guilds = rows
+            .into_iter()
+            .map(|row| Id::new(row.guild_id as u64))
+            .collect();
+
+        Ok(guilds)
+    }
+}
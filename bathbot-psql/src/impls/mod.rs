@@ -1,5 +1,6 @@
 mod bookmarks;
 mod configs;
 mod games;
+mod leaderboard_refresh;
 mod osu;
 mod tracked_streams;
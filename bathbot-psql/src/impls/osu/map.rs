@@ -412,6 +412,58 @@ ORDER BY
         query.fetch_all(self).await.wrap_err("failed to fetch all")
     }
 
+    /// Returns checksum-map id pairs for all stored maps of the given
+    /// checksums.
+    pub async fn select_map_ids_by_checksums(
+        &self,
+        checksums: &[String],
+    ) -> Result<Vec<(Box<str>, u32)>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  checksum, 
+  map_id 
+FROM 
+  osu_maps 
+WHERE 
+  checksum = ANY($1)"#,
+            checksums
+        );
+
+        let rows = query
+            .fetch_all(self)
+            .await
+            .wrap_err("failed to fetch all")?;
+
+        let pairs = rows
+            .into_iter()
+            .map(|row| (row.checksum.into_boxed_str(), row.map_id as u32))
+            .collect();
+
+        Ok(pairs)
+    }
+
+    /// Returns the subset of the given map ids that are stored.
+    pub async fn select_stored_map_ids(&self, map_ids: &[i32]) -> Result<Vec<u32>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  map_id 
+FROM 
+  osu_maps 
+WHERE 
+  map_id = ANY($1)"#,
+            map_ids
+        );
+
+        let rows = query
+            .fetch_all(self)
+            .await
+            .wrap_err("failed to fetch all")?;
+
+        Ok(rows.into_iter().map(|row| row.map_id as u32).collect())
+    }
+
     pub async fn insert_beatmap_file(&self, map_id: u32, path: impl AsRef<str>) -> Result<()> {
         let query = sqlx::query!(
             r#"
@@ -0,0 +1,238 @@
+use eyre::{Result, WrapErr};
+use futures::StreamExt;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    model::osu::{Mappool, MappoolEntry, MappoolSlot},
+    Database,
+};
+
+impl Database {
+    pub async fn select_mappool(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+    ) -> Result<Option<Mappool>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  pool.pool_id, 
+  pool.name, 
+  pool.created_by, 
+  slots.slot AS "slot?", 
+  slots.map_id AS "map_id?" 
+FROM 
+  (
+    SELECT 
+      pool_id, 
+      name, 
+      created_by 
+    FROM 
+      mappools 
+    WHERE 
+      guild_id = $1 
+      AND name = $2
+  ) AS pool 
+  LEFT JOIN mappool_slots AS slots ON pool.pool_id = slots.pool_id"#,
+            guild.get() as i64,
+            name,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut pool: Option<Mappool> = None;
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let pool = pool.get_or_insert_with(|| Mappool {
+                pool_id: row.pool_id as u32,
+                name: row.name.into_boxed_str(),
+                created_by: Id::new(row.created_by as u64),
+                slots: Vec::new(),
+            });
+
+            if let (Some(slot), Some(map_id)) = (row.slot, row.map_id) {
+                pool.slots.push(MappoolSlot {
+                    slot: slot.into_boxed_str(),
+                    map_id: map_id as u32,
+                });
+            }
+        }
+
+        Ok(pool)
+    }
+
+    pub async fn select_mappool_by_id(&self, pool_id: u32) -> Result<Option<Mappool>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  pool.pool_id, 
+  pool.name, 
+  pool.created_by, 
+  slots.slot AS "slot?", 
+  slots.map_id AS "map_id?" 
+FROM 
+  (
+    SELECT 
+      pool_id, 
+      name, 
+      created_by 
+    FROM 
+      mappools 
+    WHERE 
+      pool_id = $1
+  ) AS pool 
+  LEFT JOIN mappool_slots AS slots ON pool.pool_id = slots.pool_id"#,
+            pool_id as i32,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut pool: Option<Mappool> = None;
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let pool = pool.get_or_insert_with(|| Mappool {
+                pool_id: row.pool_id as u32,
+                name: row.name.into_boxed_str(),
+                created_by: Id::new(row.created_by as u64),
+                slots: Vec::new(),
+            });
+
+            if let (Some(slot), Some(map_id)) = (row.slot, row.map_id) {
+                pool.slots.push(MappoolSlot {
+                    slot: slot.into_boxed_str(),
+                    map_id: map_id as u32,
+                });
+            }
+        }
+
+        Ok(pool)
+    }
+
+    pub async fn select_guild_mappools(&self, guild: Id<GuildMarker>) -> Result<Vec<MappoolEntry>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  pool.name, 
+  COUNT(slots.slot) AS "slot_count!" 
+FROM 
+  mappools AS pool 
+  LEFT JOIN mappool_slots AS slots ON pool.pool_id = slots.pool_id 
+WHERE 
+  pool.guild_id = $1 
+GROUP BY 
+  pool.name 
+ORDER BY 
+  pool.name"#,
+            guild.get() as i64,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut entries = Vec::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            entries.push(MappoolEntry {
+                name: row.name.into_boxed_str(),
+                slot_count: row.slot_count as usize,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Returns whether the pool was created i.e. it did not exist yet
+    pub async fn insert_mappool(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        user: Id<UserMarker>,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO mappools (guild_id, name, created_by) 
+VALUES 
+  ($1, $2, $3) ON CONFLICT (guild_id, name) DO NOTHING"#,
+            guild.get() as i64,
+            name,
+            user.get() as i64,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Returns whether the pool was deleted
+    pub async fn delete_mappool(&self, guild: Id<GuildMarker>, name: &str) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  mappools 
+WHERE 
+  guild_id = $1 
+  AND name = $2"#,
+            guild.get() as i64,
+            name,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// The pool must already exist.
+    pub async fn upsert_mappool_slot(&self, pool_id: u32, slot: &str, map_id: u32) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO mappool_slots (pool_id, slot, map_id) 
+VALUES 
+  ($1, $2, $3) ON CONFLICT (pool_id, slot) DO 
+UPDATE 
+SET 
+  map_id = $3"#,
+            pool_id as i32,
+            slot,
+            map_id as i32,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Returns whether the slot was removed from the pool
+    pub async fn delete_mappool_slot(&self, pool_id: u32, slot: &str) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  mappool_slots 
+WHERE 
+  pool_id = $1 
+  AND slot = $2"#,
+            pool_id as i32,
+            slot,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
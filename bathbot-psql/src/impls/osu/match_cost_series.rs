@@ -0,0 +1,214 @@
+use eyre::{Result, WrapErr};
+use futures::StreamExt;
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::{
+    model::osu::{MatchCostSeries, MatchCostSeriesEntry, MatchCostSeriesMatch},
+    Database,
+};
+
+impl Database {
+    pub async fn select_match_cost_series(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+    ) -> Result<Option<MatchCostSeries>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  series.created_by, 
+  matches.match_id AS "match_id?", 
+  matches.warmups AS "warmups?" 
+FROM 
+  (
+    SELECT 
+      guild_id, 
+      name, 
+      created_by 
+    FROM 
+      match_cost_series 
+    WHERE 
+      guild_id = $1 
+      AND name = $2
+  ) AS series 
+  LEFT JOIN match_cost_series_matches AS matches ON series.guild_id = matches.guild_id 
+  AND series.name = matches.name 
+ORDER BY 
+  matches.added_at"#,
+            guild.get() as i64,
+            name,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut series: Option<MatchCostSeries> = None;
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let series = series.get_or_insert_with(|| MatchCostSeries {
+                created_by: Id::new(row.created_by as u64),
+                matches: Vec::new(),
+            });
+
+            if let (Some(match_id), Some(warmups)) = (row.match_id, row.warmups) {
+                series.matches.push(MatchCostSeriesMatch {
+                    match_id: match_id as u32,
+                    warmups: warmups as u8,
+                });
+            }
+        }
+
+        Ok(series)
+    }
+
+    pub async fn select_guild_match_cost_series(
+        &self,
+        guild: Id<GuildMarker>,
+    ) -> Result<Vec<MatchCostSeriesEntry>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  series.name, 
+  COUNT(matches.match_id) AS "match_count!" 
+FROM 
+  match_cost_series AS series 
+  LEFT JOIN match_cost_series_matches AS matches ON series.guild_id = matches.guild_id 
+  AND series.name = matches.name 
+WHERE 
+  series.guild_id = $1 
+GROUP BY 
+  series.name 
+ORDER BY 
+  series.name"#,
+            guild.get() as i64,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut entries = Vec::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            entries.push(MatchCostSeriesEntry {
+                name: row.name.into_boxed_str(),
+                match_count: row.match_count as usize,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Returns whether the series was created i.e. it did not exist yet
+    pub async fn insert_match_cost_series(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        user: Id<UserMarker>,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO match_cost_series (guild_id, name, created_by) 
+VALUES 
+  ($1, $2, $3) ON CONFLICT (guild_id, name) DO NOTHING"#,
+            guild.get() as i64,
+            name,
+            user.get() as i64,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// Returns whether the series was deleted
+    pub async fn delete_match_cost_series(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  match_cost_series 
+WHERE 
+  guild_id = $1 
+  AND name = $2"#,
+            guild.get() as i64,
+            name,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+
+    /// The series must already exist.
+    pub async fn upsert_match_cost_series_match(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        series_match: MatchCostSeriesMatch,
+        user: Id<UserMarker>,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO match_cost_series_matches (
+  guild_id, name, match_id, warmups, added_by
+) 
+VALUES 
+  ($1, $2, $3, $4, $5) ON CONFLICT (guild_id, name, match_id) DO 
+UPDATE 
+SET 
+  warmups = $4"#,
+            guild.get() as i64,
+            name,
+            series_match.match_id as i32,
+            series_match.warmups as i16,
+            user.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Returns whether the match was removed from the series
+    pub async fn delete_match_cost_series_match(
+        &self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        match_id: u32,
+    ) -> Result<bool> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  match_cost_series_matches 
+WHERE 
+  guild_id = $1 
+  AND name = $2 
+  AND match_id = $3"#,
+            guild.get() as i64,
+            name,
+            match_id as i32,
+        );
+
+        let res = query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(res.rows_affected() > 0)
+    }
+}
@@ -1,8 +1,11 @@
 pub mod map;
+pub mod mappool;
 pub mod mapset;
+pub mod match_cost_series;
 pub mod name;
 pub mod rank_pp;
 pub mod render;
 pub mod score;
+pub mod tracked_matches;
 pub mod tracked_users;
 pub mod user;
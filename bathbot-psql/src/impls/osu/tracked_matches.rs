@@ -0,0 +1,177 @@
+use eyre::{Result, WrapErr};
+use futures::StreamExt;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+use crate::{
+    model::osu::{DbTrackedMatch, TrackedMatchChannel},
+    Database,
+};
+
+impl Database {
+    pub async fn select_tracked_matches(&self) -> Result<Vec<TrackedMatchChannel>> {
+        let query = sqlx::query_as!(
+            DbTrackedMatch,
+            r#"
+SELECT
+  match_id,
+  channel_id,
+  last_msg_id,
+  last_event_id,
+  last_game_id,
+  mappool_id
+FROM
+  tracked_matches"#
+        );
+
+        let mut rows = query.fetch(self);
+        let mut tracks = Vec::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+            tracks.push(row.into());
+        }
+
+        Ok(tracks)
+    }
+
+    pub async fn upsert_tracked_match(
+        &self,
+        match_id: u32,
+        channel: Id<ChannelMarker>,
+        msg: Id<MessageMarker>,
+        last_event_id: u64,
+        last_game_id: Option<u64>,
+        mappool_id: Option<u32>,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO tracked_matches (
+  match_id, channel_id, last_msg_id,
+  last_event_id, last_game_id, mappool_id
+)
+VALUES
+  ($1, $2, $3, $4, $5, $6) ON CONFLICT (match_id, channel_id) DO
+UPDATE
+SET
+  last_msg_id = $3,
+  last_event_id = $4,
+  last_game_id = $5,
+  mappool_id = $6"#,
+            match_id as i32,
+            channel.get() as i64,
+            msg.get() as i64,
+            last_event_id as i64,
+            last_game_id.map(|game_id| game_id as i64),
+            mappool_id.map(|pool_id| pool_id as i32),
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Stores the latest processed event for all channels tracking the match.
+    pub async fn update_tracked_match_progress(
+        &self,
+        match_id: u32,
+        last_event_id: u64,
+        last_game_id: Option<u64>,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE
+  tracked_matches
+SET
+  last_event_id = $2,
+  last_game_id = $3
+WHERE
+  match_id = $1"#,
+            match_id as i32,
+            last_event_id as i64,
+            last_game_id.map(|game_id| game_id as i64),
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn update_tracked_match_msg(
+        &self,
+        match_id: u32,
+        channel: Id<ChannelMarker>,
+        msg: Id<MessageMarker>,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE
+  tracked_matches
+SET
+  last_msg_id = $3
+WHERE
+  match_id = $1
+  AND channel_id = $2"#,
+            match_id as i32,
+            channel.get() as i64,
+            msg.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_match_channel(
+        &self,
+        match_id: u32,
+        channel: Id<ChannelMarker>,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM
+  tracked_matches
+WHERE
+  match_id = $1
+  AND channel_id = $2"#,
+            match_id as i32,
+            channel.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_match(&self, match_id: u32) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM
+  tracked_matches
+WHERE
+  match_id = $1"#,
+            match_id as i32,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+}
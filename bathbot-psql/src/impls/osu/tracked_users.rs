@@ -1,4 +1,4 @@
-use std::{hash::BuildHasher, num::NonZeroU64};
+use std::{collections::HashMap, hash::BuildHasher, num::NonZeroU64};
 
 use eyre::{Result, WrapErr};
 use futures::StreamExt;
@@ -9,7 +9,11 @@ use rkyv::ser::{
 use rosu_v2::prelude::GameMode;
 
 use crate::{
-    model::osu::{Channels, DbTrackedOsuUser, TrackedOsuUserKey, TrackedOsuUserValue},
+    model::osu::{
+        Channels, DbTrackedOsuUser, DbTrackedOsuUserFilter, DbTrackedOsuUserMilestones,
+        DbTrackedOsuUserSession, DbTrackedOsuUserSessionTimeout, DbTrackedOsuUserWebhook,
+        TrackKind, TrackedOsuUserKey, TrackedOsuUserSession, TrackedOsuUserValue,
+    },
     Database,
 };
 
@@ -26,8 +30,10 @@ impl Database {
 SELECT 
   user_id, 
   gamemode, 
+  kind, 
   channels, 
-  last_update 
+  last_update, 
+  last_score_id 
 FROM 
   tracked_osu_users"#
         );
@@ -41,10 +47,557 @@ FROM
             tracks.push((key, value));
         }
 
+        let query = sqlx::query_as!(
+            DbTrackedOsuUserFilter,
+            r#"
+SELECT
+  user_id,
+  gamemode,
+  kind,
+  channel_id,
+  criteria
+FROM
+  tracked_osu_user_filters"#
+        );
+
+        let mut rows = query.fetch(self);
+        let mut filters = HashMap::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let key = TrackedOsuUserKey {
+                user_id: row.user_id as u32,
+                mode: (row.gamemode as u8).into(),
+                kind: row.kind.into(),
+            };
+
+            let Some(channel) = NonZeroU64::new(row.channel_id as u64) else {
+                continue;
+            };
+
+            filters
+                .entry(key)
+                .or_insert_with(Vec::new)
+                .push((channel, row.criteria.into_boxed_str()));
+        }
+
+        let query = sqlx::query_as!(
+            DbTrackedOsuUserMilestones,
+            r#"
+SELECT
+  user_id,
+  gamemode,
+  kind,
+  channel_id,
+  milestones
+FROM
+  tracked_osu_user_milestones"#
+        );
+
+        let mut rows = query.fetch(self);
+        let mut milestones = HashMap::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let key = TrackedOsuUserKey {
+                user_id: row.user_id as u32,
+                mode: (row.gamemode as u8).into(),
+                kind: row.kind.into(),
+            };
+
+            let Some(channel) = NonZeroU64::new(row.channel_id as u64) else {
+                continue;
+            };
+
+            milestones
+                .entry(key)
+                .or_insert_with(Vec::new)
+                .push((channel, row.milestones as u8));
+        }
+
+        let query = sqlx::query_as!(
+            DbTrackedOsuUserSessionTimeout,
+            r#"
+SELECT
+  user_id,
+  gamemode,
+  kind,
+  channel_id,
+  timeout
+FROM
+  tracked_osu_user_sessions"#
+        );
+
+        let mut rows = query.fetch(self);
+        let mut sessions = HashMap::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let key = TrackedOsuUserKey {
+                user_id: row.user_id as u32,
+                mode: (row.gamemode as u8).into(),
+                kind: row.kind.into(),
+            };
+
+            let Some(channel) = NonZeroU64::new(row.channel_id as u64) else {
+                continue;
+            };
+
+            sessions
+                .entry(key)
+                .or_insert_with(Vec::new)
+                .push((channel, row.timeout as u16));
+        }
+
+        let query = sqlx::query_as!(
+            DbTrackedOsuUserWebhook,
+            r#"
+SELECT
+  user_id,
+  gamemode,
+  kind,
+  channel_id,
+  webhook_url
+FROM
+  tracked_osu_user_webhooks"#
+        );
+
+        let mut rows = query.fetch(self);
+        let mut webhooks = HashMap::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            let key = TrackedOsuUserKey {
+                user_id: row.user_id as u32,
+                mode: (row.gamemode as u8).into(),
+                kind: row.kind.into(),
+            };
+
+            let Some(channel) = NonZeroU64::new(row.channel_id as u64) else {
+                continue;
+            };
+
+            webhooks
+                .entry(key)
+                .or_insert_with(Vec::new)
+                .push((channel, row.webhook_url.into_boxed_str()));
+        }
+
+        for (key, value) in tracks.iter_mut() {
+            if let Some(user_filters) = filters.remove(key) {
+                value.filters.extend(user_filters);
+            }
+
+            if let Some(user_milestones) = milestones.remove(key) {
+                value.milestones.extend(user_milestones);
+            }
+
+            if let Some(user_sessions) = sessions.remove(key) {
+                value.sessions.extend(user_sessions);
+            }
+
+            if let Some(user_webhooks) = webhooks.remove(key) {
+                value.webhooks.extend(user_webhooks);
+            }
+        }
+
         Ok(tracks)
     }
 
-    pub async fn update_tracked_osu_user_date(&self, user_id: u32, mode: GameMode) -> Result<()> {
+    pub async fn upsert_tracked_osu_user_filter(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+        criteria: &str,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO tracked_osu_user_filters (
+  user_id, gamemode, kind, channel_id, criteria
+)
+VALUES
+  ($1, $2, $3, $4, $5) ON CONFLICT (user_id, gamemode, kind, channel_id) DO
+UPDATE
+SET
+  criteria = $5"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+            criteria,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_osu_user_filter(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM
+  tracked_osu_user_filters
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3
+  AND channel_id = $4"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_tracked_osu_user_milestones(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+        milestones: u8,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO tracked_osu_user_milestones (
+  user_id, gamemode, kind, channel_id, milestones
+)
+VALUES
+  ($1, $2, $3, $4, $5) ON CONFLICT (user_id, gamemode, kind, channel_id) DO
+UPDATE
+SET
+  milestones = $5"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+            milestones as i16,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_osu_user_milestones(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM
+  tracked_osu_user_milestones
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3
+  AND channel_id = $4"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_tracked_osu_user_webhook(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+        webhook_url: &str,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO tracked_osu_user_webhooks (
+  user_id, gamemode, kind, channel_id, webhook_url
+)
+VALUES
+  ($1, $2, $3, $4, $5) ON CONFLICT (user_id, gamemode, kind, channel_id) DO
+UPDATE
+SET
+  webhook_url = $5"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+            webhook_url,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_osu_user_webhook(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM
+  tracked_osu_user_webhooks
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3
+  AND channel_id = $4"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_tracked_osu_user_session_timeout(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+        timeout: u16,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO tracked_osu_user_sessions (
+  user_id, gamemode, kind, channel_id, timeout
+)
+VALUES
+  ($1, $2, $3, $4, $5) ON CONFLICT (user_id, gamemode, kind, channel_id) DO
+UPDATE
+SET
+  timeout = $5"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+            timeout as i16,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracked_osu_user_session(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM
+  tracked_osu_user_sessions
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3
+  AND channel_id = $4"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Returns `None` if the channel has no sessions enabled.
+    pub async fn select_tracked_osu_user_session(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+    ) -> Result<Option<TrackedOsuUserSession>> {
+        let query = sqlx::query_as!(
+            DbTrackedOsuUserSession,
+            r#"
+SELECT
+  msg_id,
+  started_at,
+  last_activity,
+  start_pp,
+  start_rank,
+  score_count,
+  plays
+FROM
+  tracked_osu_user_sessions
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3
+  AND channel_id = $4"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+        );
+
+        let row_opt = query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")?;
+
+        Ok(row_opt.map(TrackedOsuUserSession::from))
+    }
+
+    /// Closes all sessions whose last activity exceeds their timeout.
+    ///
+    /// Returns the channel and summary message of each closed session.
+    pub async fn close_stale_tracked_osu_user_sessions(&self) -> Result<Vec<(i64, i64)>> {
+        let query = sqlx::query!(
+            r#"
+WITH stale AS (
+  SELECT
+    user_id,
+    gamemode,
+    kind,
+    channel_id,
+    msg_id
+  FROM
+    tracked_osu_user_sessions
+  WHERE
+    msg_id IS NOT NULL
+    AND last_activity + make_interval(mins => timeout::INT4) < NOW()
+)
+UPDATE
+  tracked_osu_user_sessions AS sessions
+SET
+  msg_id = NULL
+FROM
+  stale
+WHERE
+  sessions.user_id = stale.user_id
+  AND sessions.gamemode = stale.gamemode
+  AND sessions.kind = stale.kind
+  AND sessions.channel_id = stale.channel_id
+RETURNING
+  stale.channel_id,
+  stale.msg_id AS "msg_id!""#
+        );
+
+        let rows = query
+            .fetch_all(self)
+            .await
+            .wrap_err("failed to fetch all")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| (row.channel_id, row.msg_id))
+            .collect())
+    }
+
+    pub async fn update_tracked_osu_user_session(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        channel_id: NonZeroU64,
+        session: &TrackedOsuUserSession,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE
+  tracked_osu_user_sessions
+SET
+  msg_id = $5,
+  started_at = $6,
+  last_activity = $7,
+  start_pp = $8,
+  start_rank = $9,
+  score_count = $10,
+  plays = $11
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3
+  AND channel_id = $4"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+            channel_id.get() as i64,
+            session.msg_id.map(|msg_id| msg_id.get() as i64),
+            session.started_at,
+            session.last_activity,
+            session.start_pp,
+            session.start_rank as i32,
+            session.score_count as i32,
+            &session.plays as &[String],
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn update_tracked_osu_user_date(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+    ) -> Result<()> {
         let query = sqlx::query!(
             r#"
 UPDATE
@@ -53,9 +606,42 @@ SET
   last_update = NOW()
 WHERE
   user_id = $1
-  AND gamemode = $2"#,
+  AND gamemode = $2
+  AND kind = $3"#,
+            user_id as i32,
+            mode as i16,
+            kind as i16,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    pub async fn update_tracked_osu_user_last_score(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        kind: TrackKind,
+        score_id: u64,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE
+  tracked_osu_users
+SET
+  last_score_id = $4
+WHERE
+  user_id = $1
+  AND gamemode = $2
+  AND kind = $3"#,
             user_id as i32,
             mode as i16,
+            kind as i16,
+            score_id as i64,
         );
 
         query
@@ -70,6 +656,7 @@ WHERE
         &self,
         user_id: u32,
         mode: GameMode,
+        kind: TrackKind,
         channels: &Channels<S>,
     ) -> Result<()> {
         let channels =
@@ -80,12 +667,14 @@ WHERE
 UPDATE 
   tracked_osu_users 
 SET 
-  channels = $3 
+  channels = $4 
 WHERE 
   user_id = $1 
-  AND gamemode = $2"#,
+  AND gamemode = $2 
+  AND kind = $3"#,
             user_id as i32,
             mode as i16,
+            kind as i16,
             &channels as &[u8],
         );
 
@@ -101,6 +690,7 @@ WHERE
         &self,
         user_id: u32,
         mode: GameMode,
+        kind: TrackKind,
     ) -> Result<()> {
         let query = sqlx::query!(
             r#"
@@ -108,9 +698,11 @@ DELETE FROM
   tracked_osu_users 
 WHERE 
   user_id = $1 
-  AND gamemode = $2"#,
+  AND gamemode = $2 
+  AND kind = $3"#,
             user_id as i32,
             mode as i16,
+            kind as i16,
         );
 
         query
@@ -125,6 +717,7 @@ WHERE
         &self,
         user_id: u32,
         mode: GameMode,
+        kind: TrackKind,
         channel_id: NonZeroU64,
         limit: u8,
     ) -> Result<()>
@@ -146,14 +739,15 @@ WHERE
 
         let query = sqlx::query!(
             r#"
-INSERT INTO tracked_osu_users (user_id, gamemode, channels) 
+INSERT INTO tracked_osu_users (user_id, gamemode, kind, channels) 
 VALUES 
-  ($1, $2, $3) ON CONFLICT (user_id, gamemode) DO 
+  ($1, $2, $3, $4) ON CONFLICT (user_id, gamemode, kind) DO 
 UPDATE 
 SET 
   last_update = NOW() RETURNING channels"#,
             user_id as i32,
             mode as i16,
+            kind as i16,
             &channels_bytes as &[u8],
         );
 
@@ -182,12 +776,14 @@ SET
 UPDATE 
   tracked_osu_users 
 SET 
-  channels = $3 
+  channels = $4 
 WHERE 
   user_id = $1 
-  AND gamemode = $2"#,
+  AND gamemode = $2 
+  AND kind = $3"#,
                 user_id as i32,
                 mode as i16,
+                kind as i16,
                 &channels_bytes as &[u8],
             );
 
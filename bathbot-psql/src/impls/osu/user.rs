@@ -7,7 +7,9 @@ use rosu_v2::prelude::{GameMode, UserExtended, Username};
 use time::OffsetDateTime;
 
 use crate::{
-    model::osu::{DbUserStatsEntry, OsuUserStatsColumnName},
+    model::osu::{
+        DbUserMilestoneStats, DbUserStatsEntry, DbUserStatsHistoryEntry, OsuUserStatsColumnName,
+    },
     Database,
 };
 
@@ -628,6 +630,122 @@ JOIN (
     }
 
     /// Be sure wildcards (_, %) are escaped as required!
+    pub async fn select_osu_user_milestone_stats(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+    ) -> Result<Option<DbUserMilestoneStats>> {
+        let query = sqlx::query_as!(
+            DbUserMilestoneStats,
+            r#"
+SELECT 
+  mode_stats.pp, 
+  mode_stats.global_rank, 
+  mode_stats.country_rank, 
+  stats.medals 
+FROM 
+  osu_user_mode_stats AS mode_stats 
+  JOIN osu_user_stats AS stats ON mode_stats.user_id = stats.user_id 
+WHERE 
+  mode_stats.user_id = $1 
+  AND mode_stats.gamemode = $2"#,
+            user_id as i32,
+            mode as i16,
+        );
+
+        query
+            .fetch_optional(self)
+            .await
+            .wrap_err("failed to fetch optional")
+    }
+
+    /// Linked users of the given discord ids whose mode stats were last
+    /// updated before `stale_before`, least recently updated first.
+    pub async fn select_stale_osu_user_modes(
+        &self,
+        discord_ids: &[i64],
+        stale_before: OffsetDateTime,
+        limit: usize,
+    ) -> Result<Vec<(u32, GameMode)>> {
+        // Users with milestone tracking are skipped because milestones are
+        // detected by comparing against their stored stats.
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  mode_stats.user_id, 
+  mode_stats.gamemode, 
+  MIN(mode_stats.last_update) AS last_update 
+FROM 
+  osu_user_mode_stats AS mode_stats 
+  JOIN user_configs ON mode_stats.user_id = user_configs.osu_id 
+WHERE 
+  user_configs.discord_id = ANY($1) 
+  AND mode_stats.last_update < $2 
+  AND NOT EXISTS (
+    SELECT 
+    FROM 
+      tracked_osu_user_milestones AS milestones 
+    WHERE 
+      milestones.user_id = mode_stats.user_id 
+      AND milestones.gamemode = mode_stats.gamemode
+  ) 
+GROUP BY 
+  mode_stats.user_id, 
+  mode_stats.gamemode 
+ORDER BY 
+  last_update 
+LIMIT 
+  $3"#,
+            discord_ids,
+            stale_before,
+            limit as i64,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut users = Vec::with_capacity(limit);
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+            users.push((row.user_id as u32, GameMode::from(row.gamemode as u8)));
+        }
+
+        Ok(users)
+    }
+
+    pub async fn select_osu_user_stats_history(
+        &self,
+        user_id: u32,
+        mode: GameMode,
+        from: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> Result<Vec<DbUserStatsHistoryEntry>> {
+        let query = sqlx::query_as!(
+            DbUserStatsHistoryEntry,
+            r#"
+SELECT 
+  pp, 
+  global_rank, 
+  country_rank, 
+  accuracy, 
+  playcount, 
+  recorded_at 
+FROM 
+  osu_user_stats_history 
+WHERE 
+  user_id = $1 
+  AND gamemode = $2 
+  AND recorded_at BETWEEN $3 AND $4 
+ORDER BY 
+  recorded_at"#,
+            user_id as i32,
+            mode as i16,
+            from,
+            until,
+        );
+
+        query.fetch_all(self).await.wrap_err("failed to fetch all")
+    }
+
     pub async fn select_osu_user_ids(&self, names: &[String]) -> Result<HashMap<Username, u32>> {
         let query = sqlx::query!(
             r#"
@@ -824,6 +942,57 @@ SET
                 .execute(&mut *tx)
                 .await
                 .wrap_err("failed to execute osu_user_mode_stats query")?;
+
+            // Only append a snapshot if it differs from the previous one
+            let query = sqlx::query!(
+                r#"
+INSERT INTO osu_user_stats_history (
+  user_id, gamemode, pp, global_rank, 
+  country_rank, accuracy, playcount
+) 
+SELECT 
+  $1, $2, $3, $4, $5, $6, $7 
+WHERE 
+  NOT EXISTS (
+    SELECT 
+    FROM 
+      (
+        SELECT 
+          pp, 
+          global_rank, 
+          country_rank, 
+          accuracy, 
+          playcount 
+        FROM 
+          osu_user_stats_history 
+        WHERE 
+          user_id = $1 
+          AND gamemode = $2 
+        ORDER BY 
+          recorded_at DESC 
+        LIMIT 
+          1
+      ) AS latest 
+    WHERE 
+      latest.pp = $3 
+      AND latest.global_rank = $4 
+      AND latest.country_rank = $5 
+      AND latest.accuracy = $6 
+      AND latest.playcount = $7
+  )"#,
+                user.user_id as i32,
+                mode as i16,
+                stats.pp,
+                stats.global_rank.unwrap_or(0) as i32,
+                stats.country_rank.unwrap_or(0) as i32,
+                stats.accuracy,
+                stats.playcount as i32,
+            );
+
+            query
+                .execute(&mut *tx)
+                .await
+                .wrap_err("failed to execute osu_user_stats_history query")?;
         }
 
         tx.commit().await.wrap_err("failed to commit transaction")?;
@@ -831,6 +1000,65 @@ SET
         Ok(())
     }
 
+    /// Thins out old history snapshots so that only the latest snapshot per
+    /// day remains for entries older than `daily_before` and only the latest
+    /// per week for entries older than `weekly_before`.
+    ///
+    /// Returns the amount of deleted snapshots.
+    /// Only rows within buckets that reached `daily_before` after
+    /// `daily_after`, or `weekly_before` after `weekly_after`, are considered.
+    pub async fn delete_osu_user_stats_history_downsampled(
+        &self,
+        daily_before: OffsetDateTime,
+        weekly_before: OffsetDateTime,
+        daily_after: OffsetDateTime,
+        weekly_after: OffsetDateTime,
+    ) -> Result<u64> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  osu_user_stats_history AS history USING (
+    SELECT 
+      user_id, 
+      gamemode, 
+      recorded_at, 
+      ROW_NUMBER() OVER (
+        PARTITION BY user_id, 
+        gamemode, 
+        CASE WHEN recorded_at < $2 THEN DATE_TRUNC('week', recorded_at) ELSE DATE_TRUNC('day', recorded_at) END 
+        ORDER BY 
+          recorded_at DESC
+      ) AS idx 
+    FROM 
+      osu_user_stats_history 
+    WHERE 
+      (
+        recorded_at < $1 
+        AND recorded_at >= DATE_TRUNC('day', $3 :: TIMESTAMPTZ)
+      ) 
+      OR (
+        recorded_at < $2 
+        AND recorded_at >= DATE_TRUNC('week', $4 :: TIMESTAMPTZ)
+      )
+  ) AS buckets 
+WHERE 
+  history.user_id = buckets.user_id 
+  AND history.gamemode = buckets.gamemode 
+  AND history.recorded_at = buckets.recorded_at 
+  AND buckets.idx > 1"#,
+            daily_before,
+            weekly_before,
+            daily_after,
+            weekly_after,
+        );
+
+        query
+            .execute(self)
+            .await
+            .map(|res| res.rows_affected())
+            .wrap_err("failed to execute query")
+    }
+
     pub async fn delete_osu_user_stats_and_scores(&self, user_id: u32) -> Result<()> {
         let mut conn = self
             .acquire()
@@ -865,6 +1093,20 @@ WHERE
             .await
             .wrap_err("Failed to execute osu_user_mode_stats query")?;
 
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  osu_user_stats_history 
+WHERE 
+  user_id = $1"#,
+            user_id as i32
+        );
+
+        query
+            .execute(&mut *conn)
+            .await
+            .wrap_err("Failed to execute osu_user_stats_history query")?;
+
         Self::delete_osu_username(&mut *conn, user_id).await?;
         Self::delete_scores_by_user_id(&mut *conn, user_id).await?;
 
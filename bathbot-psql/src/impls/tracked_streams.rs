@@ -5,9 +5,16 @@ use std::{
 
 use eyre::{Result, WrapErr};
 use futures::StreamExt;
-use twilight_model::id::{marker::ChannelMarker, Id};
+use time::OffsetDateTime;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
 
-use crate::database::Database;
+use crate::{
+    database::Database,
+    model::twitch::{DbTrackedTwitchStreamTarget, DbTwitchStreamMessage},
+};
 
 impl Database {
     pub async fn select_tracked_twitch_streams<S>(
@@ -45,19 +52,33 @@ FROM
         Ok(tracks)
     }
 
-    /// Returns whether a new entry was inserted
+    /// Returns whether a new entry was inserted or the options of an existing
+    /// entry changed
     pub async fn insert_tracked_twitch_stream(
         &self,
         channel: Id<ChannelMarker>,
         user: u64,
+        webhook_url: Option<&str>,
+        followup: bool,
     ) -> Result<bool> {
         let query = sqlx::query!(
             r#"
-INSERT INTO tracked_twitch_streams (channel_id, user_id) 
+INSERT INTO tracked_twitch_streams (
+  channel_id, user_id, webhook_url, followup
+) 
 VALUES 
-  ($1, $2) ON CONFLICT (channel_id, user_id) DO NOTHING"#,
+  ($1, $2, $3, $4) ON CONFLICT (channel_id, user_id) DO 
+UPDATE 
+SET 
+  webhook_url = $3, 
+  followup = $4 
+WHERE 
+  tracked_twitch_streams.webhook_url IS DISTINCT FROM $3 
+  OR tracked_twitch_streams.followup <> $4"#,
             channel.get() as i64,
             user as i64,
+            webhook_url,
+            followup,
         );
 
         let res = query
@@ -68,6 +89,204 @@ VALUES
         Ok(res.rows_affected() > 0)
     }
 
+    /// Returns the channels tracking the stream that have a webhook or want
+    /// their notification to be followed up
+    pub async fn select_tracked_twitch_stream_targets(
+        &self,
+        user: u64,
+    ) -> Result<Vec<DbTrackedTwitchStreamTarget>> {
+        let query = sqlx::query!(
+            r#"
+SELECT 
+  channel_id, 
+  webhook_url, 
+  followup 
+FROM 
+  tracked_twitch_streams 
+WHERE 
+  user_id = $1 
+  AND (
+    webhook_url IS NOT NULL 
+    OR followup
+  )"#,
+            user as i64,
+        );
+
+        let mut rows = query.fetch(self);
+        let mut targets = Vec::new();
+
+        while let Some(row_res) = rows.next().await {
+            let row = row_res.wrap_err("failed to fetch next")?;
+
+            targets.push(DbTrackedTwitchStreamTarget {
+                channel_id: Id::new(row.channel_id as u64),
+                webhook_url: row.webhook_url.map(String::into_boxed_str),
+                followup: row.followup,
+            });
+        }
+
+        Ok(targets)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn upsert_twitch_stream_message(
+        &self,
+        channel: Id<ChannelMarker>,
+        user: u64,
+        msg: Id<MessageMarker>,
+        stream_id: u64,
+        started_at: OffsetDateTime,
+        viewers: u32,
+        title: &str,
+        game_name: &str,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+INSERT INTO tracked_twitch_stream_messages (
+  channel_id, user_id, msg_id, stream_id, 
+  started_at, peak_viewers, title, game_name
+) 
+VALUES 
+  ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT (channel_id, user_id) DO 
+UPDATE 
+SET 
+  msg_id = $3, 
+  stream_id = $4, 
+  started_at = $5, 
+  peak_viewers = $6, 
+  title = $7, 
+  game_name = $8, 
+  ended_at = NULL"#,
+            channel.get() as i64,
+            user as i64,
+            msg.get() as i64,
+            stream_id as i64,
+            started_at,
+            viewers as i32,
+            title,
+            game_name,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Updates title and game of the user's ongoing stream if the viewer count
+    /// is higher than the previous peak
+    pub async fn update_twitch_stream_message_peak(
+        &self,
+        user: u64,
+        viewers: u32,
+        title: &str,
+        game_name: &str,
+    ) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+UPDATE 
+  tracked_twitch_stream_messages 
+SET 
+  peak_viewers = $2, 
+  title = $3, 
+  game_name = $4 
+WHERE 
+  user_id = $1 
+  AND ended_at IS NULL 
+  AND peak_viewers < $2"#,
+            user as i64,
+            viewers as i32,
+            title,
+            game_name,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
+    /// Marks the user's ongoing stream as ended and returns the affected
+    /// messages
+    pub async fn end_twitch_stream_messages(
+        &self,
+        user: u64,
+        ended_at: OffsetDateTime,
+    ) -> Result<Vec<DbTwitchStreamMessage>> {
+        let query = sqlx::query_as!(
+            DbTwitchStreamMessage,
+            r#"
+UPDATE 
+  tracked_twitch_stream_messages 
+SET 
+  ended_at = $2 
+WHERE 
+  user_id = $1 
+  AND ended_at IS NULL RETURNING channel_id, 
+  user_id, 
+  msg_id, 
+  stream_id, 
+  started_at, 
+  peak_viewers, 
+  title, 
+  game_name, 
+  ended_at"#,
+            user as i64,
+            ended_at,
+        );
+
+        query.fetch_all(self).await.wrap_err("failed to fetch all")
+    }
+
+    /// Returns the messages of all streams that ended but whose VOD was not
+    /// linked yet
+    pub async fn select_ended_twitch_stream_messages(&self) -> Result<Vec<DbTwitchStreamMessage>> {
+        let query = sqlx::query_as!(
+            DbTwitchStreamMessage,
+            r#"
+SELECT 
+  channel_id, 
+  user_id, 
+  msg_id, 
+  stream_id, 
+  started_at, 
+  peak_viewers, 
+  title, 
+  game_name, 
+  ended_at 
+FROM 
+  tracked_twitch_stream_messages 
+WHERE 
+  ended_at IS NOT NULL"#
+        );
+
+        query.fetch_all(self).await.wrap_err("failed to fetch all")
+    }
+
+    /// Deletes the messages of the user's streams that ended
+    pub async fn delete_ended_twitch_stream_messages(&self, user: u64) -> Result<()> {
+        let query = sqlx::query!(
+            r#"
+DELETE FROM 
+  tracked_twitch_stream_messages 
+WHERE 
+  user_id = $1 
+  AND ended_at IS NOT NULL"#,
+            user as i64,
+        );
+
+        query
+            .execute(self)
+            .await
+            .wrap_err("failed to execute query")?;
+
+        Ok(())
+    }
+
     pub async fn delete_tracked_twitch_streams(&self, channel: Id<ChannelMarker>) -> Result<()> {
         let query = sqlx::query!(
             r#"
@@ -0,0 +1,54 @@
+use time::OffsetDateTime;
+use twilight_model::id::{marker::UserMarker, Id};
+
+pub struct DbGuildSkin {
+    pub skin_id: i32,
+    pub name: String,
+    pub author: Option<String>,
+    pub url: String,
+    pub ordr_skin_id: Option<i32>,
+    pub preview_url: Option<String>,
+    pub added_by: i64,
+    pub added_at: OffsetDateTime,
+    pub is_default: bool,
+}
+
+impl From<DbGuildSkin> for GuildSkin {
+    fn from(skin: DbGuildSkin) -> Self {
+        Self {
+            skin_id: skin.skin_id as u32,
+            name: skin.name.into_boxed_str(),
+            author: skin.author.map(String::into_boxed_str),
+            url: skin.url.into_boxed_str(),
+            ordr_skin_id: skin.ordr_skin_id.map(|id| id as u32),
+            preview_url: skin.preview_url.map(String::into_boxed_str),
+            added_by: Id::new(skin.added_by as u64),
+            added_at: skin.added_at,
+            is_default: skin.is_default,
+        }
+    }
+}
+
+pub struct GuildSkin {
+    pub skin_id: u32,
+    pub name: Box<str>,
+    pub author: Option<Box<str>>,
+    /// Download link of the skin
+    pub url: Box<str>,
+    /// Id of the skin if it's uploaded to o!rdr as custom skin
+    pub ordr_skin_id: Option<u32>,
+    pub preview_url: Option<Box<str>>,
+    pub added_by: Id<UserMarker>,
+    pub added_at: OffsetDateTime,
+    /// Whether the skin is the guild's default skin for renders
+    pub is_default: bool,
+}
+
+/// Metadata for a new guild skin
+pub struct NewGuildSkin<'a> {
+    pub name: &'a str,
+    pub author: Option<&'a str>,
+    pub url: &'a str,
+    pub ordr_skin_id: Option<u32>,
+    pub preview_url: Option<&'a str>,
+}
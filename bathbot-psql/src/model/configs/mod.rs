@@ -1,6 +1,7 @@
 pub use self::{
     authorities::{Authorities, Authority},
     guild::{DbGuildConfig, GuildConfig},
+    guild_skin::{DbGuildSkin, GuildSkin, NewGuildSkin},
     hide_solutions::HideSolutions,
     list_size::ListSize,
     prefixes::{Prefix, Prefixes, DEFAULT_PREFIX},
@@ -12,6 +13,7 @@ pub use self::{
 
 mod authorities;
 mod guild;
+mod guild_skin;
 mod hide_solutions;
 mod list_size;
 mod prefixes;
@@ -1,6 +1,19 @@
+use rkyv::{Archive, Deserialize, Serialize};
 use twilight_interactions::command::{CommandOption, CreateOption};
 
-#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, CommandOption, CreateOption)]
+#[derive(
+    Archive,
+    Copy,
+    Clone,
+    Debug,
+    Default,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Serialize,
+    CommandOption,
+    CreateOption,
+)]
 #[repr(u8)]
 pub enum ScoreData {
     #[default]
@@ -3,6 +3,7 @@ use std::fmt::Write;
 use bathbot_model::MapsetTags;
 use rosu_v2::prelude::GameMode;
 use sqlx::FromRow;
+use time::OffsetDateTime;
 
 #[derive(FromRow)]
 pub struct DbBgGameScore {
@@ -10,6 +11,15 @@ pub struct DbBgGameScore {
     pub score: i32,
 }
 
+#[derive(FromRow)]
+pub struct DbBgGameSeasonScore {
+    pub discord_id: i64,
+    pub guesses: i32,
+    pub guess_time_ms: i64,
+    pub hints: i32,
+    pub best_streak: i32,
+}
+
 pub struct MapsetTagsEntries {
     pub mode: GameMode,
     pub tags: Vec<DbMapTagEntry>,
@@ -21,6 +31,50 @@ pub struct DbMapTagEntry {
     pub image_filename: String,
 }
 
+/// A mapset of the background game including all its tags
+pub struct DbMapTag {
+    pub mapset_id: i32,
+    pub image_filename: String,
+    pub gamemode: i16,
+    pub farm: bool,
+    pub streams: bool,
+    pub alternate: bool,
+    pub old: bool,
+    pub meme: bool,
+    pub hardname: bool,
+    pub easy: bool,
+    pub hard: bool,
+    pub tech: bool,
+    pub weeb: bool,
+    pub bluesky: bool,
+    pub english: bool,
+    pub kpop: bool,
+    /// `None` if the mapset has not been tagged yet
+    pub reviewed_at: Option<OffsetDateTime>,
+}
+
+impl DbMapTag {
+    pub fn tags(&self) -> MapsetTags {
+        let mut tags = MapsetTags::empty();
+
+        tags.set(MapsetTags::Farm, self.farm);
+        tags.set(MapsetTags::Streams, self.streams);
+        tags.set(MapsetTags::Alternate, self.alternate);
+        tags.set(MapsetTags::Old, self.old);
+        tags.set(MapsetTags::Meme, self.meme);
+        tags.set(MapsetTags::HardName, self.hardname);
+        tags.set(MapsetTags::Easy, self.easy);
+        tags.set(MapsetTags::Hard, self.hard);
+        tags.set(MapsetTags::Tech, self.tech);
+        tags.set(MapsetTags::Weeb, self.weeb);
+        tags.set(MapsetTags::BlueSky, self.bluesky);
+        tags.set(MapsetTags::English, self.english);
+        tags.set(MapsetTags::Kpop, self.kpop);
+
+        tags
+    }
+}
+
 macro_rules! define_map_tags {
     ( $( $column:ident ,)* ) => {
         pub struct DbMapTagsParams {
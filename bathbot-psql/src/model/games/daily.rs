@@ -0,0 +1,21 @@
+use sqlx::FromRow;
+use time::Date;
+
+#[derive(FromRow)]
+pub struct DbDailyChallengeAttempt {
+    pub day: Date,
+    pub outcomes: Vec<i16>,
+    pub finished: bool,
+}
+
+#[derive(FromRow)]
+pub struct DbDailyHlAttempt {
+    pub score: i32,
+    pub finished: bool,
+}
+
+#[derive(FromRow)]
+pub struct DbDailyHlScore {
+    pub user_id: i32,
+    pub score_id: i64,
+}
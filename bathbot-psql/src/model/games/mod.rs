@@ -1,4 +1,5 @@
-pub use self::{bg::*, hl::*};
+pub use self::{bg::*, daily::*, hl::*};
 
 mod bg;
+mod daily;
 mod hl;
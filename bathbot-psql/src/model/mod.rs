@@ -2,3 +2,4 @@ pub mod configs;
 pub mod games;
 pub mod osu;
 pub mod render;
+pub mod twitch;
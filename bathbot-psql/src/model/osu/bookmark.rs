@@ -1,9 +1,12 @@
 use rosu_v2::prelude::{GameMode, Genre, Language, RankStatus};
 use time::OffsetDateTime;
+use twilight_model::id::{marker::UserMarker, Id};
 
 pub struct MapBookmark {
     pub insert_date: OffsetDateTime,
+    pub note: Option<Box<str>>,
     pub map_id: u32,
+    pub checksum: Box<str>,
     pub mapset_id: u32,
     pub mapper_id: u32,
     pub creator_id: u32,
@@ -28,3 +31,13 @@ pub struct MapBookmark {
     pub language: Language,
     pub cover_url: Box<str>,
 }
+
+pub struct BookmarkFolder {
+    pub folder_id: u32,
+    pub user_id: Id<UserMarker>,
+    /// Lowercase name of the folder
+    pub name: Box<str>,
+    /// Whether other users can view the folder
+    pub shared: bool,
+    pub map_count: usize,
+}
@@ -0,0 +1,29 @@
+use twilight_model::id::{marker::UserMarker, Id};
+
+pub struct Mappool {
+    pub pool_id: u32,
+    pub name: Box<str>,
+    pub created_by: Id<UserMarker>,
+    pub slots: Vec<MappoolSlot>,
+}
+
+impl Mappool {
+    /// Returns the slot of the given map if it's part of the pool
+    pub fn slot(&self, map_id: u32) -> Option<&str> {
+        self.slots
+            .iter()
+            .find(|slot| slot.map_id == map_id)
+            .map(|slot| slot.slot.as_ref())
+    }
+}
+
+pub struct MappoolSlot {
+    /// Uppercase slot label e.g. `NM1` or `TB`
+    pub slot: Box<str>,
+    pub map_id: u32,
+}
+
+pub struct MappoolEntry {
+    pub name: Box<str>,
+    pub slot_count: usize,
+}
@@ -0,0 +1,19 @@
+use twilight_model::id::{marker::UserMarker, Id};
+
+pub struct MatchCostSeries {
+    pub created_by: Id<UserMarker>,
+    /// Matches in the order they were added
+    pub matches: Vec<MatchCostSeriesMatch>,
+}
+
+#[derive(Copy, Clone)]
+pub struct MatchCostSeriesMatch {
+    pub match_id: u32,
+    /// Amount of games to skip at the start of the match
+    pub warmups: u8,
+}
+
+pub struct MatchCostSeriesEntry {
+    pub name: Box<str>,
+    pub match_count: usize,
+}
@@ -1,8 +1,14 @@
-pub use self::{bookmark::*, map::*, mapset::*, score::*, tracked_user::*, user::*};
+pub use self::{
+    bookmark::*, map::*, mappool::*, mapset::*, match_cost_series::*, score::*, tracked_match::*,
+    tracked_user::*, user::*,
+};
 
 mod bookmark;
 mod map;
+mod mappool;
 mod mapset;
+mod match_cost_series;
 mod score;
+mod tracked_match;
 mod tracked_user;
 mod user;
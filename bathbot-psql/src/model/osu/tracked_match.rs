@@ -0,0 +1,50 @@
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
+
+pub struct DbTrackedMatch {
+    pub match_id: i32,
+    pub channel_id: i64,
+    pub last_msg_id: i64,
+    pub last_event_id: i64,
+    pub last_game_id: Option<i64>,
+    pub mappool_id: Option<i32>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct TrackedMatchChannel {
+    pub match_id: u32,
+    pub channel_id: Id<ChannelMarker>,
+    /// Last message in the channel
+    pub msg_id: Id<MessageMarker>,
+    /// Latest event of the match that was processed
+    pub last_event_id: u64,
+    /// Game that was still in progress when the match was last processed
+    pub last_game_id: Option<u64>,
+    /// Mappool to label the games with
+    pub mappool_id: Option<u32>,
+}
+
+impl From<DbTrackedMatch> for TrackedMatchChannel {
+    #[inline]
+    fn from(tracked: DbTrackedMatch) -> Self {
+        let DbTrackedMatch {
+            match_id,
+            channel_id,
+            last_msg_id,
+            last_event_id,
+            last_game_id,
+            mappool_id,
+        } = tracked;
+
+        Self {
+            match_id: match_id as u32,
+            channel_id: Id::new(channel_id as u64),
+            msg_id: Id::new(last_msg_id as u64),
+            last_event_id: last_event_id as u64,
+            last_game_id: last_game_id.map(|game_id| game_id as u64),
+            mappool_id: mappool_id.map(|pool_id| pool_id as u32),
+        }
+    }
+}
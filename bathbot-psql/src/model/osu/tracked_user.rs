@@ -10,23 +10,69 @@ use time::OffsetDateTime;
 
 pub type Channels<S> = HashMap<NonZeroU64, u8, S>;
 
+/// Mapping channels to their filter criteria
+pub type ChannelFilters<S> = HashMap<NonZeroU64, Box<str>, S>;
+
+/// Mapping channels to the bitflags of milestone types they opted into
+pub type ChannelMilestones<S> = HashMap<NonZeroU64, u8, S>;
+
+/// Mapping channels to the inactivity timeout in minutes of their session
+/// summaries
+pub type ChannelSessions<S> = HashMap<NonZeroU64, u16, S>;
+
+/// Mapping channels to the webhook url that notifications are delivered to
+/// instead of the channel
+pub type ChannelWebhooks<S> = HashMap<NonZeroU64, Box<str>, S>;
+
 pub struct DbTrackedOsuUser {
     pub user_id: i32,
     pub gamemode: i16,
+    pub kind: i16,
     pub channels: Vec<u8>,
     pub last_update: OffsetDateTime,
+    pub last_score_id: Option<i64>,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct TrackedOsuUserKey {
     pub user_id: u32,
     pub mode: GameMode,
+    pub kind: TrackKind,
+}
+
+/// Which scores of a user are being tracked
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+#[repr(u8)]
+pub enum TrackKind {
+    /// New scores within the user's top scores; the channel value is the limit
+    /// within the top scores.
+    #[default]
+    Top = 0,
+    /// Recent passes that satisfy some conditions; the channel value holds the
+    /// bits of those conditions.
+    Recent = 1,
+}
+
+impl From<i16> for TrackKind {
+    #[inline]
+    fn from(kind: i16) -> Self {
+        match kind {
+            1 => Self::Recent,
+            _ => Self::Top,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct TrackedOsuUserValue<S> {
     pub channels: Channels<S>,
+    pub filters: ChannelFilters<S>,
+    pub milestones: ChannelMilestones<S>,
+    pub sessions: ChannelSessions<S>,
+    pub webhooks: ChannelWebhooks<S>,
     pub last_update: OffsetDateTime,
+    /// Id of the latest processed score, only used for [`TrackKind::Recent`]
+    pub last_score_id: Option<u64>,
 }
 
 impl<S> From<DbTrackedOsuUser> for (TrackedOsuUserKey, TrackedOsuUserValue<S>)
@@ -38,8 +84,10 @@ where
         let DbTrackedOsuUser {
             user_id,
             gamemode,
+            kind,
             channels,
             last_update,
+            last_score_id,
         } = user;
 
         // SAFETY: The bytes originate from the DB which only provides valid archived
@@ -50,13 +98,100 @@ where
         let key = TrackedOsuUserKey {
             user_id: user_id as u32,
             mode: (gamemode as u8).into(),
+            kind: kind.into(),
         };
 
         let value = TrackedOsuUserValue {
             channels,
+            filters: ChannelFilters::default(),
+            milestones: ChannelMilestones::default(),
+            sessions: ChannelSessions::default(),
+            webhooks: ChannelWebhooks::default(),
             last_update,
+            last_score_id: last_score_id.map(|score_id| score_id as u64),
         };
 
         (key, value)
     }
 }
+
+pub struct DbTrackedOsuUserFilter {
+    pub user_id: i32,
+    pub gamemode: i16,
+    pub kind: i16,
+    pub channel_id: i64,
+    pub criteria: String,
+}
+
+pub struct DbTrackedOsuUserMilestones {
+    pub user_id: i32,
+    pub gamemode: i16,
+    pub kind: i16,
+    pub channel_id: i64,
+    pub milestones: i16,
+}
+
+pub struct DbTrackedOsuUserSessionTimeout {
+    pub user_id: i32,
+    pub gamemode: i16,
+    pub kind: i16,
+    pub channel_id: i64,
+    pub timeout: i16,
+}
+
+pub struct DbTrackedOsuUserWebhook {
+    pub user_id: i32,
+    pub gamemode: i16,
+    pub kind: i16,
+    pub channel_id: i64,
+    pub webhook_url: String,
+}
+
+pub struct DbTrackedOsuUserSession {
+    pub msg_id: Option<i64>,
+    pub started_at: OffsetDateTime,
+    pub last_activity: OffsetDateTime,
+    pub start_pp: f32,
+    pub start_rank: i32,
+    pub score_count: i32,
+    pub plays: Vec<String>,
+}
+
+/// State of the session summary message of a tracked user in a channel
+#[derive(Clone, Debug)]
+pub struct TrackedOsuUserSession {
+    /// `None` if no session has been started yet
+    pub msg_id: Option<NonZeroU64>,
+    pub started_at: OffsetDateTime,
+    pub last_activity: OffsetDateTime,
+    pub start_pp: f32,
+    pub start_rank: u32,
+    pub score_count: u32,
+    /// Formatted lines of the latest plays, newest first
+    pub plays: Vec<String>,
+}
+
+impl From<DbTrackedOsuUserSession> for TrackedOsuUserSession {
+    #[inline]
+    fn from(session: DbTrackedOsuUserSession) -> Self {
+        let DbTrackedOsuUserSession {
+            msg_id,
+            started_at,
+            last_activity,
+            start_pp,
+            start_rank,
+            score_count,
+            plays,
+        } = session;
+
+        Self {
+            msg_id: msg_id.and_then(|msg_id| NonZeroU64::new(msg_id as u64)),
+            started_at,
+            last_activity,
+            start_pp,
+            start_rank: start_rank as u32,
+            score_count: score_count as u32,
+            plays,
+        }
+    }
+}
@@ -3,6 +3,7 @@ use sqlx::{
     database::HasValueRef, error::BoxDynError, postgres::PgTypeInfo, Decode, FromRow, Postgres,
     Type,
 };
+use time::OffsetDateTime;
 
 struct DbCountryCode {
     inner: [u8; 2],
@@ -45,6 +46,24 @@ pub struct DbUserStatsEntry<V> {
     pub value: V,
 }
 
+/// Stored stats of a user that are relevant for tracked milestones
+pub struct DbUserMilestoneStats {
+    pub pp: f32,
+    pub global_rank: i32,
+    pub country_rank: i32,
+    pub medals: i32,
+}
+
+/// Snapshot of a user's stats at some point in time
+pub struct DbUserStatsHistoryEntry {
+    pub pp: f32,
+    pub global_rank: i32,
+    pub country_rank: i32,
+    pub accuracy: f32,
+    pub playcount: i32,
+    pub recorded_at: OffsetDateTime,
+}
+
 pub trait OsuUserStatsColumn {
     type Stats;
     type Value;
@@ -0,0 +1,23 @@
+use time::OffsetDateTime;
+use twilight_model::id::{marker::ChannelMarker, Id};
+
+/// How a channel is notified about a tracked stream
+pub struct DbTrackedTwitchStreamTarget {
+    pub channel_id: Id<ChannelMarker>,
+    pub webhook_url: Option<Box<str>>,
+    /// Whether the notification is edited once the stream ends
+    pub followup: bool,
+}
+
+/// A sent notification of a stream that will be edited once the stream ends
+pub struct DbTwitchStreamMessage {
+    pub channel_id: i64,
+    pub user_id: i64,
+    pub msg_id: i64,
+    pub stream_id: i64,
+    pub started_at: OffsetDateTime,
+    pub peak_viewers: i32,
+    pub title: String,
+    pub game_name: String,
+    pub ended_at: Option<OffsetDateTime>,
+}
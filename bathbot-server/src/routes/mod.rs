@@ -2,3 +2,4 @@ pub mod auth;
 pub mod guild_count;
 pub mod metrics;
 pub mod osudirect;
+pub mod twitch_eventsub;
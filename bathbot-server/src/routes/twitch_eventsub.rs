@@ -0,0 +1,185 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use bathbot_model::TwitchStreamEvent;
+use hmac::{Hmac, Mac};
+use serde::{de::Error, Deserialize, Deserializer};
+use sha2::Sha256;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+use crate::state::AppState;
+
+const MESSAGE_ID: &str = "Twitch-Eventsub-Message-Id";
+const MESSAGE_TIMESTAMP: &str = "Twitch-Eventsub-Message-Timestamp";
+const MESSAGE_SIGNATURE: &str = "Twitch-Eventsub-Message-Signature";
+const MESSAGE_TYPE: &str = "Twitch-Eventsub-Message-Type";
+
+/// Messages older than this are rejected to prevent replay attacks
+const MAX_MESSAGE_AGE: Duration = Duration::from_secs(600);
+
+/// Receives notifications for twitch EventSub subscriptions and forwards
+/// stream status changes to the bot.
+///
+/// Twitch may deliver the same notification multiple times so notifications
+/// whose message id was seen recently are ignored.
+pub async fn post_twitch_eventsub(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    if let Err(reason) = verify_signature(&state.twitch_eventsub_secret, &headers, &body) {
+        warn!(reason, "Rejected twitch eventsub request");
+
+        return StatusCode::FORBIDDEN.into_response();
+    }
+
+    let payload: EventSubPayload = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(err) => {
+            warn!(?err, "Failed to deserialize twitch eventsub payload");
+
+            return StatusCode::BAD_REQUEST.into_response();
+        }
+    };
+
+    match header_str(&headers, MESSAGE_TYPE) {
+        Some("webhook_callback_verification") => match payload.challenge {
+            Some(challenge) => {
+                info!(kind = %payload.subscription.kind, "Verified twitch eventsub callback");
+
+                (StatusCode::OK, [(CONTENT_TYPE, "text/plain")], challenge).into_response()
+            }
+            None => StatusCode::BAD_REQUEST.into_response(),
+        },
+        Some("notification") => {
+            let Some(event) = payload.event else {
+                return StatusCode::BAD_REQUEST.into_response();
+            };
+
+            // The header was already checked while verifying the signature
+            let id = header_str(&headers, MESSAGE_ID).unwrap_or_default();
+
+            if !insert_message_id(&state, id) {
+                debug!(id, "Ignoring duplicate twitch eventsub notification");
+
+                return StatusCode::NO_CONTENT.into_response();
+            }
+
+            let user_id = event.broadcaster_user_id;
+
+            let event = match payload.subscription.kind.as_str() {
+                "stream.online" => TwitchStreamEvent::Online { user_id },
+                "stream.offline" => TwitchStreamEvent::Offline { user_id },
+                kind => {
+                    debug!(kind, "Ignoring twitch eventsub notification");
+
+                    return StatusCode::NO_CONTENT.into_response();
+                }
+            };
+
+            if state.twitch_events.send(event).is_err() {
+                warn!("Receiver of twitch events was dropped");
+            }
+
+            StatusCode::NO_CONTENT.into_response()
+        }
+        Some("revocation") => {
+            warn!(
+                kind = %payload.subscription.kind,
+                status = %payload.subscription.status,
+                "Twitch eventsub subscription was revoked"
+            );
+
+            StatusCode::NO_CONTENT.into_response()
+        }
+        _ => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+/// Checks that the request was signed by twitch with our secret.
+fn verify_signature(secret: &str, headers: &HeaderMap, body: &[u8]) -> Result<(), &'static str> {
+    let id = header_str(headers, MESSAGE_ID).ok_or("missing message id")?;
+    let timestamp = header_str(headers, MESSAGE_TIMESTAMP).ok_or("missing timestamp")?;
+
+    let signature = header_str(headers, MESSAGE_SIGNATURE)
+        .and_then(|signature| signature.strip_prefix("sha256="))
+        .ok_or("missing signature")?;
+
+    let sent_at = OffsetDateTime::parse(timestamp, &Rfc3339).map_err(|_| "invalid timestamp")?;
+
+    if (OffsetDateTime::now_utc() - sent_at).unsigned_abs() > MAX_MESSAGE_AGE {
+        return Err("outdated timestamp");
+    }
+
+    let signature = hex::decode(signature).map_err(|_| "signature is not hex")?;
+
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).map_err(|_| "invalid secret")?;
+
+    mac.update(id.as_bytes());
+    mac.update(timestamp.as_bytes());
+    mac.update(body);
+
+    mac.verify_slice(&signature)
+        .map_err(|_| "signature mismatch")
+}
+
+/// Stores the message id and returns whether it was not already stored.
+///
+/// Ids are only kept for as long as their message would still be accepted.
+fn insert_message_id(state: &AppState, id: &str) -> bool {
+    let now = Instant::now();
+
+    let mut ids = state
+        .twitch_eventsub_ids
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    ids.retain(|_, received| now.duration_since(*received) < MAX_MESSAGE_AGE);
+
+    if ids.contains_key(id) {
+        return false;
+    }
+
+    ids.insert(Box::from(id), now);
+
+    true
+}
+
+fn header_str<'h>(headers: &'h HeaderMap, name: &str) -> Option<&'h str> {
+    headers.get(name)?.to_str().ok()
+}
+
+#[derive(Deserialize)]
+struct EventSubPayload {
+    subscription: EventSubSubscription,
+    challenge: Option<String>,
+    event: Option<EventSubEvent>,
+}
+
+#[derive(Deserialize)]
+struct EventSubSubscription {
+    #[serde(rename = "type")]
+    kind: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+struct EventSubEvent {
+    #[serde(deserialize_with = "str_to_u64")]
+    broadcaster_user_id: u64,
+}
+
+fn str_to_u64<'de, D: Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+    <&str as Deserialize>::deserialize(d)?
+        .parse()
+        .map_err(Error::custom)
+}
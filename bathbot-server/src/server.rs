@@ -4,12 +4,16 @@ use axum::{
     http::StatusCode,
     middleware,
     response::Response,
-    routing::{get, get_service},
+    routing::{get, get_service, post},
     Router,
 };
+use bathbot_model::TwitchStreamEvent;
 use eyre::Result;
 use hyper::Request;
-use tokio::sync::oneshot::{channel, Receiver, Sender};
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver},
+    oneshot::{channel, Receiver, Sender},
+};
 use tower_http::{services::ServeDir, trace::TraceLayer};
 use tracing::Span;
 
@@ -20,12 +24,22 @@ use crate::{
         guild_count::get_guild_count,
         metrics::get_metrics,
         osudirect::redirect_osudirect,
+        twitch_eventsub::post_twitch_eventsub,
     },
     standby::AuthenticationStandby,
     state::AppState,
     AppStateBuilder,
 };
 
+/// The server, the standby for authentication, the shutdown sender, and the
+/// receiver for twitch stream events
+pub type ServerHandles = (
+    Server,
+    Arc<AuthenticationStandby>,
+    Sender<()>,
+    UnboundedReceiver<TwitchStreamEvent>,
+);
+
 pub struct Server {
     state: AppState,
     website_path: PathBuf,
@@ -33,10 +47,11 @@ pub struct Server {
 }
 
 impl Server {
-    pub fn new(builder: AppStateBuilder) -> Result<(Self, Arc<AuthenticationStandby>, Sender<()>)> {
+    pub fn new(builder: AppStateBuilder) -> Result<ServerHandles> {
         let (shutdown_tx, shutdown_rx) = channel();
+        let (twitch_tx, twitch_rx) = mpsc::unbounded_channel();
         let standby = Arc::new(AuthenticationStandby::new());
-        let (state, website_path) = builder.build(Arc::clone(&standby))?;
+        let (state, website_path) = builder.build(Arc::clone(&standby), twitch_tx)?;
 
         let server = Self {
             state,
@@ -44,7 +59,7 @@ impl Server {
             shutdown_rx,
         };
 
-        Ok((server, standby, shutdown_tx))
+        Ok((server, standby, shutdown_tx, twitch_rx))
     }
 
     pub async fn run(self, port: u16) {
@@ -88,6 +103,7 @@ impl Server {
             .route("/guild_count", get(get_guild_count))
             .nest("/auth", Self::auth_app(website_path))
             .route("/osudirect/:mapset_id", get(redirect_osudirect))
+            .route("/twitch/eventsub", post(post_twitch_eventsub))
             .layer(middleware::from_fn_with_state(state, track_metrics))
             .layer(trace)
     }
@@ -1,7 +1,13 @@
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
+use bathbot_model::TwitchStreamEvent;
 use bathbot_util::MetricsReader;
-use eyre::{Result, WrapErr};
+use eyre::{ensure, Result, WrapErr};
 use handlebars::Handlebars;
 use hyper::{
     client::{connect::dns::GaiResolver, HttpConnector},
@@ -10,6 +16,7 @@ use hyper::{
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use metrics::describe_histogram;
 use metrics_exporter_prometheus::PrometheusHandle;
+use tokio::sync::mpsc::UnboundedSender;
 
 use crate::standby::AuthenticationStandby;
 
@@ -22,6 +29,10 @@ pub struct AppState {
     pub osu_client_secret: Box<str>,
     pub twitch_client_id: Box<str>,
     pub twitch_token: Box<str>,
+    pub twitch_eventsub_secret: Box<str>,
+    /// Ids of recently received EventSub messages to ignore re-deliveries
+    pub twitch_eventsub_ids: Mutex<HashMap<Box<str>, Instant>>,
+    pub twitch_events: UnboundedSender<TwitchStreamEvent>,
     pub redirect_base: Box<str>,
     pub standby: Arc<AuthenticationStandby>,
 }
@@ -34,11 +45,17 @@ pub struct AppStateBuilder {
     pub osu_client_secret: String,
     pub twitch_client_id: String,
     pub twitch_token: String,
+    /// Secret that twitch uses to sign EventSub notifications
+    pub twitch_eventsub_secret: String,
     pub redirect_base: String,
 }
 
 impl AppStateBuilder {
-    pub(crate) fn build(self, standby: Arc<AuthenticationStandby>) -> Result<(AppState, PathBuf)> {
+    pub(crate) fn build(
+        self,
+        standby: Arc<AuthenticationStandby>,
+        twitch_events: UnboundedSender<TwitchStreamEvent>,
+    ) -> Result<(AppState, PathBuf)> {
         let Self {
             website_path,
             prometheus,
@@ -47,9 +64,15 @@ impl AppStateBuilder {
             osu_client_secret,
             twitch_client_id,
             twitch_token,
+            twitch_eventsub_secret,
             redirect_base,
         } = self;
 
+        ensure!(
+            !twitch_eventsub_secret.trim().is_empty(),
+            "The twitch eventsub secret must not be empty"
+        );
+
         let connector = HttpsConnectorBuilder::new()
             .with_webpki_roots()
             .https_or_http()
@@ -80,6 +103,9 @@ impl AppStateBuilder {
             osu_client_secret: osu_client_secret.into_boxed_str(),
             twitch_client_id: twitch_client_id.into_boxed_str(),
             twitch_token: twitch_token.into_boxed_str(),
+            twitch_eventsub_secret: twitch_eventsub_secret.into_boxed_str(),
+            twitch_eventsub_ids: Mutex::new(HashMap::new()),
+            twitch_events,
             redirect_base: redirect_base.into_boxed_str(),
             standby,
         };
@@ -1,5 +1,3 @@
-use twilight_model::id::{marker::UserMarker, Id};
-
 // Colors
 pub const DARK_GREEN: u32 = 0x1F8B4C;
 pub const RED: u32 = 0xE74C3C;
@@ -17,6 +15,7 @@ pub const HUISMETBENEN: &str = "https://api.huismetbenen.nl/";
 
 // twitch
 pub const TWITCH_BASE: &str = "https://www.twitch.tv/";
+pub const TWITCH_EVENTSUB_ENDPOINT: &str = "https://api.twitch.tv/helix/eventsub/subscriptions";
 pub const TWITCH_STREAM_ENDPOINT: &str = "https://api.twitch.tv/helix/streams";
 pub const TWITCH_USERS_ENDPOINT: &str = "https://api.twitch.tv/helix/users";
 pub const TWITCH_VIDEOS_ENDPOINT: &str = "https://api.twitch.tv/helix/videos";
@@ -44,4 +43,3 @@ pub const BATHBOT_WORKSHOP: &str = "https://discord.gg/n9fFstG";
 pub const BATHBOT_GITHUB: &str = "https://github.com/MaxOhn/Bathbot";
 pub const BATHBOT_ROADMAP: &str = "https://github.com/users/MaxOhn/projects/3";
 pub const KOFI: &str = "https://ko-fi.com/bathbot";
-pub const MISS_ANALYZER_ID: Id<UserMarker> = Id::new(752035690237394944);
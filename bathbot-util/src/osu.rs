@@ -32,6 +32,11 @@ impl ModSelection {
 
     /// Returns `true` if the score's mods coincide with this [`ModSelection`]
     pub fn filter_score(&self, score: &Score) -> bool {
+        self.filter_mods(&score.mods)
+    }
+
+    /// Returns `true` if the mods coincide with this [`ModSelection`]
+    pub fn filter_mods(&self, score_mods: &GameMods) -> bool {
         const DT: GameModIntermode = GameModIntermode::DoubleTime;
         const NC: GameModIntermode = GameModIntermode::Nightcore;
         const SD: GameModIntermode = GameModIntermode::SuddenDeath;
@@ -39,20 +44,20 @@ impl ModSelection {
 
         match self {
             ModSelection::Include(mods) | ModSelection::Exact(mods) if mods.is_empty() => {
-                score.mods.is_empty()
+                score_mods.is_empty()
             }
             ModSelection::Include(mods) => mods.iter().all(|gamemod| match gamemod {
-                DT => score.mods.contains_intermode(DT) || score.mods.contains_intermode(NC),
-                SD => score.mods.contains_intermode(SD) || score.mods.contains_intermode(PF),
-                _ => score.mods.contains_intermode(gamemod),
+                DT => score_mods.contains_intermode(DT) || score_mods.contains_intermode(NC),
+                SD => score_mods.contains_intermode(SD) || score_mods.contains_intermode(PF),
+                _ => score_mods.contains_intermode(gamemod),
             }),
-            ModSelection::Exclude(mods) if mods.is_empty() => !score.mods.is_empty(),
+            ModSelection::Exclude(mods) if mods.is_empty() => !score_mods.is_empty(),
             ModSelection::Exclude(mods) => !mods.iter().any(|gamemod| match gamemod {
-                DT => score.mods.contains_intermode(DT) || score.mods.contains_intermode(NC),
-                SD => score.mods.contains_intermode(SD) || score.mods.contains_intermode(PF),
-                _ => score.mods.contains_intermode(gamemod),
+                DT => score_mods.contains_intermode(DT) || score_mods.contains_intermode(NC),
+                SD => score_mods.contains_intermode(SD) || score_mods.contains_intermode(PF),
+                _ => score_mods.contains_intermode(gamemod),
             }),
-            ModSelection::Exact(mods) => score.mods.iter().map(GameMod::intermode).eq(mods.iter()),
+            ModSelection::Exact(mods) => score_mods.iter().map(GameMod::intermode).eq(mods.iter()),
         }
     }
 
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use bathbot_util::MessageBuilder;
 use eyre::{Report, Result, WrapErr};
@@ -84,14 +84,20 @@ impl ActiveMessagesBuilder {
 
             let msg = response.id;
             let response = ActiveResponse::new(&orig, &response);
-            let (activity_tx, activity_rx) = watch::channel(());
+            let (activity_tx, activity_rx) = watch::channel(Instant::now());
 
             if let Some(until_timeout) = active_msg.until_timeout() {
-                ActiveMessagesBuilder::spawn_timeout(activity_rx, response, until_timeout);
+                ActiveMessagesBuilder::spawn_timeout(
+                    activity_rx,
+                    response.clone(),
+                    until_timeout,
+                    until_timeout,
+                );
 
                 let full = FullActiveMessage {
                     active_msg,
                     activity_tx,
+                    response,
                 };
 
                 Context::get().active_msgs.insert(msg, full).await;
@@ -114,16 +120,27 @@ impl ActiveMessagesBuilder {
         }
     }
 
-    fn spawn_timeout(mut rx: Receiver<()>, response: ActiveResponse, until_timeout: Duration) {
+    /// Times out the active message after `remaining` unless there was
+    /// activity in which case the timeout restarts with `until_timeout`.
+    pub(super) fn spawn_timeout(
+        mut rx: Receiver<Instant>,
+        response: ActiveResponse,
+        until_timeout: Duration,
+        remaining: Duration,
+    ) {
         tokio::spawn(async move {
+            let mut timeout = remaining;
+
             loop {
                 tokio::select! {
                     res = rx.changed() => if res.is_ok() {
+                        timeout = until_timeout;
+
                         continue
                     } else {
                         return
                     },
-                    _ = sleep(until_timeout) => {
+                    _ = sleep(timeout) => {
                         let active_msg = Context::get().active_msgs.remove_full(response.msg).await;
 
                         if let Some(FullActiveMessage { mut active_msg, .. }) = active_msg {
@@ -0,0 +1,324 @@
+use eyre::{ContextCompat, Result};
+
+/// Length in seconds of the initial clip
+const INITIAL_LENGTH: f32 = 2.0;
+
+/// Seconds that are added to the clip whenever it's increased
+const LENGTH_STEP: f32 = 2.0;
+
+/// Reveals an mp3 piece by piece, always starting from the beginning.
+pub struct AudioReveal {
+    original: Vec<u8>,
+    frames: Vec<Mp3Frame>,
+    length: f32,
+}
+
+struct Mp3Frame {
+    start: usize,
+    end: usize,
+    /// Duration in seconds
+    duration: f32,
+}
+
+impl AudioReveal {
+    pub fn new(original: Vec<u8>) -> Result<Self> {
+        let frames = parse_frames(&original);
+
+        if frames.is_empty() {
+            bail!("audio contains no mp3 frames");
+        }
+
+        Ok(Self {
+            original,
+            frames,
+            length: INITIAL_LENGTH,
+        })
+    }
+
+    pub fn increase_length(&mut self) {
+        self.length += LENGTH_STEP;
+    }
+
+    pub fn sub_clip(&self) -> Result<Vec<u8>> {
+        let start = self.frames.first().map(|frame| frame.start);
+
+        let mut duration = 0.0;
+        let mut end = None;
+
+        for frame in self.frames.iter() {
+            end = Some(frame.end);
+            duration += frame.duration;
+
+            if duration >= self.length {
+                break;
+            }
+        }
+
+        let (start, end) = start.zip(end).wrap_err("missing mp3 frames")?;
+
+        Ok(self.original[start..end].to_vec())
+    }
+}
+
+/// Collect all mp3 frames, skipping metadata such as ID3 tags or
+/// Xing headers.
+fn parse_frames(bytes: &[u8]) -> Vec<Mp3Frame> {
+    let mut idx = id3_len(bytes);
+    let mut frames = Vec::new();
+
+    while idx + 4 <= bytes.len() {
+        let header = [bytes[idx], bytes[idx + 1], bytes[idx + 2], bytes[idx + 3]];
+
+        let Some((len, duration)) = frame_info(header) else {
+            // Resynchronize on garbage data
+            idx += 1;
+
+            continue;
+        };
+
+        let end = idx + len;
+
+        if end > bytes.len() {
+            break;
+        }
+
+        frames.push(Mp3Frame {
+            start: idx,
+            end,
+            duration,
+        });
+
+        idx = end;
+    }
+
+    // The first frame may only contain info about the whole file which would
+    // be wrong for clips
+    let is_info_frame = frames.first().is_some_and(|frame| {
+        bytes[frame.start..frame.end]
+            .windows(4)
+            .any(|window| window == b"Xing" || window == b"Info")
+    });
+
+    if is_info_frame {
+        frames.remove(0);
+    }
+
+    frames
+}
+
+fn id3_len(bytes: &[u8]) -> usize {
+    if bytes.len() < 10 || &bytes[..3] != b"ID3" {
+        return 0;
+    }
+
+    // Sizes are stored as syncsafe integers
+    let size = bytes[6..10]
+        .iter()
+        .fold(0, |size, byte| (size << 7) | (*byte & 0x7F) as usize);
+
+    let footer = if bytes[5] & 0x10 > 0 { 10 } else { 0 };
+
+    10 + size + footer
+}
+
+/// Returns the length in bytes and the duration in seconds of a MPEG layer III
+/// frame.
+fn frame_info(header: [u8; 4]) -> Option<(usize, f32)> {
+    const BITRATES_V1: [u32; 15] = [
+        0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+    ];
+    const BITRATES_V2: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    // Frame sync
+    if header[0] != 0xFF || header[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+
+    let version = (header[1] >> 3) & 0b11;
+    let layer = (header[1] >> 1) & 0b11;
+
+    // Only layer III is supported
+    if version == 0b01 || layer != 0b01 {
+        return None;
+    }
+
+    let bitrate_idx = (header[2] >> 4) as usize;
+    let sample_rate_idx = ((header[2] >> 2) & 0b11) as usize;
+    let padding = ((header[2] >> 1) & 0b1) as u32;
+
+    if bitrate_idx == 0 || bitrate_idx == 15 || sample_rate_idx == 3 {
+        return None;
+    }
+
+    let (bitrate, sample_rate, samples) = match version {
+        // MPEG 1
+        0b11 => (
+            BITRATES_V1[bitrate_idx],
+            [44_100, 48_000, 32_000][sample_rate_idx],
+            1152,
+        ),
+        // MPEG 2
+        0b10 => (
+            BITRATES_V2[bitrate_idx],
+            [22_050, 24_000, 16_000][sample_rate_idx],
+            576,
+        ),
+        // MPEG 2.5
+        _ => (
+            BITRATES_V2[bitrate_idx],
+            [11_025, 12_000, 8_000][sample_rate_idx],
+            576,
+        ),
+    };
+
+    let len = samples / 8 * bitrate * 1000 / sample_rate + padding;
+    let duration = samples as f32 / sample_rate as f32;
+
+    Some((len as usize, duration))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MPEG1: u8 = 0b11;
+    const MPEG2: u8 = 0b10;
+    const MPEG2_5: u8 = 0b00;
+
+    /// Layer III header without CRC
+    fn header(version: u8, bitrate_idx: u8, sample_rate_idx: u8, padding: bool) -> [u8; 4] {
+        [
+            0xFF,
+            0xE0 | (version << 3) | (0b01 << 1) | 1,
+            (bitrate_idx << 4) | (sample_rate_idx << 2) | ((padding as u8) << 1),
+            0,
+        ]
+    }
+
+    /// MPEG1 layer III at 128kbps and 44.1kHz, each frame being 417 bytes.
+    fn frame(content: &[u8]) -> Vec<u8> {
+        let mut frame = header(MPEG1, 9, 0, false).to_vec();
+        frame.extend_from_slice(content);
+        frame.resize(417, 0);
+
+        frame
+    }
+
+    /// ID3v2 tag with 300 bytes of data that contain a frame sync.
+    fn id3_tag() -> Vec<u8> {
+        let mut tag = b"ID3\x04\x00\x00\x00\x00\x02\x2C".to_vec();
+        tag.extend_from_slice(&header(MPEG1, 9, 0, false));
+        tag.resize(310, 0);
+
+        tag
+    }
+
+    fn mp3(frames: usize) -> Vec<u8> {
+        let mut bytes = id3_tag();
+
+        for _ in 0..frames {
+            bytes.extend(frame(&[]));
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn frame_length() {
+        let mpeg1 = frame_info(header(MPEG1, 9, 0, false)).unwrap();
+        assert_eq!(mpeg1.0, 417);
+        assert!((mpeg1.1 - 1152.0 / 44_100.0).abs() < f32::EPSILON);
+
+        let padded = frame_info(header(MPEG1, 9, 0, true)).unwrap();
+        assert_eq!(padded.0, 418);
+
+        let mpeg1_48k = frame_info(header(MPEG1, 14, 1, false)).unwrap();
+        assert_eq!(mpeg1_48k.0, 960);
+
+        let mpeg2 = frame_info(header(MPEG2, 8, 0, false)).unwrap();
+        assert_eq!(mpeg2.0, 208);
+        assert!((mpeg2.1 - 576.0 / 22_050.0).abs() < f32::EPSILON);
+
+        let mpeg2_5 = frame_info(header(MPEG2_5, 4, 2, false)).unwrap();
+        assert_eq!(mpeg2_5.0, 288);
+        assert!((mpeg2_5.1 - 576.0 / 8_000.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn invalid_frame_header() {
+        // Reserved version
+        assert!(frame_info(header(0b01, 9, 0, false)).is_none());
+        // Free and bad bitrate
+        assert!(frame_info(header(MPEG1, 0, 0, false)).is_none());
+        assert!(frame_info(header(MPEG1, 15, 0, false)).is_none());
+        // Reserved sample rate
+        assert!(frame_info(header(MPEG1, 9, 3, false)).is_none());
+        // Layer II
+        assert!(frame_info([0xFF, 0xFD, 0x90, 0]).is_none());
+        // Missing frame sync
+        assert!(frame_info([0xFF, 0x1B, 0x90, 0]).is_none());
+    }
+
+    #[test]
+    fn skip_id3() {
+        let bytes = mp3(3);
+        assert_eq!(id3_len(&bytes), 310);
+
+        let frames = parse_frames(&bytes);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].start, 310);
+        assert_eq!(frames[2].end, bytes.len());
+
+        assert_eq!(id3_len(&frame(&[])), 0);
+    }
+
+    #[test]
+    fn skip_info_frame() {
+        let mut bytes = id3_tag();
+        bytes.extend(frame(b"\0\0\0\0Info"));
+        bytes.extend(frame(&[]));
+        bytes.extend(frame(&[]));
+
+        let frames = parse_frames(&bytes);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].start, 310 + 417);
+    }
+
+    #[test]
+    fn resync_and_truncated_frame() {
+        let mut bytes = vec![0, 1, 2];
+        bytes.extend(frame(&[]));
+        bytes.extend_from_slice(&frame(&[])[..100]);
+
+        let frames = parse_frames(&bytes);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].start, 3);
+    }
+
+    #[test]
+    fn clip_duration() {
+        let bytes = mp3(200);
+        let mut reveal = AudioReveal::new(bytes).unwrap();
+
+        // 2 seconds require 77 frames of ~26ms
+        let clip = reveal.sub_clip().unwrap();
+        assert_eq!(clip.len(), 77 * 417);
+        assert_eq!(clip[..4], header(MPEG1, 9, 0, false));
+
+        // 4 seconds require 154 frames
+        reveal.increase_length();
+        assert_eq!(reveal.sub_clip().unwrap().len(), 154 * 417);
+
+        // Clips can't be longer than the audio itself
+        for _ in 0..10 {
+            reveal.increase_length();
+        }
+
+        assert_eq!(reveal.sub_clip().unwrap().len(), 200 * 417);
+    }
+
+    #[test]
+    fn no_frames() {
+        assert!(AudioReveal::new(id3_tag()).is_err());
+    }
+}
@@ -0,0 +1,419 @@
+use std::{fmt::Write, time::Duration};
+
+use bathbot_model::{DailyGuess, Effects};
+use bathbot_psql::model::games::DbMapTagsParams;
+use bathbot_util::{
+    constants::OSU_BASE,
+    modal::{ModalBuilder, TextInputBuilder},
+    CowUtils, EmbedBuilder, FooterBuilder, MessageBuilder,
+};
+use eyre::{ContextCompat, Report, Result, WrapErr};
+use futures::future::BoxFuture;
+use rand::{rngs::StdRng, SeedableRng};
+use rosu_v2::prelude::GameMode;
+use time::{Date, Month, OffsetDateTime, Time};
+use twilight_model::{
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle},
+        Component,
+    },
+    id::{marker::UserMarker, Id},
+};
+
+use super::{game::Game, img_reveal::ImageReveal, mapset::GameMapset, util};
+use crate::{
+    active::{BuildPage, ComponentResult, IActiveMessage},
+    commands::fun::GameDifficulty,
+    core::{BotConfig, Context},
+    util::{
+        interaction::{InteractionComponent, InteractionModal},
+        Authored, ComponentExt, ModalExt,
+    },
+};
+
+const MAX_GUESSES: usize = 6;
+
+/// The day of the first daily challenge
+const FIRST_DAY: Date = match Date::from_calendar_date(2024, Month::October, 18) {
+    Ok(day) => day,
+    Err(_) => panic!("invalid date"),
+};
+
+/// Wordle-style daily puzzle where everyone guesses the same background and
+/// only has a single attempt per day.
+pub struct DailyChallenge {
+    day: Date,
+    mapset: GameMapset,
+    reveal: ImageReveal,
+    guesses: Vec<DailyGuess>,
+    finished: bool,
+    /// Feedback about the previous guess
+    note: Option<String>,
+    msg_owner: Id<UserMarker>,
+}
+
+impl IActiveMessage for DailyChallenge {
+    fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
+        // Responses that contain a new image are sent manually so
+        // the page only needs to update the embed afterwards
+        BuildPage::new(self.embed(), true).boxed()
+    }
+
+    fn build_components(&self) -> Vec<Component> {
+        if self.finished {
+            return Vec::new();
+        }
+
+        let guess = Button {
+            custom_id: Some("daily_guess".to_owned()),
+            disabled: false,
+            emoji: None,
+            label: Some("Guess".to_owned()),
+            style: ButtonStyle::Success,
+            url: None,
+        };
+
+        let give_up = Button {
+            custom_id: Some("daily_give_up".to_owned()),
+            disabled: false,
+            emoji: None,
+            label: Some("Give up".to_owned()),
+            style: ButtonStyle::Danger,
+            url: None,
+        };
+
+        let components = vec![Component::Button(guess), Component::Button(give_up)];
+
+        vec![Component::ActionRow(ActionRow { components })]
+    }
+
+    fn handle_component<'a>(
+        &'a mut self,
+        component: &'a mut InteractionComponent,
+    ) -> BoxFuture<'a, ComponentResult> {
+        let user_id = match component.user_id() {
+            Ok(user_id) => user_id,
+            Err(err) => return ComponentResult::Err(err).boxed(),
+        };
+
+        if user_id != self.msg_owner || self.finished {
+            return ComponentResult::Ignore.boxed();
+        }
+
+        match component.data.custom_id.as_str() {
+            "daily_guess" => {
+                let input = TextInputBuilder::new("daily_guess", "Title")
+                    .placeholder("Title of the map")
+                    .required(true);
+
+                let modal = ModalBuilder::new("daily_guess", "Guess the map").input(input);
+
+                ComponentResult::CreateModal(modal).boxed()
+            }
+            "daily_give_up" => Box::pin(self.give_up(component)),
+            other => {
+                warn!(name = %other, ?component, "Unknown daily challenge component");
+
+                ComponentResult::Ignore.boxed()
+            }
+        }
+    }
+
+    fn handle_modal<'a>(
+        &'a mut self,
+        modal: &'a mut InteractionModal,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.async_handle_modal(modal))
+    }
+
+    fn until_timeout(&self) -> Option<Duration> {
+        (!self.finished).then_some(Duration::from_secs(600))
+    }
+}
+
+impl DailyChallenge {
+    /// Prepares today's challenge for the user, continuing a previous attempt
+    /// if there is one.
+    pub async fn new(msg_owner: Id<UserMarker>) -> Result<(Self, Vec<u8>)> {
+        let day = OffsetDateTime::now_utc().date();
+        let games = Context::games();
+
+        let mapset_id = match games.daily_challenge_mapset(day).await? {
+            Some(mapset_id) => mapset_id,
+            None => {
+                let entries = games
+                    .bggame_tags(DbMapTagsParams::new(GameMode::Osu))
+                    .await?;
+
+                let entry = util::get_seeded_mapset(&entries, Self::number_of(day) as u64)
+                    .wrap_err("No mapsets available for the daily challenge")?;
+
+                games
+                    .daily_challenge_set_mapset(day, entry.mapset_id as u32)
+                    .await?
+            }
+        };
+
+        let mapset_fut = games.bggame_mapset(mapset_id);
+        let attempt_fut = games.daily_challenge_attempt(msg_owner, day);
+
+        let (mapset_tag, attempt) = tokio::try_join!(mapset_fut, attempt_fut)?;
+        let mapset_tag = mapset_tag.wrap_err("Missing daily challenge mapset")?;
+
+        let mut path = BotConfig::get().paths.backgrounds.clone();
+        path.push("osu");
+        path.push(&mapset_tag.image_filename);
+
+        let img_fut = Game::load_image(path, mapset_tag.mapset_id, Effects::empty());
+
+        let (img, mapset) = tokio::try_join!(img_fut, GameMapset::new(mapset_id))?;
+
+        // Everyone gets the same part of the image revealed
+        let mut rng = StdRng::seed_from_u64(Self::number_of(day) as u64);
+        let mut reveal = ImageReveal::with_rng(img, &mut rng);
+
+        let (guesses, finished) = attempt.unwrap_or_default();
+
+        for _ in guesses.iter().filter(|guess| **guess != DailyGuess::Title) {
+            reveal.increase_radius();
+        }
+
+        let challenge = Self {
+            day,
+            mapset,
+            reveal,
+            guesses,
+            finished,
+            note: None,
+            msg_owner,
+        };
+
+        let bytes = challenge.image()?;
+
+        Ok((challenge, bytes))
+    }
+
+    pub fn attachment_name() -> &'static str {
+        "daily_challenge.png"
+    }
+
+    /// Spoiler-free summary of the attempt that can be shared
+    fn share_string(&self) -> String {
+        let mut share = format!("osu! daily #{} ", Self::number_of(self.day));
+
+        if self.solved() {
+            let _ = write!(share, "{}/{MAX_GUESSES}", self.guesses.len());
+        } else {
+            let _ = write!(share, "X/{MAX_GUESSES}");
+        }
+
+        share.push('\n');
+
+        for guess in self.guesses.iter() {
+            share.push_str(guess.emote());
+        }
+
+        for _ in self.guesses.len()..MAX_GUESSES {
+            share.push('⬛');
+        }
+
+        share
+    }
+
+    /// Adopts the stored state of the attempt and reveals the image
+    /// accordingly.
+    fn sync(&mut self, guesses: Vec<DailyGuess>, finished: bool) {
+        fn reveals(guesses: &[DailyGuess]) -> usize {
+            guesses
+                .iter()
+                .filter(|guess| **guess != DailyGuess::Title)
+                .count()
+        }
+
+        for _ in reveals(&self.guesses)..reveals(&guesses) {
+            self.reveal.increase_radius();
+        }
+
+        self.guesses = guesses;
+        self.finished = finished;
+    }
+
+    /// Number of the daily puzzle on the given day, starting at 1.
+    pub fn number_of(day: Date) -> i64 {
+        (day - FIRST_DAY).whole_days() + 1
+    }
+
+    fn solved(&self) -> bool {
+        self.guesses.last() == Some(&DailyGuess::Title)
+    }
+
+    fn image(&self) -> Result<Vec<u8>> {
+        if self.finished {
+            self.reveal.full_image()
+        } else {
+            self.reveal.sub_image()
+        }
+    }
+
+    fn embed(&self) -> EmbedBuilder {
+        let mut description = String::new();
+
+        if self.finished {
+            let mapset_id = self.mapset.mapset_id;
+
+            let _ = writeln!(
+                description,
+                "{result} The map was [{artist} - {title}]({OSU_BASE}beatmapsets/{mapset_id})\n\
+                ```\n{share}\n```",
+                result = if self.solved() {
+                    "Solved!"
+                } else {
+                    "Better luck next time."
+                },
+                artist = self.mapset.artist(),
+                title = self.mapset.title(),
+                share = self.share_string(),
+            );
+
+            let tomorrow = self
+                .day
+                .next_day()
+                .unwrap_or(self.day)
+                .with_time(Time::MIDNIGHT)
+                .assume_utc();
+
+            let _ = write!(
+                description,
+                "The next challenge starts <t:{}:R>",
+                tomorrow.unix_timestamp()
+            );
+        } else {
+            description.push_str(
+                "Guess the title of the map whose background is shown.\n\
+                Each incorrect guess reveals more of the image.\n\n",
+            );
+
+            for guess in self.guesses.iter() {
+                description.push_str(guess.emote());
+            }
+
+            for _ in self.guesses.len()..MAX_GUESSES {
+                description.push('⬛');
+            }
+
+            if let Some(ref note) = self.note {
+                let _ = write!(description, "\n\n*{note}*");
+            }
+        }
+
+        let footer = FooterBuilder::new(format!(
+            "Guesses: {}/{MAX_GUESSES} • Check your streak with /daily stats",
+            self.guesses.len()
+        ));
+
+        EmbedBuilder::new()
+            .description(description)
+            .footer(footer)
+            .title(format!("Daily Challenge #{}", Self::number_of(self.day)))
+    }
+
+    async fn async_handle_modal(&mut self, modal: &mut InteractionModal) -> Result<()> {
+        if modal.user_id()? != self.msg_owner || self.finished {
+            return Ok(());
+        }
+
+        let input = modal
+            .data
+            .components
+            .first()
+            .and_then(|row| row.components.first())
+            .and_then(|component| component.value.as_deref())
+            .wrap_err("Missing daily challenge modal input")?;
+
+        let content = input.trim().cow_to_ascii_lowercase();
+        let difficulty = GameDifficulty::Normal.factor();
+
+        let guess = if self.mapset.matches_title(&content, difficulty).is_some() {
+            DailyGuess::Title
+        } else if self.mapset.matches_artist(&content, difficulty).is_some() {
+            DailyGuess::Artist
+        } else {
+            DailyGuess::Wrong
+        };
+
+        let prev_count = self.guesses.len();
+        let finished = guess == DailyGuess::Title || prev_count + 1 >= MAX_GUESSES;
+
+        let games = Context::games();
+
+        let stored = games
+            .daily_challenge_store_guess(self.msg_owner, self.day, guess, prev_count, finished)
+            .await?;
+
+        match stored {
+            Some((guesses, finished)) => {
+                self.sync(guesses, finished);
+
+                self.note = match guess {
+                    DailyGuess::Title => None,
+                    DailyGuess::Artist => Some(format!("`{input}` is the artist, not the title")),
+                    DailyGuess::Wrong => Some(format!("`{input}` is incorrect")),
+                };
+            }
+            // The attempt progressed in a different message so the guess is
+            // discarded and the stored attempt is continued instead
+            None => {
+                if let Some((guesses, finished)) = games
+                    .daily_challenge_attempt(self.msg_owner, self.day)
+                    .await?
+                {
+                    self.sync(guesses, finished);
+                }
+
+                self.note = Some(format!(
+                    "Your attempt continued elsewhere so `{input}` was not counted"
+                ));
+            }
+        }
+
+        let builder = MessageBuilder::new()
+            .embed(self.embed())
+            .components(self.build_components())
+            .attachment(Self::attachment_name(), self.image()?);
+
+        modal
+            .callback(builder)
+            .await
+            .wrap_err("Failed to callback on daily challenge modal")?;
+
+        Ok(())
+    }
+
+    async fn give_up(&mut self, component: &InteractionComponent) -> ComponentResult {
+        let give_up_fut = Context::games().daily_challenge_give_up(self.msg_owner, self.day);
+
+        match give_up_fut.await {
+            Ok(guesses) => self.sync(guesses, true),
+            Err(err) => return ComponentResult::Err(err),
+        }
+
+        let bytes = match self.image() {
+            Ok(bytes) => bytes,
+            Err(err) => return ComponentResult::Err(err),
+        };
+
+        let builder = MessageBuilder::new()
+            .embed(self.embed())
+            .components(Vec::new())
+            .attachment(Self::attachment_name(), bytes);
+
+        match component.callback(builder).await {
+            Ok(_) => ComponentResult::Ignore,
+            Err(err) => {
+                let wrap = "Failed to callback on daily challenge component";
+
+                ComponentResult::Err(Report::new(err).wrap_err(wrap))
+            }
+        }
+    }
+}
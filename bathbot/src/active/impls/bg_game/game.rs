@@ -1,4 +1,10 @@
-use std::{collections::VecDeque, sync::RwLock};
+use std::{
+    collections::VecDeque,
+    mem,
+    path::PathBuf,
+    sync::{Mutex, RwLock},
+    time::{Duration, Instant},
+};
 
 use bathbot_model::Effects;
 use bathbot_psql::model::games::MapsetTagsEntries;
@@ -6,7 +12,7 @@ use bathbot_util::{constants::OSU_BASE, CowUtils};
 use eyre::{Result, WrapErr};
 use image::{
     imageops::{self, colorops},
-    GenericImageView,
+    DynamicImage, GenericImageView,
 };
 use rosu_v2::model::GameMode;
 use tokio::{fs, sync::RwLock as TokioRwLock};
@@ -17,14 +23,24 @@ use twilight_model::id::{
 };
 use twilight_standby::future::WaitForMessageStream;
 
-use super::{hints::Hints, img_reveal::ImageReveal, mapset::GameMapset, util};
-use crate::{commands::fun::GameDifficulty, core::BotConfig, util::ChannelExt, Context};
+use super::{
+    audio_reveal::AudioReveal, hints::Hints, img_reveal::ImageReveal, mapset::GameMapset, util,
+};
+use crate::{
+    commands::fun::{BgGameKind, GameDifficulty},
+    core::BotConfig,
+    util::ChannelExt,
+    Context,
+};
 
 pub struct Game {
     pub mapset: GameMapset,
     difficulty: f32,
     hints: RwLock<Hints>,
-    reveal: RwLock<ImageReveal>,
+    reveal: RwLock<Reveal>,
+    /// Users that requested a hint, one entry per hint
+    hint_requests: Mutex<Vec<Id<UserMarker>>>,
+    started: Instant,
 }
 
 impl Game {
@@ -33,14 +49,15 @@ impl Game {
         previous_ids: &mut VecDeque<i32>,
         effects: Effects,
         difficulty: GameDifficulty,
+        kind: BgGameKind,
     ) -> (Self, Vec<u8>) {
         loop {
-            match Game::new_(entries, previous_ids, effects, difficulty).await {
+            match Game::new_(entries, previous_ids, effects, difficulty, kind).await {
                 Ok(game) => {
-                    let sub_image_result = { game.reveal.read().unwrap().sub_image() };
+                    let sub_media_result = { game.reveal.read().unwrap().sub_media() };
 
-                    match sub_image_result {
-                        Ok(img) => return (game, img),
+                    match sub_media_result {
+                        Ok(media) => return (game, media),
                         Err(err) => {
                             warn!(
                                 mapset_id = game.mapset.mapset_id,
@@ -62,6 +79,7 @@ impl Game {
         previous_ids: &mut VecDeque<i32>,
         effects: Effects,
         difficulty: GameDifficulty,
+        kind: BgGameKind,
     ) -> Result<Self> {
         let mut path = BotConfig::get().paths.backgrounds.clone();
 
@@ -76,66 +94,94 @@ impl Game {
         debug!("Next BG mapset id: {mapset_id}");
         path.push(&mapset.image_filename);
 
-        let img_fut = async {
-            let bytes = fs::read(path)
-                .await
-                .wrap_err_with(|| format!("failed to read bg image for mapset {mapset_id}"))?;
+        let reveal_fut = async {
+            match kind {
+                BgGameKind::Background => Self::load_image(path, mapset_id, effects)
+                    .await
+                    .map(|img| Reveal::Image(ImageReveal::new(img))),
+                // Full audio files are not stored so the mapset preview is used instead
+                BgGameKind::Song => {
+                    let bytes = Context::client()
+                        .get_mapset_preview(mapset_id as u32)
+                        .await
+                        .wrap_err_with(|| {
+                            format!("failed to get preview for mapset {mapset_id}")
+                        })?;
+
+                    AudioReveal::new(bytes.into()).map(Reveal::Audio)
+                }
+            }
+        };
 
-            let mut img =
-                image::load_from_memory(&bytes).wrap_err("failed to load image from memory")?;
+        let (mapset_, reveal) =
+            tokio::try_join!(GameMapset::new(mapset.mapset_id as u32), reveal_fut)?;
 
-            let (w, h) = img.dimensions();
+        Ok(Self {
+            hints: RwLock::new(Hints::new(mapset_.title())),
+            difficulty: difficulty.factor(),
+            mapset: mapset_,
+            reveal: RwLock::new(reveal),
+            hint_requests: Mutex::new(Vec::new()),
+            started: Instant::now(),
+        })
+    }
 
-            // 800*600 (4:3)
-            if w * h > 480_000 {
-                img = img.thumbnail(800, 600);
-            }
+    pub(super) async fn load_image(
+        path: PathBuf,
+        mapset_id: i32,
+        effects: Effects,
+    ) -> Result<DynamicImage> {
+        let bytes = fs::read(path)
+            .await
+            .wrap_err_with(|| format!("failed to read bg image for mapset {mapset_id}"))?;
 
-            if effects.contains(Effects::Invert) {
-                img.invert();
-            }
+        let mut img =
+            image::load_from_memory(&bytes).wrap_err("failed to load image from memory")?;
 
-            if effects.contains(Effects::Contrast) {
-                colorops::contrast_in_place(&mut img, 18.0);
-            }
+        let (w, h) = img.dimensions();
 
-            if effects.contains(Effects::FlipHorizontal) {
-                imageops::flip_horizontal_in_place(&mut img);
-            }
+        // 800*600 (4:3)
+        if w * h > 480_000 {
+            img = img.thumbnail(800, 600);
+        }
 
-            if effects.contains(Effects::FlipVertical) {
-                imageops::flip_vertical_in_place(&mut img);
-            }
+        if effects.contains(Effects::Invert) {
+            img.invert();
+        }
 
-            if effects.contains(Effects::Grayscale) {
-                img = img.grayscale();
-            }
+        if effects.contains(Effects::Contrast) {
+            colorops::contrast_in_place(&mut img, 18.0);
+        }
 
-            if effects.contains(Effects::Blur) {
-                img = img.blur(4.0);
-            }
+        if effects.contains(Effects::FlipHorizontal) {
+            imageops::flip_horizontal_in_place(&mut img);
+        }
 
-            Ok(img)
-        };
+        if effects.contains(Effects::FlipVertical) {
+            imageops::flip_vertical_in_place(&mut img);
+        }
 
-        let (mapset_, img) = tokio::try_join!(GameMapset::new(mapset.mapset_id as u32), img_fut)?;
+        if effects.contains(Effects::Grayscale) {
+            img = img.grayscale();
+        }
 
-        Ok(Self {
-            hints: RwLock::new(Hints::new(mapset_.title())),
-            difficulty: difficulty.factor(),
-            mapset: mapset_,
-            reveal: RwLock::new(ImageReveal::new(img)),
-        })
+        if effects.contains(Effects::Blur) {
+            img = img.blur(4.0);
+        }
+
+        Ok(img)
     }
 
-    pub fn sub_image(&self) -> Result<Vec<u8>> {
+    /// Reveal more of the image or audio.
+    pub fn reveal_more(&self) -> Result<Vec<u8>> {
         let mut reveal = self.reveal.write().unwrap();
-        reveal.increase_radius();
+        reveal.increase();
 
-        reveal.sub_image()
+        reveal.sub_media()
     }
 
-    pub fn hint(&self) -> String {
+    pub fn hint(&self, user: Id<UserMarker>) -> String {
+        self.hint_requests.lock().unwrap().push(user);
         let mut hints = self.hints.write().unwrap();
 
         hints.get(self.mapset.title(), self.mapset.artist())
@@ -145,6 +191,11 @@ impl Game {
         self.mapset.mapset_id
     }
 
+    /// Takes the users that requested hints since the last call.
+    pub fn take_hint_requests(&self) -> Vec<Id<UserMarker>> {
+        mem::take(&mut *self.hint_requests.lock().unwrap())
+    }
+
     fn check_msg_content(&self, content: &str) -> ContentResult {
         match self.mapset.matches_title(content, self.difficulty) {
             Some(true) => return ContentResult::Title(true),
@@ -164,9 +215,34 @@ impl Game {
     }
 }
 
+enum Reveal {
+    Image(ImageReveal),
+    Audio(AudioReveal),
+}
+
+impl Reveal {
+    fn increase(&mut self) {
+        match self {
+            Self::Image(reveal) => reveal.increase_radius(),
+            Self::Audio(reveal) => reveal.increase_length(),
+        }
+    }
+
+    fn sub_media(&self) -> Result<Vec<u8>> {
+        match self {
+            Self::Image(reveal) => reveal.sub_image(),
+            Self::Audio(reveal) => reveal.sub_clip(),
+        }
+    }
+}
+
 #[derive(Clone, Copy)]
 pub enum LoopResult {
-    Winner(Id<UserMarker>),
+    Winner {
+        user: Id<UserMarker>,
+        /// Time from showing the background until the correct guess
+        guess_time: Duration,
+    },
     Restart,
     Stop,
 }
@@ -201,7 +277,10 @@ pub async fn game_loop(
                     warn!(?err, "Error while sending msg for winner");
                 }
 
-                return LoopResult::Winner(msg.author.id);
+                return LoopResult::Winner {
+                    user: msg.author.id,
+                    guess_time: game.started.elapsed(),
+                };
             }
             // Artist correct?
             ContentResult::Artist(exact) => {
@@ -4,7 +4,7 @@ use std::{
     sync::Arc,
 };
 
-use bathbot_model::Effects;
+use bathbot_model::{BgGameSeasonScore, Effects};
 use bathbot_psql::model::games::MapsetTagsEntries;
 use bathbot_util::{constants::OSU_BASE, IntHasher, MessageBuilder};
 use eyre::Result;
@@ -17,11 +17,18 @@ use tokio::{
 };
 use twilight_model::{
     gateway::payload::incoming::MessageCreate,
-    id::{marker::ChannelMarker, Id},
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
 };
 
 use super::game::{game_loop, Game, LoopResult};
-use crate::{commands::fun::GameDifficulty, util::ChannelExt, Context};
+use crate::{
+    commands::fun::{BgGameKind, GameDifficulty},
+    util::ChannelExt,
+    Context,
+};
 
 const GAME_LEN: Duration = Duration::from_secs(180);
 
@@ -29,14 +36,17 @@ const GAME_LEN: Duration = Duration::from_secs(180);
 pub struct BackgroundGame {
     game: Arc<RwLock<Game>>,
     tx: UnboundedSender<LoopResult>,
+    kind: BgGameKind,
 }
 
 impl BackgroundGame {
     pub async fn new(
         channel: Id<ChannelMarker>,
+        guild: Option<Id<GuildMarker>>,
         entries: MapsetTagsEntries,
         effects: Effects,
         difficulty: GameDifficulty,
+        kind: BgGameKind,
     ) -> Self {
         let (tx, mut rx) = mpsc::unbounded_channel();
 
@@ -44,10 +54,14 @@ impl BackgroundGame {
             .wait_for_message_stream(channel, |event: &MessageCreate| !event.author.bot);
 
         let mut previous_ids = VecDeque::with_capacity(50);
-        let mut scores = HashMap::with_hasher(IntHasher);
+        let mut stats = GameStats::default();
+
+        // Only count stats if the pool of backgrounds is not too small
+        let ranked = entries.tags.len() >= 20;
 
         // Initialize game
-        let (game, mut img) = Game::new(&entries, &mut previous_ids, effects, difficulty).await;
+        let (game, mut media) =
+            Game::new(&entries, &mut previous_ids, effects, difficulty, kind).await;
         let game = Arc::new(RwLock::new(game));
         let game_clone = Arc::clone(&game);
 
@@ -55,7 +69,7 @@ impl BackgroundGame {
             loop {
                 let builder = MessageBuilder::new()
                     .content("Here's the next one:")
-                    .attachment("bg_img.png", mem::take(&mut img));
+                    .attachment(kind.attachment_name(), mem::take(&mut media));
 
                 if let Err(err) = channel.create_message(builder, None).await {
                     warn!(?err, "Failed to send initial bg game msg");
@@ -70,9 +84,16 @@ impl BackgroundGame {
                     _ = sleep(GAME_LEN) => LoopResult::Stop,
                 };
 
+                if ranked {
+                    let hint_requests = game_clone.read().await.take_hint_requests();
+                    stats.add_hints(hint_requests);
+                }
+
                 // Process the result
                 match result {
                     LoopResult::Restart => {
+                        stats.streak = None;
+
                         let mapset_id = game_clone.read().await.mapset_id();
 
                         // Send message
@@ -100,32 +121,46 @@ impl BackgroundGame {
                         }
 
                         // Store score for winners
-                        if let Err(err) = Context::games().bggame_increment_scores(&scores).await {
+                        if let Err(err) = Context::games()
+                            .bggame_increment_scores(&stats.scores())
+                            .await
+                        {
                             warn!("{err:?}");
                         }
 
+                        // Store season stats of the guild
+                        if let Some(guild) = guild.filter(|_| !stats.players.is_empty()) {
+                            let scores = stats.season_scores();
+                            let store_fut =
+                                Context::games().bggame_increment_guild_scores(guild, &scores);
+
+                            if let Err(err) = store_fut.await {
+                                warn!(?err, "Failed to store bg game season stats");
+                            }
+                        }
+
                         // Then quit
                         info!(%channel, "Game finished");
                         break;
                     }
-                    LoopResult::Winner(user_id) => {
-                        if entries.tags.len() >= 20 {
-                            *scores.entry(user_id).or_insert(0) += 1;
+                    LoopResult::Winner { user, guess_time } => {
+                        if ranked {
+                            stats.add_winner(user, guess_time);
                         }
                     }
                 }
 
                 // Initialize next game
-                let (game, img_) =
-                    Game::new(&entries, &mut previous_ids, effects, difficulty).await;
-                img = img_;
+                let (game, media_) =
+                    Game::new(&entries, &mut previous_ids, effects, difficulty, kind).await;
+                media = media_;
                 *game_clone.write().await = game;
             }
 
             Context::bg_games().write(&channel).await.remove();
         });
 
-        Self { game, tx }
+        Self { game, tx, kind }
     }
 
     pub fn stop(&self) -> Result<()> {
@@ -140,17 +175,80 @@ impl BackgroundGame {
             .map_err(|_| eyre!("Failed to send restart token"))
     }
 
-    pub async fn sub_image(&self) -> Result<Vec<u8>> {
+    /// Reveal more of the image or audio.
+    pub async fn reveal_more(&self) -> Result<Vec<u8>> {
         timeout(Duration::from_secs(1), self.game.read())
             .await?
-            .sub_image()
+            .reveal_more()
+    }
+
+    pub fn attachment_name(&self) -> &'static str {
+        self.kind.attachment_name()
     }
 
-    pub async fn hint(&self) -> Result<String> {
+    pub async fn hint(&self, user: Id<UserMarker>) -> Result<String> {
         let game = timeout(Duration::from_secs(1), self.game.read())
             .await
             .map_err(|_| eyre!("timeout while waiting for write"))?;
 
-        Ok(game.hint())
+        Ok(game.hint(user))
+    }
+}
+
+#[derive(Default)]
+struct PlayerStats {
+    guesses: u32,
+    guess_time: Duration,
+    hints: u32,
+    best_streak: u32,
+}
+
+#[derive(Default)]
+struct GameStats {
+    players: HashMap<Id<UserMarker>, PlayerStats, IntHasher>,
+    /// The user that guessed the latest backgrounds in a row
+    streak: Option<(Id<UserMarker>, u32)>,
+}
+
+impl GameStats {
+    fn add_winner(&mut self, user: Id<UserMarker>, guess_time: Duration) {
+        let streak = match self.streak {
+            Some((prev, streak)) if prev == user => streak + 1,
+            _ => 1,
+        };
+
+        self.streak = Some((user, streak));
+
+        let player = self.players.entry(user).or_default();
+        player.guesses += 1;
+        player.guess_time += guess_time;
+        player.best_streak = player.best_streak.max(streak);
+    }
+
+    fn add_hints(&mut self, hint_requests: Vec<Id<UserMarker>>) {
+        for user in hint_requests {
+            self.players.entry(user).or_default().hints += 1;
+        }
+    }
+
+    fn scores(&self) -> HashMap<Id<UserMarker>, u32, IntHasher> {
+        self.players
+            .iter()
+            .filter(|(_, player)| player.guesses > 0)
+            .map(|(user, player)| (*user, player.guesses))
+            .collect()
+    }
+
+    fn season_scores(&self) -> Vec<BgGameSeasonScore> {
+        self.players
+            .iter()
+            .map(|(user, player)| BgGameSeasonScore {
+                discord_id: user.get() as i64,
+                guesses: player.guesses as i32,
+                guess_time_ms: player.guess_time.as_millis() as i64,
+                hints: player.hints as i32,
+                best_streak: player.best_streak as i32,
+            })
+            .collect()
     }
 }
@@ -2,7 +2,7 @@ use std::io::Cursor;
 
 use eyre::{Result, WrapErr};
 use image::{DynamicImage, GenericImageView, ImageOutputFormat::Png};
-use rand::RngCore;
+use rand::{Rng, RngCore};
 
 pub struct ImageReveal {
     dim: (u32, u32),
@@ -14,9 +14,14 @@ pub struct ImageReveal {
 
 impl ImageReveal {
     pub fn new(original: DynamicImage) -> Self {
+        Self::with_rng(original, &mut rand::thread_rng())
+    }
+
+    /// Same as [`ImageReveal::new`] but the revealed position is determined
+    /// by the given rng, e.g. a seeded one.
+    pub fn with_rng(original: DynamicImage, rng: &mut impl Rng) -> Self {
         let (w, h) = original.dimensions();
         let radius = 100;
-        let mut rng = rand::thread_rng();
         let x = radius + rng.next_u32() % (w - 2 * radius);
         let y = radius + rng.next_u32() % (h - 2 * radius);
 
@@ -49,4 +54,17 @@ impl ImageReveal {
 
         Ok(cursor.into_inner())
     }
+
+    /// The whole image without anything hidden
+    pub fn full_image(&self) -> Result<Vec<u8>> {
+        let (w, h) = self.dim;
+        let png_bytes: Vec<u8> = Vec::with_capacity((w * h) as usize);
+
+        let mut cursor = Cursor::new(png_bytes);
+        self.original
+            .write_to(&mut cursor, Png)
+            .wrap_err("Failed to encode image")?;
+
+        Ok(cursor.into_inner())
+    }
 }
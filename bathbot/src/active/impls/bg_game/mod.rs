@@ -10,28 +10,32 @@ use twilight_model::{
         Component,
     },
     id::{
-        marker::{ChannelMarker, UserMarker},
+        marker::{ChannelMarker, GuildMarker, UserMarker},
         Id,
     },
 };
 
-pub use self::game_wrapper::BackgroundGame;
+pub use self::{daily::DailyChallenge, game_wrapper::BackgroundGame, tags::BackgroundTagging};
 use crate::{
     active::{BuildPage, ComponentResult, IActiveMessage},
-    commands::fun::GameDifficulty,
+    commands::fun::{BgGameKind, GameDifficulty},
     core::Context,
     util::{interaction::InteractionComponent, Authored, ComponentExt},
 };
 
+mod audio_reveal;
+mod daily;
 mod game;
 mod game_wrapper;
 mod hints;
 mod img_reveal;
 mod mapset;
+mod tags;
 mod util;
 
 pub struct BackgroundGameSetup {
     difficulty: GameDifficulty,
+    kind: BgGameKind,
     effects: Effects,
     excluded: MapsetTags,
     included: MapsetTags,
@@ -41,14 +45,15 @@ pub struct BackgroundGameSetup {
 
 impl IActiveMessage for BackgroundGameSetup {
     fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
-        if let SetupState::Ready { channel } = self.state {
-            return Box::pin(self.start(channel));
+        if let SetupState::Ready { channel, guild } = self.state {
+            return Box::pin(self.start(channel, guild));
         }
 
         let description = format!(
             "<@{}> select which tags should be included \
             and which ones should be excluded, then start the game. \
-            Only you can use the components below.",
+            Only you can use the components below.\n\
+            Toggle the mode to guess either based on the background or on a song snippet.",
             self.msg_owner,
         );
 
@@ -66,6 +71,10 @@ impl IActiveMessage for BackgroundGameSetup {
             fields![fields { "Effects", self.effects.join(", "), false }];
         }
 
+        if let BgGameKind::Song = self.kind {
+            fields![fields { "Mode", "Song snippets (effects are ignored)".to_owned(), false }];
+        }
+
         let embed = EmbedBuilder::new().description(description).fields(fields);
 
         BuildPage::new(embed, false).boxed()
@@ -269,6 +278,20 @@ impl IActiveMessage for BackgroundGameSetup {
             url: None,
         };
 
+        let kind_label = match self.kind {
+            BgGameKind::Background => "Mode: Background",
+            BgGameKind::Song => "Mode: Song",
+        };
+
+        let kind_button = Button {
+            custom_id: Some("bg_kind_button".to_owned()),
+            disabled: false,
+            emoji: None,
+            label: Some(kind_label.to_owned()),
+            style: ButtonStyle::Primary,
+            url: None,
+        };
+
         let cancel_button = Button {
             custom_id: Some("bg_cancel_button".to_owned()),
             disabled: false,
@@ -281,6 +304,7 @@ impl IActiveMessage for BackgroundGameSetup {
         let button_row = ActionRow {
             components: vec![
                 Component::Button(start_button),
+                Component::Button(kind_button),
                 Component::Button(cancel_button),
             ],
         };
@@ -310,9 +334,16 @@ impl IActiveMessage for BackgroundGameSetup {
             "bg_setup_include" => self.included = MapsetTags::from(&*component),
             "bg_setup_exclude" => self.excluded = MapsetTags::from(&*component),
             "bg_setup_effects" => self.effects = Effects::from(&*component),
+            "bg_kind_button" => {
+                self.kind = match self.kind {
+                    BgGameKind::Background => BgGameKind::Song,
+                    BgGameKind::Song => BgGameKind::Background,
+                }
+            }
             "bg_start_button" => {
                 self.state = SetupState::Ready {
                     channel: component.channel_id,
+                    guild: component.guild_id,
                 }
             }
             "bg_cancel_button" => return Box::pin(self.cancel(component)),
@@ -332,6 +363,7 @@ impl BackgroundGameSetup {
         Self {
             difficulty,
             msg_owner,
+            kind: BgGameKind::default(),
             effects: Effects::empty(),
             excluded: MapsetTags::empty(),
             included: MapsetTags::empty(),
@@ -339,7 +371,11 @@ impl BackgroundGameSetup {
         }
     }
 
-    async fn start(&mut self, channel: Id<ChannelMarker>) -> Result<BuildPage> {
+    async fn start(
+        &mut self,
+        channel: Id<ChannelMarker>,
+        guild: Option<Id<GuildMarker>>,
+    ) -> Result<BuildPage> {
         if let Some(game) = Context::bg_games().write(&channel).await.remove() {
             if let Err(err) = game.stop() {
                 warn!(?err, "Failed to stop previous game");
@@ -387,7 +423,10 @@ impl BackgroundGameSetup {
             "Effects", effects_value, true;
         ];
 
-        let footer = FooterBuilder::new(format!("Difficulty: {:?}", self.difficulty));
+        let footer = FooterBuilder::new(format!(
+            "Difficulty: {:?} • Mode: {:?}",
+            self.difficulty, self.kind
+        ));
         let title = format!("Selected tags ({} backgrounds)", entries.tags.len());
 
         let embed = EmbedBuilder::new()
@@ -406,7 +445,14 @@ impl BackgroundGameSetup {
                 "Starting game"
             );
 
-            let game_fut = BackgroundGame::new(channel, entries, self.effects, self.difficulty);
+            let game_fut = BackgroundGame::new(
+                channel,
+                guild,
+                entries,
+                self.effects,
+                self.difficulty,
+                self.kind,
+            );
 
             let game = game_fut.await;
             Context::bg_games().own(channel).await.insert(game);
@@ -490,5 +536,8 @@ impl From<&InteractionComponent> for Effects {
 #[derive(Copy, Clone)]
 enum SetupState {
     Ongoing,
-    Ready { channel: Id<ChannelMarker> },
+    Ready {
+        channel: Id<ChannelMarker>,
+        guild: Option<Id<GuildMarker>>,
+    },
 }
@@ -0,0 +1,366 @@
+use std::{fmt::Write, time::Duration};
+
+use bathbot_model::MapsetTags;
+use bathbot_psql::model::games::DbMapTag;
+use bathbot_util::{constants::OSU_BASE, EmbedBuilder, FooterBuilder, MessageBuilder};
+use eyre::{Report, Result};
+use futures::future::BoxFuture;
+use rosu_v2::prelude::GameMode;
+use tokio::fs;
+use twilight_model::{
+    channel::message::{
+        component::{ActionRow, Button, ButtonStyle},
+        Component,
+    },
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    active::{BuildPage, ComponentResult, IActiveMessage},
+    core::{BotConfig, Context},
+    util::{interaction::InteractionComponent, Authored, ComponentExt},
+};
+
+const TAGS: [(MapsetTags, &str, &str); 13] = [
+    (MapsetTags::Easy, "Easy", "easy"),
+    (MapsetTags::Hard, "Hard", "hard"),
+    (MapsetTags::Meme, "Meme", "meme"),
+    (MapsetTags::Weeb, "Weeb", "weeb"),
+    (MapsetTags::Kpop, "K-Pop", "kpop"),
+    (MapsetTags::Farm, "Farm", "farm"),
+    (MapsetTags::HardName, "Hard name", "hardname"),
+    (MapsetTags::Alternate, "Alternate", "alt"),
+    (MapsetTags::BlueSky, "Blue sky", "bluesky"),
+    (MapsetTags::English, "English", "english"),
+    (MapsetTags::Streams, "Streams", "streams"),
+    (MapsetTags::Old, "Old", "old"),
+    (MapsetTags::Tech, "Tech", "tech"),
+];
+
+pub struct BackgroundTagging {
+    mode: GameMode,
+    /// Mapsets that are yet to be reviewed
+    queue: Vec<DbMapTag>,
+    current: DbMapTag,
+    tags: MapsetTags,
+    /// Feedback about the previous action
+    note: Option<String>,
+    msg_owner: Id<UserMarker>,
+}
+
+impl IActiveMessage for BackgroundTagging {
+    fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
+        BuildPage::new(self.embed(), false).boxed()
+    }
+
+    fn build_components(&self) -> Vec<Component> {
+        let tag_buttons: Vec<_> = TAGS
+            .iter()
+            .map(|(tag, label, value)| {
+                let style = if self.tags.contains(*tag) {
+                    ButtonStyle::Success
+                } else {
+                    ButtonStyle::Secondary
+                };
+
+                Component::Button(Button {
+                    custom_id: Some(format!("bg_tag_{value}")),
+                    disabled: false,
+                    emoji: None,
+                    label: Some((*label).to_owned()),
+                    style,
+                    url: None,
+                })
+            })
+            .collect();
+
+        let mut components: Vec<_> = tag_buttons
+            .chunks(5)
+            .map(|chunk| {
+                Component::ActionRow(ActionRow {
+                    components: chunk.to_vec(),
+                })
+            })
+            .collect();
+
+        let control_button = |custom_id: &str, label: &str, style| {
+            Component::Button(Button {
+                custom_id: Some(custom_id.to_owned()),
+                disabled: false,
+                emoji: None,
+                label: Some(label.to_owned()),
+                style,
+                url: None,
+            })
+        };
+
+        let control_row = ActionRow {
+            components: vec![
+                control_button("bg_tags_save", "Save", ButtonStyle::Success),
+                control_button("bg_tags_skip", "Skip", ButtonStyle::Primary),
+                control_button("bg_tags_undo", "Undo", ButtonStyle::Secondary),
+                control_button("bg_tags_stop", "Stop", ButtonStyle::Danger),
+            ],
+        };
+
+        components.push(Component::ActionRow(control_row));
+
+        components
+    }
+
+    fn handle_component<'a>(
+        &'a mut self,
+        component: &'a mut InteractionComponent,
+    ) -> BoxFuture<'a, ComponentResult> {
+        let user_id = match component.user_id() {
+            Ok(user_id) => user_id,
+            Err(err) => return ComponentResult::Err(err).boxed(),
+        };
+
+        if user_id != self.msg_owner {
+            return ComponentResult::Ignore.boxed();
+        }
+
+        match component.data.custom_id.as_str() {
+            "bg_tags_save" => Box::pin(self.save(component)),
+            "bg_tags_skip" => {
+                self.note = Some(format!("Skipped mapset {}", self.current.mapset_id));
+
+                Box::pin(self.next_mapset(component))
+            }
+            "bg_tags_undo" => Box::pin(self.undo(component)),
+            "bg_tags_stop" => Box::pin(self.stop(component)),
+            other => {
+                let tag = other
+                    .strip_prefix("bg_tag_")
+                    .and_then(|value| TAGS.iter().find(|(_, _, tag)| *tag == value));
+
+                let Some((tag, ..)) = tag else {
+                    warn!(name = %other, ?component, "Unknown background tagging component");
+
+                    return ComponentResult::Ignore.boxed();
+                };
+
+                self.tags.toggle(*tag);
+
+                ComponentResult::BuildPage.boxed()
+            }
+        }
+    }
+
+    fn until_timeout(&self) -> Option<Duration> {
+        Some(Duration::from_secs(600))
+    }
+}
+
+impl BackgroundTagging {
+    /// Returns `None` if none of the mapsets' images could be loaded.
+    pub async fn new(
+        mode: GameMode,
+        mut queue: Vec<DbMapTag>,
+        msg_owner: Id<UserMarker>,
+    ) -> Option<(Self, Vec<u8>)> {
+        let (current, bytes) = Self::pop_mapset(mode, &mut queue).await?;
+
+        let tagging = Self {
+            mode,
+            queue,
+            tags: current.tags(),
+            current,
+            note: None,
+            msg_owner,
+        };
+
+        Some((tagging, bytes))
+    }
+
+    pub fn attachment_name(&self) -> String {
+        format!("bg_tags_{}", self.current.image_filename)
+    }
+
+    /// Pops mapsets off the queue until the image of one could be read.
+    async fn pop_mapset(mode: GameMode, queue: &mut Vec<DbMapTag>) -> Option<(DbMapTag, Vec<u8>)> {
+        while let Some(mapset) = queue.pop() {
+            if let Some(bytes) = Self::read_image(mode, &mapset).await {
+                return Some((mapset, bytes));
+            }
+        }
+
+        None
+    }
+
+    async fn read_image(mode: GameMode, mapset: &DbMapTag) -> Option<Vec<u8>> {
+        let mut path = BotConfig::get().paths.backgrounds.clone();
+
+        match mode {
+            GameMode::Osu => path.push("osu"),
+            GameMode::Mania => path.push("mania"),
+            GameMode::Taiko | GameMode::Catch => return None,
+        }
+
+        path.push(&mapset.image_filename);
+
+        match fs::read(path).await {
+            Ok(bytes) => Some(bytes),
+            Err(err) => {
+                warn!(
+                    mapset_id = mapset.mapset_id,
+                    ?err,
+                    "Failed to read background for tagging"
+                );
+
+                None
+            }
+        }
+    }
+
+    fn embed(&self) -> EmbedBuilder {
+        let mapset_id = self.current.mapset_id;
+
+        let mut description = format!(
+            "Toggle the tags that apply to this background, then save.\n\
+            **Tags:** {}",
+            self.tags.join(", ")
+        );
+
+        match self.current.reviewed_at {
+            Some(reviewed_at) => {
+                let _ = write!(
+                    description,
+                    "\nLast reviewed <t:{}:R>",
+                    reviewed_at.unix_timestamp()
+                );
+            }
+            None => description.push_str("\nNot reviewed yet"),
+        }
+
+        if let Some(ref note) = self.note {
+            let _ = write!(description, "\n\n*{note}*");
+        }
+
+        let footer = FooterBuilder::new(format!(
+            "Mode: {} • {} mapsets remaining",
+            self.mode,
+            self.queue.len()
+        ));
+
+        EmbedBuilder::new()
+            .description(description)
+            .footer(footer)
+            .title(format!("Background of mapset {mapset_id}"))
+            .url(format!("{OSU_BASE}beatmapsets/{mapset_id}"))
+    }
+
+    async fn save(&mut self, component: &InteractionComponent) -> ComponentResult {
+        let mapset_id = self.current.mapset_id as u32;
+        let entries = [(mapset_id, self.tags)];
+
+        if let Err(err) = Context::games()
+            .bggame_set_tags(self.msg_owner, &entries)
+            .await
+        {
+            return ComponentResult::Err(err);
+        }
+
+        self.note = Some(format!(
+            "Saved tags of mapset {mapset_id}: {}",
+            self.tags.join(", ")
+        ));
+
+        self.next_mapset(component).await
+    }
+
+    async fn undo(&mut self, component: &InteractionComponent) -> ComponentResult {
+        let reverted = match Context::games().bggame_undo_tags(self.msg_owner).await {
+            Ok(reverted) => reverted,
+            Err(err) => return ComponentResult::Err(err),
+        };
+
+        let Some(&mapset_id) = reverted.first() else {
+            self.note = Some("There are no changes to undo".to_owned());
+
+            return ComponentResult::BuildPage;
+        };
+
+        let mapset = match Context::games().bggame_mapset(mapset_id).await {
+            Ok(Some(mapset)) => mapset,
+            Ok(None) => return ComponentResult::BuildPage,
+            Err(err) => return ComponentResult::Err(err),
+        };
+
+        self.note = Some(match reverted.len() {
+            1 => format!("Reverted tags of mapset {mapset_id}"),
+            len => format!("Reverted tags of {len} mapsets"),
+        });
+
+        // Review the reverted mapset again if it's of the same mode
+        if mapset.gamemode != self.mode as i16 {
+            return ComponentResult::BuildPage;
+        }
+
+        let Some(bytes) = Self::read_image(self.mode, &mapset).await else {
+            return ComponentResult::BuildPage;
+        };
+
+        let prev = std::mem::replace(&mut self.current, mapset);
+        self.queue.push(prev);
+
+        self.show_current(component, bytes).await
+    }
+
+    /// Show the next mapset of the queue or finish if the queue is empty.
+    async fn next_mapset(&mut self, component: &InteractionComponent) -> ComponentResult {
+        let Some((mapset, bytes)) = Self::pop_mapset(self.mode, &mut self.queue).await else {
+            let description = match self.note.take() {
+                Some(note) => format!("{note}\n\nNo more mapsets to review"),
+                None => "No more mapsets to review".to_owned(),
+            };
+
+            let embed = EmbedBuilder::new().description(description);
+            let builder = MessageBuilder::new().embed(embed).components(Vec::new());
+
+            return Self::respond(component, builder).await;
+        };
+
+        self.current = mapset;
+
+        self.show_current(component, bytes).await
+    }
+
+    async fn show_current(
+        &mut self,
+        component: &InteractionComponent,
+        bytes: Vec<u8>,
+    ) -> ComponentResult {
+        self.tags = self.current.tags();
+
+        let builder = MessageBuilder::new()
+            .embed(self.embed())
+            .components(self.build_components())
+            .attachment(self.attachment_name(), bytes);
+
+        Self::respond(component, builder).await
+    }
+
+    async fn stop(&mut self, component: &InteractionComponent) -> ComponentResult {
+        let builder = MessageBuilder::new()
+            .embed(self.embed())
+            .components(Vec::new());
+
+        Self::respond(component, builder).await
+    }
+
+    async fn respond(
+        component: &InteractionComponent,
+        builder: MessageBuilder<'_>,
+    ) -> ComponentResult {
+        match component.callback(builder).await {
+            Ok(_) => ComponentResult::Ignore,
+            Err(err) => {
+                let wrap = "Failed to callback on background tagging component";
+
+                ComponentResult::Err(Report::new(err).wrap_err(wrap))
+            }
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::VecDeque;
 
 use bathbot_psql::model::games::{DbMapTagEntry, MapsetTagsEntries};
-use rand::Rng;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 pub fn get_random_mapset<'m>(
     entries: &'m MapsetTagsEntries,
@@ -25,3 +25,20 @@ pub fn get_random_mapset<'m>(
         }
     }
 }
+
+/// Pick a mapset based on the seed so that the same seed and entries always
+/// result in the same mapset.
+pub fn get_seeded_mapset(entries: &MapsetTagsEntries, seed: u64) -> Option<&DbMapTagEntry> {
+    let mut sorted: Vec<_> = entries.tags.iter().collect();
+
+    // The database does not guarantee any order
+    sorted.sort_unstable_by_key(|entry| entry.mapset_id);
+
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let idx = StdRng::seed_from_u64(seed).gen_range(0..sorted.len());
+
+    Some(sorted[idx])
+}
@@ -13,16 +13,17 @@ use bathbot_util::{
     constants::{AVATAR_URL, OSU_BASE},
     datetime::SecToMinSec,
     fields,
+    modal::{ModalBuilder, TextInputBuilder},
     numbers::round,
     EmbedBuilder, FooterBuilder, IntHasher, MessageOrigin,
 };
-use eyre::{Report, Result, WrapErr};
+use eyre::{ContextCompat, Report, Result, WrapErr};
 use futures::future::BoxFuture;
 use rosu_pp::{Beatmap, Difficulty, Performance};
 use rosu_v2::prelude::{GameMode, Username};
 use twilight_model::{
     channel::message::{
-        component::{ActionRow, Button, ButtonStyle},
+        component::{ActionRow, Button, ButtonStyle, TextInputStyle},
         Component,
     },
     id::{marker::UserMarker, Id},
@@ -36,7 +37,10 @@ use crate::{
     },
     core::Context,
     manager::redis::{osu::UserArgs, RedisData},
-    util::{interaction::InteractionComponent, Authored, ComponentExt, Emote},
+    util::{
+        interaction::{InteractionComponent, InteractionModal},
+        Authored, ComponentExt, Emote,
+    },
 };
 
 #[derive(PaginationBuilder)]
@@ -48,6 +52,8 @@ pub struct BookmarksPagination {
     defer_next: bool,
     filtered_maps: Option<bool>,
     confirm_remove: Option<bool>,
+    /// Bookmarks of a folder shared by another user can't be modified
+    read_only: Option<bool>,
     token: String,
     msg_owner: Id<UserMarker>,
     content: String,
@@ -267,6 +273,10 @@ impl BookmarksPagination {
             GameMode::Catch => {}
         }
 
+        if let Some(ref note) = map.note {
+            let _ = write!(description, "\n:pencil: *{note}*");
+        }
+
         let embed = EmbedBuilder::new()
             .description(description)
             .fields(fields)
@@ -284,7 +294,7 @@ impl BookmarksPagination {
             Err(err) => return ComponentResult::Err(err),
         };
 
-        if owner != self.msg_owner {
+        if owner != self.msg_owner || self.read_only.unwrap_or(false) {
             return ComponentResult::Ignore;
         }
 
@@ -308,6 +318,35 @@ impl BookmarksPagination {
         ComponentResult::BuildPage
     }
 
+    async fn handle_note_modal(&mut self, modal: &InteractionModal) -> Result<()> {
+        if modal.user_id()? != self.msg_owner || self.read_only.unwrap_or(false) {
+            return Ok(());
+        }
+
+        let input = modal
+            .data
+            .components
+            .first()
+            .and_then(|row| row.components.first())
+            .and_then(|component| component.value.as_deref())
+            .map(str::trim)
+            .wrap_err("Missing bookmark note modal input")?;
+
+        let note = (!input.is_empty()).then_some(input);
+
+        let Some(bookmark) = self.bookmarks.get_mut(self.pages.index()) else {
+            return Ok(());
+        };
+
+        Context::bookmarks()
+            .set_note(self.msg_owner, bookmark.map_id, note)
+            .await?;
+
+        bookmark.note = note.map(Box::from);
+
+        Ok(())
+    }
+
     pub fn set_index(&mut self, index: usize) {
         self.pages.set_index(index);
     }
@@ -341,6 +380,35 @@ impl IActiveMessage for BookmarksPagination {
             url: None,
         };
 
+        let single_step = Button {
+            custom_id: Some("pagination_step".to_owned()),
+            disabled: self.pages.index() == self.pages.last_index(),
+            emoji: Some(Emote::SingleStep.reaction_type()),
+            label: None,
+            style: ButtonStyle::Secondary,
+            url: None,
+        };
+
+        let jump_end = Button {
+            custom_id: Some("pagination_end".to_owned()),
+            disabled: self.pages.index() == self.pages.last_index(),
+            emoji: Some(Emote::JumpEnd.reaction_type()),
+            label: None,
+            style: ButtonStyle::Secondary,
+            url: None,
+        };
+
+        if self.read_only.unwrap_or(false) {
+            let components = vec![
+                Component::Button(jump_start),
+                Component::Button(single_step_back),
+                Component::Button(single_step),
+                Component::Button(jump_end),
+            ];
+
+            return vec![Component::ActionRow(ActionRow { components })];
+        }
+
         let remove = if self.confirm_remove.is_some_and(identity) {
             Button {
                 custom_id: Some("bookmarks_confirm_remove".to_owned()),
@@ -361,24 +429,6 @@ impl IActiveMessage for BookmarksPagination {
             }
         };
 
-        let single_step = Button {
-            custom_id: Some("pagination_step".to_owned()),
-            disabled: self.pages.index() == self.pages.last_index(),
-            emoji: Some(Emote::SingleStep.reaction_type()),
-            label: None,
-            style: ButtonStyle::Secondary,
-            url: None,
-        };
-
-        let jump_end = Button {
-            custom_id: Some("pagination_end".to_owned()),
-            disabled: self.pages.index() == self.pages.last_index(),
-            emoji: Some(Emote::JumpEnd.reaction_type()),
-            label: None,
-            style: ButtonStyle::Secondary,
-            url: None,
-        };
-
         let components = vec![
             Component::Button(jump_start),
             Component::Button(single_step_back),
@@ -387,7 +437,21 @@ impl IActiveMessage for BookmarksPagination {
             Component::Button(jump_end),
         ];
 
-        vec![Component::ActionRow(ActionRow { components })]
+        let note = Button {
+            custom_id: Some("bookmarks_note".to_owned()),
+            disabled: false,
+            emoji: None,
+            label: Some("Edit note".to_owned()),
+            style: ButtonStyle::Secondary,
+            url: None,
+        };
+
+        vec![
+            Component::ActionRow(ActionRow { components }),
+            Component::ActionRow(ActionRow {
+                components: vec![Component::Button(note)],
+            }),
+        ]
     }
 
     fn handle_component<'a>(
@@ -403,6 +467,34 @@ impl IActiveMessage for BookmarksPagination {
                 Box::pin(ready(ComponentResult::BuildPage))
             }
             "bookmarks_confirm_remove" => Box::pin(self.handle_remove(component)),
+            "bookmarks_note" => {
+                let is_owner = component
+                    .user_id()
+                    .is_ok_and(|user_id| user_id == self.msg_owner);
+
+                if !is_owner || self.read_only.unwrap_or(false) {
+                    return Box::pin(ready(ComponentResult::Ignore));
+                }
+
+                let mut input = TextInputBuilder::new("bookmark_note", "Note")
+                    .max_len(256)
+                    .placeholder("Leave empty to remove the note")
+                    .required(false)
+                    .style(TextInputStyle::Paragraph);
+
+                let note = self
+                    .bookmarks
+                    .get(self.pages.index())
+                    .and_then(|bookmark| bookmark.note.as_deref());
+
+                if let Some(note) = note {
+                    input = input.value(note);
+                }
+
+                let modal = ModalBuilder::new("bookmark_note", "Bookmark note").input(input);
+
+                Box::pin(ready(ComponentResult::CreateModal(modal)))
+            }
             _ => {
                 self.defer_next = true;
 
@@ -411,6 +503,13 @@ impl IActiveMessage for BookmarksPagination {
         }
     }
 
+    fn handle_modal<'a>(
+        &'a mut self,
+        modal: &'a mut InteractionModal,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.handle_note_modal(modal))
+    }
+
     fn until_timeout(&self) -> Option<Duration> {
         (!self.bookmarks.is_empty()).then_some(Duration::from_secs(60))
     }
@@ -0,0 +1,102 @@
+use std::fmt::Write;
+
+use bathbot_macros::PaginationBuilder;
+use bathbot_psql::model::configs::GuildSkin;
+use bathbot_util::{CowUtils, EmbedBuilder, FooterBuilder};
+use eyre::Result;
+use futures::future::BoxFuture;
+use twilight_model::{
+    channel::message::Component,
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    active::{
+        pagination::{handle_pagination_component, handle_pagination_modal, Pages},
+        BuildPage, ComponentResult, IActiveMessage,
+    },
+    util::interaction::{InteractionComponent, InteractionModal},
+};
+
+#[derive(PaginationBuilder)]
+pub struct GuildSkinsPagination {
+    #[pagination(per_page = 10)]
+    entries: Box<[GuildSkin]>,
+    content: Box<str>,
+    msg_owner: Id<UserMarker>,
+    pages: Pages,
+}
+
+impl IActiveMessage for GuildSkinsPagination {
+    fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
+        let pages = &self.pages;
+        let idx = pages.index();
+        let end_idx = self.entries.len().min(idx + pages.per_page());
+        let entries = &self.entries[idx..end_idx];
+
+        let mut description = String::with_capacity(entries.len() * 100);
+
+        for (skin, i) in entries.iter().zip(idx + 1..) {
+            let _ = write!(
+                description,
+                "**#{i} [{name}]({url})**",
+                name = skin.name.cow_escape_markdown(),
+                url = skin.url,
+            );
+
+            if let Some(ref author) = skin.author {
+                let _ = write!(description, " by {}", author.cow_escape_markdown());
+            }
+
+            if skin.is_default {
+                description.push_str(" • *Default*");
+            }
+
+            description.push('\n');
+
+            if let Some(id) = skin.ordr_skin_id {
+                let _ = write!(description, "o!rdr id: `{id}` • ");
+            }
+
+            let _ = writeln!(
+                description,
+                "Added by <@{user}> <t:{timestamp}:d>",
+                user = skin.added_by,
+                timestamp = skin.added_at.unix_timestamp(),
+            );
+        }
+
+        let page = pages.curr_page();
+        let pages = pages.last_page();
+        let footer = FooterBuilder::new(format!(
+            "Page {page}/{pages} • Use /skin guild show to see a preview"
+        ));
+
+        let embed = EmbedBuilder::new()
+            .description(description)
+            .footer(footer)
+            .title("Skins of this server:");
+
+        BuildPage::new(embed, false)
+            .content(self.content.clone())
+            .boxed()
+    }
+
+    fn build_components(&self) -> Vec<Component> {
+        self.pages.components()
+    }
+
+    fn handle_component<'a>(
+        &'a mut self,
+        component: &'a mut InteractionComponent,
+    ) -> BoxFuture<'a, ComponentResult> {
+        handle_pagination_component(component, self.msg_owner, false, &mut self.pages)
+    }
+
+    fn handle_modal<'a>(
+        &'a mut self,
+        modal: &'a mut InteractionModal,
+    ) -> BoxFuture<'a, Result<()>> {
+        handle_pagination_modal(modal, self.msg_owner, false, &mut self.pages)
+    }
+}
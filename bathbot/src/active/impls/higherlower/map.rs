@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+
+use bathbot_util::{
+    constants::OSU_BASE,
+    numbers::{round, WithComma},
+    EmbedBuilder, IntHasher,
+};
+use eyre::{ContextCompat, Result, WrapErr};
+use futures::{future::BoxFuture, FutureExt};
+use rosu_v2::prelude::GameMode;
+use twilight_model::channel::message::embed::EmbedField;
+
+use super::state::{
+    covers_image, random_top_scores, score_idx, side_by_side_image, HigherLowerState,
+};
+use crate::core::Context;
+
+#[derive(Copy, Clone)]
+pub(super) enum MapStat {
+    Stars,
+    Favourites,
+}
+
+pub(super) struct MapEntry {
+    pub stars: f32,
+    pub favourites: u32,
+    mapset_id: u32,
+    /// Map id for [`MapStat::Stars`] and mapset id for
+    /// [`MapStat::Favourites`]
+    id: u32,
+    map_string: Box<str>,
+    creator: Box<str>,
+}
+
+impl MapEntry {
+    pub(super) async fn random(
+        mode: GameMode,
+        stat: MapStat,
+        prev: Option<&Self>,
+        curr_score: u32,
+    ) -> Result<Self> {
+        let mut scores = random_top_scores(mode).await?;
+
+        match stat {
+            // Skip converts since their stars are not those of the original map
+            MapStat::Stars => {
+                scores.retain(|score| score.map.as_ref().is_some_and(|map| !map.convert))
+            }
+            // Top scores commonly share mapsets so there's no point in keeping
+            // duplicates around
+            MapStat::Favourites => {
+                let mut seen = HashSet::with_capacity_and_hasher(scores.len(), IntHasher);
+
+                scores.retain(|score| {
+                    score
+                        .mapset
+                        .as_ref()
+                        .is_some_and(|mapset| seen.insert(mapset.mapset_id))
+                });
+            }
+        }
+
+        match stat {
+            MapStat::Stars => {
+                let prev_stars = prev.map_or(0.0, |prev| prev.stars);
+
+                scores.sort_unstable_by(|a, b| {
+                    let a_stars = (a.map.as_ref().map_or(0.0, |map| map.stars) - prev_stars).abs();
+                    let b_stars = (b.map.as_ref().map_or(0.0, |map| map.stars) - prev_stars).abs();
+
+                    a_stars.total_cmp(&b_stars)
+                });
+            }
+            MapStat::Favourites => {
+                let prev_favourites = prev.map_or(0, |prev| prev.favourites);
+
+                scores.sort_unstable_by_key(|score| {
+                    score.mapset.as_ref().map_or(u32::MAX, |mapset| {
+                        mapset.favourite_count.abs_diff(prev_favourites)
+                    })
+                });
+            }
+        }
+
+        let idx = score_idx(scores.len(), curr_score).wrap_err("Missing top scores")?;
+        let score = scores.swap_remove(idx);
+
+        let map = score.map.wrap_err("Missing map in score")?;
+        let mapset = score.mapset.wrap_err("Missing mapset in score")?;
+
+        let (id, map_string) = match stat {
+            MapStat::Stars => (
+                map.map_id,
+                format!(
+                    "[{artist} - {title} [{version}]]({OSU_BASE}b/{map_id})",
+                    artist = mapset.artist,
+                    title = mapset.title,
+                    version = map.version,
+                    map_id = map.map_id,
+                ),
+            ),
+            MapStat::Favourites => (
+                mapset.mapset_id,
+                format!(
+                    "[{artist} - {title}]({OSU_BASE}s/{mapset_id})",
+                    artist = mapset.artist,
+                    title = mapset.title,
+                    mapset_id = mapset.mapset_id,
+                ),
+            ),
+        };
+
+        Ok(Self {
+            stars: round(map.stars),
+            favourites: mapset.favourite_count,
+            mapset_id: mapset.mapset_id,
+            id,
+            map_string: map_string.into_boxed_str(),
+            creator: mapset.creator_name.as_str().into(),
+        })
+    }
+
+    pub(super) fn image(
+        previous: &Self,
+        next: &Self,
+        stat: MapStat,
+    ) -> BoxFuture<'static, Result<String>> {
+        let mapset_id1 = previous.mapset_id;
+        let mapset_id2 = next.mapset_id;
+
+        match stat {
+            MapStat::Stars => {
+                let content = format!("Stars: {mapset_id1} ~ {mapset_id2}");
+
+                covers_image(mapset_id1, mapset_id2, content).boxed()
+            }
+            MapStat::Favourites => {
+                let content = format!("Favourites: {mapset_id1} ~ {mapset_id2}");
+
+                thumbnails_image(mapset_id1, mapset_id2, content).boxed()
+            }
+        }
+    }
+
+    pub(super) fn log(game1: &Self, game2: &Self) {
+        debug!(
+            "{}★ ({} favourites) vs {}★ ({} favourites)",
+            game1.stars, game1.favourites, game2.stars, game2.favourites
+        );
+    }
+
+    pub(super) fn to_embed(
+        previous: &Self,
+        next: &Self,
+        stat: MapStat,
+        revealed: bool,
+    ) -> EmbedBuilder {
+        let fields = vec![
+            EmbedField {
+                inline: false,
+                name: "__Previous:__".to_owned(),
+                value: previous.map_string(stat, true),
+            },
+            EmbedField {
+                inline: false,
+                name: "__Next:__".to_owned(),
+                value: next.map_string(stat, revealed),
+            },
+        ];
+
+        EmbedBuilder::new().fields(fields)
+    }
+
+    fn map_string(&self, stat: MapStat, visible: bool) -> String {
+        let value = match (stat, visible) {
+            (MapStat::Stars, true) => format!("**{}★**", self.stars),
+            (MapStat::Stars, false) => "**???★**".to_owned(),
+            (MapStat::Favourites, true) => {
+                format!("**{}** :heart:", WithComma::new(self.favourites))
+            }
+            (MapStat::Favourites, false) => "**???** :heart:".to_owned(),
+        };
+
+        format!(
+            "**{map}**\nMapped by {creator} • {value}",
+            map = self.map_string,
+            creator = self.creator,
+        )
+    }
+}
+
+impl PartialEq for MapEntry {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+/// Puts the square thumbnails of both mapsets next to each other.
+async fn thumbnails_image(mapset_id1: u32, mapset_id2: u32, content: String) -> Result<String> {
+    let thumbnail1 = mapset_thumbnail(mapset_id1);
+    let thumbnail2 = mapset_thumbnail(mapset_id2);
+
+    let client = Context::client();
+
+    let (thumbnail_left, thumbnail_right) = tokio::try_join!(
+        client.get_mapset_cover(&thumbnail1),
+        client.get_mapset_cover(&thumbnail2),
+    )
+    .wrap_err("Failed to retrieve some thumbnail")?;
+
+    let thumbnail_left = image::load_from_memory(&thumbnail_left)
+        .wrap_err("Failed to load left thumbnail from memory")?;
+
+    let thumbnail_right = image::load_from_memory(&thumbnail_right)
+        .wrap_err("Failed to load right thumbnail from memory")?;
+
+    let img = side_by_side_image(&thumbnail_left, &thumbnail_right);
+
+    HigherLowerState::upload_image(img.as_raw(), content).await
+}
+
+fn mapset_thumbnail(mapset_id: u32) -> String {
+    format!("https://assets.ppy.sh/beatmaps/{mapset_id}/covers/list@2x.jpg")
+}
@@ -0,0 +1,135 @@
+use std::future::Future;
+
+use bathbot_model::OsekaiMedal;
+use bathbot_util::{numbers::round, EmbedBuilder};
+use eyre::{ContextCompat, Result, WrapErr};
+use rand::Rng;
+use twilight_model::channel::message::embed::EmbedField;
+
+use super::state::{side_by_side_image, HigherLowerState};
+use crate::core::Context;
+
+pub(super) struct MedalRarity {
+    pub rarity: f32,
+    pub icon_url: Box<str>,
+    medal_id: u32,
+    name: Box<str>,
+    group: &'static str,
+    description: Box<str>,
+}
+
+impl MedalRarity {
+    pub(super) async fn random(prev: Option<&Self>, curr_score: u32) -> Result<Self> {
+        let mut medals = Context::redis()
+            .medals()
+            .await
+            .wrap_err("Failed to get cached medals")?
+            .into_original();
+
+        if let Some(prev) = prev {
+            medals.sort_unstable_by(|a, b| {
+                let a_rarity = (a.rarity - prev.rarity).abs();
+                let b_rarity = (b.rarity - prev.rarity).abs();
+
+                a_rarity.total_cmp(&b_rarity)
+            });
+
+            // The better the current score, the closer the rarities
+            let len = (medals.len() >> curr_score.min(4)).max(5);
+            medals.truncate(len);
+        }
+
+        if medals.is_empty() {
+            bail!("No medals available");
+        }
+
+        let idx = rand::thread_rng().gen_range(0..medals.len());
+
+        Ok(Self::from(medals.swap_remove(idx)))
+    }
+
+    pub(super) fn image(
+        previous: &Self,
+        next: &Self,
+    ) -> impl Future<Output = Result<String>> + Send + 'static {
+        let icon1 = previous.icon_url.clone();
+        let icon2 = next.icon_url.clone();
+        let content = format!("Medal: {} ~ {}", previous.medal_id, next.medal_id);
+
+        async move {
+            let client = Context::client();
+
+            let (icon_left, icon_right) = tokio::try_join!(
+                client.get_osekai_medal_icon(&icon1),
+                client.get_osekai_medal_icon(&icon2),
+            )
+            .wrap_err("Failed to retrieve some medal icon")?;
+
+            let icon_left = image::load_from_memory(&icon_left)
+                .wrap_err("Failed to load left icon from memory")?;
+
+            let icon_right = image::load_from_memory(&icon_right)
+                .wrap_err("Failed to load right icon from memory")?;
+
+            let img = side_by_side_image(&icon_left, &icon_right);
+
+            HigherLowerState::upload_image(img.as_raw(), content).await
+        }
+    }
+
+    pub(super) fn log(game1: &Self, game2: &Self) {
+        debug!("{}% vs {}%", game1.rarity, game2.rarity);
+    }
+
+    pub(super) fn to_embed(previous: &Self, next: &Self, revealed: bool) -> EmbedBuilder {
+        let fields = vec![
+            EmbedField {
+                inline: false,
+                name: format!("__Previous:__ {}", previous.name),
+                value: previous.medal_string(true),
+            },
+            EmbedField {
+                inline: false,
+                name: format!("__Next:__ {}", next.name),
+                value: next.medal_string(revealed),
+            },
+        ];
+
+        EmbedBuilder::new().fields(fields)
+    }
+
+    fn medal_string(&self, rarity_visible: bool) -> String {
+        let rarity = if rarity_visible {
+            format!("{}%", self.rarity)
+        } else {
+            "???%".to_owned()
+        };
+
+        format!(
+            "*{description}*\n{group} • Owned by **{rarity}** of players",
+            description = self.description,
+            group = self.group,
+        )
+    }
+}
+
+impl From<OsekaiMedal> for MedalRarity {
+    #[inline]
+    fn from(medal: OsekaiMedal) -> Self {
+        Self {
+            rarity: round(medal.rarity),
+            icon_url: medal.icon_url,
+            medal_id: medal.medal_id,
+            name: medal.name,
+            group: medal.grouping.as_str(),
+            description: medal.description,
+        }
+    }
+}
+
+impl PartialEq for MedalRarity {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.medal_id == other.medal_id
+    }
+}
@@ -8,7 +8,7 @@ use bathbot_util::{AuthorBuilder, EmbedBuilder, MessageBuilder};
 use eyre::{Result, WrapErr};
 use futures::future::BoxFuture;
 use rosu_v2::prelude::GameMode;
-use time::OffsetDateTime;
+use time::{Date, OffsetDateTime};
 use tokio::sync::oneshot::Receiver;
 use twilight_model::{
     channel::message::{
@@ -21,11 +21,16 @@ use twilight_model::{
 
 use self::state::{ButtonState, HigherLowerState};
 use crate::{
-    active::{response::ActiveResponse, BuildPage, ComponentResult, IActiveMessage},
+    active::{
+        impls::DailyChallenge, response::ActiveResponse, BuildPage, ComponentResult, IActiveMessage,
+    },
     core::Context,
     util::{interaction::InteractionComponent, Authored, ComponentExt, Emote},
 };
 
+mod map;
+mod medal_rarity;
+mod player;
 mod score_pp;
 mod state;
 
@@ -37,6 +42,8 @@ pub struct HigherLowerGame {
     highscore: u32,
     buttons: ButtonState,
     msg_owner: Id<UserMarker>,
+    /// Day of the daily game, if this is one
+    daily: Option<Date>,
 }
 
 impl IActiveMessage for HigherLowerGame {
@@ -99,9 +106,13 @@ impl IActiveMessage for HigherLowerGame {
 }
 
 impl HigherLowerGame {
-    pub async fn new_score_pp(mode: GameMode, msg_owner: Id<UserMarker>) -> Result<Self> {
-        let game_fut = HigherLowerState::start_score_pp(mode);
-        let highscore_fut = Context::games().higherlower_highscore(msg_owner, HlVersion::ScorePp);
+    pub async fn new(
+        version: HlVersion,
+        mode: GameMode,
+        msg_owner: Id<UserMarker>,
+    ) -> Result<Self> {
+        let game_fut = HigherLowerState::start(version, mode);
+        let highscore_fut = Context::games().higherlower_highscore(msg_owner, version);
 
         let ((state, rx), highscore) = tokio::try_join!(game_fut, highscore_fut)?;
 
@@ -113,20 +124,58 @@ impl HigherLowerGame {
             highscore,
             buttons: ButtonState::HigherLower,
             msg_owner,
+            daily: None,
+        })
+    }
+
+    /// Score pp game of the given day in which everyone gets the same chain of
+    /// scores and only has a single attempt.
+    pub async fn new_daily(day: Date, msg_owner: Id<UserMarker>) -> Result<Self> {
+        let (state, rx) = HigherLowerState::start_daily_score_pp(day).await?;
+
+        Ok(Self {
+            state,
+            revealed: false,
+            img_url_rx: Some(rx),
+            current_score: 0,
+            highscore: 0,
+            buttons: ButtonState::HigherLower,
+            msg_owner,
+            daily: Some(day),
         })
     }
 
+    /// Spoiler-free summary of a daily game that can be shared
+    pub fn daily_share_string(day: Date, score: u32) -> String {
+        let mut share = format!(
+            "osu! higherlower daily #{}: {score}\n",
+            DailyChallenge::number_of(day)
+        );
+
+        for _ in 0..score {
+            share.push('🟩');
+        }
+
+        share.push('🟥');
+
+        share
+    }
+
     async fn async_build_page(&mut self) -> Result<BuildPage> {
         let mut embed = self.state.to_embed(self.revealed);
 
+        if let Some(day) = self.daily {
+            let title = format!(
+                "Higher or Lower Daily #{}: Score PP",
+                DailyChallenge::number_of(day)
+            );
+
+            embed = embed.title(title);
+        }
+
         let deferred = match self.buttons {
             ButtonState::HigherLower => {
-                let footer = format!(
-                    "Current score: {} • Highscore: {}",
-                    self.current_score, self.highscore
-                );
-
-                embed = embed.footer(footer);
+                embed = embed.footer(self.score_footer());
 
                 match self.img_url_rx.take() {
                     Some(rx) => match rx.await {
@@ -143,9 +192,8 @@ impl HigherLowerGame {
                 last_guess,
             } => {
                 let footer = format!(
-                    "Current score: {} • Highscore: {} • \
-                    {last_guess} was correct, press Next to continue",
-                    self.current_score, self.highscore
+                    "{} • {last_guess} was correct, press Next to continue",
+                    self.score_footer()
                 );
 
                 embed = embed.footer(footer);
@@ -172,7 +220,16 @@ impl HigherLowerGame {
                     embed = embed.image(image);
                 }
 
-                let value = if self.new_highscore().await? {
+                let value = if let Some(day) = self.daily {
+                    self.store_daily(day).await?;
+
+                    format!(
+                        "You achieved a score of {} in today's daily, \
+                        the next one starts tomorrow.\n```\n{}\n```",
+                        self.current_score,
+                        Self::daily_share_string(day, self.current_score),
+                    )
+                } else if self.new_highscore().await? {
                     format!(
                         "You achieved a total score of {}, your new personal best :tada:",
                         self.current_score
@@ -209,9 +266,15 @@ impl HigherLowerGame {
             None => return Err(eyre!("Lacking permission to disable components on timeout")),
         };
 
-        self.new_highscore()
-            .await
-            .wrap_err("Failed to update highscore on timeout")?;
+        if let Some(day) = self.daily {
+            self.store_daily(day)
+                .await
+                .wrap_err("Failed to store daily on timeout")?;
+        } else {
+            self.new_highscore()
+                .await
+                .wrap_err("Failed to update highscore on timeout")?;
+        }
 
         update_res.wrap_err("Failed to disable components")?;
 
@@ -238,6 +301,20 @@ impl HigherLowerGame {
 
             self.current_score += 1;
 
+            if let Some(day) = self.daily {
+                // Keep track of the progress in case the game ends abruptly
+                let store_fut = Context::games().daily_higherlower_store(
+                    self.msg_owner,
+                    day,
+                    self.current_score,
+                    false,
+                );
+
+                if let Err(err) = store_fut.await {
+                    warn!(?err, "Failed to store daily higherlower progress");
+                }
+            }
+
             self.buttons = ButtonState::Next {
                 image,
                 last_guess: guess,
@@ -266,6 +343,11 @@ impl HigherLowerGame {
     }
 
     async fn handle_try_again(&mut self, component: &mut InteractionComponent) -> ComponentResult {
+        // Daily games only have a single attempt
+        if self.daily.is_some() {
+            return ComponentResult::Ignore;
+        }
+
         let Some(embed) = component.message.embeds.pop() else {
             return ComponentResult::Err(eyre!("Missing embed in higherlower message"));
         };
@@ -337,6 +419,22 @@ impl HigherLowerGame {
         ComponentResult::BuildPage
     }
 
+    fn score_footer(&self) -> String {
+        match self.daily {
+            Some(_) => format!("Current score: {} • Daily attempt", self.current_score),
+            None => format!(
+                "Current score: {} • Highscore: {}",
+                self.current_score, self.highscore
+            ),
+        }
+    }
+
+    async fn store_daily(&self, day: Date) -> Result<()> {
+        Context::games()
+            .daily_higherlower_store(self.msg_owner, day, self.current_score, true)
+            .await
+    }
+
     async fn new_highscore(&self) -> Result<bool> {
         Context::games()
             .upsert_higherlower_score(self.msg_owner, self.state.version(), self.current_score)
@@ -374,7 +472,7 @@ impl HigherLowerGame {
 
         let retry = Button {
             custom_id: Some("try_again_button".to_owned()),
-            disabled: !matches!(self.buttons, ButtonState::TryAgain { .. }),
+            disabled: self.daily.is_some() || !matches!(self.buttons, ButtonState::TryAgain { .. }),
             emoji: Some(ReactionType::Unicode {
                 name: "🔁".to_owned(),
             }),
@@ -0,0 +1,214 @@
+use std::future::Future;
+
+use bathbot_util::{
+    constants::OSU_BASE,
+    numbers::{round, WithComma},
+    EmbedBuilder,
+};
+use eyre::{ContextCompat, Result, WrapErr};
+use rand::Rng;
+use rosu_v2::prelude::GameMode;
+use twilight_model::channel::message::embed::EmbedField;
+
+use super::state::{side_by_side_image, HigherLowerState};
+use crate::{core::Context, manager::redis::RedisData};
+
+/// Only the top 10,000 players are available through rankings
+const MAX_RANK: u32 = 10_000;
+
+#[derive(Copy, Clone)]
+pub(super) enum PlayerStat {
+    GlobalRank,
+    Playcount,
+}
+
+pub(super) struct Player {
+    pub avatar_url: Box<str>,
+    pub global_rank: u32,
+    pub playcount: u32,
+    user_id: u32,
+    player_string: Box<str>,
+    pp: f32,
+}
+
+impl Player {
+    pub(super) async fn random(
+        mode: GameMode,
+        stat: PlayerStat,
+        prev: Option<&Self>,
+        curr_score: u32,
+    ) -> Result<Self> {
+        let rank = {
+            let mut rng = rand::thread_rng();
+
+            match (stat, prev) {
+                // The better the current score, the closer the ranks
+                (PlayerStat::GlobalRank, Some(prev)) => {
+                    let spread = (MAX_RANK >> curr_score.min(8)).max(25);
+                    let min = prev.global_rank.saturating_sub(spread).max(1);
+                    let max = (prev.global_rank + spread).min(MAX_RANK);
+
+                    rng.gen_range(min..=max)
+                }
+                _ => rng.gen_range(1..=MAX_RANK),
+            }
+        };
+
+        let page = ((rank - 1) / 50) + 1;
+        let idx = ((rank - 1) % 50) as usize;
+
+        let ranking = Context::redis()
+            .pp_ranking(mode, page, None)
+            .await
+            .wrap_err("Failed to get cached pp ranking")?;
+
+        let player = match ranking {
+            RedisData::Original(mut ranking) => {
+                let user = ranking.ranking.swap_remove(idx);
+                let stats = user.statistics.wrap_err("Missing user statistics")?;
+
+                Self::new(
+                    user.avatar_url.into_boxed_str(),
+                    user.user_id,
+                    user.country_code.as_str(),
+                    user.username.as_str(),
+                    stats.global_rank.unwrap_or(rank),
+                    stats.playcount,
+                    stats.pp,
+                )
+            }
+            RedisData::Archive(ranking) => {
+                let user = &ranking.ranking[idx];
+                let stats = user
+                    .statistics
+                    .as_ref()
+                    .wrap_err("Missing user statistics")?;
+
+                Self::new(
+                    user.avatar_url.as_ref().into(),
+                    user.user_id,
+                    user.country_code.as_str(),
+                    user.username.as_str(),
+                    stats.global_rank,
+                    stats.playcount,
+                    stats.pp,
+                )
+            }
+        };
+
+        Ok(player)
+    }
+
+    pub(super) fn image(
+        previous: &Self,
+        next: &Self,
+    ) -> impl Future<Output = Result<String>> + Send + 'static {
+        let pfp1 = previous.avatar_url.clone();
+        let pfp2 = next.avatar_url.clone();
+        let content = format!("Player: {} ~ {}", previous.user_id, next.user_id);
+
+        async move {
+            let client = Context::client();
+
+            let (pfp_left, pfp_right) =
+                tokio::try_join!(client.get_avatar(&pfp1), client.get_avatar(&pfp2))
+                    .wrap_err("Failed to retrieve some avatar")?;
+
+            let pfp_left =
+                image::load_from_memory(&pfp_left).wrap_err("Failed to load pfp1 from memory")?;
+
+            let pfp_right =
+                image::load_from_memory(&pfp_right).wrap_err("Failed to load pfp2 from memory")?;
+
+            let img = side_by_side_image(&pfp_left, &pfp_right);
+
+            HigherLowerState::upload_image(img.as_raw(), content).await
+        }
+    }
+
+    pub(super) fn log(game1: &Self, game2: &Self) {
+        debug!(
+            "#{} ({} plays) vs #{} ({} plays)",
+            game1.global_rank, game1.playcount, game2.global_rank, game2.playcount
+        );
+    }
+
+    pub(super) fn to_embed(
+        previous: &Self,
+        next: &Self,
+        stat: PlayerStat,
+        revealed: bool,
+    ) -> EmbedBuilder {
+        let fields = vec![
+            EmbedField {
+                inline: false,
+                name: format!("__Previous:__ {}", previous.player_string),
+                value: previous.stats_string(stat, true),
+            },
+            EmbedField {
+                inline: false,
+                name: format!("__Next:__ {}", next.player_string),
+                value: next.stats_string(stat, revealed),
+            },
+        ];
+
+        EmbedBuilder::new().fields(fields)
+    }
+
+    fn new(
+        avatar_url: Box<str>,
+        user_id: u32,
+        country_code: &str,
+        username: &str,
+        global_rank: u32,
+        playcount: u32,
+        pp: f32,
+    ) -> Self {
+        let country_code = country_code.to_lowercase();
+
+        Self {
+            avatar_url,
+            global_rank,
+            playcount,
+            user_id,
+            player_string: format!(":flag_{country_code}: [{username}]({OSU_BASE}u/{user_id})")
+                .into_boxed_str(),
+            pp: round(pp),
+        }
+    }
+
+    fn stats_string(&self, stat: PlayerStat, visible: bool) -> String {
+        // The pp value would give away the rank so it's only shown alongside
+        // the playcount
+        match (stat, visible) {
+            (PlayerStat::GlobalRank, true) => format!(
+                "Global rank: **#{rank}** • **{pp}pp** • {playcount} plays",
+                rank = WithComma::new(self.global_rank),
+                pp = WithComma::new(self.pp),
+                playcount = WithComma::new(self.playcount),
+            ),
+            (PlayerStat::GlobalRank, false) => format!(
+                "Global rank: **#???** • {playcount} plays",
+                playcount = WithComma::new(self.playcount),
+            ),
+            (PlayerStat::Playcount, true) => format!(
+                "Playcount: **{playcount}** • #{rank} • {pp}pp",
+                playcount = WithComma::new(self.playcount),
+                rank = WithComma::new(self.global_rank),
+                pp = WithComma::new(self.pp),
+            ),
+            (PlayerStat::Playcount, false) => format!(
+                "Playcount: **???** • #{rank} • {pp}pp",
+                rank = WithComma::new(self.global_rank),
+                pp = WithComma::new(self.pp),
+            ),
+        }
+    }
+}
+
+impl PartialEq for Player {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.user_id == other.user_id
+    }
+}
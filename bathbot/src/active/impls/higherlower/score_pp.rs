@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use bathbot_model::rosu_v2::ranking::ArchivedRankingsUser;
+use bathbot_model::rosu_v2::{ranking::ArchivedRankingsUser, user::User};
 use bathbot_util::{
     constants::OSU_BASE,
     numbers::{round, WithComma},
@@ -10,12 +10,16 @@ use eyre::{Result, WrapErr};
 use image::{GenericImageView, ImageBuffer};
 use rand::Rng;
 use rosu_v2::prelude::{CountryCode, GameMode, GameMods, Grade, Score, User as UserRosu, Username};
+use time::Date;
 use twilight_model::channel::message::embed::EmbedField;
 
 use crate::{
     active::impls::higherlower::state::{mapset_cover, HigherLowerState, H, W},
     core::Context,
-    manager::{redis::RedisData, OsuMapSlim},
+    manager::{
+        redis::{osu::UserArgs, RedisData},
+        OsuMapSlim,
+    },
     util::{osu::grade_emote, Emote},
 };
 
@@ -27,6 +31,7 @@ pub(super) struct ScorePp {
     pub mapset_id: u32,
     user_id: u32,
     map_id: u32,
+    score_id: u64,
     player_string: Box<str>,
     map_string: Box<str>,
     mods: GameMods,
@@ -88,6 +93,52 @@ impl ScorePp {
 
         let play = plays.swap_remove(play as usize);
 
+        Self::from_play(player, play).await
+    }
+
+    /// Entry at the index of the day's chain of scores.
+    ///
+    /// The first player to reach the index picks a random score and stores it
+    /// so that everyone else gets the same one.
+    pub(super) async fn daily(day: Date, idx: u32, prev: Option<&Self>) -> Result<Self> {
+        let games = Context::games();
+
+        if let Some((user_id, score_id)) = games.daily_higherlower_entry(day, idx).await? {
+            return Self::stored(user_id, score_id).await;
+        }
+
+        let curr_score = idx.saturating_sub(1);
+        let mut entry = Self::random(GameMode::Osu, prev, curr_score).await?;
+
+        while prev.is_some_and(|prev| *prev == entry) {
+            entry = Self::random(GameMode::Osu, prev, curr_score).await?;
+        }
+
+        let set_fut = games.daily_higherlower_set_entry(day, idx, entry.user_id, entry.score_id);
+        let (user_id, score_id) = set_fut.await?;
+
+        if score_id == entry.score_id {
+            Ok(entry)
+        } else {
+            Self::stored(user_id, score_id).await
+        }
+    }
+
+    /// Entry of a score that was picked previously
+    async fn stored(user_id: u32, score_id: u64) -> Result<Self> {
+        let score_fut = Context::osu().score(score_id).mode(GameMode::Osu);
+        let user_args = UserArgs::user_id(user_id, GameMode::Osu);
+        let user_fut = Context::redis().osu_user(user_args);
+
+        let (score_res, user_res) = tokio::join!(score_fut, user_fut);
+
+        let play = score_res.wrap_err("Failed to get score")?;
+        let user = user_res.wrap_err("Failed to get user")?;
+
+        Self::from_play(UserCompact::from(&user), play).await
+    }
+
+    async fn from_play(player: UserCompact, play: Score) -> Result<Self> {
         let map_manager = Context::osu_map();
         let map_fut = map_manager.map_slim(play.map_id);
         let attrs_fut = map_manager.difficulty(play.map_id, play.mode, &play.mods);
@@ -249,6 +300,7 @@ impl ScorePp {
             user_id,
             avatar_url,
             map_id: map.map_id(),
+            score_id: score.id,
             mapset_id: map.mapset_id(),
             player_string: format!(":flag_{country_code}: {username} (#{global_rank})")
                 .into_boxed_str(),
@@ -296,6 +348,19 @@ impl From<UserRosu> for UserCompact {
     }
 }
 
+impl From<&RedisData<User>> for UserCompact {
+    #[inline]
+    fn from(user: &RedisData<User>) -> Self {
+        Self {
+            avatar_url: user.avatar_url().into(),
+            country_code: user.country_code().into(),
+            global_rank: user.stats().global_rank(),
+            user_id: user.user_id(),
+            username: user.username().into(),
+        }
+    }
+}
+
 impl From<&ArchivedRankingsUser> for UserCompact {
     #[inline]
     fn from(user: &ArchivedRankingsUser) -> Self {
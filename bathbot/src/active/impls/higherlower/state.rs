@@ -1,14 +1,29 @@
-use std::mem;
+use std::{future::Future, mem};
 
 use bathbot_model::HlVersion;
 use bathbot_util::{EmbedBuilder, MessageBuilder};
 use eyre::{ContextCompat, Result, WrapErr};
-use image::{codecs::png::PngEncoder, ColorType, ImageEncoder};
-use rosu_v2::prelude::GameMode;
+use image::{
+    codecs::png::PngEncoder, imageops::FilterType, ColorType, DynamicImage, GenericImageView,
+    ImageBuffer, ImageEncoder, Rgba, RgbaImage,
+};
+use rand::Rng;
+use rosu_v2::prelude::{GameMode, Score};
+use time::Date;
 use tokio::sync::oneshot::{self, Receiver};
 
-use super::{score_pp::ScorePp, HlGuess};
-use crate::{core::BotConfig, util::ChannelExt};
+use super::{
+    map::{MapEntry, MapStat},
+    medal_rarity::MedalRarity,
+    player::{Player, PlayerStat},
+    score_pp::ScorePp,
+    HlGuess,
+};
+use crate::{
+    core::{BotConfig, Context},
+    manager::redis::RedisData,
+    util::ChannelExt,
+};
 
 pub(super) const W: u32 = 900;
 pub(super) const H: u32 = 250;
@@ -32,10 +47,125 @@ pub(super) enum HigherLowerState {
         mode: GameMode,
         previous: ScorePp,
         next: ScorePp,
+        /// Day of the daily game whose chain of scores is the same for
+        /// everyone
+        daily: Option<Date>,
+    },
+    Map {
+        mode: GameMode,
+        stat: MapStat,
+        previous: MapEntry,
+        next: MapEntry,
+    },
+    Player {
+        mode: GameMode,
+        stat: PlayerStat,
+        previous: Player,
+        next: Player,
+    },
+    MedalRarity {
+        previous: MedalRarity,
+        next: MedalRarity,
     },
 }
 
 impl HigherLowerState {
+    pub(super) async fn start(
+        version: HlVersion,
+        mode: GameMode,
+    ) -> Result<(Self, Receiver<String>)> {
+        match version {
+            HlVersion::ScorePp => Self::start_score_pp(mode).await,
+            HlVersion::MapStars | HlVersion::MapsetFavourites => {
+                let stat = if let HlVersion::MapStars = version {
+                    MapStat::Stars
+                } else {
+                    MapStat::Favourites
+                };
+
+                let previous = MapEntry::random(mode, stat, None, 0)
+                    .await
+                    .wrap_err("Failed to create map entry")?;
+
+                let mut next = MapEntry::random(mode, stat, Some(&previous), 0)
+                    .await
+                    .wrap_err("Failed to create map entry")?;
+
+                while next == previous {
+                    next = MapEntry::random(mode, stat, Some(&previous), 0)
+                        .await
+                        .wrap_err("Failed to create map entry")?;
+                }
+
+                MapEntry::log(&previous, &next);
+                let rx = spawn_image(MapEntry::image(&previous, &next, stat));
+
+                let inner = Self::Map {
+                    mode,
+                    stat,
+                    previous,
+                    next,
+                };
+
+                Ok((inner, rx))
+            }
+            HlVersion::GlobalRank | HlVersion::Playcount => {
+                let stat = if let HlVersion::GlobalRank = version {
+                    PlayerStat::GlobalRank
+                } else {
+                    PlayerStat::Playcount
+                };
+
+                let previous = Player::random(mode, stat, None, 0)
+                    .await
+                    .wrap_err("Failed to create player entry")?;
+
+                let mut next = Player::random(mode, stat, Some(&previous), 0)
+                    .await
+                    .wrap_err("Failed to create player entry")?;
+
+                while next == previous {
+                    next = Player::random(mode, stat, Some(&previous), 0)
+                        .await
+                        .wrap_err("Failed to create player entry")?;
+                }
+
+                Player::log(&previous, &next);
+                let rx = spawn_image(Player::image(&previous, &next));
+
+                let inner = Self::Player {
+                    mode,
+                    stat,
+                    previous,
+                    next,
+                };
+
+                Ok((inner, rx))
+            }
+            HlVersion::MedalRarity => {
+                let previous = MedalRarity::random(None, 0)
+                    .await
+                    .wrap_err("Failed to create medal rarity entry")?;
+
+                let mut next = MedalRarity::random(Some(&previous), 0)
+                    .await
+                    .wrap_err("Failed to create medal rarity entry")?;
+
+                while next == previous {
+                    next = MedalRarity::random(Some(&previous), 0)
+                        .await
+                        .wrap_err("Failed to create medal rarity entry")?;
+                }
+
+                MedalRarity::log(&previous, &next);
+                let rx = spawn_image(MedalRarity::image(&previous, &next));
+                let inner = Self::MedalRarity { previous, next };
+
+                Ok((inner, rx))
+            }
+        }
+    }
+
     pub(super) async fn start_score_pp(mode: GameMode) -> Result<(Self, Receiver<String>)> {
         let (previous, mut next) = tokio::try_join!(
             ScorePp::random(mode, None, 0),
@@ -49,6 +179,28 @@ impl HigherLowerState {
                 .wrap_err("Failed to create score pp entry")?;
         }
 
+        Self::score_pp_with_entries(mode, previous, next, None).await
+    }
+
+    /// Starts the score pp game of the given day.
+    pub(super) async fn start_daily_score_pp(day: Date) -> Result<(Self, Receiver<String>)> {
+        let previous = ScorePp::daily(day, 0, None)
+            .await
+            .wrap_err("Failed to get daily score pp entry")?;
+
+        let next = ScorePp::daily(day, 1, Some(&previous))
+            .await
+            .wrap_err("Failed to get daily score pp entry")?;
+
+        Self::score_pp_with_entries(GameMode::Osu, previous, next, Some(day)).await
+    }
+
+    async fn score_pp_with_entries(
+        mode: GameMode,
+        previous: ScorePp,
+        next: ScorePp,
+        daily: Option<Date>,
+    ) -> Result<(Self, Receiver<String>)> {
         ScorePp::log(&previous, &next);
 
         let (tx, rx) = oneshot::channel();
@@ -74,15 +226,21 @@ impl HigherLowerState {
             mode,
             previous,
             next,
+            daily,
         };
 
         Ok((inner, rx))
     }
 
     pub(super) async fn restart(&mut self) -> Result<(Self, Receiver<String>)> {
-        match self {
-            Self::ScorePp { mode, .. } => Self::start_score_pp(*mode).await,
-        }
+        let mode = match self {
+            Self::ScorePp { mode, .. } | Self::Map { mode, .. } | Self::Player { mode, .. } => {
+                *mode
+            }
+            Self::MedalRarity { .. } => GameMode::Osu,
+        };
+
+        Self::start(self.version(), mode).await
     }
 
     pub(super) async fn next(&mut self, curr_score: u32) -> Result<Receiver<String>> {
@@ -91,18 +249,25 @@ impl HigherLowerState {
                 mode,
                 previous,
                 next,
+                daily,
             } => {
                 let mode = *mode;
                 mem::swap(previous, next);
 
-                *next = ScorePp::random(mode, Some(&*previous), curr_score)
-                    .await
-                    .wrap_err("Failed to create score pp entry")?;
-
-                while previous == next {
+                if let Some(day) = *daily {
+                    *next = ScorePp::daily(day, curr_score + 1, Some(&*previous))
+                        .await
+                        .wrap_err("Failed to get daily score pp entry")?;
+                } else {
                     *next = ScorePp::random(mode, Some(&*previous), curr_score)
                         .await
                         .wrap_err("Failed to create score pp entry")?;
+
+                    while previous == next {
+                        *next = ScorePp::random(mode, Some(&*previous), curr_score)
+                            .await
+                            .wrap_err("Failed to create score pp entry")?;
+                    }
                 }
 
                 ScorePp::log(&*previous, &*next);
@@ -133,6 +298,71 @@ impl HigherLowerState {
 
                 rx
             }
+            Self::Map {
+                mode,
+                stat,
+                previous,
+                next,
+            } => {
+                let mode = *mode;
+                let stat = *stat;
+                mem::swap(previous, next);
+
+                *next = MapEntry::random(mode, stat, Some(&*previous), curr_score)
+                    .await
+                    .wrap_err("Failed to create map entry")?;
+
+                while previous == next {
+                    *next = MapEntry::random(mode, stat, Some(&*previous), curr_score)
+                        .await
+                        .wrap_err("Failed to create map entry")?;
+                }
+
+                MapEntry::log(previous, next);
+
+                spawn_image(MapEntry::image(previous, next, stat))
+            }
+            Self::Player {
+                mode,
+                stat,
+                previous,
+                next,
+            } => {
+                let mode = *mode;
+                let stat = *stat;
+                mem::swap(previous, next);
+
+                *next = Player::random(mode, stat, Some(&*previous), curr_score)
+                    .await
+                    .wrap_err("Failed to create player entry")?;
+
+                while previous == next {
+                    *next = Player::random(mode, stat, Some(&*previous), curr_score)
+                        .await
+                        .wrap_err("Failed to create player entry")?;
+                }
+
+                Player::log(previous, next);
+
+                spawn_image(Player::image(previous, next))
+            }
+            Self::MedalRarity { previous, next } => {
+                mem::swap(previous, next);
+
+                *next = MedalRarity::random(Some(&*previous), curr_score)
+                    .await
+                    .wrap_err("Failed to create medal rarity entry")?;
+
+                while previous == next {
+                    *next = MedalRarity::random(Some(&*previous), curr_score)
+                        .await
+                        .wrap_err("Failed to create medal rarity entry")?;
+                }
+
+                MedalRarity::log(previous, next);
+
+                spawn_image(MedalRarity::image(previous, next))
+            }
         };
 
         Ok(rx)
@@ -168,25 +398,63 @@ impl HigherLowerState {
     pub(super) fn to_embed(&self, revealed: bool) -> EmbedBuilder {
         let mut title = "Higher or Lower: ".to_owned();
 
-        let builder = match self {
+        let (builder, mode) = match self {
             HigherLowerState::ScorePp {
                 mode,
                 previous,
                 next,
+                ..
             } => {
                 title.push_str("Score PP");
 
-                match mode {
-                    GameMode::Osu => {}
-                    GameMode::Taiko => title.push_str(" (taiko)"),
-                    GameMode::Catch => title.push_str(" (ctb)"),
-                    GameMode::Mania => title.push_str(" (mania)"),
+                (ScorePp::to_embed(previous, next, revealed), Some(mode))
+            }
+            HigherLowerState::Map {
+                mode,
+                stat,
+                previous,
+                next,
+            } => {
+                match stat {
+                    MapStat::Stars => title.push_str("Map Stars"),
+                    MapStat::Favourites => title.push_str("Mapset Favourites"),
+                }
+
+                (
+                    MapEntry::to_embed(previous, next, *stat, revealed),
+                    Some(mode),
+                )
+            }
+            HigherLowerState::Player {
+                mode,
+                stat,
+                previous,
+                next,
+            } => {
+                match stat {
+                    PlayerStat::GlobalRank => title.push_str("Global Rank"),
+                    PlayerStat::Playcount => title.push_str("Playcount"),
                 }
 
-                ScorePp::to_embed(previous, next, revealed)
+                (
+                    Player::to_embed(previous, next, *stat, revealed),
+                    Some(mode),
+                )
+            }
+            HigherLowerState::MedalRarity { previous, next } => {
+                title.push_str("Medal Rarity");
+
+                (MedalRarity::to_embed(previous, next, revealed), None)
             }
         };
 
+        match mode {
+            Some(GameMode::Osu) | None => {}
+            Some(GameMode::Taiko) => title.push_str(" (taiko)"),
+            Some(GameMode::Catch) => title.push_str(" (ctb)"),
+            Some(GameMode::Mania) => title.push_str(" (mania)"),
+        }
+
         builder.title(title)
     }
 
@@ -196,14 +464,191 @@ impl HigherLowerState {
                 HlGuess::Higher => next.pp >= previous.pp,
                 HlGuess::Lower => next.pp <= previous.pp,
             },
+            Self::Map {
+                stat: MapStat::Stars,
+                previous,
+                next,
+                ..
+            } => compare(guess, previous.stars, next.stars),
+            Self::Map {
+                stat: MapStat::Favourites,
+                previous,
+                next,
+                ..
+            } => compare(guess, previous.favourites, next.favourites),
+            Self::Player {
+                stat: PlayerStat::GlobalRank,
+                previous,
+                next,
+                ..
+            } => compare(guess, previous.global_rank, next.global_rank),
+            Self::Player {
+                stat: PlayerStat::Playcount,
+                previous,
+                next,
+                ..
+            } => compare(guess, previous.playcount, next.playcount),
+            Self::MedalRarity { previous, next } => compare(guess, previous.rarity, next.rarity),
         }
     }
 
     pub(super) fn version(&self) -> HlVersion {
         match self {
             Self::ScorePp { .. } => HlVersion::ScorePp,
+            Self::Map {
+                stat: MapStat::Stars,
+                ..
+            } => HlVersion::MapStars,
+            Self::Map {
+                stat: MapStat::Favourites,
+                ..
+            } => HlVersion::MapsetFavourites,
+            Self::Player {
+                stat: PlayerStat::GlobalRank,
+                ..
+            } => HlVersion::GlobalRank,
+            Self::Player {
+                stat: PlayerStat::Playcount,
+                ..
+            } => HlVersion::Playcount,
+            Self::MedalRarity { .. } => HlVersion::MedalRarity,
+        }
+    }
+}
+
+fn compare<T: PartialOrd>(guess: HlGuess, previous: T, next: T) -> bool {
+    match guess {
+        HlGuess::Higher => next >= previous,
+        HlGuess::Lower => next <= previous,
+    }
+}
+
+/// Create the image in the background so it's available when needed later
+fn spawn_image<F>(fut: F) -> Receiver<String>
+where
+    F: Future<Output = Result<String>> + Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+
+    tokio::spawn(async move {
+        let url = match fut.await {
+            Ok(url) => url,
+            Err(err) => {
+                warn!(?err, "Failed to create image");
+
+                String::new()
+            }
+        };
+
+        let _ = tx.send(url);
+    });
+
+    rx
+}
+
+/// Top scores of a random player within the top 5,000
+pub(super) async fn random_top_scores(mode: GameMode) -> Result<Vec<Score>> {
+    let max_rank = 5000 - (mode != GameMode::Osu) as u32 * 1000;
+    let rank = rand::thread_rng().gen_range(1..=max_rank);
+
+    let page = ((rank - 1) / 50) + 1;
+    let idx = ((rank - 1) % 50) as usize;
+
+    let ranking = Context::redis()
+        .pp_ranking(mode, page, None)
+        .await
+        .wrap_err("Failed to get cached pp ranking")?;
+
+    let user_id = match ranking {
+        RedisData::Original(ranking) => ranking.ranking[idx].user_id,
+        RedisData::Archive(ranking) => ranking.ranking[idx].user_id,
+    };
+
+    Context::osu()
+        .user_scores(user_id)
+        .limit(100)
+        .mode(mode)
+        .best()
+        .await
+        .wrap_err("Failed to get user scores")
+}
+
+/// Random index into scores that are sorted by their closeness to the
+/// previous entry. The higher the current score, the closer the pick.
+pub(super) fn score_idx(len: usize, curr_score: u32) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    let max = (len >> curr_score.min(4)).max(1);
+
+    Some(rand::thread_rng().gen_range(0..max))
+}
+
+/// Combines the left half of the first mapset's cover with the right half of
+/// the second mapset's cover.
+pub(super) async fn covers_image(
+    mapset_id1: u32,
+    mapset_id2: u32,
+    content: String,
+) -> Result<String> {
+    let cover1 = mapset_cover(mapset_id1);
+    let cover2 = mapset_cover(mapset_id2);
+
+    let client = Context::client();
+
+    let (bg_left, bg_right) = tokio::try_join!(
+        client.get_mapset_cover(&cover1),
+        client.get_mapset_cover(&cover2),
+    )
+    .wrap_err("Failed to retrieve some cover")?;
+
+    let bg_left =
+        image::load_from_memory(&bg_left).wrap_err("Failed to load left bg from memory")?;
+
+    let bg_right =
+        image::load_from_memory(&bg_right).wrap_err("Failed to load right bg from memory")?;
+
+    let mut blipped = ImageBuffer::new(W, H);
+
+    let iter = blipped
+        .enumerate_pixels_mut()
+        .zip(bg_left.pixels())
+        .zip(bg_right.pixels());
+
+    for (((x, _, pixel), (.., left)), (.., right)) in iter {
+        *pixel = if x <= W / 2 { left } else { right };
+    }
+
+    HigherLowerState::upload_image(blipped.as_raw(), content).await
+}
+
+/// Centers both images on their half of a dark background.
+pub(super) fn side_by_side_image(left: &DynamicImage, right: &DynamicImage) -> RgbaImage {
+    const SIZE: u32 = H - 20;
+    const BACKGROUND: Rgba<u8> = Rgba([19, 43, 33, 255]);
+
+    let mut img = ImageBuffer::from_pixel(W, H, BACKGROUND);
+
+    for (img_part, offset) in [(left, 0), (right, W / 2)] {
+        let resized = img_part.resize(SIZE, SIZE, FilterType::Lanczos3);
+        let start_x = offset + (W / 2 - resized.width()) / 2;
+        let start_y = (H - resized.height()) / 2;
+
+        for (x, y, pixel) in resized.pixels() {
+            // Blend transparent pixels onto the background
+            let alpha = pixel.0[3] as u32;
+            let mut blended = BACKGROUND;
+
+            for (out, channel) in blended.0.iter_mut().zip(pixel.0).take(3) {
+                *out = ((channel as u32 * alpha + *out as u32 * (255 - alpha)) / 255) as u8;
+            }
+
+            img.put_pixel(start_x + x, start_y + y, blended);
         }
     }
+
+    img
 }
 
 pub(super) fn mapset_cover(mapset_id: u32) -> String {
@@ -0,0 +1,182 @@
+use std::{cmp, fmt::Write};
+
+use bathbot_model::twilight_model::id::IdRkyv;
+use bathbot_util::{constants::OSU_BASE, AuthorBuilder, CowUtils, EmbedBuilder, FooterBuilder};
+use eyre::Result;
+use futures::future::BoxFuture;
+use rkyv::{Archive, Deserialize, Serialize};
+use twilight_model::{
+    channel::message::Component,
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    active::{
+        pagination::{handle_pagination_component, handle_pagination_modal, Pages},
+        BuildPage, ComponentResult, IActiveMessage,
+    },
+    commands::osu::MatchCostSeriesResult,
+    util::interaction::{InteractionComponent, InteractionModal},
+};
+
+const PLAYERS_PER_PAGE: usize = 15;
+
+#[derive(Archive, Deserialize, Serialize)]
+pub struct MatchCostSeriesPagination {
+    result: MatchCostSeriesResult,
+    content: Box<str>,
+    #[with(IdRkyv)]
+    msg_owner: Id<UserMarker>,
+    pages: Pages,
+}
+
+impl IActiveMessage for MatchCostSeriesPagination {
+    fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
+        let idx = self.pages.index();
+        let ranking_pages = self.ranking_pages();
+
+        let embed = if idx < ranking_pages {
+            self.ranking_embed(idx)
+        } else {
+            self.match_embed(idx - ranking_pages)
+        };
+
+        let footer = FooterBuilder::new(format!(
+            "Page {}/{} • Rating = normalized match cost weighted by played maps",
+            self.pages.curr_page(),
+            self.pages.last_page(),
+        ));
+
+        BuildPage::new(embed.footer(footer), false)
+            .content(self.content.clone())
+            .boxed()
+    }
+
+    fn build_components(&self) -> Vec<Component> {
+        self.pages.components()
+    }
+
+    fn handle_component<'a>(
+        &'a mut self,
+        component: &'a mut InteractionComponent,
+    ) -> BoxFuture<'a, ComponentResult> {
+        handle_pagination_component(component, self.msg_owner, false, &mut self.pages)
+    }
+
+    fn handle_modal<'a>(
+        &'a mut self,
+        modal: &'a mut InteractionModal,
+    ) -> BoxFuture<'a, Result<()>> {
+        handle_pagination_modal(modal, self.msg_owner, false, &mut self.pages)
+    }
+}
+
+impl MatchCostSeriesPagination {
+    /// The first pages show the overall ranking, followed by one page per match
+    pub fn new(
+        result: MatchCostSeriesResult,
+        content: Box<str>,
+        msg_owner: Id<UserMarker>,
+    ) -> Self {
+        let ranking_pages = result.players.len().div_ceil(PLAYERS_PER_PAGE);
+        let pages = Pages::new(1, ranking_pages + result.matches.len());
+
+        Self {
+            result,
+            content,
+            msg_owner,
+            pages,
+        }
+    }
+
+    fn ranking_pages(&self) -> usize {
+        self.result.players.len().div_ceil(PLAYERS_PER_PAGE)
+    }
+
+    fn ranking_embed(&self, page: usize) -> EmbedBuilder {
+        let players = &self.result.players;
+        let start = page * PLAYERS_PER_PAGE;
+        let end = cmp::min(players.len(), start + PLAYERS_PER_PAGE);
+
+        let mut description = String::with_capacity(end.saturating_sub(start) * 96);
+
+        for (player, i) in players[start..end].iter().zip(start + 1..) {
+            let _ = write!(
+                description,
+                "**{i}.** [{name}]({OSU_BASE}u/{user_id}): **{rating:.2}**",
+                name = player.username.cow_escape_markdown(),
+                user_id = player.user_id,
+                rating = player.rating,
+            );
+
+            if !player.teams.is_empty() {
+                let _ = write!(
+                    description,
+                    " ({})",
+                    player.teams.join(", ").cow_escape_markdown()
+                );
+            }
+
+            let _ = writeln!(
+                description,
+                "\n~ avg cost `{avg:.2}` • {matches} match{plural} • {maps} map{plural_maps}",
+                avg = player.avg_match_cost,
+                matches = player.matches,
+                plural = if player.matches == 1 { "" } else { "es" },
+                maps = player.games,
+                plural_maps = if player.games == 1 { "" } else { "s" },
+            );
+        }
+
+        let author = AuthorBuilder::new(format!(
+            "Match cost series • {} matches",
+            self.result.matches.len()
+        ));
+
+        EmbedBuilder::new()
+            .author(author)
+            .description(description)
+            .title("Overall ranking")
+    }
+
+    fn match_embed(&self, idx: usize) -> EmbedBuilder {
+        let series_match = &self.result.matches[idx];
+        let mut description = String::with_capacity(series_match.players.len() * 64);
+
+        for (player, i) in series_match.players.iter().zip(1..) {
+            let _ = write!(
+                description,
+                "**{i}.** [{name}]({OSU_BASE}u/{user_id}): **{cost:.2}** (normalized `{norm:.2}`)",
+                name = player.username.cow_escape_markdown(),
+                user_id = player.user_id,
+                cost = player.match_cost,
+                norm = player.normalized,
+            );
+
+            if let Some(ref team) = player.team {
+                let _ = write!(description, " • {}", team.cow_escape_markdown());
+            }
+
+            let _ = writeln!(
+                description,
+                " • {maps} map{plural}",
+                maps = player.games_played,
+                plural = if player.games_played == 1 { "" } else { "s" },
+            );
+        }
+
+        let author = AuthorBuilder::new(format!("Match {}/{}", idx + 1, self.result.matches.len()));
+
+        let mut title = series_match.name.to_string();
+        title.retain(|c| c != '(' && c != ')');
+
+        EmbedBuilder::new()
+            .author(author)
+            .description(description)
+            .title(title)
+            .url(format!(
+                "{OSU_BASE}community/matches/{}",
+                series_match.match_id
+            ))
+    }
+}
@@ -3,17 +3,19 @@
 pub use self::single_score::MarkIndex;
 pub use self::{
     badges::BadgesPagination,
-    bg_game::{BackgroundGame, BackgroundGameSetup},
+    bg_game::{BackgroundGame, BackgroundGameSetup, BackgroundTagging, DailyChallenge},
     bookmarks::BookmarksPagination,
     changelog::ChangelogPagination,
     compare::{CompareMostPlayedPagination, CompareScoresPagination, CompareTopPagination},
     embed_builder::ScoreEmbedBuilderActive,
+    guild_skins::GuildSkinsPagination,
     help::{HelpInteractionCommand, HelpPrefixMenu},
     higherlower::HigherLowerGame,
     leaderboard::LeaderboardPagination,
     map::MapPagination,
     map_search::MapSearchPagination,
     match_compare::MatchComparePagination,
+    match_cost_series::MatchCostSeriesPagination,
     match_costs::MatchCostPagination,
     medals::{
         MedalsCommonPagination, MedalsListPagination, MedalsMissingPagination,
@@ -23,19 +25,23 @@ pub use self::{
     nochoke::NoChokePagination,
     osekai::{MedalCountPagination, MedalRarityPagination},
     osustats::{OsuStatsBestPagination, OsuStatsPlayersPagination, OsuStatsScoresPagination},
-    profile::ProfileMenu,
+    profile::{PersistedProfileMenu, ProfileMenu},
     ranking::RankingPagination,
     ranking_countries::RankingCountriesPagination,
     recent_list::RecentListPagination,
     region_top::RegionTopPagination,
     render::{CachedRender, CachedRenderData, RenderSettingsActive, SettingsImport},
     scores::{ScoresMapPagination, ScoresServerPagination, ScoresUserPagination},
-    simulate::{SimulateAttributes, SimulateComponents, SimulateData, SimulateMap, TopOldVersion},
+    simulate::{
+        PersistedSimulateComponents, SimulateAttributes, SimulateComponents, SimulateData,
+        SimulateMap, TopOldVersion,
+    },
+    simulate_batch::SimulateBatchPagination,
     single_score::{SingleScoreContent, SingleScorePagination},
     skins::SkinsPagination,
     slash_commands::SlashCommandsPagination,
     snipe::{SnipeCountryListPagination, SnipeDifferencePagination, SnipePlayerListPagination},
-    top::TopPagination,
+    top::{PersistedTopPagination, TopPagination},
     top_if::TopIfPagination,
 };
 
@@ -45,12 +51,14 @@ mod bookmarks;
 mod changelog;
 mod compare;
 mod embed_builder;
+mod guild_skins;
 mod help;
 mod higherlower;
 mod leaderboard;
 mod map;
 mod map_search;
 mod match_compare;
+mod match_cost_series;
 mod match_costs;
 mod medals;
 mod most_played;
@@ -65,6 +73,7 @@ mod region_top;
 mod render;
 mod scores;
 mod simulate;
+mod simulate_batch;
 mod single_score;
 mod skins;
 mod slash_commands;
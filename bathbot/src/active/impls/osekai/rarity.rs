@@ -1,10 +1,11 @@
 use std::fmt::Write;
 
 use bathbot_macros::PaginationBuilder;
-use bathbot_model::{OsekaiMedal, OsekaiRarityEntry};
+use bathbot_model::{twilight_model::id::IdRkyv, OsekaiMedal, OsekaiRarityEntry};
 use bathbot_util::{numbers::round, EmbedBuilder, FooterBuilder};
 use eyre::Result;
 use futures::future::BoxFuture;
+use rkyv::{Archive, Deserialize, Serialize};
 use twilight_model::{
     channel::message::Component,
     id::{marker::UserMarker, Id},
@@ -18,10 +19,11 @@ use crate::{
     util::interaction::{InteractionComponent, InteractionModal},
 };
 
-#[derive(PaginationBuilder)]
+#[derive(Archive, Deserialize, PaginationBuilder, Serialize)]
 pub struct MedalRarityPagination {
     #[pagination(per_page = 15)]
     ranking: Box<[OsekaiRarityEntry]>,
+    #[with(IdRkyv)]
     msg_owner: Id<UserMarker>,
     pages: Pages,
 }
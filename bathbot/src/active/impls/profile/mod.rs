@@ -3,6 +3,7 @@ use std::fmt::{Display, Write};
 use bathbot_model::{
     rkyv_util::time::DateTimeRkyv,
     rosu_v2::user::{User, UserHighestRank},
+    twilight_model::id::IdRkyv,
     RankAccPeaks,
 };
 use bathbot_util::{
@@ -12,11 +13,11 @@ use bathbot_util::{
     osu::BonusPP,
     EmbedBuilder, FooterBuilder, MessageOrigin,
 };
-use eyre::Result;
+use eyre::{Result, WrapErr};
 use futures::future::BoxFuture;
 use rkyv::{
     with::{DeserializeWith, Map},
-    Infallible,
+    Archive, Deserialize, Infallible, Serialize,
 };
 use rosu_v2::prelude::{
     GameModIntermode, GameMode, GameModsIntermode, Grade, Score,
@@ -28,7 +29,10 @@ use twilight_model::{
         component::{ActionRow, SelectMenu, SelectMenuOption},
         Component,
     },
-    id::{marker::UserMarker, Id},
+    id::{
+        marker::{ChannelMarker, GuildMarker, UserMarker},
+        Id,
+    },
 };
 
 use self::{
@@ -40,7 +44,8 @@ use self::{
 use crate::{
     active::{BuildPage, ComponentResult, IActiveMessage},
     commands::osu::ProfileKind,
-    manager::redis::RedisData,
+    core::Context,
+    manager::redis::{osu::UserArgs, RedisData},
     util::{interaction::InteractionComponent, osu::grade_emote, Authored, ComponentExt, Emote},
 };
 
@@ -1016,6 +1021,70 @@ impl ProfileMenu {
 
         FooterBuilder::new(text)
     }
+
+    pub fn persist(self) -> PersistedProfileMenu {
+        PersistedProfileMenu {
+            user_id: self.user.user_id(),
+            mode: self.user.mode(),
+            discord_id: self.discord_id,
+            tz: self.tz.map(UtcOffset::whole_seconds),
+            osutrack_peaks: self.osutrack_peaks,
+            legacy_scores: self.legacy_scores,
+            kind: self.kind,
+            guild: self.origin.guild(),
+            channel: self.origin.channel(),
+            msg_owner: self.msg_owner,
+        }
+    }
+}
+
+/// State of a [`ProfileMenu`] that can be stored across restarts.
+///
+/// The user is requested again on restore and everything that the menu
+/// requests lazily will be requested again once it's needed.
+#[derive(Archive, Deserialize, Serialize)]
+pub struct PersistedProfileMenu {
+    user_id: u32,
+    mode: GameMode,
+    #[with(Map<IdRkyv>)]
+    discord_id: Option<Id<UserMarker>>,
+    /// Offset in seconds
+    tz: Option<i32>,
+    osutrack_peaks: Option<RankAccPeaks>,
+    legacy_scores: bool,
+    kind: ProfileKind,
+    #[with(Map<IdRkyv>)]
+    guild: Option<Id<GuildMarker>>,
+    #[with(IdRkyv)]
+    channel: Id<ChannelMarker>,
+    #[with(IdRkyv)]
+    msg_owner: Id<UserMarker>,
+}
+
+impl PersistedProfileMenu {
+    pub async fn restore(self) -> Result<ProfileMenu> {
+        let user_args = UserArgs::user_id(self.user_id, self.mode);
+
+        let user = Context::redis()
+            .osu_user(user_args)
+            .await
+            .wrap_err("Failed to get user")?;
+
+        let tz = self
+            .tz
+            .and_then(|seconds| UtcOffset::from_whole_seconds(seconds).ok());
+
+        Ok(ProfileMenu::new(
+            user,
+            self.discord_id,
+            tz,
+            self.osutrack_peaks,
+            self.legacy_scores,
+            self.kind,
+            MessageOrigin::new(self.guild, self.channel),
+            self.msg_owner,
+        ))
+    }
 }
 
 trait HasLen {
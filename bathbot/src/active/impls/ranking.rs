@@ -7,13 +7,16 @@ use std::{
 };
 
 use bathbot_macros::PaginationBuilder;
-use bathbot_model::{BgGameScore, EmbedHeader, RankingEntries, RankingEntry, RankingKind};
+use bathbot_model::{
+    BgGameScore, BgSeasonColumn, EmbedHeader, RankingEntries, RankingEntry, RankingKind,
+};
 use bathbot_util::{
     numbers::{round, WithComma},
     EmbedBuilder,
 };
 use eyre::{Result, WrapErr};
 use futures::future::BoxFuture;
+use rosu_v2::prelude::Username;
 use time::OffsetDateTime;
 use twilight_model::{
     channel::message::Component,
@@ -71,6 +74,7 @@ impl RankingPagination {
         matches!(
             self.kind,
             RankingKind::BgScores { .. }
+                | RankingKind::BgSeason { .. }
                 | RankingKind::PpCountry { .. }
                 | RankingKind::PpGlobal { .. }
                 | RankingKind::RankedScore { .. }
@@ -218,37 +222,54 @@ impl RankingPagination {
                             let BgGameScore { discord_id, score } = scores[i];
                             let id = Id::new(discord_id as u64);
 
-                            let mut name_opt = match Context::user_config().osu_name(id).await {
-                                Ok(Some(name)) => Some(name),
-                                Ok(None) => None,
-                                Err(err) => {
-                                    warn!(?err, "Failed to get osu user");
-
-                                    None
-                                }
-                            };
-
-                            name_opt = match name_opt {
-                                Some(name) => Some(name),
-                                None => match Context::cache().user(id).await {
-                                    Ok(Some(user)) => Some(user.name.as_ref().into()),
-                                    Ok(None) => None,
-                                    Err(err) => {
-                                        warn!("{err:?}");
-
-                                        None
-                                    }
-                                },
-                            };
-
                             entry.insert(RankingEntry {
                                 country: None,
-                                name: name_opt.unwrap_or_else(|| "Unknown user".into()),
+                                name: bg_game_user_name(id).await,
                                 value: score as u64,
                             });
                         }
                     }
                 }
+                RankingKind::BgSeason { scores, column, .. } => {
+                    #[allow(clippy::needless_range_loop)]
+                    for i in pages.index()..(pages.index() + pages.per_page()).min(self.total) {
+                        if self.entries.contains_key(i) {
+                            continue;
+                        }
+
+                        let score = &scores[i];
+                        let name = bg_game_user_name(Id::new(score.discord_id as u64)).await;
+
+                        match self.entries {
+                            RankingEntries::Float(ref mut entries) => {
+                                let entry = RankingEntry {
+                                    country: None,
+                                    name,
+                                    value: score.avg_guess_time(),
+                                };
+
+                                entries.insert(i, entry);
+                            }
+                            RankingEntries::Amount(ref mut entries) => {
+                                let value = match column {
+                                    BgSeasonColumn::Guesses => score.guesses,
+                                    BgSeasonColumn::Hints => score.hints,
+                                    BgSeasonColumn::Streak => score.best_streak,
+                                    BgSeasonColumn::GuessTime => unreachable!(),
+                                };
+
+                                let entry = RankingEntry {
+                                    country: None,
+                                    name,
+                                    value: value as u64,
+                                };
+
+                                entries.insert(i, entry);
+                            }
+                            _ => unreachable!(),
+                        }
+                    }
+                }
                 RankingKind::PpCountry {
                     mode,
                     country_code: country,
@@ -380,6 +401,34 @@ impl RankingPagination {
     }
 }
 
+/// Prefers the linked osu! username, otherwise falls back to the discord name.
+async fn bg_game_user_name(id: Id<UserMarker>) -> Username {
+    let name_opt = match Context::user_config().osu_name(id).await {
+        Ok(Some(name)) => Some(name),
+        Ok(None) => None,
+        Err(err) => {
+            warn!(?err, "Failed to get osu user");
+
+            None
+        }
+    };
+
+    let name_opt = match name_opt {
+        Some(name) => Some(name),
+        None => match Context::cache().user(id).await {
+            Ok(Some(user)) => Some(user.name.as_ref().into()),
+            Ok(None) => None,
+            Err(err) => {
+                warn!("{err:?}");
+
+                None
+            }
+        },
+    };
+
+    name_opt.unwrap_or_else(|| "Unknown user".into())
+}
+
 struct Lengths {
     idx: usize,
     name: usize,
@@ -17,7 +17,9 @@ use twilight_model::{
 
 use crate::{
     active::{response::ActiveResponse, BuildPage, ComponentResult, IActiveMessage},
-    commands::osu::{OngoingRender, RenderStatus, RenderStatusInner, RENDERER_NAME},
+    commands::osu::{
+        guild_skin_settings, OngoingRender, RenderStatus, RenderStatusInner, RENDERER_NAME,
+    },
     core::{buckets::BucketName, Context},
     manager::{OwnedReplayScore, ReplayScore},
     util::{interaction::InteractionComponent, Authored, ComponentExt, MessageExt},
@@ -243,16 +245,8 @@ impl CachedRender {
         status.set(RenderStatusInner::CommissioningRender);
         let _ = component.update(status.as_message()).await;
 
-        let allow_custom_skins = match component.guild_id {
-            Some(guild_id) => {
-                Context::guild_config()
-                    .peek(guild_id, |config| config.allow_custom_skins.unwrap_or(true))
-                    .await
-            }
-            None => true,
-        };
-
-        let skin = settings.skin(allow_custom_skins);
+        let (allow_custom_skins, guild_skin) = guild_skin_settings(component.guild_id).await;
+        let skin = settings.skin_or(allow_custom_skins, guild_skin.as_ref());
 
         let render_fut = Context::ordr()
             .expect("ordr unavailable")
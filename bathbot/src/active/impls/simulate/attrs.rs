@@ -1,6 +1,7 @@
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_pp::Beatmap;
 
-#[derive(Copy, Clone, Default)]
+#[derive(Archive, Copy, Clone, Default, Deserialize, Serialize)]
 pub struct SimulateAttributes {
     pub ar: Option<f32>,
     pub cs: Option<f32>,
@@ -1,3 +1,4 @@
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_v2::{
     mods,
     prelude::{GameMod, GameMods},
@@ -9,6 +10,7 @@ use crate::{
     commands::osu::{TopOldCatchVersion, TopOldManiaVersion, TopOldOsuVersion, TopOldTaikoVersion},
 };
 
+#[derive(Archive, Deserialize, Serialize)]
 pub struct SimulateData {
     pub mods: Option<GameMods>,
     pub acc: Option<f32>,
@@ -357,6 +359,13 @@ impl SimulateData {
             score_state,
         }
     }
+
+    /// Simulate the score and return only its star rating and pp.
+    pub fn stars_and_pp(&mut self, map: &SimulateMap) -> (f32, f32) {
+        let SimulateValues { stars, pp, .. } = self.simulate(map);
+
+        (stars, pp)
+    }
 }
 
 fn score_multiplier(mods: &GameMods) -> f32 {
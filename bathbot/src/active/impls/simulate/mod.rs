@@ -1,5 +1,6 @@
 use std::{borrow::Cow, fmt::Write};
 
+use bathbot_model::twilight_model::id::IdRkyv;
 use bathbot_util::{
     constants::OSU_BASE,
     datetime::SecToMinSec,
@@ -9,8 +10,9 @@ use bathbot_util::{
     osu::calculate_grade,
     CowUtils, EmbedBuilder, FooterBuilder,
 };
-use eyre::{ContextCompat, Report, Result};
+use eyre::{ContextCompat, Report, Result, WrapErr};
 use futures::future::BoxFuture;
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_pp::{
     model::{
         hit_object::{HitObjectKind, HoldNote, Spinner},
@@ -41,6 +43,7 @@ use crate::{
         BuildPage, ComponentResult, IActiveMessage,
     },
     commands::osu::parsed_map::AttachedSimulateMap,
+    core::Context,
     embeds::{ComboFormatter, HitResultFormatter, KeyFormatter, PpFormatter},
     manager::OsuMap,
     util::{
@@ -63,25 +66,7 @@ pub struct SimulateComponents {
 
 impl IActiveMessage for SimulateComponents {
     fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
-        {
-            let pp_map = self.map.pp_map_mut();
-
-            if let Some(ar) = self.data.attrs.ar {
-                pp_map.ar = ar;
-            }
-
-            if let Some(cs) = self.data.attrs.cs {
-                pp_map.cs = cs;
-            }
-
-            if let Some(hp) = self.data.attrs.hp {
-                pp_map.hp = hp;
-            }
-
-            if let Some(od) = self.data.attrs.od {
-                pp_map.od = od;
-            }
-        }
+        self.map.apply_attrs(self.data.attrs);
 
         let mut title = match self.map {
             SimulateMap::Full(ref map) => {
@@ -423,6 +408,21 @@ impl SimulateComponents {
         }
     }
 
+    /// Returns `None` for attached maps since they are no longer available
+    /// after a restart.
+    pub fn persist(self) -> Option<PersistedSimulateComponents> {
+        let SimulateMap::Full(ref map) = self.map else {
+            return None;
+        };
+
+        Some(PersistedSimulateComponents {
+            map_id: map.map_id(),
+            mode: map.mode(),
+            data: self.data,
+            msg_owner: self.msg_owner,
+        })
+    }
+
     async fn handle_topold_menu(
         &mut self,
 
@@ -615,6 +615,35 @@ fn parse_attr(modal: &InteractionModal, component_id: &str) -> Option<f32> {
         .flatten()
 }
 
+/// State of [`SimulateComponents`] that can be stored across restarts.
+///
+/// Only the map id is stored and the map is loaded again on restore.
+#[derive(Archive, Deserialize, Serialize)]
+pub struct PersistedSimulateComponents {
+    map_id: u32,
+    /// Mode the map was converted to
+    mode: GameMode,
+    data: SimulateData,
+    #[with(IdRkyv)]
+    msg_owner: Id<UserMarker>,
+}
+
+impl PersistedSimulateComponents {
+    pub async fn restore(self) -> Result<SimulateComponents> {
+        let map = Context::osu_map()
+            .map(self.map_id, None)
+            .await
+            .wrap_err("Failed to get map")?
+            .convert(self.mode);
+
+        Ok(SimulateComponents::new(
+            SimulateMap::Full(map),
+            self.data,
+            self.msg_owner,
+        ))
+    }
+}
+
 pub enum SimulateMap {
     Full(OsuMap),
     Attached(AttachedSimulateMap),
@@ -644,6 +673,27 @@ impl SimulateMap {
         }
     }
 
+    /// Overwrite the map's attributes with the given ones.
+    pub fn apply_attrs(&mut self, attrs: SimulateAttributes) {
+        let pp_map = self.pp_map_mut();
+
+        if let Some(ar) = attrs.ar {
+            pp_map.ar = ar;
+        }
+
+        if let Some(cs) = attrs.cs {
+            pp_map.cs = cs;
+        }
+
+        if let Some(hp) = attrs.hp {
+            pp_map.hp = hp;
+        }
+
+        if let Some(od) = attrs.od {
+            pp_map.od = od;
+        }
+    }
+
     pub fn n_objects(&self) -> u32 {
         self.pp_map().hit_objects.len() as u32
     }
@@ -1,5 +1,6 @@
 use std::fmt::{Display, Formatter, Result as FmtResult};
 
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_pp::{
     catch::CatchScoreState, mania::ManiaScoreState, osu::OsuScoreState, taiko::TaikoScoreState,
     Beatmap, Performance,
@@ -14,7 +15,7 @@ use crate::commands::osu::{
     TopOldCatchVersion, TopOldManiaVersion, TopOldOsuVersion, TopOldTaikoVersion,
 };
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Archive, Copy, Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum TopOldVersion {
     Osu(TopOldOsuVersion),
     Taiko(TopOldTaikoVersion),
@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use bathbot_macros::PaginationBuilder;
+use bathbot_util::{constants::OSU_BASE, CowUtils, EmbedBuilder, FooterBuilder};
+use eyre::Result;
+use futures::future::BoxFuture;
+use twilight_model::{
+    channel::message::Component,
+    id::{marker::UserMarker, Id},
+};
+
+use crate::{
+    active::{
+        pagination::{handle_pagination_component, handle_pagination_modal, Pages},
+        BuildPage, ComponentResult, IActiveMessage,
+    },
+    commands::osu::{SimulateBatchEntry, SimulateBatchOrder},
+    util::interaction::{InteractionComponent, InteractionModal},
+};
+
+#[derive(PaginationBuilder)]
+pub struct SimulateBatchPagination {
+    #[pagination(per_page = 10)]
+    entries: Box<[SimulateBatchEntry]>,
+    title: Box<str>,
+    content: Box<str>,
+    sort: SimulateBatchOrder,
+    msg_owner: Id<UserMarker>,
+    pages: Pages,
+}
+
+impl IActiveMessage for SimulateBatchPagination {
+    fn build_page(&mut self) -> BoxFuture<'_, Result<BuildPage>> {
+        let pages = &self.pages;
+        let end_idx = self.entries.len().min(pages.index() + pages.per_page());
+        let entries = &self.entries[pages.index()..end_idx];
+
+        let mut description = String::with_capacity(entries.len() * 160);
+
+        for entry in entries {
+            let _ = writeln!(
+                description,
+                "**#{idx}** [{artist} - {title} [{version}]]({OSU_BASE}b/{map_id}) [{stars:.2}★]",
+                idx = entry.original_idx,
+                artist = entry.artist.cow_escape_markdown(),
+                title = entry.title.cow_escape_markdown(),
+                version = entry.version.cow_escape_markdown(),
+                map_id = entry.map_id,
+                stars = entry.stars,
+            );
+
+            let _ = match entry.score_pp {
+                Some(score_pp) => writeln!(
+                    description,
+                    "{score_pp:.2}pp → **{pp:.2}pp** ({delta:+.2})",
+                    pp = entry.pp,
+                    delta = entry.pp - score_pp,
+                ),
+                None => writeln!(description, "**{:.2}pp**", entry.pp),
+            };
+        }
+
+        description.pop();
+
+        let sort = match self.sort {
+            SimulateBatchOrder::Pp => "pp",
+            SimulateBatchOrder::Stars => "stars",
+            SimulateBatchOrder::Original => "original order",
+        };
+
+        let footer_text = format!(
+            "Page {}/{} • Sorted by {sort}",
+            pages.curr_page(),
+            pages.last_page()
+        );
+
+        let embed = EmbedBuilder::new()
+            .description(description)
+            .footer(FooterBuilder::new(footer_text))
+            .title(self.title.as_ref());
+
+        BuildPage::new(embed, false)
+            .content(self.content.clone())
+            .boxed()
+    }
+
+    fn build_components(&self) -> Vec<Component> {
+        self.pages.components()
+    }
+
+    fn handle_component<'a>(
+        &'a mut self,
+        component: &'a mut InteractionComponent,
+    ) -> BoxFuture<'a, ComponentResult> {
+        handle_pagination_component(component, self.msg_owner, false, &mut self.pages)
+    }
+
+    fn handle_modal<'a>(
+        &'a mut self,
+        modal: &'a mut InteractionModal,
+    ) -> BoxFuture<'a, Result<()>> {
+        handle_pagination_modal(modal, self.msg_owner, false, &mut self.pages)
+    }
+}
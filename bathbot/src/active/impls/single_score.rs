@@ -42,15 +42,19 @@ use crate::{
         ActiveMessages, BuildPage, ComponentResult, IActiveMessage,
     },
     commands::{
-        osu::{OngoingRender, RenderStatus, RenderStatusInner, RENDERER_NAME},
+        osu::{
+            guild_skin_settings, miss_analysis_message, OngoingRender, RenderStatus,
+            RenderStatusInner, RENDERER_NAME,
+        },
         utility::{ScoreEmbedData, ScoreEmbedDataWrap},
     },
     core::{buckets::BucketName, Context},
     embeds::{attachment, HitResultFormatter},
-    manager::{redis::RedisData, OwnedReplayScore, ReplayScore},
+    manager::{redis::RedisData, OsuMap, OwnedReplayScore, ReplayScore},
     util::{
         interaction::{InteractionComponent, InteractionModal},
         osu::{GradeFormatter, ScoreFormatter},
+        replay::Replay as OsuReplay,
         Authored, Emote, MessageExt,
     },
 };
@@ -191,30 +195,68 @@ impl SingleScorePagination {
             Err(err) => return ComponentResult::Err(err),
         };
 
-        let score_id = match data.miss_analyzer.take() {
-            Some(miss_analyzer) => miss_analyzer.score_id,
-            None => return ComponentResult::Err(eyre!("Unexpected miss analyzer component")),
+        let Some(replay) = data.miss_analyzer.take() else {
+            return ComponentResult::Err(eyre!("Unexpected miss analyzer component"));
         };
 
-        let Some(guild) = component.guild_id.map(Id::get) else {
-            return ComponentResult::Err(eyre!("Missing guild id for miss analyzer button"));
-        };
+        let map = data.map.clone();
+        let username = self.username.clone();
 
-        let channel = component.channel_id.get();
-        let msg = component.message.id.get();
+        // Spawn in new task so that we're sure to callback the component in time
+        tokio::spawn(Self::miss_analyzer_response(
+            (component.message.id, component.message.channel_id),
+            component.permissions,
+            replay,
+            map,
+            username,
+        ));
 
-        debug!(
-            score_id,
-            msg, channel, guild, "Sending message to miss analyzer",
-        );
+        ComponentResult::BuildPage
+    }
+
+    async fn miss_analyzer_response(
+        orig: (Id<MessageMarker>, Id<ChannelMarker>),
+        permissions: Option<Permissions>,
+        replay: OwnedReplayScore,
+        map: OsuMap,
+        username: Box<str>,
+    ) {
+        let score = ReplayScore::from(replay);
 
-        let res_fut = Context::client().miss_analyzer_score_response(guild, channel, msg, score_id);
+        let builder = match Context::replay().get_replay(&score, &username).await {
+            Ok(Some(bytes)) => match OsuReplay::parse(&bytes) {
+                Ok(replay) => miss_analysis_message(&replay, &map).unwrap_or_else(|| {
+                    let content = "Only osu!standard replays can be analyzed";
+                    let embed = EmbedBuilder::new().color_red().description(content);
 
-        if let Err(err) = res_fut.await {
-            warn!(?err, "Failed to send miss analyzer response");
-        }
+                    MessageBuilder::new().embed(embed)
+                }),
+                Err(err) => {
+                    warn!(?err, "Failed to parse replay for miss analysis");
 
-        ComponentResult::BuildPage
+                    let embed = EmbedBuilder::new().color_red().description(GENERAL_ISSUE);
+
+                    MessageBuilder::new().embed(embed)
+                }
+            },
+            Ok(None) => {
+                let content = "Looks like the replay for that score is not available";
+                let embed = EmbedBuilder::new().color_red().description(content);
+
+                MessageBuilder::new().embed(embed)
+            }
+            Err(err) => {
+                error!(?err, "Failed to get replay for miss analysis");
+
+                let embed = EmbedBuilder::new().color_red().description(GENERAL_ISSUE);
+
+                MessageBuilder::new().embed(embed)
+            }
+        };
+
+        if let Err(err) = orig.reply(builder, permissions).await {
+            error!(?err, "Failed to reply with miss analysis");
+        }
     }
 
     async fn handle_render_button(&mut self, component: &InteractionComponent) -> ComponentResult {
@@ -381,16 +423,8 @@ impl SingleScorePagination {
             let _ = update_fut.await;
         }
 
-        let allow_custom_skins = match guild {
-            Some(guild_id) => {
-                Context::guild_config()
-                    .peek(guild_id, |config| config.allow_custom_skins.unwrap_or(true))
-                    .await
-            }
-            None => true,
-        };
-
-        let skin = settings.skin(allow_custom_skins);
+        let (allow_custom_skins, guild_skin) = guild_skin_settings(guild).await;
+        let skin = settings.skin_or(allow_custom_skins, guild_skin.as_ref());
 
         let render_fut = Context::ordr()
             .expect("ordr unavailable")
@@ -456,7 +490,7 @@ impl IActiveMessage for SingleScorePagination {
                     custom_id: Some("miss_analyzer".to_owned()),
                     disabled: false,
                     emoji: Some(Emote::Miss.reaction_type()),
-                    label: Some("Miss analyzer".to_owned()),
+                    label: Some("Miss analysis".to_owned()),
                     style: ButtonStyle::Primary,
                     url: None,
                 }));
@@ -1,6 +1,6 @@
 use std::fmt::{Display, Formatter, Result as FmtResult, Write};
 
-use bathbot_model::rosu_v2::user::User;
+use bathbot_model::{rosu_v2::user::User, twilight_model::id::IdRkyv, ScoreSlim};
 use bathbot_psql::model::configs::ScoreData;
 use bathbot_util::{
     constants::OSU_BASE,
@@ -8,8 +8,9 @@ use bathbot_util::{
     numbers::{round, WithComma},
     CowUtils, EmbedBuilder, FooterBuilder, ModsFormatter, ScoreExt,
 };
-use eyre::Result;
+use eyre::{ContextCompat, Result, WrapErr};
 use futures::future::BoxFuture;
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_v2::prelude::GameMode;
 use time::OffsetDateTime;
 use twilight_model::{
@@ -24,10 +25,14 @@ use crate::{
     },
     commands::{
         osu::TopScoreOrder,
-        utility::{ScoreEmbedDataHalf, ScoreEmbedDataWrap},
+        utility::{ScoreEmbedDataHalf, ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap},
     },
+    core::Context,
     embeds::{ComboFormatter, HitResultFormatter, PpFormatter},
-    manager::{redis::RedisData, OsuMap},
+    manager::{
+        redis::{osu::UserArgs, RedisData},
+        OsuMap,
+    },
     util::{
         interaction::{InteractionComponent, InteractionModal},
         osu::{GradeFormatter, ScoreFormatter},
@@ -61,6 +66,28 @@ impl TopPagination {
         }
     }
 
+    /// Returns `None` if any entry is not a [`ScoreEmbedDataHalf`].
+    pub fn persist(self) -> Option<PersistedTopPagination> {
+        let entries = self
+            .entries
+            .into_vec()
+            .into_iter()
+            .map(|entry| entry.into_half().map(PersistedTopEntry::from))
+            .collect::<Option<_>>()?;
+
+        Some(PersistedTopPagination {
+            user_id: self.user.user_id(),
+            mode: self.mode,
+            entries,
+            sort_by: self.sort_by,
+            condensed_list: self.condensed_list,
+            score_data: self.score_data,
+            content: self.content,
+            msg_owner: self.msg_owner,
+            pages: self.pages,
+        })
+    }
+
     fn build_condensed(&self) -> BuildPage {
         let pages = &self.pages;
         let end_idx = self.entries.len().min(pages.index() + pages.per_page());
@@ -354,6 +381,126 @@ impl TopPaginationBuilder {
     }
 }
 
+/// State of a [`TopPagination`] that can be stored across restarts.
+///
+/// Instead of the maps themselves only their ids are stored and the maps are
+/// loaded again on restore.
+#[derive(Archive, Deserialize, Serialize)]
+pub struct PersistedTopPagination {
+    user_id: u32,
+    mode: GameMode,
+    entries: Box<[PersistedTopEntry]>,
+    sort_by: TopScoreOrder,
+    condensed_list: bool,
+    score_data: ScoreData,
+    content: Box<str>,
+    #[with(IdRkyv)]
+    msg_owner: Id<UserMarker>,
+    pages: Pages,
+}
+
+impl PersistedTopPagination {
+    pub async fn restore(self) -> Result<TopPagination> {
+        let maps_id_checksum = self
+            .entries
+            .iter()
+            .map(|entry| (entry.map_id as i32, entry.checksum.as_deref()))
+            .collect();
+
+        let user_args = UserArgs::user_id(self.user_id, self.mode);
+        let user_fut = Context::redis().osu_user(user_args);
+        let maps_fut = Context::osu_map().maps(&maps_id_checksum);
+
+        let (user_res, maps_res) = tokio::join!(user_fut, maps_fut);
+
+        let user = user_res.wrap_err("Failed to get user")?;
+        let maps = maps_res?;
+
+        let entries = self
+            .entries
+            .into_vec()
+            .into_iter()
+            .map(|entry| {
+                // Multiple entries may share a map so it's cloned
+                let map = maps
+                    .get(&entry.map_id)
+                    .cloned()
+                    .wrap_err("Missing map of persisted entry")?
+                    .convert(entry.score.mode);
+
+                Ok(ScoreEmbedDataWrap::from(entry.into_half(map)))
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(TopPagination {
+            user,
+            mode: self.mode,
+            entries,
+            sort_by: self.sort_by,
+            condensed_list: self.condensed_list,
+            score_data: self.score_data,
+            content: self.content,
+            msg_owner: self.msg_owner,
+            pages: self.pages,
+        })
+    }
+}
+
+/// [`ScoreEmbedDataHalf`] without its map.
+#[derive(Archive, Deserialize, Serialize)]
+struct PersistedTopEntry {
+    user_id: u32,
+    checksum: Option<String>,
+    score: ScoreSlim,
+    map_id: u32,
+    stars: f32,
+    max_combo: u32,
+    max_pp: f32,
+    pb_idx: Option<ScoreEmbedDataPersonalBest>,
+    legacy_scores: bool,
+    with_render: bool,
+    with_miss_analyzer: bool,
+    original_idx: Option<usize>,
+}
+
+impl PersistedTopEntry {
+    fn into_half(self, map: OsuMap) -> ScoreEmbedDataHalf {
+        ScoreEmbedDataHalf {
+            user_id: self.user_id,
+            checksum: self.checksum,
+            score: self.score,
+            map,
+            stars: self.stars,
+            max_combo: self.max_combo,
+            max_pp: self.max_pp,
+            pb_idx: self.pb_idx,
+            legacy_scores: self.legacy_scores,
+            with_render: self.with_render,
+            with_miss_analyzer: self.with_miss_analyzer,
+            original_idx: self.original_idx,
+        }
+    }
+}
+
+impl From<ScoreEmbedDataHalf> for PersistedTopEntry {
+    fn from(half: ScoreEmbedDataHalf) -> Self {
+        Self {
+            user_id: half.user_id,
+            map_id: half.map.map_id(),
+            checksum: half.checksum,
+            score: half.score,
+            stars: half.stars,
+            max_combo: half.max_combo,
+            max_pp: half.max_pp,
+            pb_idx: half.pb_idx,
+            legacy_scores: half.legacy_scores,
+            with_render: half.with_render,
+            with_miss_analyzer: half.with_miss_analyzer,
+            original_idx: half.original_idx,
+        }
+    }
+}
+
 fn mode_str(mode: GameMode) -> &'static str {
     match mode {
         GameMode::Osu => "osu!",
@@ -7,7 +7,7 @@ use bathbot_util::{modal::ModalBuilder, EmbedBuilder, IntHasher, MessageBuilder}
 use enum_dispatch::enum_dispatch;
 use eyre::{Report, Result, WrapErr};
 use flexmap::tokio::TokioMutexMap;
-use futures::future::BoxFuture;
+use futures::{future::BoxFuture, StreamExt};
 use tokio::sync::watch::Sender;
 use twilight_model::{
     channel::message::Component,
@@ -18,20 +18,21 @@ pub use self::origin::ActiveMessageOriginError;
 use self::{
     builder::ActiveMessagesBuilder,
     impls::{
-        BackgroundGameSetup, BadgesPagination, BookmarksPagination, CachedRender,
-        ChangelogPagination, CompareMostPlayedPagination, CompareScoresPagination,
-        CompareTopPagination, HelpInteractionCommand, HelpPrefixMenu, HigherLowerGame,
-        LeaderboardPagination, MapPagination, MapSearchPagination, MatchComparePagination,
-        MatchCostPagination, MedalCountPagination, MedalRarityPagination, MedalsCommonPagination,
-        MedalsListPagination, MedalsMissingPagination, MedalsRecentPagination,
-        MostPlayedPagination, NoChokePagination, OsuStatsBestPagination, OsuStatsPlayersPagination,
-        OsuStatsScoresPagination, ProfileMenu, RankingCountriesPagination, RankingPagination,
-        RecentListPagination, RegionTopPagination, RenderSettingsActive, ScoreEmbedBuilderActive,
-        ScoresMapPagination, ScoresServerPagination, ScoresUserPagination, SettingsImport,
-        SimulateComponents, SingleScorePagination, SkinsPagination, SlashCommandsPagination,
-        SnipeCountryListPagination, SnipeDifferencePagination, SnipePlayerListPagination,
-        TopIfPagination, TopPagination,
+        BackgroundGameSetup, BackgroundTagging, BadgesPagination, BookmarksPagination,
+        CachedRender, ChangelogPagination, CompareMostPlayedPagination, CompareScoresPagination,
+        CompareTopPagination, DailyChallenge, GuildSkinsPagination, HelpInteractionCommand,
+        HelpPrefixMenu, HigherLowerGame, LeaderboardPagination, MapPagination, MapSearchPagination,
+        MatchComparePagination, MatchCostPagination, MatchCostSeriesPagination,
+        MedalCountPagination, MedalRarityPagination, MedalsCommonPagination, MedalsListPagination,
+        MedalsMissingPagination, MedalsRecentPagination, MostPlayedPagination, NoChokePagination,
+        OsuStatsBestPagination, OsuStatsPlayersPagination, OsuStatsScoresPagination, ProfileMenu,
+        RankingCountriesPagination, RankingPagination, RecentListPagination, RegionTopPagination,
+        RenderSettingsActive, ScoreEmbedBuilderActive, ScoresMapPagination, ScoresServerPagination,
+        ScoresUserPagination, SettingsImport, SimulateComponents, SingleScorePagination,
+        SkinsPagination, SlashCommandsPagination, SnipeCountryListPagination,
+        SnipeDifferencePagination, SnipePlayerListPagination, TopIfPagination, TopPagination,
     },
+    persist::PersistedActive,
     response::ActiveResponse,
 };
 use crate::{
@@ -47,11 +48,13 @@ pub mod impls;
 mod builder;
 mod origin;
 mod pagination;
+mod persist;
 mod response;
 
 #[enum_dispatch(IActiveMessage)]
 pub enum ActiveMessage {
     BackgroundGameSetup,
+    BackgroundTagging,
     BadgesPagination,
     BookmarksPagination,
     CachedRender,
@@ -59,6 +62,8 @@ pub enum ActiveMessage {
     CompareMostPlayedPagination,
     CompareScoresPagination,
     CompareTopPagination,
+    DailyChallenge,
+    GuildSkinsPagination,
     HelpInteractionCommand,
     HelpPrefixMenu,
     HigherLowerGame,
@@ -67,6 +72,7 @@ pub enum ActiveMessage {
     MapSearchPagination,
     MatchComparePagination,
     MatchCostPagination,
+    MatchCostSeriesPagination,
     MedalCountPagination,
     MedalRarityPagination,
     MedalsCommonPagination,
@@ -102,7 +108,8 @@ pub enum ActiveMessage {
 
 struct FullActiveMessage {
     active_msg: ActiveMessage,
-    activity_tx: Sender<()>,
+    activity_tx: Sender<Instant>,
+    response: ActiveResponse,
 }
 
 pub struct ActiveMessages {
@@ -136,9 +143,21 @@ impl ActiveMessages {
         let msg_id = component.message.id;
         let mut guard = Context::get().active_msgs.inner.lock(&msg_id).await;
 
+        // The active message might have been persisted before a restart
+        if guard.get().is_none() {
+            match PersistedActive::restore(msg_id).await {
+                Ok(Some(full)) => {
+                    guard.insert(full);
+                }
+                Ok(None) => {}
+                Err(err) => warn!(?err, "Failed to restore active message"),
+            }
+        }
+
         let Some(FullActiveMessage {
             active_msg,
             activity_tx,
+            ..
         }) = guard.get_mut()
         else {
             return error!(
@@ -150,7 +169,7 @@ impl ActiveMessages {
 
         async fn handle_component_inner(
             active_msg: &mut ActiveMessage,
-            activity_tx: &Sender<()>,
+            activity_tx: &Sender<Instant>,
             component: &mut InteractionComponent,
         ) {
             match active_msg.handle_component(component).await {
@@ -190,7 +209,7 @@ impl ActiveMessages {
                             );
                         }
 
-                        let _ = activity_tx.send(());
+                        let _ = activity_tx.send(Instant::now());
                     }
                     Err(err) => {
                         BotMetrics::inc_command_error(
@@ -215,7 +234,7 @@ impl ActiveMessages {
                         return error!(?err, "Failed to create modal");
                     }
 
-                    let _ = activity_tx.send(());
+                    let _ = activity_tx.send(Instant::now());
                 }
                 ComponentResult::Err(err) => {
                     BotMetrics::inc_command_error("component", component.data.custom_id.clone());
@@ -241,14 +260,27 @@ impl ActiveMessages {
 
         EventKind::Modal.log(&modal, &modal.data.custom_id).await;
 
-        let mut guard = match modal.message {
-            Some(ref msg) => Context::get().active_msgs.inner.own(msg.id).await,
-            None => return warn!("Received modal without message"),
+        let Some(msg_id) = modal.message.as_ref().map(|msg| msg.id) else {
+            return warn!("Received modal without message");
         };
 
+        let mut guard = Context::get().active_msgs.inner.own(msg_id).await;
+
+        // The active message might have been persisted before a restart
+        if guard.get().is_none() {
+            match PersistedActive::restore(msg_id).await {
+                Ok(Some(full)) => {
+                    guard.insert(full);
+                }
+                Ok(None) => {}
+                Err(err) => warn!(?err, "Failed to restore active message"),
+            }
+        }
+
         let Some(FullActiveMessage {
             active_msg,
             activity_tx,
+            ..
         }) = guard.get_mut()
         else {
             return error!(name = %modal.data.custom_id, ?modal, "Unknown modal");
@@ -256,7 +288,7 @@ impl ActiveMessages {
 
         async fn handle_modal_inner(
             active_msg: &mut ActiveMessage,
-            activity_tx: &Sender<()>,
+            activity_tx: &Sender<Instant>,
             modal: &mut InteractionModal,
         ) {
             if let Err(err) = active_msg.handle_modal(modal).await {
@@ -295,7 +327,7 @@ impl ActiveMessages {
                         );
                     }
 
-                    let _ = activity_tx.send(());
+                    let _ = activity_tx.send(Instant::now());
                 }
                 Err(err) => {
                     BotMetrics::inc_command_error("modal", modal.data.custom_id.clone());
@@ -315,8 +347,33 @@ impl ActiveMessages {
         BotMetrics::observe_command("modal", modal.data.custom_id, elapsed);
     }
 
-    pub async fn clear(&self) {
-        self.inner.clear().await
+    /// Removes all active messages and stores those that support it so that
+    /// they can be restored after a restart.
+    ///
+    /// Returns how many active messages were stored.
+    pub async fn persist(&self) -> usize {
+        let mut msgs = Vec::new();
+        let mut stream = self.inner.iter();
+
+        while let Some(guard) = stream.next().await {
+            msgs.push(*guard.key());
+        }
+
+        let mut count = 0;
+
+        for msg in msgs {
+            let Some(full) = self.remove_full(msg).await else {
+                continue;
+            };
+
+            match PersistedActive::store(full).await {
+                Ok(true) => count += 1,
+                Ok(false) => {}
+                Err(err) => warn!(?err, "Failed to persist active message"),
+            }
+        }
+
+        count
     }
 
     pub async fn remove(&self, msg: Id<MessageMarker>) {
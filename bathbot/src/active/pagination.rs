@@ -4,6 +4,7 @@ use bathbot_util::{
 };
 use eyre::{ContextCompat, Result, WrapErr};
 use futures::{future::BoxFuture, FutureExt};
+use rkyv::{Archive, Deserialize, Serialize};
 use twilight_model::{
     channel::message::{
         component::{ActionRow, Button, ButtonStyle},
@@ -18,7 +19,7 @@ use crate::util::{
     Authored, ComponentExt, Emote, ModalExt,
 };
 
-#[derive(Clone, Debug)]
+#[derive(Archive, Clone, Debug, Deserialize, Serialize)]
 pub struct Pages {
     index: usize,
     last_index: usize,
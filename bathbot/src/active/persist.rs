@@ -0,0 +1,187 @@
+use std::time::{Duration, Instant};
+
+use bathbot_model::rkyv_util::time::DateTimeRkyv;
+use eyre::{Result, WrapErr};
+use rkyv::{Archive, Deserialize, Serialize};
+use time::OffsetDateTime;
+use tokio::sync::watch;
+use twilight_model::id::{marker::MessageMarker, Id};
+
+use super::{
+    builder::ActiveMessagesBuilder,
+    impls::{
+        MatchCostSeriesPagination, MedalCountPagination, MedalRarityPagination,
+        PersistedProfileMenu, PersistedSimulateComponents, PersistedTopPagination,
+    },
+    response::ActiveResponse,
+    ActiveMessage, FullActiveMessage, IActiveMessage,
+};
+use crate::core::Context;
+
+/// Active messages whose state can be stored across restarts.
+///
+/// Active messages whose state consists of plain data are stored as is.
+/// Those holding maps or users store only their ids instead and load them
+/// again on restore. Simulations of attached maps can not be restored and are
+/// still dropped on shutdown.
+///
+/// To make an active message persistable, derive rkyv's traits for it or for
+/// a persisted counterpart, add it as variant here, and handle it in
+/// [`ActiveMessage::into_persisted`].
+#[derive(Archive, Deserialize, Serialize)]
+pub(super) enum PersistedActiveMessage {
+    MatchCostSeries(MatchCostSeriesPagination),
+    MedalCount(MedalCountPagination),
+    MedalRarity(MedalRarityPagination),
+    Profile(PersistedProfileMenu),
+    Simulate(PersistedSimulateComponents),
+    Top(PersistedTopPagination),
+}
+
+impl PersistedActiveMessage {
+    async fn into_active(self) -> Result<ActiveMessage> {
+        let active = match self {
+            Self::MatchCostSeries(active) => active.into(),
+            Self::MedalCount(active) => active.into(),
+            Self::MedalRarity(active) => active.into(),
+            Self::Profile(persisted) => persisted.restore().await?.into(),
+            Self::Simulate(persisted) => persisted.restore().await?.into(),
+            Self::Top(persisted) => persisted.restore().await?.into(),
+        };
+
+        Ok(active)
+    }
+}
+
+impl ActiveMessage {
+    /// Returns `None` if the active message can not be persisted.
+    fn into_persisted(self) -> Option<PersistedActiveMessage> {
+        match self {
+            Self::MatchCostSeriesPagination(active) => {
+                Some(PersistedActiveMessage::MatchCostSeries(active))
+            }
+            Self::MedalCountPagination(active) => Some(PersistedActiveMessage::MedalCount(active)),
+            Self::MedalRarityPagination(active) => {
+                Some(PersistedActiveMessage::MedalRarity(active))
+            }
+            Self::ProfileMenu(active) => Some(PersistedActiveMessage::Profile(active.persist())),
+            Self::SimulateComponents(active) => {
+                active.persist().map(PersistedActiveMessage::Simulate)
+            }
+            Self::TopPagination(active) => active.persist().map(PersistedActiveMessage::Top),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Archive, Deserialize, Serialize)]
+pub(super) struct PersistedActive {
+    active_msg: PersistedActiveMessage,
+    response: ActiveResponse,
+    /// When the active message would have timed out
+    #[with(DateTimeRkyv)]
+    expires_at: OffsetDateTime,
+}
+
+impl PersistedActive {
+    /// Stores the active message in redis for the time it has left until it
+    /// would have timed out.
+    ///
+    /// Returns `false` if the active message does not support persisting.
+    pub(super) async fn store(full: FullActiveMessage) -> Result<bool> {
+        let FullActiveMessage {
+            active_msg,
+            activity_tx,
+            response,
+        } = full;
+
+        let Some(until_timeout) = active_msg.until_timeout() else {
+            return Ok(false);
+        };
+
+        let last_activity = *activity_tx.borrow();
+        let remaining = until_timeout.saturating_sub(last_activity.elapsed());
+
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+
+        let Some(active_msg) = active_msg.into_persisted() else {
+            return Ok(false);
+        };
+
+        let key = Self::key(response.msg);
+        let persisted = Self {
+            active_msg,
+            response,
+            expires_at: OffsetDateTime::now_utc() + remaining,
+        };
+
+        Context::cache()
+            .store_new::<_, _, 4096>(&key, &persisted, remaining.as_secs().max(1))
+            .await
+            .wrap_err("Failed to store persisted active message")?;
+
+        Ok(true)
+    }
+
+    /// Restores the active message that was stored for the given message.
+    ///
+    /// Returns `None` if no active message was stored for it.
+    pub(super) async fn restore(msg: Id<MessageMarker>) -> Result<Option<FullActiveMessage>> {
+        let key = Self::key(msg);
+
+        let persisted = match Context::cache().fetch::<_, Self>(&key).await {
+            Ok(Ok(archived)) => archived.deserialize::<Self>(),
+            Ok(Err(_)) => return Ok(None),
+            Err(err) => return Err(err.wrap_err("Failed to fetch persisted active message")),
+        };
+
+        // The message will be persisted again on the next shutdown
+        if let Err(err) = Context::cache().delete(&key).await {
+            warn!(?err, "Failed to delete persisted active message");
+        }
+
+        let Self {
+            active_msg,
+            response,
+            expires_at,
+        } = persisted;
+
+        // Only the time that was left before the shutdown remains
+        let Ok(remaining) = Duration::try_from(expires_at - OffsetDateTime::now_utc()) else {
+            return Ok(None);
+        };
+
+        let active_msg = active_msg
+            .into_active()
+            .await
+            .wrap_err("Failed to restore persisted active message")?;
+
+        let Some(until_timeout) = active_msg.until_timeout() else {
+            return Ok(None);
+        };
+
+        let last_activity = Instant::now()
+            .checked_sub(until_timeout.saturating_sub(remaining))
+            .unwrap_or_else(Instant::now);
+
+        let (activity_tx, activity_rx) = watch::channel(last_activity);
+        ActiveMessagesBuilder::spawn_timeout(
+            activity_rx,
+            response.clone(),
+            until_timeout,
+            remaining,
+        );
+
+        Ok(Some(FullActiveMessage {
+            active_msg,
+            activity_tx,
+            response,
+        }))
+    }
+
+    fn key(msg: Id<MessageMarker>) -> String {
+        format!("active_msg_{msg}")
+    }
+}
@@ -1,4 +1,6 @@
+use bathbot_model::twilight_model::id::IdRkyv;
 use bathbot_util::MessageBuilder;
+use rkyv::{Archive, Deserialize, Serialize};
 use twilight_http::response::ResponseFuture;
 use twilight_model::{
     channel::Message,
@@ -14,14 +16,22 @@ use crate::{
     util::{InteractionToken, MessageExt},
 };
 
+#[derive(Archive, Clone, Deserialize, Serialize)]
 pub struct ActiveResponse {
+    #[with(IdRkyv)]
     pub msg: Id<MessageMarker>,
     pub inner: ActiveResponseInner,
 }
 
+#[derive(Archive, Clone, Deserialize, Serialize)]
 pub enum ActiveResponseInner {
-    Message { channel: Id<ChannelMarker> },
-    Interaction { token: Box<str> },
+    Message {
+        #[with(IdRkyv)]
+        channel: Id<ChannelMarker>,
+    },
+    Interaction {
+        token: Box<str>,
+    },
 }
 
 impl ActiveResponse {
@@ -31,15 +31,15 @@ pub async fn bigger(msg: &Message, permissions: Option<Permissions>) -> Result<(
     let _ = Context::http().create_typing_trigger(msg.channel_id).await;
 
     match Context::bg_games().read(&msg.channel_id).await.get() {
-        Some(game) => match game.sub_image().await {
+        Some(game) => match game.reveal_more().await {
             Ok(bytes) => {
-                let builder = MessageBuilder::new().attachment("bg_img.png", bytes);
+                let builder = MessageBuilder::new().attachment(game.attachment_name(), bytes);
                 msg.create_message(builder, permissions).await?;
             }
             Err(err) => {
                 let _ = msg.error(GENERAL_ISSUE).await;
 
-                return Err(err.wrap_err("Failed to get subimage"));
+                return Err(err.wrap_err("Failed to reveal more"));
             }
         },
         None => {
@@ -17,7 +17,7 @@ pub async fn hint(msg: &Message, permissions: Option<Permissions>) -> Result<()>
     }
 
     match Context::bg_games().read(&msg.channel_id).await.get() {
-        Some(game) => match game.hint().await {
+        Some(game) => match game.hint(msg.author.id).await {
             Ok(hint) => {
                 let builder = MessageBuilder::new().content(hint);
                 msg.create_message(builder, permissions).await?;
@@ -20,6 +20,7 @@ use crate::{
         impls::{BackgroundGame, BackgroundGameSetup},
         ActiveMessages,
     },
+    core::commands::CommandOrigin,
     util::{
         interaction::InteractionCommand, Authored, ChannelExt, CheckPermissions,
         InteractionCommandExt,
@@ -32,7 +33,7 @@ mod hint;
 mod rankings;
 mod skip;
 mod stop;
-// mod tags; // TODO
+mod tags;
 
 #[command]
 #[desc("Play the background guessing game, use `/bg` to start")]
@@ -56,11 +57,13 @@ pub async fn prefix_backgroundgame(
                 • `<bg s[kip]` / `<bg r[esolve]`: Resolve the current background and \
                 give a new one with the same tag specs.\n\
                 • `<bg h[int]`: Receive a hint (can be used multiple times).\n\
-                • `<bg b[igger]`: Increase the radius of the displayed image (can be used multiple times).\n\
+                • `<bg b[igger]`: Increase the radius of the displayed image or the length of the audio clip \
+                (can be used multiple times).\n\
                 • `<bg stop`: Resolve the current background and stop the game.
                 • `<bg l[eaderboard] s[erver]`: Check out the global leaderboard for \
                 amount of correct guesses. If `server` or `s` is added at the end, \
-                I will only show members of this server.";
+                I will only show members of this server.\n\n\
+                Use `/bgrankings` for seasonal server rankings including guess times, hints, and streaks.";
 
             let builder = MessageBuilder::new().embed(content);
             msg.create_message(builder, permissions).await?;
@@ -75,8 +78,10 @@ pub async fn prefix_backgroundgame(
             let arg = args.next();
 
             match arg.as_ref().map(|arg| arg.as_ref()) {
-                Some("s" | "server") => leaderboard(msg, false).await,
-                _ => leaderboard(msg, true).await,
+                Some("s" | "server") => {
+                    leaderboard(CommandOrigin::from_msg(msg, permissions), false).await
+                }
+                _ => leaderboard(CommandOrigin::from_msg(msg, permissions), true).await,
             }
         }
         _ => {
@@ -103,11 +108,13 @@ pub async fn prefix_backgroundgame(
     • `<bg s[kip]` / `<bg r[esolve]`: Resolve the current background and \
     give a new one with the same tag specs.\n\
     • `<bg h[int]`: Receive a hint (can be used multiple times).\n\
-    • `<bg b[igger]`: Increase the radius of the displayed image (can be used multiple times).\n\
+    • `<bg b[igger]`: Increase the radius of the displayed image or the length of the audio clip \
+    (can be used multiple times).\n\
     • `<bg stop`: Resolve the current background and stop the game.
     • `<bg l[eaderboard] s[erver]`: Check out the global leaderboard for \
     amount of correct guesses. If `server` or `s` is added at the end, \
-    I will only show members of this server."
+    I will only show members of this server.\n\n\
+    Use `/bgrankings` for seasonal server rankings including guess times, hints, and streaks."
 )]
 #[flags(SKIP_DEFER)]
 pub struct Bg {
@@ -153,6 +160,23 @@ impl GameDifficulty {
     }
 }
 
+/// What players are given to guess the map's title
+#[derive(Copy, Clone, Debug, Default)]
+pub enum BgGameKind {
+    #[default]
+    Background,
+    Song,
+}
+
+impl BgGameKind {
+    pub fn attachment_name(self) -> &'static str {
+        match self {
+            Self::Background => "bg_img.png",
+            Self::Song => "bg_audio.mp3",
+        }
+    }
+}
+
 impl Default for GameDifficulty {
     fn default() -> Self {
         Self::Normal
@@ -313,7 +337,14 @@ async fn slash_bg(mut command: InteractionCommand) -> Result<()> {
                 command.callback(builder, false).await?;
             }
 
-            let game_fut = BackgroundGame::new(channel, entries, Effects::empty(), difficulty);
+            let game_fut = BackgroundGame::new(
+                channel,
+                command.guild_id,
+                entries,
+                Effects::empty(),
+                difficulty,
+                BgGameKind::Background,
+            );
 
             Context::bg_games()
                 .own(channel)
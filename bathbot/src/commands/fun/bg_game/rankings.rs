@@ -1,30 +1,101 @@
-use std::collections::BTreeMap;
+use std::{cmp::Ordering, collections::BTreeMap};
 
-use bathbot_model::{RankingEntries, RankingEntry, RankingKind};
-use bathbot_util::{constants::GENERAL_ISSUE, IntHasher};
+use bathbot_macros::SlashCommand;
+use bathbot_model::{BgSeasonColumn, RankingEntries, RankingEntry, RankingKind};
+use bathbot_util::{constants::GENERAL_ISSUE, IntHasher, MessageBuilder};
 use eyre::Result;
 use hashbrown::HashSet;
-use twilight_model::{channel::Message, id::Id};
+use rosu_v2::prelude::Username;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::UserMarker, Id};
 
 use crate::{
     active::{impls::RankingPagination, ActiveMessages},
-    util::ChannelExt,
+    core::commands::{checks::check_authority, CommandOrigin},
+    util::{interaction::InteractionCommand, InteractionCommandExt},
     Context,
 };
 
-pub async fn leaderboard(msg: &Message, global: bool) -> Result<()> {
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "bgrankings",
+    dm_permission = false,
+    desc = "Leaderboards of the background guessing game",
+    help = "Leaderboards of the background guessing game.\n\
+    Besides the all-time amount of correct guesses, each server has seasonal rankings \
+    that also track the average guess time, used hints, and the best streak of consecutive \
+    correct guesses.\n\
+    Server authorities can start a new season with `/bgrankings newseason`."
+)]
+pub enum BgRankings {
+    #[command(name = "global")]
+    Global(BgRankingsGlobal),
+    #[command(name = "server")]
+    Server(BgRankingsServer),
+    #[command(name = "season")]
+    Season(BgRankingsSeason),
+    #[command(name = "newseason")]
+    NewSeason(BgRankingsNewSeason),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "global", desc = "All-time correct guesses across all servers")]
+pub struct BgRankingsGlobal;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "server",
+    desc = "All-time correct guesses of members in this server"
+)]
+pub struct BgRankingsServer;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "season", desc = "Seasonal ranking of this server")]
+pub struct BgRankingsSeason {
+    #[command(min_value = 1, desc = "Specify a season, defaults to the current one")]
+    season: Option<i64>,
+    #[command(desc = "Specify the stat to sort by, defaults to correct guesses")]
+    sort: Option<BgSeasonColumn>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "newseason",
+    desc = "End the current season and start a new one",
+    help = "End the current season and start a new one.\n\
+    Stats of previous seasons remain available through the `season` option of \
+    `/bgrankings season`.\n\
+    Only server authorities can use this command."
+)]
+pub struct BgRankingsNewSeason;
+
+async fn slash_bgrankings(mut command: InteractionCommand) -> Result<()> {
+    match BgRankings::from_interaction(command.input_data())? {
+        BgRankings::Global(_) => leaderboard((&mut command).into(), true).await,
+        BgRankings::Server(_) => leaderboard((&mut command).into(), false).await,
+        BgRankings::Season(args) => {
+            let season = args.season.map(|season| season as i32);
+            let column = args.sort.unwrap_or_default();
+
+            season_leaderboard((&mut command).into(), season, column).await
+        }
+        BgRankings::NewSeason(_) => new_season((&mut command).into()).await,
+    }
+}
+
+pub async fn leaderboard(orig: CommandOrigin<'_>, global: bool) -> Result<()> {
     let cache = Context::cache();
 
     let mut scores = match Context::games().bggame_leaderboard().await {
         Ok(scores) => scores,
         Err(err) => {
-            let _ = msg.error(GENERAL_ISSUE).await;
+            let _ = orig.error(GENERAL_ISSUE).await;
 
             return Err(err.wrap_err("failed to get bggame scores"));
         }
     };
 
-    let guild = msg.guild_id;
+    let guild = orig.guild_id();
 
     if let Some(guild) = guild.filter(|_| !global) {
         let members: HashSet<_, IntHasher> = cache
@@ -37,7 +108,8 @@ pub async fn leaderboard(msg: &Message, global: bool) -> Result<()> {
         scores.retain(|row| members.contains(&row.discord_id));
     }
 
-    let author = msg.author.id.get() as i64;
+    let msg_owner = orig.user_id()?;
+    let author = msg_owner.get() as i64;
 
     scores.sort_unstable_by(|a, b| b.score.cmp(&a.score));
     let author_idx = scores.iter().position(|row| row.discord_id == author);
@@ -46,31 +118,9 @@ pub async fn leaderboard(msg: &Message, global: bool) -> Result<()> {
     let mut entries = BTreeMap::new();
 
     for (i, row) in scores.iter().enumerate().take(20) {
-        let id = Id::new(row.discord_id as u64);
-
-        let name_opt = match Context::user_config().osu_name(id).await {
-            Ok(Some(name)) => Some(name),
-            Ok(None) => match cache.user(id).await {
-                Ok(Some(user)) => Some(user.name.as_ref().into()),
-                Ok(None) => None,
-                Err(err) => {
-                    warn!("{err:?}");
-
-                    None
-                }
-            },
-            Err(err) => {
-                warn!("{err:?}");
-
-                None
-            }
-        };
-
-        let name = name_opt.unwrap_or_else(|| "<unknown user>".into());
-
         let entry = RankingEntry {
             value: row.score as u64,
-            name,
+            name: user_name(Id::new(row.discord_id as u64)).await,
             country: None,
         };
 
@@ -90,8 +140,188 @@ pub async fn leaderboard(msg: &Message, global: bool) -> Result<()> {
         .author_idx(author_idx)
         .kind(data)
         .defer(false)
-        .msg_owner(msg.author.id)
+        .msg_owner(msg_owner)
+        .build();
+
+    ActiveMessages::builder(pagination).begin(orig).await
+}
+
+pub async fn season_leaderboard(
+    orig: CommandOrigin<'_>,
+    season: Option<i32>,
+    column: BgSeasonColumn,
+) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        orig.error("Seasons are only available in servers").await?;
+
+        return Ok(());
+    };
+
+    let season = match season {
+        Some(season) => season,
+        None => match Context::games().bggame_season(guild).await {
+            Ok((season, _)) => season,
+            Err(err) => {
+                let _ = orig.error(GENERAL_ISSUE).await;
+
+                return Err(err);
+            }
+        },
+    };
+
+    let mut scores = match Context::games()
+        .bggame_season_leaderboard(guild, season)
+        .await
+    {
+        Ok(scores) => scores,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    // Users that only requested hints don't have a guess time
+    if let BgSeasonColumn::GuessTime = column {
+        scores.retain(|score| score.guesses > 0);
+    }
+
+    if scores.is_empty() {
+        let content = format!("No background game stats have been stored for season {season}");
+        orig.error(content).await?;
+
+        return Ok(());
+    }
+
+    match column {
+        BgSeasonColumn::Guesses => scores.sort_unstable_by(|a, b| b.guesses.cmp(&a.guesses)),
+        BgSeasonColumn::GuessTime => scores.sort_unstable_by(|a, b| {
+            a.avg_guess_time()
+                .partial_cmp(&b.avg_guess_time())
+                .unwrap_or(Ordering::Equal)
+        }),
+        BgSeasonColumn::Hints => scores.sort_unstable_by(|a, b| b.hints.cmp(&a.hints)),
+        BgSeasonColumn::Streak => scores.sort_unstable_by(|a, b| b.best_streak.cmp(&a.best_streak)),
+    }
+
+    let msg_owner = orig.user_id()?;
+    let author = msg_owner.get() as i64;
+    let author_idx = scores.iter().position(|row| row.discord_id == author);
+
+    // Gather usernames for initial page
+    let entries = if let BgSeasonColumn::GuessTime = column {
+        let mut entries = BTreeMap::new();
+
+        for (i, row) in scores.iter().enumerate().take(20) {
+            let entry = RankingEntry {
+                value: row.avg_guess_time(),
+                name: user_name(Id::new(row.discord_id as u64)).await,
+                country: None,
+            };
+
+            entries.insert(i, entry);
+        }
+
+        RankingEntries::Float(entries)
+    } else {
+        let mut entries = BTreeMap::new();
+
+        for (i, row) in scores.iter().enumerate().take(20) {
+            let value = match column {
+                BgSeasonColumn::Guesses => row.guesses,
+                BgSeasonColumn::Hints => row.hints,
+                BgSeasonColumn::Streak => row.best_streak,
+                BgSeasonColumn::GuessTime => unreachable!(),
+            };
+
+            let entry = RankingEntry {
+                value: value as u64,
+                name: user_name(Id::new(row.discord_id as u64)).await,
+                country: None,
+            };
+
+            entries.insert(i, entry);
+        }
+
+        RankingEntries::Amount(entries)
+    };
+
+    let total = scores.len();
+
+    let data = RankingKind::BgSeason {
+        season,
+        column,
+        scores,
+    };
+
+    let pagination = RankingPagination::builder()
+        .entries(entries)
+        .total(total)
+        .author_idx(author_idx)
+        .kind(data)
+        .defer(false)
+        .msg_owner(msg_owner)
         .build();
 
-    ActiveMessages::builder(pagination).begin(msg).await
+    ActiveMessages::builder(pagination).begin(orig).await
+}
+
+pub async fn new_season(orig: CommandOrigin<'_>) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        orig.error("Seasons are only available in servers").await?;
+
+        return Ok(());
+    };
+
+    match check_authority(orig.user_id()?, Some(guild)).await {
+        Ok(None) => {}
+        Ok(Some(content)) => return orig.error(content).await,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    }
+
+    let season = match Context::games().bggame_new_season(guild).await {
+        Ok(season) => season,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let content = format!(
+        "Season {season} of the background game has started, good luck!\n\
+        Stats of season {prev} remain available through `/bgrankings season season:{prev}`.",
+        prev = season - 1
+    );
+
+    let builder = MessageBuilder::new().embed(content);
+    orig.create_message(builder).await?;
+
+    Ok(())
+}
+
+async fn user_name(id: Id<UserMarker>) -> Username {
+    let name_opt = match Context::user_config().osu_name(id).await {
+        Ok(Some(name)) => Some(name),
+        Ok(None) => match Context::cache().user(id).await {
+            Ok(Some(user)) => Some(user.name.as_ref().into()),
+            Ok(None) => None,
+            Err(err) => {
+                warn!("{err:?}");
+
+                None
+            }
+        },
+        Err(err) => {
+            warn!("{err:?}");
+
+            None
+        }
+    };
+
+    name_opt.unwrap_or_else(|| "<unknown user>".into())
 }
@@ -1,428 +1,549 @@
-use std::{str::FromStr, sync::Arc, time::Duration};
+use std::{collections::HashSet, fmt::Write, str::FromStr};
 
-use eyre::Report;
-use rand::RngCore;
-use rosu_v2::model::GameMode;
+use bathbot_macros::SlashCommand;
+use bathbot_model::MapsetTags;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_BASE},
+    EmbedBuilder, MessageBuilder,
+};
+use eyre::{Report, Result, WrapErr};
+use rand::seq::SliceRandom;
+use rosu_v2::prelude::GameMode;
+use serde::Deserialize;
 use tokio::fs;
-use tokio_stream::StreamExt;
-use twilight_model::{channel::ReactionType, gateway::event::Event, http::attachment::Attachment};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::{
+    channel::Attachment,
+    id::{marker::UserMarker, Id},
+};
 
-use super::ReactionWrapper;
+use super::BgGameMode;
 use crate::{
-    database::MapsetTagWrapper,
-    games::bg::MapsetTags,
-    util::{
-        constants::{
-            common_literals::{MANIA, OSU},
-            GENERAL_ISSUE, OSU_BASE, OWNER_USER_ID,
-        },
-        send_reaction, CowUtils, Emote,
-    },
-    Context, Result, CONFIG,
+    active::{impls::BackgroundTagging, ActiveMessages},
+    core::BotConfig,
+    util::{interaction::InteractionCommand, Authored, InteractionCommandExt},
+    Context,
 };
 
-#[command]
-#[short_desc("Help tagging backgrounds by tagging them manually")]
-#[long_desc(
-    "Manage the tags of a background for the bg game.\n\
-    First argument must be the mapset id, second argument must be either \
-    `a` or `add` to add tags, or `r` or `remove` to remove them. \n\
-    After that provide any of these pre-selected keywords:\n\
-    `farm, streams, alternate, old, meme, hardname, easy, hard, tech, weeb, bluesky, english`\n\
-    By default, all tags are marked as **true**, so removing them will be more important."
+/// How many already reviewed mapsets are queued when no unreviewed are left
+const REVIEWED_QUEUE_SIZE: usize = 50;
+
+/// How many entries are listed per section of the untagged report
+const REPORT_LIST_SIZE: usize = 20;
+
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "bgtags",
+    desc = "Manage the tags of background game mapsets",
+    help = "Manage the tags of background game mapsets.\n\
+    Tags are used to include or exclude backgrounds when starting a game via `/bg`.\n\
+    Only the bot owner and members with an authority role in the bot's dev server \
+    can use this command."
 )]
-#[usage("[mapset id] [add/a/remove/r] [list of tags]")]
-#[example("21662 r hard farm streams alternate hardname tech weeb bluesky")]
-#[aliases("bgtm", "bgtagmanual")]
-#[owner()]
-async fn bgtagsmanual(data: CommandData) -> Result<()> {
-    let (msg, mut args) = match data {
-        CommandData::Message { msg, args, .. } => (msg, args),
-        CommandData::Interaction { .. } => unreachable!(),
-    };
+pub enum BgTags {
+    #[command(name = "review")]
+    Review(BgTagsReview),
+    #[command(name = "import")]
+    Import(BgTagsImport),
+    #[command(name = "untagged")]
+    Untagged(BgTagsUntagged),
+    #[command(name = "undo")]
+    Undo(BgTagsUndo),
+}
 
-    // Parse mapset id
-    let mapset_id = match args.next().map(u32::from_str) {
-        Some(Ok(num)) => num,
-        Some(Err(_)) => {
-            let content = "Could not parse mapset id. Be sure to specify it as first argument";
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "review",
+    desc = "Review backgrounds and toggle their tags",
+    help = "Review backgrounds and toggle their tags.\n\
+    Backgrounds that have not been reviewed yet come first. \
+    If all backgrounds have been reviewed, random ones are shown instead."
+)]
+pub struct BgTagsReview {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<BgGameMode>,
+    #[command(min_value = 1, desc = "Review a specific mapset")]
+    mapset: Option<i64>,
+}
 
-            return msg.error(content).await;
-        }
-        None => {
-            let content = "Arguments: `[mapset id] [add/a/remove/r] [list of tags]`\n\
-            Example: `21662 r hard farm streams alternate hardname tech weeb bluesky`\n\
-            Tags: `farm, streams, alternate, old, meme, hardname, easy, hard, tech, \
-            weeb, bluesky, english`";
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "import",
+    desc = "Set the tags of many mapsets at once",
+    help = "Set the tags of many mapsets at once.\n\
+    The file must either be a `.json` file of the form \
+    `[{ \"mapset_id\": 123, \"tags\": [\"farm\", \"weeb\"] }, ...]` \
+    or a `.csv` file where each line is of the form `123,farm;weeb`.\n\
+    The given tags replace all previous tags of the mapset."
+)]
+pub struct BgTagsImport {
+    #[command(desc = "A json or csv file containing mapset ids and their tags")]
+    file: Attachment,
+}
 
-            let builder = MessageBuilder::new().content(content);
-            msg.create_message(builder).await?;
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "untagged",
+    desc = "List backgrounds that are missing tags",
+    help = "List backgrounds that are missing tags.\n\
+    Shows image files that have no database entry, \
+    entries that have not been reviewed yet, and entries whose image file is missing."
+)]
+pub struct BgTagsUntagged {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<BgGameMode>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "undo",
+    desc = "Revert your latest tag changes",
+    help = "Revert your latest tag changes.\n\
+    If the latest change was an import, all mapsets of that import are reverted."
+)]
+pub struct BgTagsUndo;
+
+async fn slash_bgtags(mut command: InteractionCommand) -> Result<()> {
+    let args = BgTags::from_interaction(command.input_data())?;
+    let author = command.user_id()?;
+
+    match is_trusted_tagger(author).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let content = "Only trusted members of the bot's dev server can manage tags";
+            command.error(content).await?;
 
             return Ok(());
         }
-    };
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
 
-    // Check if there is background for the given mapset id
-    if ctx.psql().get_tags_mapset(mapset_id).await.is_err() {
-        let content = "No background entry found with this id";
+            return Err(err);
+        }
+    }
 
-        return msg.error(content).await;
+    match args {
+        BgTags::Review(args) => review(command, args, author).await,
+        BgTags::Import(args) => import(command, args, author).await,
+        BgTags::Untagged(args) => untagged(command, args).await,
+        BgTags::Undo(_) => undo(command, author).await,
     }
+}
 
-    // Parse action
-    let action = match args.next().map(Action::from_str) {
-        Some(Ok(action)) => action,
-        None | Some(Err(_)) => {
-            let content = "Could not parse action. \
-                Be sure to specify `r`, `remove`, `a`, or `add` as second argument";
+/// The bot owner and members of the dev guild with an authority role are
+/// trusted to manage tags.
+async fn is_trusted_tagger(user: Id<UserMarker>) -> Result<bool> {
+    let config = BotConfig::get();
 
-            return msg.error(content).await;
-        }
+    if user == config.owner {
+        return Ok(true);
+    }
+
+    let member = Context::cache()
+        .member(config.dev_guild, user)
+        .await
+        .wrap_err("Failed to get member")?;
+
+    let Some(member) = member else {
+        return Ok(false);
     };
 
-    // Parse tags
-    let mut tags = MapsetTags::empty();
-
-    while !args.is_empty() {
-        match args.next().map(MapsetTags::from_str) {
-            Some(Ok(tag)) => tags.insert(tag),
-            Some(Err(tag)) => {
-                let content = format!(
-                    "Could not parse tag `{tag}`.\n\
-                    Be sure to only give these tags:\n\
-                    `farm, streams, alternate, old, meme, hardname, \
-                    easy, hard, tech, weeb, bluesky, english`"
-                );
-
-                return msg.error(content).await;
-            }
-            None => unreachable!(),
-        }
+    let authorities = Context::guild_config()
+        .peek(config.dev_guild, |config| config.authorities.clone())
+        .await;
+
+    Ok(member.roles().iter().any(|role| authorities.contains(role)))
+}
+
+fn game_mode(mode: Option<BgGameMode>) -> GameMode {
+    match mode {
+        Some(BgGameMode::Osu) | None => GameMode::Osu,
+        Some(BgGameMode::Mania) => GameMode::Mania,
     }
+}
+
+async fn review(
+    mut command: InteractionCommand,
+    args: BgTagsReview,
+    author: Id<UserMarker>,
+) -> Result<()> {
+    let mut mode = game_mode(args.mode);
+
+    let queue = if let Some(mapset_id) = args.mapset {
+        match Context::games().bggame_mapset(mapset_id as u32).await {
+            Ok(Some(mapset)) => {
+                mode = match mapset.gamemode {
+                    3 => GameMode::Mania,
+                    _ => GameMode::Osu,
+                };
+
+                vec![mapset]
+            }
+            Ok(None) => {
+                let content = format!("There is no background for mapset {mapset_id}");
+                command.error(content).await?;
+
+                return Ok(());
+            }
+            Err(err) => {
+                let _ = command.error(GENERAL_ISSUE).await;
 
-    let result = if tags.is_empty() {
-        ctx.psql().get_tags_mapset(mapset_id).await
+                return Err(err);
+            }
+        }
     } else {
-        let db_result = match action {
-            Action::Add => ctx.psql().add_tags_mapset(mapset_id, tags).await,
-            Action::Remove => ctx.psql().remove_tags_mapset(mapset_id, tags).await,
+        let mapsets = match Context::games().bggame_mapsets(mode).await {
+            Ok(mapsets) => mapsets,
+            Err(err) => {
+                let _ = command.error(GENERAL_ISSUE).await;
+
+                return Err(err);
+            }
         };
 
-        match db_result {
-            Ok(_) => ctx.psql().get_tags_mapset(mapset_id).await,
-            Err(err) => Err(err),
+        let (mut unreviewed, mut reviewed): (Vec<_>, Vec<_>) = mapsets
+            .into_iter()
+            .partition(|mapset| mapset.reviewed_at.is_none());
+
+        let mut rng = rand::thread_rng();
+
+        if unreviewed.is_empty() {
+            reviewed.shuffle(&mut rng);
+            reviewed.truncate(REVIEWED_QUEUE_SIZE);
+
+            reviewed
+        } else {
+            unreviewed.shuffle(&mut rng);
+
+            unreviewed
+        }
+    };
+
+    let Some((tagging, bytes)) = BackgroundTagging::new(mode, queue, author).await else {
+        let content = "There are no backgrounds to review";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    let attachment = Some((tagging.attachment_name(), bytes));
+
+    ActiveMessages::builder(tagging)
+        .attachment(attachment)
+        .begin(&mut command)
+        .await
+}
+
+#[derive(Deserialize)]
+struct ImportEntry {
+    mapset_id: u32,
+    tags: Vec<String>,
+}
+
+async fn import(
+    command: InteractionCommand,
+    args: BgTagsImport,
+    author: Id<UserMarker>,
+) -> Result<()> {
+    let BgTagsImport { file } = args;
+
+    let bytes = match Context::client().get_discord_attachment(&file).await {
+        Ok(bytes) => bytes,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err.wrap_err("Failed to get discord attachment"));
         }
     };
 
-    // Then show the final tags
-    match result {
-        Ok(tags) => {
-            let content = format!("{OSU_BASE}beatmapsets/{mapset_id} is now tagged as:\n{tags}");
+    let parsed = if file.filename.ends_with(".json") {
+        parse_json(&bytes)
+    } else if file.filename.ends_with(".csv") || file.filename.ends_with(".txt") {
+        parse_csv(&bytes)
+    } else {
+        Err("The file must be either a `.json` or a `.csv` file".to_owned())
+    };
+
+    let entries = match parsed {
+        Ok(entries) if entries.is_empty() => {
+            command.error("The file contains no entries").await?;
 
-            let builder = MessageBuilder::new().content(content);
-            msg.create_message(builder).await?;
+            return Ok(());
         }
+        Ok(entries) => entries,
+        Err(content) => {
+            command.error(content).await?;
+
+            return Ok(());
+        }
+    };
+
+    let updated = match Context::games().bggame_set_tags(author, &entries).await {
+        Ok(updated) => updated,
         Err(err) => {
-            let _ = msg.error(GENERAL_ISSUE).await;
+            let _ = command.error(GENERAL_ISSUE).await;
 
             return Err(err);
         }
+    };
+
+    let mut description = format!("Updated the tags of {updated}/{} mapsets", entries.len());
+
+    if updated < entries.len() as u64 {
+        let mut known = HashSet::new();
+
+        for mode in [GameMode::Osu, GameMode::Mania] {
+            match Context::games().bggame_mapsets(mode).await {
+                Ok(mapsets) => known.extend(mapsets.iter().map(|mapset| mapset.mapset_id as u32)),
+                Err(err) => warn!(?err, "Failed to get mapsets for unknown ids"),
+            }
+        }
+
+        let unknown: Vec<_> = entries
+            .iter()
+            .map(|(mapset_id, _)| *mapset_id)
+            .filter(|mapset_id| !known.contains(mapset_id))
+            .collect();
+
+        if !unknown.is_empty() {
+            description.push_str("\nThese mapsets have no background: ");
+            write_id_list(&mut description, &unknown);
+        }
     }
 
+    description.push_str("\nUse `/bgtags undo` to revert the import.");
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .title("Imported background tags");
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
+
     Ok(())
 }
 
-// #[command]
-// #[short_desc("Help out tagging backgrounds")]
-// #[long_desc(
-//     "Let me give you mapsets that still need to be tagged.\n\
-//     React to them properly, then lock it in by reacting with ✅.\n\
-//     To leave the loop, react with ❌ or just wait 10 minutes.\n\
-//     Mode can be specified in the first argument, defaults to std.\n\
-//     **You need to be verified to use this command**, feel free to \
-//     let Badewanne3 know if you want to help out tagging backgrounds."
-// )]
-// #[usage("[std / mna]")]
-// #[aliases("bgt", "bgtag")]
-// #[owner()]
-async fn bgtags(data: CommandData) -> Result<()> {
-    let (msg, mut args) = match data {
-        CommandData::Message { msg, args, .. } => (msg, args),
-        CommandData::Interaction { .. } => unreachable!(),
-    };
+fn parse_json(bytes: &[u8]) -> Result<Vec<(u32, MapsetTags)>, String> {
+    let entries: Vec<ImportEntry> = serde_json::from_slice(bytes)
+        .map_err(|err| format!("Failed to deserialize json file: {err}"))?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            parse_tags(entry.tags.iter().map(String::as_str))
+                .map(|tags| (entry.mapset_id, tags))
+                .map_err(|tag| format!("Unknown tag `{tag}` for mapset {}", entry.mapset_id))
+        })
+        .collect()
+}
 
-    // Parse arguments as mode
-    let mode = match args.next() {
-        Some(arg) => match arg.cow_to_ascii_lowercase().as_ref() {
-            "mna" | "mania" | "m" => GameMode::Mania,
-            "osu" | "std" | "standard" | "o" => GameMode::Osu,
-            _ => {
-                let content = "Could not parse first argument as mode. \
-                Provide either `mna`, or `std`";
+fn parse_csv(bytes: &[u8]) -> Result<Vec<(u32, MapsetTags)>, String> {
+    let content = std::str::from_utf8(bytes).map_err(|_| "The file is not valid UTF-8")?;
+    let mut entries = Vec::new();
+
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+
+        if line.is_empty() {
+            continue;
+        }
 
-                return msg.error(content).await;
+        let (mapset_id, tags) = line.split_once(',').unwrap_or((line, ""));
+
+        let Ok(mapset_id) = u32::from_str(mapset_id.trim()) else {
+            // Allow a header line
+            if i == 0 {
+                continue;
             }
-        },
-        None => GameMode::Osu,
-    };
 
-    let mut untagged = match ctx.psql().get_all_tags_mapset(mode).await {
-        Ok(tags) => tags.iter().any(|tag| tag.untagged()),
+            return Err(format!("Invalid mapset id on line {}", i + 1));
+        };
+
+        let tags = tags
+            .split([';', ',', ' '])
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty());
+
+        let tags =
+            parse_tags(tags).map_err(|tag| format!("Unknown tag `{tag}` on line {}", i + 1))?;
+
+        entries.push((mapset_id, tags));
+    }
+
+    Ok(entries)
+}
+
+/// Returns the unknown tag on error.
+fn parse_tags<'t>(tags: impl Iterator<Item = &'t str>) -> Result<MapsetTags, String> {
+    tags.map(MapsetTags::from_str)
+        .try_fold(MapsetTags::empty(), |tags, tag| tag.map(|tag| tags | tag))
+}
+
+async fn untagged(command: InteractionCommand, args: BgTagsUntagged) -> Result<()> {
+    let mode = game_mode(args.mode);
+
+    let mapsets = match Context::games().bggame_mapsets(mode).await {
+        Ok(mapsets) => mapsets,
         Err(err) => {
-            let _ = msg.error(GENERAL_ISSUE).await;
+            let _ = command.error(GENERAL_ISSUE).await;
 
             return Err(err);
         }
     };
 
-    if !untagged {
-        let content = "All backgrounds have been tagged, \
-            here are some random ones you can review again though";
+    let mut path = BotConfig::get().paths.backgrounds.clone();
 
-        let builder = MessageBuilder::new().content(content);
-        let _ = msg.create_message(builder).await;
+    match mode {
+        GameMode::Osu => path.push("osu"),
+        GameMode::Mania => path.push("mania"),
+        GameMode::Taiko | GameMode::Catch => unreachable!(),
     }
 
-    let mut owner = msg.author.id;
+    let mut files = HashSet::new();
 
-    loop {
-        // Get all mapsets for which tags are missing
-        let tags_result = if untagged {
-            ctx.psql().get_all_tags_mapset(mode).await
-        } else {
-            ctx.psql()
-                .get_random_tags_mapset(mode)
-                .await
-                .map(|tags| vec![tags])
-        };
+    let mut dir = match fs::read_dir(&path).await {
+        Ok(dir) => dir,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+            let err = Report::new(err).wrap_err("Failed to read backgrounds directory");
 
-        let mapsets = match tags_result {
-            Ok(mut tags) => {
-                if untagged {
-                    if tags.iter().any(|tag| tag.untagged()) {
-                        tags.retain(|tag| tag.untagged());
-                    } else {
-                        let content = "All backgrounds have been tagged, \
-                            here are some random ones you can review again though";
-
-                        let builder = MessageBuilder::new().content(content);
-                        let _ = msg.create_message(builder).await;
-                        untagged = false;
-                        tags.truncate(1);
-                    }
-                }
+            return Err(err);
+        }
+    };
 
-                tags
+    loop {
+        match dir.next_entry().await {
+            Ok(Some(entry)) => {
+                if let Ok(filename) = entry.file_name().into_string() {
+                    files.insert(filename);
+                }
             }
+            Ok(None) => break,
             Err(err) => {
-                let _ = msg.error(GENERAL_ISSUE).await;
+                let _ = command.error(GENERAL_ISSUE).await;
+                let err = Report::new(err).wrap_err("Failed to read backgrounds directory entry");
 
                 return Err(err);
             }
-        };
-
-        let (mapset_id, img) = get_random_image(mapsets, mode).await;
-
-        let content = format!(
-            "<@{owner}> Which tags should this mapsets get: {OSU_BASE}beatmapsets/{mapset_id}\n\
-            ```\n\
-            🍋: Easy 🎨: Weeb 😱: Hard name 🗽: English 💯: Tech\n\
-            🤓: Hard 🍨: Kpop 🪀: Alternate 🌀: Streams ✅: Lock in\n\
-            🤡: Meme 👨‍🌾: Farm 🟦: Blue sky  👴: Old     ❌: Exit loop\n\
-            ```"
-        );
-
-        let img = Attachment::from_bytes("bg_img.png".to_owned(), img);
-
-        // Send response
-        let response = ctx
-            .http
-            .create_message(msg.channel_id)
-            .content(&content)?
-            .attachments(&[img])
-            .unwrap()
-            .exec()
-            .await?
-            .model()
-            .await?;
-
-        let msg_id = response.id;
-
-        // Setup collector
-        let reaction_stream = ctx
-            .standby
-            .wait_for_event_stream(move |event: &Event| match event {
-                Event::ReactionAdd(event) => {
-                    event.message_id == msg_id && event.user_id.get() == OWNER_USER_ID
-                }
-                Event::ReactionRemove(event) => {
-                    event.message_id == msg_id && event.user_id.get() == OWNER_USER_ID
-                }
-                _ => false,
-            })
-            .map(|event| match event {
-                Event::ReactionAdd(add) => ReactionWrapper::Add(add.0),
-                Event::ReactionRemove(remove) => ReactionWrapper::Remove(remove.0),
-                _ => unreachable!(),
-            })
-            .timeout(Duration::from_secs(600));
-
-        tokio::pin!(reaction_stream);
-
-        // Add reactions
-        let reactions = [
-            "🍋",
-            "🤓",
-            "🤡",
-            "🎨",
-            "🍨",
-            "👨‍🌾",
-            "😱",
-            "🪀",
-            "🟦",
-            "🗽",
-            "🌀",
-            "👴",
-            "💯",
-            "✅",
-            "❌",
-        ];
-
-        for &reaction in reactions.iter() {
-            let emote = Emote::Custom(reaction);
-            send_reaction(&*ctx, &response, emote).await?;
         }
+    }
 
-        let mut break_loop = true;
-
-        // Run collector
-        let mut add_tags = MapsetTags::empty();
-        let mut remove_tags = MapsetTags::empty();
-
-        while let Some(Ok(reaction)) = reaction_stream.next().await {
-            let tag = if let ReactionType::Unicode { ref name } = reaction.as_deref().emoji {
-                match name.as_str() {
-                    "🍋" => MapsetTags::Easy,
-                    "🤓" => MapsetTags::Hard,
-                    "🤡" => MapsetTags::Meme,
-                    "👴" => MapsetTags::Old,
-                    "😱" => MapsetTags::HardName,
-                    "🟦" => MapsetTags::BlueSky,
-                    "🪀" => MapsetTags::Alternate,
-                    "🗽" => MapsetTags::English,
-                    "👨‍🌾" => MapsetTags::Farm,
-                    "💯" => MapsetTags::Tech,
-                    "🎨" => MapsetTags::Weeb,
-                    "🌀" => MapsetTags::Streams,
-                    "🍨" => MapsetTags::Kpop,
-                    "✅" => {
-                        owner = reaction.as_deref().user_id;
-                        break_loop = false;
-
-                        break;
-                    }
-                    "❌" => break,
-                    _ => continue,
-                }
-            } else {
-                continue;
-            };
+    let stored: HashSet<_> = mapsets
+        .iter()
+        .map(|mapset| mapset.image_filename.as_str())
+        .collect();
 
-            match reaction {
-                ReactionWrapper::Add(_) => {
-                    add_tags.insert(tag);
-                }
-                ReactionWrapper::Remove(_) => {
-                    remove_tags.insert(tag);
-                }
-            }
-        }
+    let mut missing_entry: Vec<_> = files
+        .iter()
+        .filter(|filename| !stored.contains(filename.as_str()))
+        .map(String::as_str)
+        .collect();
 
-        if !add_tags.is_empty() {
-            if let Err(err) = ctx.psql().add_tags_mapset(mapset_id, add_tags).await {
-                warn!(?err, "Failed to add tags");
+    missing_entry.sort_unstable();
+
+    let mut unreviewed: Vec<_> = mapsets
+        .iter()
+        .filter(|mapset| mapset.reviewed_at.is_none())
+        .map(|mapset| mapset.mapset_id as u32)
+        .collect();
+
+    unreviewed.sort_unstable();
+
+    let mut missing_file: Vec<_> = mapsets
+        .iter()
+        .filter(|mapset| !files.contains(&mapset.image_filename))
+        .map(|mapset| mapset.mapset_id as u32)
+        .collect();
+
+    missing_file.sort_unstable();
+
+    let mut description = format!("**Image files without entry ({}):**\n", missing_entry.len());
+
+    if missing_entry.is_empty() {
+        description.push_str("None");
+    } else {
+        let mut iter = missing_entry.iter().take(REPORT_LIST_SIZE);
+
+        if let Some(filename) = iter.next() {
+            let _ = write!(description, "`{filename}`");
+
+            for filename in iter {
+                let _ = write!(description, ", `{filename}`");
             }
         }
 
-        if !remove_tags.is_empty() {
-            if let Err(err) = ctx.psql().remove_tags_mapset(mapset_id, remove_tags).await {
-                warn!(?err, "Failed to remove tags");
-            }
+        if missing_entry.len() > REPORT_LIST_SIZE {
+            description.push_str(", ...");
         }
+    }
 
-        // Then show the final tags
-        match ctx.psql().get_tags_mapset(mapset_id).await {
-            Ok(tags) => {
-                if !tags.is_empty() {
-                    let content = format!(
-                        "{}beatmapsets/{} is now tagged as:\n{}",
-                        OSU_BASE, mapset_id, tags,
-                    );
-
-                    let builder = MessageBuilder::new().content(content);
-                    msg.create_message(builder).await?;
-                }
-            }
-            Err(err) => {
-                let _ = msg.error(GENERAL_ISSUE).await;
+    let _ = write!(
+        description,
+        "\n\n**Unreviewed entries ({}):**\n",
+        unreviewed.len()
+    );
 
-                return Err(err);
-            }
-        };
+    write_id_list(&mut description, &unreviewed);
 
-        if break_loop {
-            let builder = MessageBuilder::new().content("Exiting loop :wave:");
-            msg.create_message(builder).await?;
+    let _ = write!(
+        description,
+        "\n\n**Entries without image file ({}):**\n",
+        missing_file.len()
+    );
 
-            break;
-        }
-    }
+    write_id_list(&mut description, &missing_file);
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .title(format!("Untagged {mode} backgrounds"));
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
 
     Ok(())
 }
 
-async fn get_random_image(mut mapsets: Vec<MapsetTagWrapper>, mode: GameMode) -> (u32, Vec<u8>) {
-    let mut path = CONFIG.get().unwrap().paths.backgrounds.clone();
+async fn undo(command: InteractionCommand, author: Id<UserMarker>) -> Result<()> {
+    let reverted = match Context::games().bggame_undo_tags(author).await {
+        Ok(reverted) => reverted,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
 
-    match mode {
-        GameMode::Osu => path.push(OSU),
-        GameMode::Mania => path.push(MANIA),
-        _ => unreachable!(),
+            return Err(err);
+        }
+    };
+
+    if reverted.is_empty() {
+        command.error("You have no tag changes to undo").await?;
+
+        return Ok(());
     }
 
-    loop {
-        let random_idx = {
-            let mut rng = rand::thread_rng();
-            rng.next_u32() as usize % mapsets.len()
-        };
+    let mut description = format!("Reverted the tags of {} mapsets: ", reverted.len());
+    write_id_list(&mut description, &reverted);
 
-        let mapset = mapsets.swap_remove(random_idx);
-        path.push(&mapset.filename);
+    let builder = MessageBuilder::new().embed(description);
+    command.update(builder).await?;
 
-        match fs::read(&path).await {
-            Ok(bytes) => return (mapset.mapset_id, bytes),
-            Err(err) => {
-                warn!(path = path.display(), ?err, "Failed to read file");
-                path.pop();
-            }
-        }
-    }
+    Ok(())
 }
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-enum Action {
-    Add,
-    Remove,
-}
+fn write_id_list(content: &mut String, mapset_ids: &[u32]) {
+    let mut iter = mapset_ids.iter().take(REPORT_LIST_SIZE);
 
-impl FromStr for Action {
-    type Err = ();
+    let Some(first) = iter.next() else {
+        content.push_str("None");
 
-    fn from_str(value: &str) -> Result<Self, Self::Err> {
-        match value.cow_to_ascii_lowercase().as_ref() {
-            "r" | "remove" => Ok(Self::Remove),
-            "a" | "add" => Ok(Self::Add),
-            _ => Err(()),
-        }
+        return;
+    };
+
+    let _ = write!(content, "[{first}]({OSU_BASE}s/{first})");
+
+    for mapset_id in iter {
+        let _ = write!(content, ", [{mapset_id}]({OSU_BASE}s/{mapset_id})");
+    }
+
+    if mapset_ids.len() > REPORT_LIST_SIZE {
+        content.push_str(", ...");
     }
 }
@@ -0,0 +1,202 @@
+use bathbot_macros::SlashCommand;
+use bathbot_util::{constants::GENERAL_ISSUE, EmbedBuilder, MessageBuilder};
+use eyre::Result;
+use time::{Date, OffsetDateTime};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use crate::{
+    active::{
+        impls::{DailyChallenge, HigherLowerGame},
+        ActiveMessages,
+    },
+    util::{interaction::InteractionCommand, Authored, InteractionCommandExt},
+    Context,
+};
+
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "daily",
+    desc = "A daily osu! puzzle",
+    help = "A daily osu! puzzle.\n\
+    Every day everyone gets the same background to guess and only has a single attempt. \
+    Each incorrect guess reveals more of the background, up to six guesses.\n\
+    When you're done, you get a spoiler-free result to share with others \
+    and your streak of solved days continues.\n\
+    There is also a daily higher-lower game of score pp where everyone gets \
+    the same chain of scores and only has a single attempt."
+)]
+pub enum Daily {
+    #[command(name = "play")]
+    Play(DailyPlay),
+    #[command(name = "higherlower")]
+    HigherLower(DailyHigherLower),
+    #[command(name = "stats")]
+    Stats(DailyStats),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "play",
+    desc = "Play today's puzzle or look at your result of it"
+)]
+pub struct DailyPlay;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "higherlower",
+    desc = "Play today's higher-lower game of score pp"
+)]
+pub struct DailyHigherLower;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "stats", desc = "Show streaks and stats of the daily puzzle")]
+pub struct DailyStats {
+    #[command(desc = "Specify a user")]
+    discord: Option<Id<UserMarker>>,
+}
+
+async fn slash_daily(mut command: InteractionCommand) -> Result<()> {
+    match Daily::from_interaction(command.input_data())? {
+        Daily::Play(_) => play(command).await,
+        Daily::HigherLower(_) => higherlower(command).await,
+        Daily::Stats(args) => stats(command, args).await,
+    }
+}
+
+async fn play(mut command: InteractionCommand) -> Result<()> {
+    let owner = command.user_id()?;
+
+    let (challenge, bytes) = match DailyChallenge::new(owner).await {
+        Ok(tuple) => tuple,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err.wrap_err("Failed to prepare daily challenge"));
+        }
+    };
+
+    let attachment = Some((DailyChallenge::attachment_name().to_owned(), bytes));
+
+    ActiveMessages::builder(challenge)
+        .attachment(attachment)
+        .begin(&mut command)
+        .await
+}
+
+async fn higherlower(mut command: InteractionCommand) -> Result<()> {
+    let owner = command.user_id()?;
+    let day = OffsetDateTime::now_utc().date();
+    let games = Context::games();
+
+    // Check first since preparing the game requires several requests
+    match games.daily_higherlower_score(owner, day).await {
+        Ok(Some(score)) => return higherlower_played(&command, day, score).await,
+        Ok(None) => {}
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    }
+
+    let game = match HigherLowerGame::new_daily(day, owner).await {
+        Ok(game) => game,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err.wrap_err("Failed to prepare daily higherlower game"));
+        }
+    };
+
+    // Starting the attempt only succeeds once per day so concurrent commands
+    // can't play the same day twice
+    match games.daily_higherlower_start(owner, day).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let score = match games.daily_higherlower_score(owner, day).await {
+                Ok(score) => score.unwrap_or(0),
+                Err(err) => {
+                    let _ = command.error(GENERAL_ISSUE).await;
+
+                    return Err(err);
+                }
+            };
+
+            return higherlower_played(&command, day, score).await;
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    }
+
+    ActiveMessages::builder(game).begin(&mut command).await
+}
+
+async fn higherlower_played(command: &InteractionCommand, day: Date, score: u32) -> Result<()> {
+    let description = format!(
+        "You already played today's daily, come back tomorrow!\n```\n{}\n```",
+        HigherLowerGame::daily_share_string(day, score)
+    );
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .title("Higher or Lower Daily");
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+async fn stats(command: InteractionCommand, args: DailyStats) -> Result<()> {
+    let user_id = match args.discord {
+        Some(user_id) => user_id,
+        None => command.user_id()?,
+    };
+
+    let today = OffsetDateTime::now_utc().date();
+
+    let stats = match Context::games().daily_challenge_stats(user_id, today).await {
+        Ok(stats) => stats,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    if stats.played == 0 {
+        let content = if args.discord.is_some() {
+            format!("<@{user_id}> has not finished a daily puzzle yet")
+        } else {
+            "You have not finished a daily puzzle yet, try `/daily play`".to_owned()
+        };
+
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    let description = format!(
+        "Stats of <@{user_id}>\n\
+        Played: **{played}** • Solved: **{solved}** ({percent:.1}%)\n\
+        Current streak: **{current}** • Best streak: **{best}**",
+        played = stats.played,
+        solved = stats.solved,
+        percent = 100.0 * stats.solved as f32 / stats.played as f32,
+        current = stats.current_streak,
+        best = stats.best_streak,
+    );
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .title("Daily puzzle stats");
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
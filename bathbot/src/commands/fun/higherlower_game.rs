@@ -24,6 +24,16 @@ use crate::{
 pub enum HigherLower {
     #[command(name = "pp")]
     ScorePp(HigherLowerScorePp),
+    #[command(name = "stars")]
+    MapStars(HigherLowerMapStars),
+    #[command(name = "rank")]
+    GlobalRank(HigherLowerGlobalRank),
+    #[command(name = "playcount")]
+    Playcount(HigherLowerPlaycount),
+    #[command(name = "medals")]
+    MedalRarity(HigherLowerMedalRarity),
+    #[command(name = "favourites")]
+    MapsetFavourites(HigherLowerMapsetFavourites),
     #[command(name = "leaderboard")]
     Leaderboard(HigherLowerLeaderboard),
 }
@@ -42,34 +52,107 @@ pub struct HigherLowerScorePp {
     mode: Option<GameModeOption>,
 }
 
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "stars",
+    desc = "Is the map's star rating higher or lower?",
+    help = "Is the map's star rating higher or lower?\n\
+    The maps are chosen from top scores of random players in the top 5,000. \
+    The higher the current score is, the closer the star ratings will be."
+)]
+pub struct HigherLowerMapStars {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "rank",
+    desc = "Is the player's global rank higher or lower?",
+    help = "Is the player's global rank higher or lower?\n\
+    The players are chosen randomly from the top 10,000. \
+    The higher the current score is, the closer the ranks will be.\n\
+    Note that a better rank means a lower number."
+)]
+pub struct HigherLowerGlobalRank {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "playcount",
+    desc = "Is the player's playcount higher or lower?",
+    help = "Is the player's playcount higher or lower?\n\
+    The players are chosen randomly from the top 10,000."
+)]
+pub struct HigherLowerPlaycount {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "medals",
+    desc = "Is the medal's rarity higher or lower?",
+    help = "Is the medal's rarity higher or lower?\n\
+    The rarity is the percentage of players that own the medal, \
+    based on data from [osekai](https://osekai.net/medals/). \
+    The higher the current score is, the closer the rarities will be."
+)]
+pub struct HigherLowerMedalRarity;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "favourites",
+    desc = "Is the mapset's favourite count higher or lower?",
+    help = "Is the mapset's favourite count higher or lower?\n\
+    The mapsets are chosen from top scores of random players in the top 5,000. \
+    The higher the current score is, the closer the favourite counts will be."
+)]
+pub struct HigherLowerMapsetFavourites {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+}
+
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "leaderboard",
     desc = "Get the server leaderboard for higherlower highscores"
 )]
-pub struct HigherLowerLeaderboard;
+pub struct HigherLowerLeaderboard {
+    #[command(desc = "Specify a version, defaults to score pp")]
+    version: Option<HlVersion>,
+}
 
 async fn slash_higherlower(mut command: InteractionCommand) -> Result<()> {
     let args = HigherLower::from_interaction(command.input_data())?;
     let user = command.user_id()?;
 
-    let game_res = match args {
-        HigherLower::ScorePp(args) => {
-            let mode = match args.mode.map(GameMode::from) {
-                Some(mode) => mode,
-                None => Context::user_config()
-                    .mode(user)
-                    .await?
-                    .unwrap_or(GameMode::Osu),
-            };
-
-            HigherLowerGame::new_score_pp(mode, user).await
-        }
-        HigherLower::Leaderboard(_) => {
-            return higherlower_leaderboard(command, HlVersion::ScorePp).await
+    let (version, mode) = match args {
+        HigherLower::ScorePp(args) => (HlVersion::ScorePp, args.mode),
+        HigherLower::MapStars(args) => (HlVersion::MapStars, args.mode),
+        HigherLower::GlobalRank(args) => (HlVersion::GlobalRank, args.mode),
+        HigherLower::Playcount(args) => (HlVersion::Playcount, args.mode),
+        HigherLower::MedalRarity(_) => (HlVersion::MedalRarity, Some(GameModeOption::Osu)),
+        HigherLower::MapsetFavourites(args) => (HlVersion::MapsetFavourites, args.mode),
+        HigherLower::Leaderboard(args) => {
+            let version = args.version.unwrap_or(HlVersion::ScorePp);
+
+            return higherlower_leaderboard(command, version).await;
         }
     };
 
+    let mode = match mode.map(GameMode::from) {
+        Some(mode) => mode,
+        None => Context::user_config()
+            .mode(user)
+            .await?
+            .unwrap_or(GameMode::Osu),
+    };
+
+    let game_res = HigherLowerGame::new(version, mode, user).await;
+
     match game_res {
         Ok(game) => {
             ActiveMessages::builder(game)
@@ -1,5 +1,6 @@
 pub use self::bg_game::*;
 
 mod bg_game;
+mod daily_challenge;
 mod higherlower_game;
 mod minesweeper;
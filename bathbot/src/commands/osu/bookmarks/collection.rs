@@ -0,0 +1,61 @@
+use eyre::Result;
+
+use crate::util::osu_binary::{write_string, Reader};
+
+/// Version that is written into exported files
+const VERSION: i32 = 20240820;
+
+/// Contents of an osu!stable `collection.db` file.
+pub struct CollectionDb {
+    pub collections: Vec<Collection>,
+}
+
+pub struct Collection {
+    pub name: String,
+    /// MD5 hashes of the collection's maps
+    pub checksums: Vec<String>,
+}
+
+impl CollectionDb {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+
+        let _version = reader.read_i32()?;
+        let count = reader.read_i32()?;
+
+        let mut collections = Vec::with_capacity(count.clamp(0, 256) as usize);
+
+        for _ in 0..count {
+            let name = reader.read_string()?;
+            let map_count = reader.read_i32()?;
+
+            let mut checksums = Vec::with_capacity(map_count.clamp(0, 1024) as usize);
+
+            for _ in 0..map_count {
+                checksums.push(reader.read_string()?);
+            }
+
+            collections.push(Collection { name, checksums });
+        }
+
+        Ok(Self { collections })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(self.collections.len() as i32).to_le_bytes());
+
+        for collection in self.collections.iter() {
+            write_string(&mut bytes, &collection.name);
+            bytes.extend_from_slice(&(collection.checksums.len() as i32).to_le_bytes());
+
+            for checksum in collection.checksums.iter() {
+                write_string(&mut bytes, checksum);
+            }
+        }
+
+        bytes
+    }
+}
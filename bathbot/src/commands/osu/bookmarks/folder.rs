@@ -0,0 +1,672 @@
+use std::{collections::HashSet, fmt::Write};
+
+use bathbot_macros::SlashCommand;
+use bathbot_psql::model::osu::BookmarkFolder as DbBookmarkFolder;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_BASE},
+    matcher, CowUtils, EmbedBuilder, IntHasher, MessageBuilder,
+};
+use eyre::{Report, Result};
+use rosu_v2::prelude::OsuError;
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::{
+    channel::Attachment,
+    id::{marker::UserMarker, Id},
+};
+
+use super::collection::{Collection, CollectionDb};
+use crate::{
+    core::Context,
+    util::{interaction::InteractionCommand, Authored, InteractionCommandExt},
+};
+
+/// Maximum amount of maps that can be imported at once
+const MAX_IMPORT: usize = 200;
+
+/// Maximum amount of osu!api requests for maps that are not stored yet
+const MAX_MAP_REQUESTS: usize = 50;
+
+/// Maximum size of attached collection.db files
+const MAX_COLLECTION_SIZE: u64 = 16 * 1024 * 1024;
+
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "bookmarkfolder",
+    desc = "Manage folders of your bookmarked maps",
+    help = "Manage folders of your bookmarked maps.\n\
+    A bookmark can be in multiple folders. \
+    Folders can be viewed through the `folder` option of `/bookmarks`.\n\
+    Shared folders can be viewed and exported by other users but only you can modify them."
+)]
+#[flags(EPHEMERAL)]
+pub enum BookmarkFolder {
+    #[command(name = "create")]
+    Create(BookmarkFolderCreate),
+    #[command(name = "delete")]
+    Delete(BookmarkFolderDelete),
+    #[command(name = "list")]
+    List(BookmarkFolderList),
+    #[command(name = "share")]
+    Share(BookmarkFolderShare),
+    #[command(name = "import")]
+    Import(BookmarkFolderImport),
+    #[command(name = "export")]
+    Export(BookmarkFolderExport),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "create", desc = "Create a new bookmark folder")]
+pub struct BookmarkFolderCreate {
+    #[command(max_length = 32, desc = "Name of the folder")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "delete",
+    desc = "Delete a bookmark folder",
+    help = "Delete a bookmark folder.\n\
+    The maps of the folder stay bookmarked, they just no longer belong to it."
+)]
+pub struct BookmarkFolderDelete {
+    #[command(desc = "Name of the folder")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "list", desc = "List all of your bookmark folders")]
+pub struct BookmarkFolderList;
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "share",
+    desc = "Share a bookmark folder with other users",
+    help = "Share a bookmark folder with other users.\n\
+    Other users can then view the folder via `/bookmarks folder:<name> discord:<you>` \
+    and export it but they can't modify it."
+)]
+pub struct BookmarkFolderShare {
+    #[command(desc = "Name of the folder")]
+    name: String,
+    #[command(desc = "Whether other users can view the folder")]
+    shared: bool,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "import",
+    desc = "Import maps into a bookmark folder",
+    help = "Import maps into a bookmark folder.\n\
+    Maps can be specified as a list of map urls or map ids, or through an osu! `collection.db` \
+    file which you can find in your osu! folder.\n\
+    Already bookmarked maps are added to the folder and stay in their other folders. \
+    At most 200 maps can be imported at once."
+)]
+pub struct BookmarkFolderImport {
+    #[command(desc = "Name of the folder")]
+    name: String,
+    #[command(desc = "Map urls or map ids separated by spaces or commas")]
+    maps: Option<String>,
+    #[command(desc = "Specify an osu! collection.db file")]
+    collection: Option<Attachment>,
+    #[command(
+        desc = "Name of the collection inside the collection.db file",
+        help = "Name of the collection inside the collection.db file.\n\
+        Only required if the file contains multiple collections."
+    )]
+    collection_name: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "export", desc = "Export a bookmark folder")]
+pub struct BookmarkFolderExport {
+    #[command(desc = "Name of the folder")]
+    name: String,
+    #[command(desc = "Specify the export format")]
+    format: Option<FolderExportFormat>,
+    #[command(desc = "Specify the user whose shared folder should be exported")]
+    discord: Option<Id<UserMarker>>,
+}
+
+#[derive(Copy, Clone, Default, CommandOption, CreateOption)]
+pub enum FolderExportFormat {
+    #[default]
+    #[option(name = "collection.db", value = "collection")]
+    Collection,
+    #[option(name = "List of links", value = "links")]
+    Links,
+}
+
+async fn slash_bookmarkfolder(mut command: InteractionCommand) -> Result<()> {
+    match BookmarkFolder::from_interaction(command.input_data())? {
+        BookmarkFolder::Create(args) => create(command, args).await,
+        BookmarkFolder::Delete(args) => delete(command, args).await,
+        BookmarkFolder::List(_) => list(command).await,
+        BookmarkFolder::Share(args) => share(command, args).await,
+        BookmarkFolder::Import(args) => import(command, args).await,
+        BookmarkFolder::Export(args) => export(command, args).await,
+    }
+}
+
+async fn create(command: InteractionCommand, args: BookmarkFolderCreate) -> Result<()> {
+    let owner = command.user_id()?;
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    if name.is_empty() {
+        command.error("The name must not be empty").await?;
+
+        return Ok(());
+    }
+
+    match Context::bookmarks().create_folder(owner, &name).await {
+        Ok(true) => {
+            let content = format!(
+                "Created bookmark folder `{name}`.\n\
+                Maps can be added via `/bookmarkfolder import`."
+            );
+
+            let builder = MessageBuilder::new().embed(content);
+            command.update(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("You already have a bookmark folder named `{name}`");
+            command.error(content).await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn delete(command: InteractionCommand, args: BookmarkFolderDelete) -> Result<()> {
+    let owner = command.user_id()?;
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    match Context::bookmarks().delete_folder(owner, &name).await {
+        Ok(true) => {
+            let content = format!("Deleted bookmark folder `{name}`");
+            let builder = MessageBuilder::new().embed(content);
+            command.update(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("You have no bookmark folder named `{name}`");
+            command.error(content).await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn list(command: InteractionCommand) -> Result<()> {
+    let owner = command.user_id()?;
+
+    let folders = match Context::bookmarks().folders(owner).await {
+        Ok(folders) => folders,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    if folders.is_empty() {
+        let content = "You don't have any bookmark folders, you can create one with \
+            `/bookmarkfolder create`";
+
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    let mut description = String::with_capacity(folders.len() * 32);
+
+    for folder in folders.iter() {
+        let _ = writeln!(
+            description,
+            "- `{name}`: {count} map{plural}{shared}",
+            name = folder.name,
+            count = folder.map_count,
+            plural = if folder.map_count == 1 { "" } else { "s" },
+            shared = if folder.shared { " • *shared*" } else { "" },
+        );
+    }
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .title("Your bookmark folders");
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+async fn share(command: InteractionCommand, args: BookmarkFolderShare) -> Result<()> {
+    let owner = command.user_id()?;
+
+    let Some(folder) = get_folder(&command, owner, &args.name).await? else {
+        return Ok(());
+    };
+
+    let set_fut = Context::bookmarks().set_folder_shared(folder.folder_id, args.shared);
+
+    if let Err(err) = set_fut.await {
+        let _ = command.error(GENERAL_ISSUE).await;
+
+        return Err(err);
+    }
+
+    let content = if args.shared {
+        format!(
+            "Bookmark folder `{name}` is now shared.\n\
+            Others can view it via `/bookmarks folder:{name} discord:<@{owner}>`",
+            name = folder.name,
+        )
+    } else {
+        format!("Bookmark folder `{}` is no longer shared", folder.name)
+    };
+
+    let builder = MessageBuilder::new().embed(content);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+async fn import(command: InteractionCommand, args: BookmarkFolderImport) -> Result<()> {
+    let BookmarkFolderImport {
+        name,
+        maps,
+        collection,
+        collection_name,
+    } = args;
+
+    let owner = command.user_id()?;
+
+    if maps.is_none() && collection.is_none() {
+        let content = "You must specify either maps or a collection.db file";
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    let Some(folder) = get_folder(&command, owner, &name).await? else {
+        return Ok(());
+    };
+
+    let mut map_ids = Vec::new();
+    let mut invalid = 0;
+
+    if let Some(ref maps) = maps {
+        for arg in maps.split([' ', ',', '\n']).filter(|arg| !arg.is_empty()) {
+            match matcher::get_osu_map_id(arg) {
+                Some(map_id) => map_ids.push(map_id),
+                None => invalid += 1,
+            }
+        }
+    }
+
+    let mut requests_left = MAX_MAP_REQUESTS;
+    let mut truncated = 0;
+
+    if let Some(ref attachment) = collection {
+        if attachment.size > MAX_COLLECTION_SIZE {
+            let content = "The attached file is too large, it must be at most 16MB";
+            command.error(content).await?;
+
+            return Ok(());
+        }
+
+        let bytes = match Context::client().get_discord_attachment(attachment).await {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                let _ = command.error(GENERAL_ISSUE).await;
+
+                return Err(err.wrap_err("Failed to download collection attachment"));
+            }
+        };
+
+        let db = match CollectionDb::parse(&bytes) {
+            Ok(db) => db,
+            Err(err) => {
+                debug!(?err, "Failed to parse collection.db");
+
+                let content = "Failed to parse the attachment, \
+                    be sure it's the `collection.db` file of your osu! folder";
+
+                command.error(content).await?;
+
+                return Ok(());
+            }
+        };
+
+        let collection = match select_collection(db, collection_name.as_deref()) {
+            Ok(collection) => collection,
+            Err(content) => {
+                command.error(content).await?;
+
+                return Ok(());
+            }
+        };
+
+        let mut checksums = collection.checksums;
+        truncated += checksums.len().saturating_sub(MAX_IMPORT);
+        checksums.truncate(MAX_IMPORT);
+
+        let (resolved, unresolved) = match resolve_checksums(checksums, &mut requests_left).await {
+            Ok(tuple) => tuple,
+            Err(err) => {
+                let _ = command.error(GENERAL_ISSUE).await;
+
+                return Err(err);
+            }
+        };
+
+        map_ids.extend(resolved);
+        invalid += unresolved;
+    }
+
+    let mut seen = HashSet::with_capacity_and_hasher(map_ids.len(), IntHasher);
+    map_ids.retain(|map_id| seen.insert(*map_id));
+
+    truncated += map_ids.len().saturating_sub(MAX_IMPORT);
+    map_ids.truncate(MAX_IMPORT);
+
+    let (map_ids, missing) = match store_missing_maps(map_ids, &mut requests_left).await {
+        Ok(tuple) => tuple,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    invalid += missing;
+
+    if map_ids.is_empty() {
+        command
+            .error("Could not find any of the given maps")
+            .await?;
+
+        return Ok(());
+    }
+
+    let add_fut = Context::bookmarks().add_many(owner, &map_ids, folder.folder_id);
+
+    if let Err(err) = add_fut.await {
+        let _ = command.error(GENERAL_ISSUE).await;
+
+        return Err(err);
+    }
+
+    let mut content = format!(
+        "Imported {count} map{plural} into bookmark folder `{name}`",
+        count = map_ids.len(),
+        plural = if map_ids.len() == 1 { "" } else { "s" },
+        name = folder.name,
+    );
+
+    if invalid > 0 {
+        let _ = write!(content, "\nSkipped {invalid} unknown or invalid maps");
+    }
+
+    if truncated > 0 {
+        let _ = write!(
+            content,
+            "\nSkipped {truncated} maps because at most {MAX_IMPORT} maps can be imported at once"
+        );
+    }
+
+    let builder = MessageBuilder::new().embed(content);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+async fn export(command: InteractionCommand, args: BookmarkFolderExport) -> Result<()> {
+    let owner = command.user_id()?;
+    let user = args.discord.unwrap_or(owner);
+
+    let Some(folder) = get_folder(&command, user, &args.name).await? else {
+        return Ok(());
+    };
+
+    if user != owner && !folder.shared {
+        let content = format!(
+            "<@{user}> has not shared the bookmark folder `{}`",
+            folder.name
+        );
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    let bookmarks = match Context::bookmarks().get(user, Some(folder.folder_id)).await {
+        Ok(bookmarks) => bookmarks,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    if bookmarks.is_empty() {
+        let content = format!("The bookmark folder `{}` is empty", folder.name);
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    let (filename, bytes) = match args.format.unwrap_or_default() {
+        FolderExportFormat::Collection => {
+            let collection = Collection {
+                name: folder.name.as_ref().to_owned(),
+                checksums: bookmarks
+                    .iter()
+                    .map(|bookmark| bookmark.checksum.as_ref().to_owned())
+                    .collect(),
+            };
+
+            let db = CollectionDb {
+                collections: vec![collection],
+            };
+
+            ("collection.db".to_owned(), db.serialize())
+        }
+        FolderExportFormat::Links => {
+            let mut links = String::with_capacity(bookmarks.len() * 32);
+
+            for bookmark in bookmarks.iter() {
+                let _ = writeln!(links, "{OSU_BASE}b/{}", bookmark.map_id);
+            }
+
+            (format!("{}.txt", folder.name), links.into_bytes())
+        }
+    };
+
+    let content = format!(
+        "Bookmark folder `{name}` with {count} map{plural}",
+        name = folder.name,
+        count = bookmarks.len(),
+        plural = if bookmarks.len() == 1 { "" } else { "s" },
+    );
+
+    let builder = MessageBuilder::new()
+        .embed(content)
+        .attachment(filename, bytes);
+
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+/// Responds with an error if the folder could not be found.
+async fn get_folder(
+    command: &InteractionCommand,
+    user: Id<UserMarker>,
+    name: &str,
+) -> Result<Option<DbBookmarkFolder>> {
+    let name = name.trim().cow_to_ascii_lowercase();
+
+    match Context::bookmarks().folder(user, &name).await {
+        Ok(Some(folder)) => Ok(Some(folder)),
+        Ok(None) => {
+            let content = if command.user_id()? == user {
+                format!(
+                    "You have no bookmark folder named `{name}`.\n\
+                    Use `/bookmarkfolder list` to see all of your folders."
+                )
+            } else {
+                format!("<@{user}> has no bookmark folder named `{name}`")
+            };
+
+            command.error(content).await?;
+
+            Ok(None)
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+/// Returns the collection to import or an error message for the user.
+fn select_collection(db: CollectionDb, name: Option<&str>) -> Result<Collection, String> {
+    let CollectionDb { mut collections } = db;
+
+    if let Some(name) = name {
+        let name = name.trim();
+
+        return collections
+            .into_iter()
+            .find(|collection| collection.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| format!("The file contains no collection named `{name}`"));
+    }
+
+    match collections.len() {
+        0 => Err("The file contains no collections".to_owned()),
+        1 => Ok(collections.swap_remove(0)),
+        _ => {
+            let mut content = "The file contains multiple collections, \
+                specify one through the `collection_name` option:\n"
+                .to_owned();
+
+            for collection in collections.iter().take(20) {
+                let _ = writeln!(content, "- `{}`", collection.name);
+            }
+
+            if collections.len() > 20 {
+                let _ = write!(content, "- ... and {} more", collections.len() - 20);
+            }
+
+            Err(content)
+        }
+    }
+}
+
+/// Returns the map ids of the checksums and the amount of checksums that
+/// could not be resolved.
+async fn resolve_checksums(
+    checksums: Vec<String>,
+    requests_left: &mut usize,
+) -> Result<(Vec<u32>, usize)> {
+    let pairs = Context::osu_map().map_ids_by_checksums(&checksums).await?;
+
+    let mut map_ids = Vec::with_capacity(checksums.len());
+
+    for checksum in checksums.iter() {
+        if let Some((_, map_id)) = pairs.iter().find(|(hash, _)| hash.as_ref() == checksum) {
+            map_ids.push(*map_id);
+
+            continue;
+        }
+
+        // Maps that are not stored yet need to be requested
+        if *requests_left == 0 {
+            continue;
+        }
+
+        *requests_left -= 1;
+
+        match Context::osu().beatmap().checksum(checksum.as_str()).await {
+            Ok(map) => map_ids.push(map.map_id),
+            Err(OsuError::NotFound) => {}
+            Err(err) => {
+                warn!(?err, checksum, "Failed to request map by checksum");
+            }
+        }
+    }
+
+    let unresolved = checksums.len() - map_ids.len();
+
+    Ok((map_ids, unresolved))
+}
+
+/// Makes sure all maps are stored, requesting them if necessary.
+///
+/// Returns the map ids of all stored maps and the amount of maps that could
+/// not be found.
+async fn store_missing_maps(
+    map_ids: Vec<u32>,
+    requests_left: &mut usize,
+) -> Result<(Vec<i32>, usize)> {
+    let map_ids: Vec<_> = map_ids.into_iter().map(|map_id| map_id as i32).collect();
+
+    let mut stored = Context::osu_map().stored_map_ids(&map_ids).await?;
+    let mut missing = 0;
+
+    for &map_id in map_ids.iter() {
+        if stored.contains(&(map_id as u32)) {
+            continue;
+        }
+
+        if *requests_left == 0 {
+            missing += 1;
+
+            continue;
+        }
+
+        *requests_left -= 1;
+
+        match Context::osu().beatmapset_from_map_id(map_id as u32).await {
+            Ok(mapset) => {
+                // Store all maps of the set so maps of the same set don't
+                // need to be requested again
+                Context::osu_map().store(&mapset).await;
+
+                if let Some(ref maps) = mapset.maps {
+                    stored.extend(maps.iter().map(|map| map.map_id));
+                }
+
+                if !stored.contains(&(map_id as u32)) {
+                    missing += 1;
+                }
+            }
+            Err(OsuError::NotFound) => missing += 1,
+            Err(err) => {
+                return Err(Report::new(err).wrap_err("Failed to get mapset"));
+            }
+        }
+    }
+
+    let map_ids = map_ids
+        .into_iter()
+        .filter(|map_id| stored.contains(&(*map_id as u32)))
+        .collect();
+
+    Ok((map_ids, missing))
+}
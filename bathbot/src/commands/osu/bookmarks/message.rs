@@ -99,7 +99,7 @@ async fn bookmark_map(mut command: InteractionCommand) -> Result<()> {
 
     let user_id = command.user_id()?;
 
-    let bookmarks = match Context::bookmarks().get(user_id).await {
+    let bookmarks = match Context::bookmarks().get(user_id, None).await {
         Ok(bookmarks) => bookmarks,
         Err(err) => {
             let _ = command.error(GENERAL_ISSUE).await?;
@@ -123,7 +123,7 @@ async fn bookmark_map(mut command: InteractionCommand) -> Result<()> {
 
     debug!(user = %user_id, map = map_id, "Added bookmarked map");
 
-    let bookmarks = match Context::bookmarks().get(user_id).await {
+    let bookmarks = match Context::bookmarks().get(user_id, None).await {
         Ok(bookmarks) => bookmarks,
         Err(err) => {
             let _ = command.error(GENERAL_ISSUE).await?;
@@ -1,2 +1,4 @@
+mod collection;
+mod folder;
 mod message;
 mod slash;
@@ -7,6 +7,7 @@ use bathbot_util::{constants::GENERAL_ISSUE, CowUtils, MessageOrigin};
 use eyre::Result;
 use rosu_v2::prelude::GameMode;
 use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::UserMarker, Id};
 
 use crate::{
     active::{impls::BookmarksPagination, ActiveMessages},
@@ -25,7 +26,8 @@ use crate::{
     help = "List all your bookmarked maps. You can bookmark maps by:\n\
     1. Rightclicking a bot message that contains a single map\n\
     2. Click on `Apps`\n\
-    3. Click on `Bookmark map`."
+    3. Click on `Bookmark map`.\n\
+    Bookmarks can be organized into folders via `/bookmarkfolder`."
 )]
 #[flags(EPHEMERAL)]
 pub struct Bookmarks {
@@ -41,6 +43,14 @@ pub struct Bookmarks {
     query: Option<String>,
     #[command(desc = "Filter out maps that don't belong to a gamemode")]
     mode: Option<GameModeOption>,
+    #[command(desc = "Only show the maps of a bookmark folder")]
+    folder: Option<String>,
+    #[command(
+        desc = "Specify a user to view their shared bookmark folder",
+        help = "Specify a user to view their shared bookmark folder.\n\
+        Requires the `folder` option and the folder must be shared by the user."
+    )]
+    discord: Option<Id<UserMarker>>,
 }
 
 #[derive(Copy, Clone, CommandOption, CreateOption)]
@@ -72,8 +82,52 @@ impl Default for BookmarksSort {
 pub async fn slash_bookmarks(mut command: InteractionCommand) -> Result<()> {
     let args = Bookmarks::from_interaction(command.input_data())?;
     let owner = command.user_id()?;
+    let user = args.discord.unwrap_or(owner);
+    let read_only = user != owner;
+
+    let folder = match args.folder.as_deref() {
+        Some(name) => {
+            let name = name.trim().cow_to_ascii_lowercase();
+
+            match Context::bookmarks().folder(user, &name).await {
+                Ok(Some(folder)) if !read_only || folder.shared => Some(folder),
+                Ok(Some(_)) => {
+                    let content = format!("<@{user}> has not shared the bookmark folder `{name}`");
+                    command.error(content).await?;
+
+                    return Ok(());
+                }
+                Ok(None) => {
+                    let content = if read_only {
+                        format!("<@{user}> has no bookmark folder named `{name}`")
+                    } else {
+                        format!("You have no bookmark folder named `{name}`")
+                    };
+
+                    command.error(content).await?;
+
+                    return Ok(());
+                }
+                Err(err) => {
+                    let _ = command.error(GENERAL_ISSUE).await;
+
+                    return Err(err);
+                }
+            }
+        }
+        None if read_only => {
+            let content = "To view the bookmarks of someone else you must specify \
+                one of their shared folders";
+            command.error(content).await?;
 
-    let mut bookmarks = match Context::bookmarks().get(owner).await {
+            return Ok(());
+        }
+        None => None,
+    };
+
+    let folder_id = folder.as_ref().map(|folder| folder.folder_id);
+
+    let mut bookmarks = match Context::bookmarks().get(user, folder_id).await {
         Ok(bookmarks) => bookmarks,
         Err(err) => {
             let _ = command.error(GENERAL_ISSUE).await?;
@@ -85,7 +139,20 @@ pub async fn slash_bookmarks(mut command: InteractionCommand) -> Result<()> {
     let criteria = args.query.as_deref().map(BookmarkCriteria::create);
 
     process_bookmarks(&mut bookmarks, &args, criteria.as_ref());
-    let content = msg_content(&args, criteria.as_ref());
+    let mut content = msg_content(&args, criteria.as_ref());
+
+    if let Some(ref folder) = folder {
+        if !content.is_empty() {
+            content.push_str(" • ");
+        }
+
+        if read_only {
+            let _ = write!(content, "`Folder: {}` of <@{user}>", folder.name);
+        } else {
+            let _ = write!(content, "`Folder: {}`", folder.name);
+        }
+    }
+
     let filtered = criteria.is_some() || args.mode.is_some();
 
     let origin = MessageOrigin::new(command.guild_id(), command.channel_id());
@@ -96,6 +163,7 @@ pub async fn slash_bookmarks(mut command: InteractionCommand) -> Result<()> {
         .cached_entries(HashMap::default())
         .filtered_maps(Some(filtered))
         .defer_next(false)
+        .read_only(Some(read_only))
         .token(command.token.clone())
         .content(content)
         .msg_owner(owner)
@@ -30,6 +30,7 @@ use self::{
     rank::rank_graph,
     snipe_count::snipe_count_graph,
     sniped::sniped_graph,
+    stats_history::stats_history_graph,
     top_date::top_graph_date,
     top_index::top_graph_index,
     top_time::top_graph_time,
@@ -47,6 +48,7 @@ mod playcount_replays;
 mod rank;
 mod snipe_count;
 mod sniped;
+mod stats_history;
 mod top_date;
 mod top_index;
 mod top_time;
@@ -54,6 +56,8 @@ mod top_time;
 #[derive(CommandModel, CreateCommand, SlashCommand)]
 #[command(name = "graph", desc = "Display graphs about some user data")]
 pub enum Graph {
+    #[command(name = "history")]
+    History(GraphHistory),
     #[command(name = "medals")]
     Medals(GraphMedals),
     #[command(name = "playcount_replays")]
@@ -68,6 +72,34 @@ pub enum Graph {
     Top(GraphTop),
 }
 
+#[derive(CommandModel, CreateCommand, HasName)]
+#[command(
+    name = "history",
+    desc = "Display a user's pp, ranks, and accuracy over time",
+    help = "Display a user's pp, global rank, country rank, and accuracy over time.\n\
+    Unlike `/graph rank`, this is not limited to the last 90 days. \
+    Instead, it's based on the stats that were stored whenever the user was retrieved \
+    through a command so gaps are to be expected.\n\
+    Older snapshots are thinned out to one per day and eventually one per week."
+)]
+pub struct GraphHistory {
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+    #[command(desc = "Specify a username")]
+    name: Option<String>,
+    #[command(desc = "Specify a start date of the form YYYY-MM-DD")]
+    from: Option<String>,
+    #[command(desc = "Specify an end date of the form YYYY-MM-DD")]
+    until: Option<String>,
+    #[command(
+        desc = "Specify a linked discord user",
+        help = "Instead of specifying an osu! username with the `name` option, \
+        you can use this option to choose a discord user.\n\
+        Only works on users who have used the `/link` command."
+    )]
+    discord: Option<Id<UserMarker>>,
+}
+
 #[derive(CommandModel, CreateCommand, HasName)]
 #[command(name = "medals", desc = "Display a user's medal progress over time")]
 pub struct GraphMedals {
@@ -201,6 +233,15 @@ async fn slash_graph(mut command: InteractionCommand) -> Result<()> {
 // `InteractionCommand`
 async fn graph(orig: CommandOrigin<'_>, args: Graph) -> Result<()> {
     let tuple_option = match args {
+        Graph::History(args) => {
+            let (user_id, mode) = user_id_mode!(orig, args);
+            let from = args.from.as_deref();
+            let until = args.until.as_deref();
+
+            stats_history_graph(&orig, user_id, mode, from, until)
+                .await
+                .wrap_err("failed to create stats history graph")?
+        }
         Graph::Medals(args) => {
             let user_id = match user_id!(orig, args) {
                 Some(user_id) => user_id,
@@ -0,0 +1,252 @@
+use std::ops::Range;
+
+use bathbot_model::rosu_v2::user::User;
+use bathbot_psql::model::osu::DbUserStatsHistoryEntry;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_API_ISSUE},
+    datetime::DATE_FORMAT,
+};
+use eyre::{Report, Result, WrapErr};
+use plotters::{
+    coord::Shift,
+    prelude::{ChartBuilder, DrawingArea, IntoDrawingArea},
+    series::LineSeries,
+    style::{Color, RGBColor, WHITE},
+};
+use plotters_backend::FontStyle;
+use plotters_skia::SkiaBackend;
+use rosu_v2::{
+    prelude::{GameMode, OsuError},
+    request::UserId,
+};
+use skia_safe::{surfaces, EncodedImageFormat};
+use time::{Date, Duration, OffsetDateTime};
+
+use super::{H, W};
+use crate::{
+    commands::osu::user_not_found,
+    core::{commands::CommandOrigin, Context},
+    manager::redis::{osu::UserArgs, RedisData},
+    util::Monthly,
+};
+
+pub async fn stats_history_graph(
+    orig: &CommandOrigin<'_>,
+    user_id: UserId,
+    mode: GameMode,
+    from: Option<&str>,
+    until: Option<&str>,
+) -> Result<Option<(RedisData<User>, Vec<u8>)>> {
+    let from = match from.map(|date| Date::parse(date, DATE_FORMAT)) {
+        Some(Ok(date)) => date.midnight().assume_utc(),
+        Some(Err(_)) => {
+            let content = "Failed to parse `from` date. Be sure it's of the form `YYYY-MM-DD`.";
+            orig.error(content).await?;
+
+            return Ok(None);
+        }
+        None => OffsetDateTime::UNIX_EPOCH,
+    };
+
+    let until = match until.map(|date| Date::parse(date, DATE_FORMAT)) {
+        // Include the whole day
+        Some(Ok(date)) => date.midnight().assume_utc() + Duration::DAY,
+        Some(Err(_)) => {
+            let content = "Failed to parse `until` date. Be sure it's of the form `YYYY-MM-DD`.";
+            orig.error(content).await?;
+
+            return Ok(None);
+        }
+        None => OffsetDateTime::now_utc(),
+    };
+
+    if from >= until {
+        orig.error("The `from` date must be before `until`").await?;
+
+        return Ok(None);
+    }
+
+    // Requesting the user from the api stores its current stats into the
+    // history. If it's cached instead, its stats were stored when it was
+    // requested.
+    let user_args = UserArgs::rosu_id(&user_id, mode).await;
+
+    let user = match Context::redis().osu_user(user_args).await {
+        Ok(user) => user,
+        Err(OsuError::NotFound) => {
+            let content = user_not_found(user_id).await;
+            orig.error(content).await?;
+
+            return Ok(None);
+        }
+        Err(err) => {
+            let _ = orig.error(OSU_API_ISSUE).await;
+            let err = Report::new(err).wrap_err("Failed to get user");
+
+            return Err(err);
+        }
+    };
+
+    let history_fut = Context::osu_user().stats_history(user.user_id(), mode, from, until);
+
+    let history = match history_fut.await {
+        Ok(history) => history,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    if history.len() < 2 {
+        let content = format!(
+            "Not enough stats of `{name}` have been stored in that time range yet.\n\
+            Stats are stored whenever the user is retrieved through a command.",
+            name = user.username()
+        );
+
+        orig.error(content).await?;
+
+        return Ok(None);
+    }
+
+    let bytes = match draw_graph(&history) {
+        Ok(graph) => graph,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+            warn!(?err, "Failed to draw stats history graph");
+
+            return Ok(None);
+        }
+    };
+
+    Ok(Some((user, bytes)))
+}
+
+type Area<'a> = DrawingArea<SkiaBackend<'a>, Shift>;
+
+fn draw_graph(history: &[DbUserStatsHistoryEntry]) -> Result<Vec<u8>> {
+    let first = history[0].recorded_at;
+    let last = history[history.len() - 1].recorded_at;
+
+    let pp: Vec<_> = history
+        .iter()
+        .map(|entry| (entry.recorded_at, entry.pp))
+        .collect();
+
+    // Ranks are negated so that better ranks are higher up
+    let global_rank: Vec<_> = history
+        .iter()
+        .filter(|entry| entry.global_rank > 0)
+        .map(|entry| (entry.recorded_at, -(entry.global_rank as f32)))
+        .collect();
+
+    let country_rank: Vec<_> = history
+        .iter()
+        .filter(|entry| entry.country_rank > 0)
+        .map(|entry| (entry.recorded_at, -(entry.country_rank as f32)))
+        .collect();
+
+    let accuracy: Vec<_> = history
+        .iter()
+        .map(|entry| (entry.recorded_at, entry.accuracy))
+        .collect();
+
+    let mut surface =
+        surfaces::raster_n32_premul((W as i32, H as i32)).wrap_err("Failed to create surface")?;
+
+    {
+        let root = SkiaBackend::new(surface.canvas(), W, H).into_drawing_area();
+
+        let background = RGBColor(19, 43, 33);
+        root.fill(&background)
+            .wrap_err("Failed to fill background")?;
+
+        let areas = root.split_evenly((2, 2));
+
+        draw_chart(&areas[0], "PP", first..last, &pp, &|pp| {
+            format!("{pp:.0}pp")
+        })
+        .wrap_err("Failed to draw pp chart")?;
+
+        draw_chart(&areas[1], "Accuracy", first..last, &accuracy, &|acc| {
+            format!("{acc:.2}%")
+        })
+        .wrap_err("Failed to draw accuracy chart")?;
+
+        draw_chart(
+            &areas[2],
+            "Global rank",
+            first..last,
+            &global_rank,
+            &|rank| format!("#{:.0}", -rank),
+        )
+        .wrap_err("Failed to draw global rank chart")?;
+
+        draw_chart(
+            &areas[3],
+            "Country rank",
+            first..last,
+            &country_rank,
+            &|rank| format!("#{:.0}", -rank),
+        )
+        .wrap_err("Failed to draw country rank chart")?;
+    }
+
+    let png_bytes = surface
+        .image_snapshot()
+        .encode(None, EncodedImageFormat::PNG, None)
+        .wrap_err("Failed to encode image")?
+        .to_vec();
+
+    Ok(png_bytes)
+}
+
+fn draw_chart(
+    area: &Area<'_>,
+    desc: &str,
+    range: Range<OffsetDateTime>,
+    data: &[(OffsetDateTime, f32)],
+    y_label_formatter: &dyn Fn(&f32) -> String,
+) -> Result<()> {
+    let (min, max) = data
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(min, max), (_, value)| {
+            (min.min(*value), max.max(*value))
+        });
+
+    // Ranks might be missing entirely for inactive users
+    if min > max {
+        return Ok(());
+    }
+
+    let padding = if max > min { (max - min) * 0.1 } else { 1.0 };
+
+    let mut chart = ChartBuilder::on(area)
+        .x_label_area_size(30_i32)
+        .y_label_area_size(75_i32)
+        .margin(10_i32)
+        .build_cartesian_2d(Monthly(range), min - padding..max + padding)
+        .wrap_err("Failed to build chart")?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_labels(6)
+        .x_label_formatter(&|datetime| datetime.date().to_string())
+        .y_labels(6)
+        .y_label_formatter(y_label_formatter)
+        .y_desc(desc)
+        .label_style(("sans-serif", 14_i32, &WHITE))
+        .bold_line_style(WHITE.mix(0.3))
+        .axis_style(RGBColor(7, 18, 14))
+        .axis_desc_style(("sans-serif", 16_i32, FontStyle::Bold, &WHITE))
+        .draw()
+        .wrap_err("Failed to draw mesh")?;
+
+    let line_style = RGBColor(0, 208, 138).stroke_width(2);
+    let series = LineSeries::new(data.iter().copied(), line_style);
+    chart.draw_series(series).wrap_err("Failed to draw line")?;
+
+    Ok(())
+}
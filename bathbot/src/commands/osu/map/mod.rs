@@ -19,6 +19,8 @@ use twilight_model::{
     guild::Permissions,
 };
 
+use self::strains::strains;
+pub use self::strains::MapStrains;
 use super::{BitMapElement, HasMods, ModsResult};
 use crate::{
     active::{impls::MapPagination, ActiveMessages},
@@ -27,9 +29,20 @@ use crate::{
     Context,
 };
 
+mod strains;
+
 #[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(name = "map", desc = "Display a bunch of stats about a map(set)")]
+pub enum Map<'a> {
+    #[command(name = "info")]
+    Info(MapInfo<'a>),
+    #[command(name = "strains")]
+    Strains(MapStrains<'a>),
+}
+
+#[derive(CommandModel, CreateCommand)]
 #[command(
-    name = "map",
+    name = "info",
     desc = "Display a bunch of stats about a map(set)",
     help = "Display a bunch of stats about a map(set).\n\
     The values in the map info will be adjusted to mods.\n\
@@ -37,7 +50,7 @@ use crate::{
     the strain graph always belongs to the initial map, even after moving to \
     other maps of the set through the pagination buttons."
 )]
-pub struct Map<'a> {
+pub struct MapInfo<'a> {
     #[command(
         desc = "Specify a map url or map id",
         help = "Specify a map either by map url or map id.\n\
@@ -159,11 +172,11 @@ impl<'m> MapArgs<'m> {
     }
 }
 
-impl<'a> TryFrom<Map<'a>> for MapArgs<'a> {
+impl<'a> TryFrom<MapInfo<'a>> for MapArgs<'a> {
     type Error = &'static str;
 
-    fn try_from(args: Map<'a>) -> Result<Self, Self::Error> {
-        let Map {
+    fn try_from(args: MapInfo<'a>) -> Result<Self, Self::Error> {
+        let MapInfo {
             map,
             mods,
             ar,
@@ -218,7 +231,10 @@ async fn prefix_map(msg: &Message, args: Args<'_>, permissions: Option<Permissio
 }
 
 async fn slash_map(mut command: InteractionCommand) -> Result<()> {
-    let args = Map::from_interaction(command.input_data())?;
+    let args = match Map::from_interaction(command.input_data())? {
+        Map::Info(args) => args,
+        Map::Strains(args) => return strains(command, args).await,
+    };
 
     match MapArgs::try_from(args) {
         Ok(args) => map((&mut command).into(), args).await,
@@ -0,0 +1,614 @@
+use std::{borrow::Cow, fmt::Write};
+
+use bathbot_macros::HasMods;
+use bathbot_model::command_fields::GameModeOption;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_BASE},
+    datetime::SecToMinSec,
+    fields, matcher,
+    osu::MapIdType,
+    CowUtils, EmbedBuilder, FooterBuilder, MessageBuilder,
+};
+use eyre::Result;
+use rosu_pp::{any::Strains, model::hit_object::HitObjectKind, Beatmap};
+use rosu_v2::prelude::{GameMode, GameModsIntermode};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    commands::osu::{HasMods, ModsResult},
+    core::Context,
+    manager::{MapError, Mods},
+    util::{interaction::InteractionCommand, InteractionCommandExt},
+};
+
+/// Length of the hardest part of the map in milliseconds
+const HARDEST_WINDOW: f64 = 10_000.0;
+
+/// Strain peaks are weighted by this factor to the power of their rank
+const DECAY_WEIGHT: f64 = 0.9;
+
+/// Sections shorter than this many milliseconds are merged into the previous
+/// one
+const MIN_SECTION_LEN: f64 = 1000.0;
+
+/// Maximum amount of listed sections
+const SECTION_LIST_LEN: usize = 20;
+
+/// Maximum amount of listed BPM changes
+const BPM_LIST_LEN: usize = 10;
+
+const FLASHLIGHT: u32 = 1 << 10;
+
+#[derive(CommandModel, CreateCommand, HasMods)]
+#[command(
+    name = "strains",
+    desc = "Split the difficulty of a map into its sections",
+    help = "Split the difficulty of a map into its sections.\n\
+    Sections are determined through the map's kiai times and breaks. \
+    For each section, its contribution to the star rating is shown.\n\
+    The contribution is approximated by weighing each strain peak the same way \
+    the star rating calculation does so values won't add up exactly.\n\
+    Timestamps are in map time i.e. they're not adjusted to the clock rate."
+)]
+pub struct MapStrains<'a> {
+    #[command(
+        desc = "Specify a map url or map id",
+        help = "Specify a map either by map url or map id.\n\
+        If none is specified, it will search in the recent channel history \
+        and pick the first map it can find."
+    )]
+    map: Option<Cow<'a, str>>,
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+    #[command(
+        desc = "Specify mods e.g. hdhr or nm",
+        help = "Specify mods either directly or through the explicit `+mods!` / `+mods` syntax e.g. `hdhr` or `+hdhr!`"
+    )]
+    mods: Option<Cow<'a, str>>,
+    #[command(
+        min_value = 0.5,
+        max_value = 2.0,
+        desc = "Specify a custom clock rate that overwrites mods"
+    )]
+    clock_rate: Option<f32>,
+}
+
+pub(super) async fn strains(command: InteractionCommand, args: MapStrains<'_>) -> Result<()> {
+    let mods = match args.mods() {
+        ModsResult::Mods(mods) => mods.into_mods(),
+        ModsResult::None => GameModsIntermode::new(),
+        ModsResult::Invalid => {
+            let content =
+                "Failed to parse mods. Be sure to specify a valid abbreviation e.g. `hdhr`.";
+
+            return command.error(content).await;
+        }
+    };
+
+    let map_id = match args.map.as_deref() {
+        Some(arg) => match matcher::get_osu_map_id(arg) {
+            Some(id) => id,
+            None if matcher::get_osu_mapset_id(arg).is_some() => {
+                let content = "Looks like you gave me a mapset id, I need a map id though";
+
+                return command.error(content).await;
+            }
+            None => {
+                let content =
+                    "Failed to parse map url. Be sure you specify a valid map id or url to a map.";
+
+                return command.error(content).await;
+            }
+        },
+        None => {
+            let msgs = match Context::retrieve_channel_history(command.channel_id).await {
+                Ok(msgs) => msgs,
+                Err(_) => {
+                    let content =
+                        "No beatmap specified and lacking permission to search the channel \
+                        history for maps.\nTry specifying a map either by url to the map, or \
+                        just by map id, or give me the \"Read Message History\" permission.";
+
+                    return command.error(content).await;
+                }
+            };
+
+            match Context::find_map_id_in_msgs(&msgs, 0).await {
+                Some(MapIdType::Map(id)) => id,
+                None | Some(MapIdType::Set(_)) => {
+                    let content = "No beatmap specified and none found in recent channel history. \
+                    Try specifying a map either by url to the map, or just by map id.";
+
+                    return command.error(content).await;
+                }
+            }
+        }
+    };
+
+    let map = match Context::osu_map().map(map_id, None).await {
+        Ok(map) => map,
+        Err(MapError::NotFound) => {
+            let content = format!("Could not find beatmap with id `{map_id}`");
+
+            return command.error(content).await;
+        }
+        Err(MapError::Report(err)) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let map = match args.mode.map(GameMode::from) {
+        Some(mode) if map.mode() != GameMode::Osu && map.mode() != mode => {
+            let content = format!(
+                "Only osu!standard maps can be converted, this map is {:?}",
+                map.mode()
+            );
+
+            return command.error(content).await;
+        }
+        Some(mode) => map.convert(mode),
+        None => map,
+    };
+
+    let mode = map.mode();
+
+    let game_mods = match mods.clone().try_with_mode(mode) {
+        Some(mods) if mods.is_valid() => mods,
+        Some(_) => {
+            let content =
+                format!("Looks like some mods in `{mods}` are incompatible with each other");
+
+            return command.error(content).await;
+        }
+        None => {
+            let content = format!("The mods `{mods}` are incompatible with the mode {mode:?}");
+
+            return command.error(content).await;
+        }
+    };
+
+    let mut pp_mods = Mods::from(&game_mods);
+
+    if let Some(clock_rate) = args.clock_rate {
+        pp_mods.clock_rate = Some(clock_rate);
+    }
+
+    let clock_rate = pp_mods.clock_rate.map_or(1.0, f64::from);
+
+    let mut calc = Context::pp(&map).mods(pp_mods);
+    let stars = calc.difficulty().await.stars();
+    let strains = calc.strains();
+
+    let breakdown = StrainBreakdown::new(&map.pp_map, &strains, pp_mods.bits, clock_rate);
+
+    let mut description = String::with_capacity(1024);
+    description.push_str("```\nSection     Start   End     Stars   Share\n");
+
+    let names = breakdown.section_names();
+
+    for (section, name) in breakdown.sections.iter().zip(names).take(SECTION_LIST_LEN) {
+        let _ = writeln!(
+            description,
+            "{name:<10} {start:>6} - {end:<6} {stars:>6.2} {share:>6.1}%",
+            start = format_time(section.start),
+            end = format_time(section.end),
+            stars = stars * section.share,
+            share = 100.0 * section.share,
+        );
+    }
+
+    description.push_str("```");
+
+    if breakdown.sections.len() > SECTION_LIST_LEN {
+        let _ = write!(
+            description,
+            "... and {} more sections",
+            breakdown.sections.len() - SECTION_LIST_LEN
+        );
+    }
+
+    let hardest = match breakdown.hardest {
+        Some((start, end)) => format!("`{}` - `{}`", format_time(start), format_time(end)),
+        None => "-".to_owned(),
+    };
+
+    let bpm_changes = match breakdown.bpm_changes.as_slice() {
+        [] => "-".to_owned(),
+        [(_, bpm)] => format!("Constant {bpm:.0} BPM"),
+        changes => {
+            let mut value = String::with_capacity(changes.len().min(BPM_LIST_LEN) * 20);
+
+            for (time, bpm) in changes.iter().take(BPM_LIST_LEN) {
+                let _ = writeln!(value, "`{}` {bpm:.0} BPM", format_time(*time));
+            }
+
+            if changes.len() > BPM_LIST_LEN {
+                let _ = write!(value, "... and {} more", changes.len() - BPM_LIST_LEN);
+            }
+
+            value
+        }
+    };
+
+    let fields = fields![
+        "Stars", format!("{stars:.2}★"), true;
+        "Hardest 10 seconds", hardest, true;
+        "BPM changes", bpm_changes, false;
+    ];
+
+    let mut title = format!(
+        "{artist} - {title} [{version}]",
+        artist = map.artist().cow_escape_markdown(),
+        title = map.title().cow_escape_markdown(),
+        version = map.version().cow_escape_markdown(),
+    );
+
+    if !mods.is_empty() {
+        let _ = write!(title, " +{mods}");
+    }
+
+    if let Some(clock_rate) = args.clock_rate {
+        let _ = write!(title, " ({clock_rate}x)");
+    }
+
+    let footer = FooterBuilder::new(format!("Mode: {mode:?}"));
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .fields(fields)
+        .footer(footer)
+        .thumbnail(map.thumbnail())
+        .title(title)
+        .url(format!("{OSU_BASE}b/{map_id}"));
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum SectionKind {
+    Regular,
+    Kiai,
+    Break,
+}
+
+struct MapSection {
+    kind: SectionKind,
+    /// Map time of the section start
+    start: f64,
+    /// Map time of the section end
+    end: f64,
+    /// Approximate share of the total difficulty
+    share: f64,
+}
+
+struct StrainBreakdown {
+    sections: Vec<MapSection>,
+    /// Map time of start and end of the hardest window
+    hardest: Option<(f64, f64)>,
+    /// Map time of each BPM change alongside the new BPM adjusted to the
+    /// clock rate
+    bpm_changes: Vec<(f64, f64)>,
+}
+
+impl StrainBreakdown {
+    fn new(map: &Beatmap, strains: &Strains, mods: u32, clock_rate: f64) -> Self {
+        let skills = skill_strains(strains, mods);
+
+        // Strains are given in real time so they need to be converted to map
+        // time through the clock rate
+        let section_len = strains.section_len() * clock_rate;
+        let first_start = first_section_start(map, strains, clock_rate);
+        let strain_count = skills.iter().map(|skill| skill.len()).max().unwrap_or(0);
+
+        let mut contributions = vec![0.0; strain_count];
+        let mut combined = vec![0.0; strain_count];
+
+        for skill in skills.iter() {
+            for (i, contribution) in peak_contributions(skill).into_iter().enumerate() {
+                contributions[i] += contribution;
+            }
+
+            for (sum, strain) in combined.iter_mut().zip(skill.iter()) {
+                *sum += *strain;
+            }
+        }
+
+        let total: f64 = contributions.iter().sum();
+        let mut sections = map_sections(map);
+
+        if total > 0.0 {
+            for (i, contribution) in contributions.iter().enumerate() {
+                let time = first_start + (i as f64 + 0.5) * section_len;
+
+                let idx = sections
+                    .iter()
+                    .position(|section| time < section.end)
+                    .unwrap_or(sections.len().saturating_sub(1));
+
+                if let Some(section) = sections.get_mut(idx) {
+                    section.share += contribution / total;
+                }
+            }
+        }
+
+        let window_len = ((HARDEST_WINDOW / strains.section_len()).round() as usize)
+            .min(combined.len())
+            .max(1);
+
+        let hardest = combined
+            .windows(window_len)
+            .map(|window| window.iter().sum::<f64>())
+            .enumerate()
+            .filter(|(_, sum)| *sum > 0.0)
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| {
+                let start = first_start + i as f64 * section_len;
+
+                (start, start + window_len as f64 * section_len)
+            });
+
+        let mut bpm_changes = Vec::<(f64, f64)>::new();
+
+        for point in map.timing_points.iter() {
+            if point.beat_len <= 0.0 {
+                continue;
+            }
+
+            let bpm = 60_000.0 / point.beat_len * clock_rate;
+
+            if bpm_changes
+                .last()
+                .map_or(true, |(_, prev)| (prev - bpm).abs() >= 0.5)
+            {
+                bpm_changes.push((point.time, bpm));
+            }
+        }
+
+        Self {
+            sections,
+            hardest,
+            bpm_changes,
+        }
+    }
+
+    fn section_names(&self) -> Vec<String> {
+        let mut kiai = 0;
+        let mut part = 0;
+        let len = self.sections.len();
+
+        self.sections
+            .iter()
+            .enumerate()
+            .map(|(i, section)| match section.kind {
+                SectionKind::Break => "Break".to_owned(),
+                SectionKind::Kiai => {
+                    kiai += 1;
+
+                    format!("Kiai #{kiai}")
+                }
+                SectionKind::Regular if len == 1 => "Whole map".to_owned(),
+                SectionKind::Regular if i == 0 => "Intro".to_owned(),
+                SectionKind::Regular if i == len - 1 => "Outro".to_owned(),
+                SectionKind::Regular => {
+                    part += 1;
+
+                    format!("Part #{part}")
+                }
+            })
+            .collect()
+    }
+}
+
+/// Map time at which the first strain section starts.
+///
+/// Strains are calculated from the second hit object onwards, the third for
+/// taiko, and the first section ends on the next multiple of the section
+/// length in real time.
+fn first_section_start(map: &Beatmap, strains: &Strains, clock_rate: f64) -> f64 {
+    let skipped = if let Strains::Taiko(_) = strains {
+        2
+    } else {
+        1
+    };
+
+    let Some(h) = map.hit_objects.get(skipped) else {
+        return 0.0;
+    };
+
+    let section_len = strains.section_len();
+    let first_end = (h.start_time / clock_rate / section_len).ceil() * section_len;
+
+    (first_end - section_len) * clock_rate
+}
+
+/// Strain values of each skill that is relevant for the star rating.
+fn skill_strains(strains: &Strains, mods: u32) -> Vec<&[f64]> {
+    match strains {
+        Strains::Osu(strains) => {
+            let mut skills = vec![strains.aim.as_slice(), strains.speed.as_slice()];
+
+            if mods & FLASHLIGHT > 0 {
+                skills.push(strains.flashlight.as_slice());
+            }
+
+            skills
+        }
+        Strains::Taiko(strains) => vec![
+            strains.color.as_slice(),
+            strains.rhythm.as_slice(),
+            strains.stamina.as_slice(),
+        ],
+        Strains::Catch(strains) => vec![strains.movement.as_slice()],
+        Strains::Mania(strains) => vec![strains.strains.as_slice()],
+    }
+}
+
+/// Weighs each strain peak by its rank among all peaks, similar to how the
+/// difficulty of a skill is calculated.
+fn peak_contributions(peaks: &[f64]) -> Vec<f64> {
+    let mut order: Vec<_> = (0..peaks.len()).collect();
+    order.sort_unstable_by(|&a, &b| peaks[b].total_cmp(&peaks[a]));
+
+    let mut contributions = vec![0.0; peaks.len()];
+    let mut weight = 1.0;
+
+    for idx in order {
+        contributions[idx] = peaks[idx] * weight;
+        weight *= DECAY_WEIGHT;
+    }
+
+    contributions
+}
+
+/// Splits the map into regular sections, kiai sections, and breaks.
+fn map_sections(map: &Beatmap) -> Vec<MapSection> {
+    let start = map.hit_objects.first().map_or(0.0, |h| h.start_time);
+
+    let end = map
+        .hit_objects
+        .iter()
+        .map(|h| match h.kind {
+            HitObjectKind::Spinner { duration } | HitObjectKind::Hold { duration } => {
+                h.start_time + duration
+            }
+            HitObjectKind::Circle | HitObjectKind::Slider(_) => h.start_time,
+        })
+        .fold(start, f64::max);
+
+    let mut bounds = vec![start, end];
+
+    bounds.extend(map.effect_points.iter().map(|point| point.time));
+
+    bounds.extend(
+        map.breaks
+            .iter()
+            .flat_map(|period| [period.start_time, period.end_time]),
+    );
+
+    bounds.retain(|time| (start..=end).contains(time));
+    bounds.sort_unstable_by(f64::total_cmp);
+    bounds.dedup();
+
+    let kind_at = |time: f64| {
+        let in_break = map
+            .breaks
+            .iter()
+            .any(|period| period.start_time <= time && time < period.end_time);
+
+        if in_break {
+            return SectionKind::Break;
+        }
+
+        let kiai = map
+            .effect_points
+            .iter()
+            .take_while(|point| point.time <= time)
+            .last()
+            .is_some_and(|point| point.kiai);
+
+        if kiai {
+            SectionKind::Kiai
+        } else {
+            SectionKind::Regular
+        }
+    };
+
+    let mut sections: Vec<MapSection> = Vec::new();
+
+    for bound in bounds.windows(2) {
+        let (start, end) = (bound[0], bound[1]);
+        let kind = kind_at((start + end) / 2.0);
+
+        match sections.last_mut() {
+            Some(last) if last.kind == kind || end - start < MIN_SECTION_LEN => last.end = end,
+            _ => sections.push(MapSection {
+                kind,
+                start,
+                end,
+                share: 0.0,
+            }),
+        }
+    }
+
+    if sections.is_empty() {
+        sections.push(MapSection {
+            kind: SectionKind::Regular,
+            start,
+            end,
+            share: 0.0,
+        });
+    }
+
+    sections
+}
+
+fn format_time(ms: f64) -> String {
+    SecToMinSec::new((ms / 1000.0).max(0.0) as u32).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Write;
+
+    use rosu_pp::Difficulty;
+
+    use super::*;
+
+    /// Map whose first object only appears after a minute with a short burst
+    /// of streams after another minute.
+    fn late_map() -> Beatmap {
+        let mut content = "osu file format v14\n\n\
+            [General]\nMode: 0\n\n\
+            [Difficulty]\nHPDrainRate:5\nCircleSize:4\nOverallDifficulty:8\n\
+            ApproachRate:9\nSliderMultiplier:1.4\nSliderTickRate:1\n\n\
+            [TimingPoints]\n0,500,4,2,0,100,1,0\n\n\
+            [HitObjects]\n"
+            .to_owned();
+
+        for i in 0..60 {
+            let x = if i % 2 == 0 { 100 } else { 400 };
+            let _ = writeln!(content, "{x},200,{},1,0,0:0:0:0:", 60_000 + i * 1000);
+        }
+
+        for i in 0..80 {
+            let x = if i % 2 == 0 { 100 } else { 140 };
+            let _ = writeln!(content, "{x},200,{},1,0,0:0:0:0:", 125_000 + i * 125);
+        }
+
+        Beatmap::from_bytes(content.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn first_section_of_late_map() {
+        let map = late_map();
+        let strains = Difficulty::new().strains(&map);
+
+        // The second object at 61s is in the section from 60.8s to 61.2s
+        assert_eq!(first_section_start(&map, &strains, 1.0), 60_800.0);
+
+        // With DoubleTime, 61s in map time is ~40.67s in real time which is in
+        // the section from 40.4s to 40.8s i.e. 60.6s to 61.2s in map time
+        let strains = Difficulty::new().mods(1 << 6).strains(&map);
+        assert_eq!(first_section_start(&map, &strains, 1.5), 60_600.0);
+    }
+
+    #[test]
+    fn hardest_window_of_late_map() {
+        let map = late_map();
+        let strains = Difficulty::new().strains(&map);
+        let breakdown = StrainBreakdown::new(&map, &strains, 0, 1.0);
+
+        let (start, end) = breakdown.hardest.unwrap();
+
+        // The window must overlap with the burst of streams at 125s-135s
+        assert!(start < 135_000.0 && end > 125_000.0, "{start}..{end}");
+
+        let share: f64 = breakdown.sections.iter().map(|section| section.share).sum();
+        assert!((share - 1.0).abs() < 1e-9);
+    }
+}
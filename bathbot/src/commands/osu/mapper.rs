@@ -27,7 +27,7 @@ use crate::{
         impls::{SingleScoreContent, SingleScorePagination, TopPagination},
         ActiveMessages,
     },
-    commands::utility::{MissAnalyzerCheck, ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap},
+    commands::utility::{ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap},
     core::commands::{prefix::Args, CommandOrigin},
     manager::redis::{osu::UserArgs, RedisData},
     util::{interaction::InteractionCommand, ChannelExt, CheckPermissions, InteractionCommandExt},
@@ -458,7 +458,7 @@ async fn process_scores(
             pb_idx,
             legacy_scores,
             with_render,
-            MissAnalyzerCheck::without(),
+            false,
         )
         .await;
 
@@ -0,0 +1,560 @@
+use std::{cmp::Ordering, collections::HashMap, fmt::Write};
+
+use bathbot_macros::SlashCommand;
+use bathbot_psql::model::osu::Mappool as DbMappool;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_BASE},
+    datetime::SecToMinSec,
+    matcher, CowUtils, EmbedBuilder, FooterBuilder, IntHasher, MessageBuilder,
+};
+use eyre::{Report, Result};
+use rosu_v2::prelude::{GameModIntermode, GameModsIntermode};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use crate::{
+    core::commands::{checks::check_authority, CommandOrigin},
+    manager::{MapError, Mods},
+    util::{interaction::InteractionCommand, InteractionCommandExt},
+    Context,
+};
+
+/// Maximum amount of slots in one pool so that it fits into one embed
+const MAX_POOL_SLOTS: usize = 24;
+
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "mappool",
+    dm_permission = false,
+    desc = "Manage tournament mappools of this server",
+    help = "Manage tournament mappools of this server.\n\
+    A mappool consists of slots such as `NM1`, `HD2`, `FM1`, or `TB` each with a map.\n\
+    The letters of a slot determine its mods when calculating star ratings, \
+    i.e. `HDHR1` is calculated with HDHR whereas `NM`, `FM`, and `TB` slots are calculated nomod.\n\
+    Pools can be passed to `/matchcost` and `/matchlive` to label each game with its slot."
+)]
+pub enum Mappool {
+    #[command(name = "create")]
+    Create(MappoolCreate),
+    #[command(name = "add")]
+    Add(MappoolAdd),
+    #[command(name = "remove")]
+    Remove(MappoolRemove),
+    #[command(name = "show")]
+    Show(MappoolShow),
+    #[command(name = "delete")]
+    Delete(MappoolDelete),
+    #[command(name = "list")]
+    List(MappoolList),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "create", desc = "Create a new mappool for this server")]
+pub struct MappoolCreate {
+    #[command(max_length = 32, desc = "Name of the mappool")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "add",
+    desc = "Add a map to a mappool",
+    help = "Add a map to a mappool.\n\
+    Adding a map to a slot that's already in the pool will replace the slot's map.\n\
+    Only the creator of the pool or server authorities can add maps."
+)]
+pub struct MappoolAdd {
+    #[command(desc = "Name of the mappool")]
+    name: String,
+    #[command(max_length = 8, desc = "Slot of the map e.g. NM1, HD2, or TB")]
+    slot: String,
+    #[command(desc = "Specify a map url or map id")]
+    map: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove a slot from a mappool",
+    help = "Remove a slot from a mappool.\n\
+    Only the creator of the pool or server authorities can remove slots."
+)]
+pub struct MappoolRemove {
+    #[command(desc = "Name of the mappool")]
+    name: String,
+    #[command(desc = "Slot to remove e.g. NM1, HD2, or TB")]
+    slot: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "show", desc = "Show all maps of a mappool")]
+pub struct MappoolShow {
+    #[command(desc = "Name of the mappool")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "delete",
+    desc = "Delete a mappool",
+    help = "Delete a mappool.\n\
+    Only the creator of the pool or server authorities can delete it."
+)]
+pub struct MappoolDelete {
+    #[command(desc = "Name of the mappool")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "list", desc = "List all mappools of this server")]
+pub struct MappoolList;
+
+async fn slash_mappool(mut command: InteractionCommand) -> Result<()> {
+    match Mappool::from_interaction(command.input_data())? {
+        Mappool::Create(args) => create((&mut command).into(), args).await,
+        Mappool::Add(args) => add((&mut command).into(), args).await,
+        Mappool::Remove(args) => remove((&mut command).into(), args).await,
+        Mappool::Show(args) => show((&mut command).into(), args).await,
+        Mappool::Delete(args) => delete((&mut command).into(), args).await,
+        Mappool::List(_) => list((&mut command).into()).await,
+    }
+}
+
+async fn create(orig: CommandOrigin<'_>, args: MappoolCreate) -> Result<()> {
+    let owner = orig.user_id()?;
+
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Mappools can only be saved in servers").await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    if name.is_empty() {
+        return orig.error("The name must not be empty").await;
+    }
+
+    match Context::mappools().create(guild, &name, owner).await {
+        Ok(true) => {
+            let content = format!(
+                "Created mappool `{name}`.\n\
+                Maps can now be added via `/mappool add`."
+            );
+
+            let builder = MessageBuilder::new().embed(content);
+            orig.create_message(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("There already is a mappool named `{name}` in this server");
+
+            orig.error(content).await
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn add(orig: CommandOrigin<'_>, args: MappoolAdd) -> Result<()> {
+    let Some(slot) = parse_slot(&args.slot) else {
+        let content = "Failed to parse slot.\n\
+            Slots must start with letters and consist of at most 8 letters and digits \
+            e.g. `NM1`, `HD2`, or `TB`.";
+
+        return orig.error(content).await;
+    };
+
+    let Some(map_id) = matcher::get_osu_map_id(args.map.trim()) else {
+        let content = "Failed to parse map.\n\
+            Be sure it's a valid map url or map id.";
+
+        return orig.error(content).await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    let Some(mappool) = check_pool_permission(&orig, &name).await? else {
+        return Ok(());
+    };
+
+    let contains_slot = mappool.slots.iter().any(|entry| *entry.slot == slot);
+
+    if !contains_slot && mappool.slots.len() >= MAX_POOL_SLOTS {
+        let content = format!("A mappool can consist of at most {MAX_POOL_SLOTS} slots");
+
+        return orig.error(content).await;
+    }
+
+    // Make sure the map exists and is available for star calculations
+    let map = match Context::osu_map().map(map_id, None).await {
+        Ok(map) => map,
+        Err(MapError::NotFound) => {
+            let content = format!(
+                "Could not find beatmap with id `{map_id}`. \
+                Did you give me a mapset id instead of a map id?",
+            );
+
+            return orig.error(content).await;
+        }
+        Err(MapError::Report(err)) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let set_fut = Context::mappools().set_slot(mappool.pool_id, &slot, map_id);
+
+    if let Err(err) = set_fut.await {
+        let _ = orig.error(GENERAL_ISSUE).await;
+
+        return Err(err);
+    }
+
+    let map_name = format!(
+        "{artist} - {title} [{version}]",
+        artist = map.artist(),
+        title = map.title(),
+        version = map.version(),
+    );
+
+    let content = if contains_slot {
+        format!(
+            "Replaced the map of slot `{slot}` in mappool `{name}` with {}",
+            map_name.cow_escape_markdown()
+        )
+    } else {
+        format!(
+            "Added {map} to mappool `{name}` as `{slot}` ({count} slots)",
+            map = map_name.cow_escape_markdown(),
+            count = mappool.slots.len() + 1
+        )
+    };
+
+    let builder = MessageBuilder::new().embed(content);
+    orig.create_message(builder).await?;
+
+    Ok(())
+}
+
+async fn remove(orig: CommandOrigin<'_>, args: MappoolRemove) -> Result<()> {
+    let slot = args.slot.trim().to_ascii_uppercase();
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    let Some(mappool) = check_pool_permission(&orig, &name).await? else {
+        return Ok(());
+    };
+
+    match Context::mappools()
+        .remove_slot(mappool.pool_id, &slot)
+        .await
+    {
+        Ok(true) => {
+            let content = format!("Removed slot `{slot}` from mappool `{name}`");
+            let builder = MessageBuilder::new().embed(content);
+            orig.create_message(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("Mappool `{name}` has no slot `{slot}`");
+
+            orig.error(content).await
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn show(orig: CommandOrigin<'_>, args: MappoolShow) -> Result<()> {
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    let Some(mut mappool) = get_mappool(&orig, &name).await? else {
+        return Ok(());
+    };
+
+    if mappool.slots.is_empty() {
+        let content = format!(
+            "Mappool `{name}` has no maps yet.\n\
+            Add maps via `/mappool add`."
+        );
+
+        return orig.error(content).await;
+    }
+
+    mappool
+        .slots
+        .sort_unstable_by(|a, b| compare_slots(&a.slot, &b.slot));
+
+    let maps_id_checksum = mappool
+        .slots
+        .iter()
+        .map(|slot| (slot.map_id as i32, None))
+        .collect::<HashMap<_, _, IntHasher>>();
+
+    let maps = match Context::osu_map().maps(&maps_id_checksum).await {
+        Ok(maps) => maps,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(Report::new(err).wrap_err("Failed to get mappool maps"));
+        }
+    };
+
+    let mut description = String::with_capacity(mappool.slots.len() * 128);
+
+    for slot in mappool.slots.iter() {
+        let _ = write!(description, "`{}` ", slot.slot);
+
+        let Some(map) = maps.get(&slot.map_id) else {
+            let _ = writeln!(
+                description,
+                "[Unknown map]({OSU_BASE}b/{map_id})",
+                map_id = slot.map_id
+            );
+
+            continue;
+        };
+
+        let bits = slot_mods(&slot.slot);
+        let stars = Context::pp(map)
+            .mods(Mods::new(bits))
+            .difficulty()
+            .await
+            .stars();
+
+        let clock_rate = clock_rate(bits);
+        let seconds_drain = (map.seconds_drain() as f32 / clock_rate) as u32;
+        let bpm = map.bpm() * clock_rate;
+
+        let _ = writeln!(
+            description,
+            "[{artist} - {title} [{version}]]({OSU_BASE}b/{map_id})\n\
+            ~ `{stars:.2}★` • `{len}` • `{bpm:.0} BPM`",
+            artist = map.artist().cow_escape_markdown(),
+            title = map.title().cow_escape_markdown(),
+            version = map.version().cow_escape_markdown(),
+            map_id = slot.map_id,
+            len = SecToMinSec::new(seconds_drain),
+        );
+    }
+
+    let footer = FooterBuilder::new(format!(
+        "{count} slot{plural} • Star ratings calculated with the slot's mods",
+        count = mappool.slots.len(),
+        plural = if mappool.slots.len() == 1 { "" } else { "s" },
+    ));
+
+    let embed = EmbedBuilder::new()
+        .description(description)
+        .footer(footer)
+        .title(format!("Mappool `{}`", mappool.name));
+
+    let builder = MessageBuilder::new().embed(embed);
+    orig.create_message(builder).await?;
+
+    Ok(())
+}
+
+async fn delete(orig: CommandOrigin<'_>, args: MappoolDelete) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Mappools can only be saved in servers").await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    if check_pool_permission(&orig, &name).await?.is_none() {
+        return Ok(());
+    }
+
+    match Context::mappools().delete(guild, &name).await {
+        Ok(_) => {
+            let content = format!("Deleted mappool `{name}`");
+            let builder = MessageBuilder::new().embed(content);
+            orig.create_message(builder).await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn list(orig: CommandOrigin<'_>) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Mappools can only be saved in servers").await;
+    };
+
+    let entries = match Context::mappools().list(guild).await {
+        Ok(entries) => entries,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let content = if entries.is_empty() {
+        "There are no mappools in this server.\n\
+        Create one via `/mappool create`."
+            .to_owned()
+    } else {
+        let mut content = String::from("Mappools of this server:\n");
+
+        for entry in entries {
+            let _ = writeln!(
+                content,
+                "- `{name}`: {count} slot{plural}",
+                name = entry.name,
+                count = entry.slot_count,
+                plural = if entry.slot_count == 1 { "" } else { "s" },
+            );
+        }
+
+        content
+    };
+
+    let builder = MessageBuilder::new().embed(content);
+    orig.create_message(builder).await?;
+
+    Ok(())
+}
+
+/// Retrieves the mappool of the origin's guild.
+///
+/// Returns `None` if an error message was sent to the author.
+pub(super) async fn get_mappool(orig: &CommandOrigin<'_>, name: &str) -> Result<Option<DbMappool>> {
+    let Some(guild) = orig.guild_id() else {
+        orig.error("Mappools can only be used in servers").await?;
+
+        return Ok(None);
+    };
+
+    let name = name.trim().cow_to_ascii_lowercase();
+
+    match Context::mappools().get(guild, &name).await {
+        Ok(Some(mappool)) => Ok(Some(mappool)),
+        Ok(None) => {
+            let content = format!("There is no mappool named `{name}` in this server");
+            orig.error(content).await?;
+
+            Ok(None)
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+/// Retrieves the mappool and checks whether the author created it or is an
+/// authority.
+///
+/// Returns `None` if an error message was sent to the author.
+async fn check_pool_permission(orig: &CommandOrigin<'_>, name: &str) -> Result<Option<DbMappool>> {
+    let owner = orig.user_id()?;
+
+    let Some(mappool) = get_mappool(orig, name).await? else {
+        return Ok(None);
+    };
+
+    if mappool.created_by == owner {
+        return Ok(Some(mappool));
+    }
+
+    match check_authority(owner, orig.guild_id()).await {
+        Ok(None) => Ok(Some(mappool)),
+        Ok(Some(_)) => {
+            let content = "Only the creator of the mappool or server authorities can do that";
+            orig.error(content).await?;
+
+            Ok(None)
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err.wrap_err("Failed to check authority status"))
+        }
+    }
+}
+
+/// Uppercases the slot and validates that it starts with letters followed
+/// by letters or digits.
+fn parse_slot(slot: &str) -> Option<String> {
+    let slot = slot.trim().to_ascii_uppercase();
+
+    let valid = slot.len() <= 8
+        && slot.starts_with(|c: char| c.is_ascii_alphabetic())
+        && slot.chars().all(|c| c.is_ascii_alphanumeric());
+
+    valid.then_some(slot)
+}
+
+/// The leading letters of a slot.
+fn slot_prefix(slot: &str) -> &str {
+    let end = slot
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(slot.len());
+
+    &slot[..end]
+}
+
+/// Mod bits based on the slot's prefix, e.g. `HDHR2` results in HDHR.
+///
+/// `NM`, `FM`, `TB`, and unknown prefixes are considered nomod.
+fn slot_mods(slot: &str) -> u32 {
+    match slot_prefix(slot) {
+        "NM" | "FM" | "TB" => 0,
+        prefix => GameModsIntermode::try_from_acronyms(prefix).map_or(0, |mods| mods.bits()),
+    }
+}
+
+fn clock_rate(bits: u32) -> f32 {
+    let is_set =
+        |gamemod: GameModIntermode| gamemod.bits().is_some_and(|mod_bits| bits & mod_bits > 0);
+
+    if is_set(GameModIntermode::DoubleTime) || is_set(GameModIntermode::Nightcore) {
+        1.5
+    } else if is_set(GameModIntermode::HalfTime) {
+        0.75
+    } else {
+        1.0
+    }
+}
+
+/// Orders slots the way they're commonly listed in tournaments, i.e.
+/// NM, HD, HR, DT, FM, other mods, and TB last.
+fn compare_slots(a: &str, b: &str) -> Ordering {
+    fn prefix_order(prefix: &str) -> u8 {
+        match prefix {
+            "NM" => 0,
+            "HD" => 1,
+            "HR" => 2,
+            "DT" => 3,
+            "FM" => 4,
+            "TB" => 6,
+            _ => 5,
+        }
+    }
+
+    fn number(slot: &str) -> u32 {
+        slot[slot_prefix(slot).len()..].parse().unwrap_or(0)
+    }
+
+    let (prefix_a, prefix_b) = (slot_prefix(a), slot_prefix(b));
+
+    prefix_order(prefix_a)
+        .cmp(&prefix_order(prefix_b))
+        .then_with(|| prefix_a.cmp(prefix_b))
+        .then_with(|| number(a).cmp(&number(b)))
+        .then_with(|| a.cmp(b))
+}
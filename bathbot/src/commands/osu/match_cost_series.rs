@@ -0,0 +1,806 @@
+use std::{collections::HashMap, fmt::Write};
+
+use bathbot_macros::SlashCommand;
+use bathbot_model::rkyv_util::DerefAsString;
+use bathbot_psql::model::osu::MatchCostSeriesMatch;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_API_ISSUE},
+    matcher, CowUtils, IntHasher, MessageBuilder,
+};
+use eyre::{Report, Result};
+use rkyv::{Archive, Deserialize, Serialize};
+use rosu_v2::prelude::{OsuError, Team, User, Username};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use super::{prepare_games, process_match, retrieve_previous, MatchResult};
+use crate::{
+    active::{impls::MatchCostSeriesPagination, ActiveMessages},
+    core::commands::{checks::check_authority, CommandOrigin},
+    util::{interaction::InteractionCommand, InteractionCommandExt},
+    Context,
+};
+
+/// Maximum amount of matches that are considered in one series
+const MAX_SERIES_MATCHES: usize = 32;
+
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "matchcostseries",
+    dm_permission = false,
+    desc = "Performance ratings across multiple multiplayer matches",
+    help = "Calculate performance ratings for players across multiple multiplayer matches, \
+    e.g. a whole tournament bracket.\n\
+    For each match, the ratings are calculated just like in `/matchcost`. \
+    They're then normalized so that the average player of each match has a rating of 1.0 \
+    and combined into an overall rating weighted by the amount of maps each player played.\n\
+    Series of matches can be saved for a server so that matches can be added over time."
+)]
+pub enum MatchCostSeries {
+    #[command(name = "calculate")]
+    Calculate(MatchCostSeriesCalculate),
+    #[command(name = "create")]
+    Create(MatchCostSeriesCreate),
+    #[command(name = "add")]
+    Add(MatchCostSeriesAdd),
+    #[command(name = "remove")]
+    Remove(MatchCostSeriesRemove),
+    #[command(name = "delete")]
+    Delete(MatchCostSeriesDelete),
+    #[command(name = "list")]
+    List(MatchCostSeriesList),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "calculate",
+    desc = "Calculate ratings for a list of matches or a saved series",
+    help = "Calculate ratings for a list of matches or a saved series.\n\
+    Either the `matches` or the `series` option must be specified."
+)]
+pub struct MatchCostSeriesCalculate {
+    #[command(desc = "Space separated match urls or match ids")]
+    matches: Option<String>,
+    #[command(desc = "Name of a saved series")]
+    series: Option<String>,
+    #[command(
+        min_value = 0,
+        max_value = 20,
+        desc = "Amount of warmups to ignore in each listed match (defaults to 0)",
+        help = "Amount of warmups to ignore in each match of the `matches` option.\n\
+        For saved series, the warmups are specified when adding a match."
+    )]
+    warmups: Option<usize>,
+    #[command(
+        max_value = 100.0,
+        desc = "Specify a multiplier for EZ scores",
+        help = "Specify a multiplier for EZ scores.\n\
+        The suggested multiplier range is 1.0-2.0"
+    )]
+    ez_mult: Option<f32>,
+    #[command(desc = "Attach a CSV file with all ratings")]
+    csv: Option<bool>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "create", desc = "Save a new series of matches for this server")]
+pub struct MatchCostSeriesCreate {
+    #[command(max_length = 32, desc = "Name of the series")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "add",
+    desc = "Add a match to a saved series",
+    help = "Add a match to a saved series.\n\
+    Adding a match that's already in the series will update its warmups."
+)]
+pub struct MatchCostSeriesAdd {
+    #[command(desc = "Name of the series")]
+    name: String,
+    #[command(desc = "Specify a match url or match id")]
+    match_url: String,
+    #[command(
+        min_value = 0,
+        max_value = 20,
+        desc = "Amount of warmups to ignore (defaults to 0)"
+    )]
+    warmups: Option<usize>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove a match from a saved series",
+    help = "Remove a match from a saved series.\n\
+    Only the creator of the series or server authorities can remove matches."
+)]
+pub struct MatchCostSeriesRemove {
+    #[command(desc = "Name of the series")]
+    name: String,
+    #[command(desc = "Specify a match url or match id")]
+    match_url: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "delete",
+    desc = "Delete a saved series",
+    help = "Delete a saved series.\n\
+    Only the creator of the series or server authorities can delete it."
+)]
+pub struct MatchCostSeriesDelete {
+    #[command(desc = "Name of the series")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "list", desc = "List all saved series of this server")]
+pub struct MatchCostSeriesList;
+
+async fn slash_matchcostseries(mut command: InteractionCommand) -> Result<()> {
+    match MatchCostSeries::from_interaction(command.input_data())? {
+        MatchCostSeries::Calculate(args) => calculate((&mut command).into(), args).await,
+        MatchCostSeries::Create(args) => create((&mut command).into(), args).await,
+        MatchCostSeries::Add(args) => add((&mut command).into(), args).await,
+        MatchCostSeries::Remove(args) => remove((&mut command).into(), args).await,
+        MatchCostSeries::Delete(args) => delete((&mut command).into(), args).await,
+        MatchCostSeries::List(_) => list((&mut command).into()).await,
+    }
+}
+
+async fn calculate(orig: CommandOrigin<'_>, args: MatchCostSeriesCalculate) -> Result<()> {
+    let owner = orig.user_id()?;
+
+    let MatchCostSeriesCalculate {
+        matches,
+        series,
+        warmups,
+        ez_mult,
+        csv,
+    } = args;
+
+    let series_matches = match (matches, series) {
+        (Some(matches), _) => {
+            let warmups = warmups.unwrap_or(0) as u8;
+            let mut series_matches = Vec::new();
+
+            for arg in matches.split(|c: char| c.is_whitespace() || c == ',') {
+                if arg.is_empty() {
+                    continue;
+                }
+
+                let Some(match_id) = matcher::get_osu_match_id(arg) else {
+                    let content = format!(
+                        "Failed to parse `{arg}`.\n\
+                        Be sure to specify valid mp urls or match ids."
+                    );
+
+                    return orig.error(content).await;
+                };
+
+                if series_matches
+                    .iter()
+                    .all(|m: &MatchCostSeriesMatch| m.match_id != match_id)
+                {
+                    series_matches.push(MatchCostSeriesMatch { match_id, warmups });
+                }
+            }
+
+            series_matches
+        }
+        (None, Some(name)) => {
+            let Some(guild) = orig.guild_id() else {
+                return orig
+                    .error("Saved series are only available in servers")
+                    .await;
+            };
+
+            let name = name.cow_to_ascii_lowercase();
+
+            match Context::match_cost_series().get(guild, &name).await {
+                Ok(Some(series)) => series.matches,
+                Ok(None) => {
+                    let content = format!("There is no series named `{name}` in this server");
+
+                    return orig.error(content).await;
+                }
+                Err(err) => {
+                    let _ = orig.error(GENERAL_ISSUE).await;
+
+                    return Err(err);
+                }
+            }
+        }
+        (None, None) => {
+            let content = "You must specify either the `matches` or the `series` option";
+
+            return orig.error(content).await;
+        }
+    };
+
+    if series_matches.is_empty() {
+        return orig.error("There are no matches to rate").await;
+    } else if series_matches.len() > MAX_SERIES_MATCHES {
+        let content = format!("A series can consist of at most {MAX_SERIES_MATCHES} matches");
+
+        return orig.error(content).await;
+    }
+
+    let ez_mult = ez_mult.unwrap_or(1.0);
+    let osu = Context::osu();
+
+    let mut processed = Vec::with_capacity(series_matches.len());
+    let mut skipped = Vec::new();
+
+    for MatchCostSeriesMatch { match_id, warmups } in series_matches {
+        let mut osu_match = match osu.osu_match(match_id).await {
+            Ok(mut osu_match) => match retrieve_previous(&mut osu_match, osu).await {
+                Ok(_) => osu_match,
+                Err(err) => {
+                    let _ = orig.error(OSU_API_ISSUE).await;
+                    let err = Report::new(err).wrap_err("Failed to get previous match events");
+
+                    return Err(err);
+                }
+            },
+            Err(OsuError::NotFound) => {
+                skipped.push((match_id, "not found"));
+
+                continue;
+            }
+            Err(OsuError::Response { status, .. }) if status == 401 => {
+                skipped.push((match_id, "private"));
+
+                continue;
+            }
+            Err(err) => {
+                let _ = orig.error(OSU_API_ISSUE).await;
+                let err = Report::new(err).wrap_err("Failed to get match");
+
+                return Err(err);
+            }
+        };
+
+        let games = prepare_games(&mut osu_match, warmups as usize, ez_mult, 0);
+
+        if games.is_empty() {
+            skipped.push((match_id, "no games"));
+
+            continue;
+        }
+
+        let result = process_match(&games, osu_match.end_time.is_some(), &osu_match.users);
+
+        processed.push(SeriesMatch::new(
+            match_id,
+            osu_match.name,
+            result,
+            &osu_match.users,
+        ));
+    }
+
+    if processed.is_empty() {
+        return orig.error("None of the matches could be rated").await;
+    }
+
+    let result = MatchCostSeriesResult::new(processed);
+
+    let mut content = String::new();
+
+    if ez_mult != 1.0 {
+        let _ = write!(content, "EZ multiplier: {ez_mult:.2}");
+    }
+
+    if !skipped.is_empty() {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+
+        content.push_str("Skipped matches: ");
+
+        for ((match_id, reason), i) in skipped.iter().zip(0..) {
+            if i > 0 {
+                content.push_str(", ");
+            }
+
+            let _ = write!(content, "`{match_id}` ({reason})");
+        }
+    }
+
+    let attachment =
+        (csv == Some(true)).then(|| ("match_costs.csv".to_owned(), result.to_csv().into_bytes()));
+
+    let pagination = MatchCostSeriesPagination::new(result, content.into_boxed_str(), owner);
+
+    ActiveMessages::builder(pagination)
+        .attachment(attachment)
+        .start_by_update(true)
+        .begin(orig)
+        .await
+}
+
+async fn create(orig: CommandOrigin<'_>, args: MatchCostSeriesCreate) -> Result<()> {
+    let owner = orig.user_id()?;
+
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Series can only be saved in servers").await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    if name.is_empty() {
+        return orig.error("The name must not be empty").await;
+    }
+
+    match Context::match_cost_series()
+        .create(guild, &name, owner)
+        .await
+    {
+        Ok(true) => {
+            let content = format!(
+                "Created series `{name}`.\n\
+                Matches can now be added via `/matchcostseries add`."
+            );
+
+            let builder = MessageBuilder::new().embed(content);
+            orig.create_message(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("There already is a series named `{name}` in this server");
+
+            orig.error(content).await
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn add(orig: CommandOrigin<'_>, args: MatchCostSeriesAdd) -> Result<()> {
+    let owner = orig.user_id()?;
+
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Series can only be saved in servers").await;
+    };
+
+    let Some(match_id) = matcher::get_osu_match_id(&args.match_url) else {
+        let content = "Failed to parse match url.\n\
+            Be sure it's a valid mp url or a match id.";
+
+        return orig.error(content).await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+    let manager = Context::match_cost_series();
+
+    let series = match manager.get(guild, &name).await {
+        Ok(Some(series)) => series,
+        Ok(None) => {
+            let content = format!("There is no series named `{name}` in this server");
+
+            return orig.error(content).await;
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let contains_match = series.matches.iter().any(|m| m.match_id == match_id);
+
+    if !contains_match && series.matches.len() >= MAX_SERIES_MATCHES {
+        let content = format!("A series can consist of at most {MAX_SERIES_MATCHES} matches");
+
+        return orig.error(content).await;
+    }
+
+    let series_match = MatchCostSeriesMatch {
+        match_id,
+        warmups: args.warmups.unwrap_or(0) as u8,
+    };
+
+    if let Err(err) = manager.add_match(guild, &name, series_match, owner).await {
+        let _ = orig.error(GENERAL_ISSUE).await;
+
+        return Err(err);
+    }
+
+    let content = if contains_match {
+        format!("Updated match `{match_id}` in series `{name}`")
+    } else {
+        format!(
+            "Added match `{match_id}` to series `{name}` ({count} matches)",
+            count = series.matches.len() + 1
+        )
+    };
+
+    let builder = MessageBuilder::new().embed(content);
+    orig.create_message(builder).await?;
+
+    Ok(())
+}
+
+async fn remove(orig: CommandOrigin<'_>, args: MatchCostSeriesRemove) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Series can only be saved in servers").await;
+    };
+
+    let Some(match_id) = matcher::get_osu_match_id(&args.match_url) else {
+        let content = "Failed to parse match url.\n\
+            Be sure it's a valid mp url or a match id.";
+
+        return orig.error(content).await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    if !check_series_permission(&orig, &name).await? {
+        return Ok(());
+    }
+
+    match Context::match_cost_series()
+        .remove_match(guild, &name, match_id)
+        .await
+    {
+        Ok(true) => {
+            let content = format!("Removed match `{match_id}` from series `{name}`");
+            let builder = MessageBuilder::new().embed(content);
+            orig.create_message(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("Series `{name}` does not contain match `{match_id}`");
+
+            orig.error(content).await
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn delete(orig: CommandOrigin<'_>, args: MatchCostSeriesDelete) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Series can only be saved in servers").await;
+    };
+
+    let name = args.name.trim().cow_to_ascii_lowercase();
+
+    if !check_series_permission(&orig, &name).await? {
+        return Ok(());
+    }
+
+    match Context::match_cost_series().delete(guild, &name).await {
+        Ok(_) => {
+            let content = format!("Deleted series `{name}`");
+            let builder = MessageBuilder::new().embed(content);
+            orig.create_message(builder).await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn list(orig: CommandOrigin<'_>) -> Result<()> {
+    let Some(guild) = orig.guild_id() else {
+        return orig.error("Series can only be saved in servers").await;
+    };
+
+    let entries = match Context::match_cost_series().list(guild).await {
+        Ok(entries) => entries,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let content = if entries.is_empty() {
+        "There are no saved series in this server.\n\
+        Create one via `/matchcostseries create`."
+            .to_owned()
+    } else {
+        let mut content = String::from("Saved series of this server:\n");
+
+        for entry in entries {
+            let _ = writeln!(
+                content,
+                "- `{name}`: {count} match{plural}",
+                name = entry.name,
+                count = entry.match_count,
+                plural = if entry.match_count == 1 { "" } else { "es" },
+            );
+        }
+
+        content
+    };
+
+    let builder = MessageBuilder::new().embed(content);
+    orig.create_message(builder).await?;
+
+    Ok(())
+}
+
+/// Checks whether the author created the series or is an authority.
+///
+/// Returns `false` if an error message was sent to the author.
+async fn check_series_permission(orig: &CommandOrigin<'_>, name: &str) -> Result<bool> {
+    let owner = orig.user_id()?;
+    let guild = orig.guild_id();
+
+    let Some(guild_id) = guild else {
+        orig.error("Series can only be saved in servers").await?;
+
+        return Ok(false);
+    };
+
+    let series = match Context::match_cost_series().get(guild_id, name).await {
+        Ok(Some(series)) => series,
+        Ok(None) => {
+            let content = format!("There is no series named `{name}` in this server");
+            orig.error(content).await?;
+
+            return Ok(false);
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    if series.created_by == owner {
+        return Ok(true);
+    }
+
+    match check_authority(owner, guild).await {
+        Ok(None) => Ok(true),
+        Ok(Some(_)) => {
+            let content = "Only the creator of the series or server authorities can do that";
+            orig.error(content).await?;
+
+            Ok(false)
+        }
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            Err(err.wrap_err("Failed to check authority status"))
+        }
+    }
+}
+
+#[derive(Archive, Deserialize, Serialize)]
+pub struct MatchCostSeriesResult {
+    /// Sorted by rating
+    pub players: Vec<SeriesPlayer>,
+    pub matches: Vec<SeriesMatch>,
+}
+
+impl MatchCostSeriesResult {
+    fn new(matches: Vec<SeriesMatch>) -> Self {
+        let mut players: HashMap<u32, SeriesPlayer, IntHasher> = HashMap::default();
+
+        for (series_match, i) in matches.iter().zip(0..) {
+            for entry in series_match.players.iter() {
+                let player = players
+                    .entry(entry.user_id)
+                    .or_insert_with(|| SeriesPlayer {
+                        user_id: entry.user_id,
+                        username: entry.username.clone(),
+                        teams: Vec::new(),
+                        matches: 0,
+                        games: 0,
+                        rating: 0.0,
+                        avg_match_cost: 0.0,
+                        costs: vec![None; matches.len()],
+                    });
+
+                if let Some(ref team) = entry.team {
+                    if !player.teams.contains(team) {
+                        player.teams.push(team.clone());
+                    }
+                }
+
+                let games = entry.games_played as f32;
+
+                player.matches += 1;
+                player.games += entry.games_played;
+                player.rating += entry.normalized * games;
+                player.avg_match_cost += entry.match_cost * games;
+                player.costs[i] = Some(entry.match_cost);
+            }
+        }
+
+        let mut players: Vec<_> = players
+            .into_values()
+            .map(|mut player| {
+                let games = player.games.max(1) as f32;
+                player.rating /= games;
+                player.avg_match_cost /= games;
+
+                player
+            })
+            .collect();
+
+        players.sort_unstable_by(|a, b| b.rating.total_cmp(&a.rating));
+
+        Self { players, matches }
+    }
+
+    fn to_csv(&self) -> String {
+        let mut csv =
+            String::from("rank,user_id,username,teams,matches,maps,rating,avg_match_cost");
+
+        for series_match in self.matches.iter() {
+            let _ = write!(csv, ",{}", series_match.match_id);
+        }
+
+        for (player, i) in self.players.iter().zip(1..) {
+            let _ = write!(
+                csv,
+                "\n{i},{user_id},{name},{teams},{matches},{maps},{rating:.4},{avg:.4}",
+                user_id = player.user_id,
+                name = CsvField(&player.username),
+                teams = CsvField(&player.teams.join(" | ")),
+                matches = player.matches,
+                maps = player.games,
+                rating = player.rating,
+                avg = player.avg_match_cost,
+            );
+
+            for cost in player.costs.iter() {
+                match cost {
+                    Some(cost) => {
+                        let _ = write!(csv, ",{cost:.4}");
+                    }
+                    None => csv.push(','),
+                }
+            }
+        }
+
+        csv
+    }
+}
+
+#[derive(Archive, Deserialize, Serialize)]
+pub struct SeriesPlayer {
+    pub user_id: u32,
+    #[with(DerefAsString)]
+    pub username: Username,
+    /// All teams that the player played for
+    pub teams: Vec<Box<str>>,
+    pub matches: usize,
+    pub games: usize,
+    /// Normalized match costs weighted by the amount of games
+    pub rating: f32,
+    /// Match costs weighted by the amount of games
+    pub avg_match_cost: f32,
+    /// Match cost for each match of the series
+    pub costs: Vec<Option<f32>>,
+}
+
+#[derive(Archive, Deserialize, Serialize)]
+pub struct SeriesMatch {
+    pub match_id: u32,
+    pub name: Box<str>,
+    /// Sorted by match cost
+    pub players: Vec<SeriesMatchPlayer>,
+}
+
+impl SeriesMatch {
+    fn new(match_id: u32, name: String, result: MatchResult, users: &HashMap<u32, User>) -> Self {
+        let teams = team_names(&name);
+
+        let team_name = |team: Team| -> Option<Box<str>> {
+            let name = match (team, teams) {
+                (Team::Red, Some((red, _))) => red,
+                (Team::Blue, Some((_, blue))) => blue,
+                (Team::Red, None) => "Red",
+                (Team::Blue, None) => "Blue",
+                (Team::None, _) => return None,
+            };
+
+            Some(Box::from(name))
+        };
+
+        let entries: Vec<_> = match result {
+            MatchResult::TeamVS { blue, red, .. } => blue
+                .players
+                .into_iter()
+                .map(|entry| (entry, team_name(Team::Blue)))
+                .chain(
+                    red.players
+                        .into_iter()
+                        .map(|entry| (entry, team_name(Team::Red))),
+                )
+                .collect(),
+            MatchResult::HeadToHead { players, .. } => {
+                players.into_iter().map(|entry| (entry, None)).collect()
+            }
+            MatchResult::NoGames { .. } => Vec::new(),
+        };
+
+        // Normalize so that the average player of the match has a rating of 1.0
+        let cost_sum: f32 = entries.iter().map(|(entry, _)| entry.match_cost).sum();
+        let avg_cost = cost_sum / entries.len().max(1) as f32;
+
+        let mut players: Vec<_> = entries
+            .into_iter()
+            .map(|(entry, team)| SeriesMatchPlayer {
+                user_id: entry.user_id,
+                username: users.get(&entry.user_id).map_or_else(
+                    || entry.user_id.to_string().into(),
+                    |user| user.username.clone(),
+                ),
+                team,
+                match_cost: entry.match_cost,
+                normalized: if avg_cost > 0.0 {
+                    entry.match_cost / avg_cost
+                } else {
+                    0.0
+                },
+                games_played: entry.games_played,
+            })
+            .collect();
+
+        players.sort_unstable_by(|a, b| b.match_cost.total_cmp(&a.match_cost));
+
+        Self {
+            match_id,
+            name: name.into_boxed_str(),
+            players,
+        }
+    }
+}
+
+#[derive(Archive, Deserialize, Serialize)]
+pub struct SeriesMatchPlayer {
+    pub user_id: u32,
+    #[with(DerefAsString)]
+    pub username: Username,
+    pub team: Option<Box<str>>,
+    pub match_cost: f32,
+    /// Match cost relative to the average of the match
+    pub normalized: f32,
+    pub games_played: usize,
+}
+
+/// Parses team names of match names like `ABC: (Red team) vs (Blue team)`
+fn team_names(name: &str) -> Option<(&str, &str)> {
+    let start = name.find('(')? + 1;
+
+    // ASCII lowercasing keeps byte indices intact
+    let lowercase = name.to_ascii_lowercase();
+    let sep = lowercase[start..].find(") vs (")? + start;
+
+    let red = &name[start..sep];
+    let blue = name[sep + 6..].trim_end().strip_suffix(')')?;
+
+    Some((red, blue))
+}
+
+struct CsvField<'s>(&'s str);
+
+impl std::fmt::Display for CsvField<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.contains([',', '"', '\n']) {
+            write!(f, "\"{}\"", self.0.replace('"', "\"\""))
+        } else {
+            f.write_str(self.0)
+        }
+    }
+}
@@ -6,7 +6,11 @@ use std::{
 };
 
 use bathbot_macros::{command, SlashCommand};
-use bathbot_util::{constants::OSU_API_ISSUE, matcher, IntHasher};
+use bathbot_psql::model::osu::Mappool;
+use bathbot_util::{
+    constants::{OSU_API_ISSUE, OSU_BASE},
+    matcher, IntHasher,
+};
 use eyre::{Report, Result};
 use rosu_v2::{
     model::mods::GameModsIntermode,
@@ -16,6 +20,7 @@ use rosu_v2::{
 };
 use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
 
+use super::get_mappool;
 use crate::{
     active::{impls::MatchCostPagination, ActiveMessages},
     core::commands::{
@@ -63,6 +68,13 @@ pub struct MatchCost<'a> {
     skip_last: Option<usize>,
     #[command(desc = "How the data should be displayed")]
     display: Option<MatchCostDisplay>,
+    #[command(
+        desc = "Name of a mappool of this server to label the picks with",
+        help = "Name of a mappool of this server, see `/mappool`.\n\
+        If specified, each pick will be labeled with its slot \
+        and maps that are not part of the pool will be pointed out."
+    )]
+    mappool: Option<Cow<'a, str>>,
 }
 
 #[derive(Copy, Clone, CommandOption, CreateOption, Default)]
@@ -97,6 +109,7 @@ impl<'m> MatchCost<'m> {
             skip_last: None,
             ez_mult: None,
             display: None,
+            mappool: None,
         })
     }
 }
@@ -139,6 +152,7 @@ async fn matchcosts(orig: CommandOrigin<'_>, args: MatchCost<'_>) -> Result<()>
         skip_last,
         ez_mult,
         display,
+        mappool,
     } = args;
 
     let Some(match_id) = matcher::get_osu_match_id(&match_url) else {
@@ -148,6 +162,14 @@ async fn matchcosts(orig: CommandOrigin<'_>, args: MatchCost<'_>) -> Result<()>
         return orig.error(content).await;
     };
 
+    let mappool = match mappool {
+        Some(name) => match get_mappool(&orig, &name).await? {
+            Some(mappool) => Some(mappool),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
     let warmups = warmups.unwrap_or(0);
     let ez_mult = ez_mult.unwrap_or(1.0);
     let skip_last = skip_last.unwrap_or(0);
@@ -157,36 +179,7 @@ async fn matchcosts(orig: CommandOrigin<'_>, args: MatchCost<'_>) -> Result<()>
     let (osu_match, games) = match osu.osu_match(match_id).await {
         Ok(mut osu_match) => {
             retrieve_previous(&mut osu_match, osu).await?;
-
-            let games_iter = osu_match
-                .drain_games()
-                .filter(|game| game.end_time.is_some())
-                .skip(warmups)
-                .map(|mut game| {
-                    game.scores.retain(|score| score.score > 0);
-
-                    game
-                });
-
-            let mut games: Vec<_> = if ez_mult != 1.0 {
-                games_iter
-                    .map(|mut game| {
-                        game.scores.iter_mut().for_each(|score| {
-                            if score.mods.contains(GameModIntermode::Easy) {
-                                score.score = (score.score as f32 * ez_mult) as u32;
-                            }
-                        });
-
-                        game
-                    })
-                    .collect()
-            } else {
-                games_iter.collect()
-            };
-
-            if skip_last > 0 {
-                games.truncate(games.len() - skip_last);
-            }
+            let games = prepare_games(&mut osu_match, warmups, ez_mult, skip_last);
 
             (osu_match, games)
         }
@@ -220,9 +213,16 @@ async fn matchcosts(orig: CommandOrigin<'_>, args: MatchCost<'_>) -> Result<()>
         process_match(&games, osu_match.end_time.is_some(), &osu_match.users)
     };
 
-    let mut content = String::new();
+    let mut content = match mappool {
+        Some(ref mappool) if !games.is_empty() => mappool_picks(&games, mappool),
+        Some(_) | None => String::new(),
+    };
 
     if warmups > 0 {
+        if !content.is_empty() {
+            content.push('\n');
+        }
+
         content.push_str("Ignoring the first ");
 
         if warmups == 1 {
@@ -235,12 +235,16 @@ async fn matchcosts(orig: CommandOrigin<'_>, args: MatchCost<'_>) -> Result<()>
     }
 
     if ez_mult != 1.0 {
-        let _ = if content.is_empty() {
+        let _ = if warmups == 0 {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+
             write!(content, "EZ multiplier: {ez_mult:.2}")
         } else {
             write!(content, " (EZ multiplier: {ez_mult:.2}):")
         };
-    } else if !content.is_empty() {
+    } else if warmups > 0 {
         content.push(':');
     }
 
@@ -258,6 +262,76 @@ async fn matchcosts(orig: CommandOrigin<'_>, args: MatchCost<'_>) -> Result<()>
         .await
 }
 
+/// Lists the slot of each game's map and points out the maps that are not
+/// part of the mappool.
+fn mappool_picks(games: &[MatchGame], mappool: &Mappool) -> String {
+    let mut content = format!("Picks from mappool `{}`:", mappool.name);
+    let mut not_in_pool = Vec::new();
+
+    for game in games {
+        match mappool.slot(game.map_id) {
+            Some(slot) => {
+                let _ = write!(content, " `{slot}`");
+            }
+            None => {
+                content.push_str(" `?`");
+                not_in_pool.push(game.map_id);
+            }
+        }
+    }
+
+    if !not_in_pool.is_empty() {
+        content.push_str("\n:warning: Maps not in the mappool:");
+
+        for map_id in not_in_pool {
+            let _ = write!(content, " <{OSU_BASE}b/{map_id}>");
+        }
+    }
+
+    content
+}
+
+/// Drains the finished games of the match without the skipped ones and
+/// adjusts their scores
+pub fn prepare_games(
+    osu_match: &mut OsuMatch,
+    warmups: usize,
+    ez_mult: f32,
+    skip_last: usize,
+) -> Vec<MatchGame> {
+    let games_iter = osu_match
+        .drain_games()
+        .filter(|game| game.end_time.is_some())
+        .skip(warmups)
+        .map(|mut game| {
+            game.scores.retain(|score| score.score > 0);
+
+            game
+        });
+
+    let mut games: Vec<_> = if ez_mult != 1.0 {
+        games_iter
+            .map(|mut game| {
+                game.scores.iter_mut().for_each(|score| {
+                    if score.mods.contains(GameModIntermode::Easy) {
+                        score.score = (score.score as f32 * ez_mult) as u32;
+                    }
+                });
+
+                game
+            })
+            .collect()
+    } else {
+        games_iter.collect()
+    };
+
+    if skip_last > 0 {
+        games.truncate(games.len().saturating_sub(skip_last));
+    }
+
+    games
+}
+
 pub async fn retrieve_previous(osu_match: &mut OsuMatch, osu: &Osu) -> OsuResult<()> {
     let mut curr = &*osu_match;
     let mut prev: Option<OsuMatch> = None;
@@ -371,6 +445,7 @@ pub fn process_match(
                 tiebreaker_bonus: entry.tiebreaker_bonus,
                 match_cost: entry.match_cost(),
                 avg_score: entry.avg_score,
+                games_played: entry.games_played,
             };
 
             match team {
@@ -399,6 +474,7 @@ pub fn process_match(
                 tiebreaker_bonus: entry.tiebreaker_bonus,
                 match_cost: entry.match_cost(),
                 avg_score: entry.avg_score,
+                games_played: entry.games_played,
             })
             .collect();
 
@@ -506,6 +582,7 @@ impl UsersPerformanceCosts {
                 mods_bonus_factor,
                 tiebreaker_bonus,
                 avg_score,
+                games_played: entries.len(),
             };
 
             match_costs.insert(*user_id, entry);
@@ -589,6 +666,7 @@ struct MatchCostEntry {
     mods_bonus_factor: f32,
     tiebreaker_bonus: f32,
     avg_score: u32,
+    games_played: usize,
 }
 
 impl MatchCostEntry {
@@ -606,6 +684,7 @@ pub struct UserMatchCostEntry {
     pub tiebreaker_bonus: f32,
     pub match_cost: f32,
     pub avg_score: u32,
+    pub games_played: usize,
 }
 
 impl UserMatchCostEntry {
@@ -14,6 +14,7 @@ use twilight_http::{api_error::ApiError, error::ErrorType};
 use twilight_interactions::command::{CommandModel, CreateCommand};
 use twilight_model::channel::{thread::AutoArchiveDuration, ChannelType};
 
+use super::get_mappool;
 use crate::{
     core::commands::CommandOrigin,
     matchlive::MatchTrackResult,
@@ -43,6 +44,15 @@ pub struct MatchliveAdd<'a> {
     match_url: Cow<'a, str>,
     #[command(desc = "Choose if a new thread should be started")]
     thread: ThreadChannel,
+    #[command(
+        desc = "Name of a mappool of this server to label the picks with",
+        help = "Name of a mappool of this server, see `/mappool`.\n\
+        If specified, each game will be labeled with its slot \
+        and maps that are not part of the pool will be pointed out.\n\
+        If the match is already tracked with a mappool in another channel, \
+        that mappool will be used instead."
+    )]
+    mappool: Option<Cow<'a, str>>,
 }
 
 #[derive(CommandModel, CreateCommand)]
@@ -79,6 +89,7 @@ async fn prefix_matchlive(msg: &Message, mut args: Args<'_>) -> Result<()> {
             let args = MatchliveAdd {
                 match_url: arg.into(),
                 thread: ThreadChannel::Channel,
+                mappool: None,
             };
 
             matchlive(msg.into(), args).await
@@ -135,13 +146,25 @@ fn parse_match_id(match_url: &str) -> Result<u32, &'static str> {
 }
 
 async fn matchlive(orig: CommandOrigin<'_>, args: MatchliveAdd<'_>) -> Result<()> {
-    let MatchliveAdd { match_url, thread } = args;
+    let MatchliveAdd {
+        match_url,
+        thread,
+        mappool,
+    } = args;
 
     let match_id = match parse_match_id(&match_url) {
         Ok(id) => id,
         Err(content) => return orig.error(content).await,
     };
 
+    let mappool = match mappool {
+        Some(name) => match get_mappool(&orig, &name).await? {
+            Some(mappool) => Some(mappool),
+            None => return Ok(()),
+        },
+        None => None,
+    };
+
     let mut channel = orig.channel_id();
 
     if let ThreadChannel::Thread = thread {
@@ -191,7 +214,7 @@ async fn matchlive(orig: CommandOrigin<'_>, args: MatchliveAdd<'_>) -> Result<()
         }
     }
 
-    let content: &str = match Context::add_match_track(channel, match_id).await {
+    let content: &str = match Context::add_match_track(channel, match_id, mappool).await {
         MatchTrackResult::Added => match orig {
             CommandOrigin::Message { .. } => return Ok(()),
             CommandOrigin::Interaction { command } => {
@@ -71,8 +71,9 @@ use twilight_model::id::{marker::UserMarker, Id};
 
 pub use self::{
     badges::*, claim_name::*, compare::*, fix::*, graphs::*, leaderboard::*, map::*, map_search::*,
-    match_compare::*, match_costs::*, medals::*, nochoke::*, osustats::*, profile::*, recent::*,
-    region_top::*, render::*, scores::*, simulate::*, snipe::*, top::*, whatif::*,
+    mappool::*, match_compare::*, match_cost_series::*, match_costs::*, medals::*, nochoke::*,
+    osustats::*, profile::*, recent::*, region_top::*, render::*, replay::*, scores::*,
+    simulate::*, snipe::*, top::*, whatif::*,
 };
 use crate::{
     core::commands::{interaction::InteractionCommands, CommandOrigin},
@@ -93,7 +94,9 @@ mod leaderboard;
 mod map;
 mod map_search;
 mod mapper;
+mod mappool;
 mod match_compare;
+mod match_cost_series;
 mod match_costs;
 mod medals;
 mod most_played;
@@ -109,6 +112,7 @@ mod ratios;
 mod recent;
 mod region_top;
 mod render;
+mod replay;
 mod scores;
 mod serverleaderboard;
 mod simulate;
@@ -29,9 +29,7 @@ use crate::{
         impls::{SingleScoreContent, SingleScorePagination, TopPagination},
         ActiveMessages,
     },
-    commands::utility::{
-        MissAnalyzerCheck, ScoreEmbedDataHalf, ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap,
-    },
+    commands::utility::{ScoreEmbedDataHalf, ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap},
     core::commands::CommandOrigin,
     manager::redis::{
         osu::{UserArgs, UserArgsSlim},
@@ -405,7 +403,7 @@ async fn process_scores(
             None,
             legacy_scores,
             with_render,
-            MissAnalyzerCheck::without(),
+            false,
         )
         .await;
 
@@ -8,6 +8,7 @@ use bathbot_util::{
     matcher, CowUtils, MessageOrigin,
 };
 use eyre::{Report, Result};
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_v2::{
     prelude::{GameMode, OsuError},
     request::UserId,
@@ -42,7 +43,9 @@ pub struct Profile<'a> {
     discord: Option<Id<UserMarker>>,
 }
 
-#[derive(Copy, Clone, CommandOption, CreateOption, Debug, Eq, PartialEq)]
+#[derive(
+    Archive, Copy, Clone, CommandOption, CreateOption, Debug, Deserialize, Eq, PartialEq, Serialize,
+)]
 pub enum ProfileKind {
     #[option(name = "Compact", value = "compact")]
     Compact,
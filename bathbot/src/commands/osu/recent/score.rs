@@ -30,7 +30,7 @@ use crate::{
     },
     commands::{
         osu::{map_strain_graph, require_link, user_not_found},
-        utility::{MissAnalyzerCheck, ScoreEmbedDataWrap},
+        utility::ScoreEmbedDataWrap,
     },
     core::commands::{interaction::InteractionCommands, prefix::Args, CommandOrigin},
     manager::redis::osu::{UserArgs, UserArgsSlim},
@@ -555,11 +555,6 @@ pub(super) async fn score(orig: CommandOrigin<'_>, args: RecentScore<'_>) -> Res
     let user_id = user.user_id();
     let grade = if score.passed { score.grade } else { Grade::F };
 
-    let mut with_miss_analyzer = orig
-        .guild_id()
-        .as_ref()
-        .map_or(false, Context::has_miss_analyzer);
-
     let mut with_render = match (guild_render_button, config.render_button) {
         (None | Some(true), None) => true,
         (None | Some(true), Some(with_render)) => with_render,
@@ -587,7 +582,10 @@ pub(super) async fn score(orig: CommandOrigin<'_>, args: RecentScore<'_>) -> Res
         }
     };
 
-    with_miss_analyzer &= mode == GameMode::Osu;
+    let with_miss_analyzer = settings.buttons.miss_analyzer
+        && mode == GameMode::Osu
+        && orig.has_permission_to(Permissions::SEND_MESSAGES);
+
     with_render &= settings.buttons.render
         && mode == GameMode::Osu
         && orig.has_permission_to(Permissions::SEND_MESSAGES)
@@ -619,7 +617,6 @@ pub(super) async fn score(orig: CommandOrigin<'_>, args: RecentScore<'_>) -> Res
     };
 
     let guild_id = orig.guild_id();
-    let miss_analyzer = MissAnalyzerCheck::new(guild_id, with_miss_analyzer);
     let origin = MessageOrigin::new(guild_id, orig.channel_id());
 
     let mut entries = process_scores(
@@ -630,7 +627,7 @@ pub(super) async fn score(orig: CommandOrigin<'_>, args: RecentScore<'_>) -> Res
         origin,
         legacy_scores,
         with_render,
-        miss_analyzer,
+        with_miss_analyzer,
     );
 
     let mut content = tries.map_or(SingleScoreContent::None, |tries| {
@@ -730,7 +727,7 @@ fn process_scores(
     origin: MessageOrigin,
     legacy_scores: bool,
     with_render: bool,
-    miss_analyzer: MissAnalyzerCheck,
+    with_miss_analyzer: bool,
 ) -> Box<[ScoreEmbedDataWrap]> {
     let top100 = top100.map(Arc::from);
 
@@ -744,7 +741,7 @@ fn process_scores(
                 score,
                 legacy_scores,
                 with_render,
-                miss_analyzer,
+                with_miss_analyzer,
                 top100.as_ref().map(Arc::clone),
                 #[cfg(feature = "twitch")]
                 twitch_data.as_ref().map(Arc::clone),
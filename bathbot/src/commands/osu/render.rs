@@ -13,13 +13,16 @@ use rosu_render::{
     client::error::{
         ApiError as OrdrApiError, ClientError as OrdrError, ErrorCode as OrdrErrorCode,
     },
-    model::RenderDone,
+    model::{RenderDone, RenderSkinOption},
 };
 use rosu_v2::prelude::{GameMode, OsuError};
 use twilight_interactions::command::{CommandModel, CreateCommand};
 use twilight_model::{
     channel::Attachment,
-    id::{marker::UserMarker, Id},
+    id::{
+        marker::{GuildMarker, UserMarker},
+        Id,
+    },
 };
 
 use crate::{
@@ -28,7 +31,7 @@ use crate::{
         ActiveMessages,
     },
     core::{buckets::BucketName, commands::OwnedCommandOrigin, Context},
-    manager::{ReplayScore, ReplaySettings},
+    manager::{ReplayScore, ReplaySettings, ReplaySkin},
     tracking::OrdrReceivers,
     util::{interaction::InteractionCommand, Authored, InteractionCommandExt},
 };
@@ -154,16 +157,8 @@ async fn render_replay(command: InteractionCommand, replay: RenderReplay) -> Res
         }
     };
 
-    let allow_custom_skins = match command.guild_id {
-        Some(guild_id) => {
-            Context::guild_config()
-                .peek(guild_id, |config| config.allow_custom_skins.unwrap_or(true))
-                .await
-        }
-        None => true,
-    };
-
-    let skin = settings.skin(allow_custom_skins);
+    let (allow_custom_skins, guild_skin) = guild_skin_settings(command.guild_id).await;
+    let skin = settings.skin_or(allow_custom_skins, guild_skin.as_ref());
 
     let render_fut = Context::ordr()
         .expect("ordr unavailable")
@@ -217,6 +212,35 @@ async fn render_replay(command: InteractionCommand, replay: RenderReplay) -> Res
     Ok(())
 }
 
+/// Whether custom skins are allowed in the guild and the guild's default skin
+pub async fn guild_skin_settings(guild: Option<Id<GuildMarker>>) -> (bool, Option<ReplaySkin>) {
+    let Some(guild_id) = guild else {
+        return (true, None);
+    };
+
+    let allow_custom_skins = Context::guild_config()
+        .peek(guild_id, |config| config.allow_custom_skins.unwrap_or(true))
+        .await;
+
+    let default_skin = match Context::guild_skins().default_skin(guild_id).await {
+        Ok(skin) => skin.and_then(|skin| {
+            let id = skin.ordr_skin_id?;
+
+            Some(ReplaySkin {
+                skin: RenderSkinOption::Custom { id },
+                display_name: skin.name,
+            })
+        }),
+        Err(err) => {
+            warn!(?err, "Failed to get default skin of guild");
+
+            None
+        }
+    };
+
+    (allow_custom_skins, default_skin)
+}
+
 async fn render_score(mut command: InteractionCommand, score: RenderScore) -> Result<()> {
     command.defer(false).await?;
 
@@ -323,16 +347,8 @@ async fn render_score(mut command: InteractionCommand, score: RenderScore) -> Re
     status.set(RenderStatusInner::CommissioningRender);
     let _ = command.update(status.as_message()).await;
 
-    let allow_custom_skins = match command.guild_id {
-        Some(guild_id) => {
-            Context::guild_config()
-                .peek(guild_id, |config| config.allow_custom_skins.unwrap_or(true))
-                .await
-        }
-        None => true,
-    };
-
-    let skin = settings.skin(allow_custom_skins);
+    let (allow_custom_skins, guild_skin) = guild_skin_settings(command.guild_id).await;
+    let skin = settings.skin_or(allow_custom_skins, guild_skin.as_ref());
 
     let render_fut = Context::ordr()
         .expect("ordr unavailable")
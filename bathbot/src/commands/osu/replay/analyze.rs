@@ -0,0 +1,121 @@
+use bathbot_util::{
+    constants::OSU_BASE, fields, AuthorBuilder, CowUtils, EmbedBuilder, FooterBuilder,
+    MessageBuilder,
+};
+use eyre::Result;
+use rosu_v2::prelude::GameModsIntermode;
+
+use super::{graph::analysis_graph, load_replay, LoadedReplay, ReplayAnalyze, ReplaySource};
+use crate::{
+    embeds::attachment,
+    util::{interaction::InteractionCommand, replay::ReplayAnalysis, InteractionCommandExt},
+};
+
+pub(super) async fn analyze(command: InteractionCommand, args: ReplayAnalyze) -> Result<()> {
+    let Some(source) = ReplaySource::new(args.score_id, args.replay.as_ref()) else {
+        let content = "You must specify either a score id or a replay file";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    let Some(LoadedReplay { replay, map }) = load_replay(&command, source).await? else {
+        return Ok(());
+    };
+
+    let Some(analysis) = ReplayAnalysis::new(&replay, &map.pp_map) else {
+        let content = "Only osu!standard replays on osu!standard maps can be analyzed";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    let graph = match analysis_graph(&analysis, &replay.frames) {
+        Ok(graph) => Some(graph),
+        Err(err) => {
+            warn!(?err, "Failed to create replay analysis graph");
+
+            None
+        }
+    };
+
+    let mods = GameModsIntermode::from_bits(replay.mods);
+
+    let mut title = format!(
+        "{artist} - {title} [{version}]",
+        artist = map.artist().cow_escape_markdown(),
+        title = map.title().cow_escape_markdown(),
+        version = map.version().cow_escape_markdown(),
+    );
+
+    if !mods.is_empty() {
+        title.push_str(" +");
+        title.push_str(&mods.to_string());
+    }
+
+    let unstable_rate = analysis
+        .unstable_rate()
+        .map_or_else(|| "-".to_owned(), |ur| format!("{ur:.2}"));
+
+    let mean_error = analysis.mean_error().map_or_else(
+        || "-".to_owned(),
+        |mean| {
+            let timing = if mean < 0.0 { "early" } else { "late" };
+
+            format!("{:.2}ms {timing}", mean.abs())
+        },
+    );
+
+    let [great, ok, meh, miss] = analysis.counts();
+    let judgements = format!("{great} / {ok} / {meh} / {miss}");
+
+    let [great_window, ok_window, meh_window] = analysis
+        .hit_windows
+        .map(|window| window / analysis.clock_rate);
+
+    let hit_windows = format!("±{great_window:.0} / ±{ok_window:.0} / ±{meh_window:.0}ms");
+
+    let (left, right) = analysis.key_counts();
+    let total = (left + right).max(1) as f32;
+
+    let keys = format!(
+        "Left: {left} ({left_percent:.1}%)\nRight: {right} ({right_percent:.1}%)",
+        left_percent = 100.0 * left as f32 / total,
+        right_percent = 100.0 * right as f32 / total,
+    );
+
+    let tapping_bpm = analysis
+        .max_tapping_bpm()
+        .map_or_else(|| "-".to_owned(), |bpm| format!("{bpm:.0}"));
+
+    let fields = fields![
+        "Unstable rate", unstable_rate, true;
+        "Mean hit error", mean_error, true;
+        "Judgements", judgements, true;
+        "Hit windows", hit_windows, true;
+        "Keypresses", keys, true;
+        "Tapping BPM", tapping_bpm, true;
+    ];
+
+    let footer =
+        FooterBuilder::new("Sliders are judged by their head only and spinners are not judged");
+
+    let mut embed = EmbedBuilder::new()
+        .author(AuthorBuilder::new(replay.username.as_str()))
+        .fields(fields)
+        .footer(footer)
+        .title(title)
+        .url(format!("{OSU_BASE}b/{}", map.map_id()));
+
+    let mut builder = MessageBuilder::new();
+
+    if let Some(graph) = graph {
+        embed = embed.image(attachment("replay_analysis.png"));
+        builder = builder.attachment("replay_analysis.png", graph);
+    }
+
+    let builder = builder.embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
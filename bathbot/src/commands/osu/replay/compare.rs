@@ -0,0 +1,256 @@
+use std::fmt::Write;
+
+use bathbot_util::{
+    constants::OSU_BASE, fields, AuthorBuilder, CowUtils, EmbedBuilder, FooterBuilder,
+    MessageBuilder,
+};
+use eyre::Result;
+use rosu_v2::prelude::GameModsIntermode;
+
+use super::{graph::compare_graph, load_replay, LoadedReplay, ReplayCompare, ReplaySource};
+use crate::{
+    embeds::attachment,
+    util::{
+        interaction::InteractionCommand,
+        replay::{accuracy, ReplayAnalysis, Section},
+        InteractionCommandExt,
+    },
+};
+
+/// Amount of equally long sections the map is split into
+const SECTION_COUNT: usize = 8;
+
+pub(super) async fn compare(command: InteractionCommand, args: ReplayCompare) -> Result<()> {
+    let first = ReplaySource::new(args.score_id_1, args.replay_1.as_ref());
+    let second = ReplaySource::new(args.score_id_2, args.replay_2.as_ref());
+
+    let (Some(first), Some(second)) = (first, second) else {
+        let content = "You must specify a score id or a replay file for both replays";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    let Some(first) = load_replay(&command, first).await? else {
+        return Ok(());
+    };
+
+    let Some(second) = load_replay(&command, second).await? else {
+        return Ok(());
+    };
+
+    if first.map.map_id() != second.map.map_id() {
+        let content = "Both replays must be on the same map";
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    let analyses = (
+        ReplayAnalysis::new(&first.replay, &first.map.pp_map),
+        ReplayAnalysis::new(&second.replay, &second.map.pp_map),
+    );
+
+    let (Some(first_analysis), Some(second_analysis)) = analyses else {
+        let content = "Only osu!standard replays on osu!standard maps can be compared";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    let LoadedReplay { replay: first, map } = first;
+    let LoadedReplay { replay: second, .. } = second;
+
+    let hit_objects = &map.pp_map.hit_objects;
+    let start = hit_objects.first().map_or(0.0, |h| h.start_time);
+    let end = hit_objects.last().map_or(0.0, |h| h.start_time) + 1.0;
+
+    let first_sections = first_analysis.sections(start, end, SECTION_COUNT);
+    let second_sections = second_analysis.sections(start, end, SECTION_COUNT);
+
+    let first_name = first.username.as_str();
+
+    // Distinguish the names if the same player is compared with themselves
+    let second_name = if first.username == second.username {
+        format!("{} (2)", second.username)
+    } else {
+        second.username.clone()
+    };
+
+    let mut description = String::with_capacity(1024);
+    description.push_str("```\n # Start │   Acc 1   Acc 2 │  UR 1  UR 2 │ Miss │  100s\n");
+
+    for (i, (first, second)) in first_sections
+        .iter()
+        .zip(second_sections.iter())
+        .enumerate()
+    {
+        let secs = (first.start / 1000.0).max(0.0) as u32;
+
+        let _ = writeln!(
+            description,
+            "{idx:>2} {min:>2}:{sec:0>2} │ {acc1:>7} {acc2:>7} │ {ur1:>5} {ur2:>5} │ {miss:>4} │ {oks:>5}",
+            idx = i + 1,
+            min = secs / 60,
+            sec = secs % 60,
+            acc1 = format_acc(first),
+            acc2 = format_acc(second),
+            ur1 = format_ur(first),
+            ur2 = format_ur(second),
+            miss = format!("{}/{}", first.counts[3], second.counts[3]),
+            oks = format!("{}/{}", non_greats(first), non_greats(second)),
+        );
+    }
+
+    let _ = writeln!(
+        description,
+        "```1 = {first_name}, 2 = {second_name}\n",
+        first_name = first_name.cow_escape_markdown(),
+        second_name = second_name.cow_escape_markdown(),
+    );
+
+    for (name, sections) in [
+        (first_name, &first_sections),
+        (second_name.as_str(), &second_sections),
+    ] {
+        let clusters = clusters(sections);
+
+        if clusters.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(
+            description,
+            "**{name}:** {clusters}",
+            name = name.cow_escape_markdown()
+        );
+    }
+
+    let fields = fields![
+        first_name, replay_summary(first.mods, &first_analysis), true;
+        second_name.as_str(), replay_summary(second.mods, &second_analysis), true;
+    ];
+
+    let graph = match compare_graph(
+        [
+            (first_name, &first_analysis),
+            (&second_name, &second_analysis),
+        ],
+        &first_sections,
+    ) {
+        Ok(graph) => Some(graph),
+        Err(err) => {
+            warn!(?err, "Failed to create replay comparison graph");
+
+            None
+        }
+    };
+
+    let title = format!(
+        "{artist} - {title} [{version}]",
+        artist = map.artist().cow_escape_markdown(),
+        title = map.title().cow_escape_markdown(),
+        version = map.version().cow_escape_markdown(),
+    );
+
+    let author = format!("{first_name} vs {second_name}");
+
+    let footer = FooterBuilder::new(
+        "Sliders are judged by their head only • Crosses in the graph mark misses",
+    );
+
+    let mut embed = EmbedBuilder::new()
+        .author(AuthorBuilder::new(author))
+        .description(description)
+        .fields(fields)
+        .footer(footer)
+        .title(title)
+        .url(format!("{OSU_BASE}b/{}", map.map_id()));
+
+    let mut builder = MessageBuilder::new();
+
+    if let Some(graph) = graph {
+        embed = embed.image(attachment("replay_compare.png"));
+        builder = builder.attachment("replay_compare.png", graph);
+    }
+
+    let builder = builder.embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+fn replay_summary(mods: u32, analysis: &ReplayAnalysis) -> String {
+    let mods = GameModsIntermode::from_bits(mods);
+    let counts = analysis.counts();
+    let [great, ok, meh, miss] = counts;
+
+    let mut value = format!(
+        "Accuracy: {acc:.2}%\nUR: {ur}\n{great} / {ok} / {meh} / {miss}",
+        acc = accuracy(counts).unwrap_or(0.0),
+        ur = analysis
+            .unstable_rate()
+            .map_or_else(|| "-".to_owned(), |ur| format!("{ur:.2}")),
+    );
+
+    if !mods.is_empty() {
+        let _ = write!(value, "\nMods: {mods}");
+    }
+
+    value
+}
+
+/// Describes in which sections misses and 100s/50s occur the most.
+fn clusters(sections: &[Section]) -> String {
+    let mut clusters = Vec::with_capacity(2);
+
+    let misses: usize = sections.iter().map(|section| section.counts[3]).sum();
+
+    if let Some((idx, section)) = max_section(sections, |section| section.counts[3]) {
+        clusters.push(format!(
+            "{count}/{misses} misses in section {idx}",
+            count = section.counts[3],
+            idx = idx + 1,
+        ));
+    }
+
+    let oks: usize = sections.iter().map(non_greats).sum();
+
+    if let Some((idx, section)) = max_section(sections, non_greats) {
+        clusters.push(format!(
+            "{count}/{oks} 100s/50s in section {idx}",
+            count = non_greats(section),
+            idx = idx + 1,
+        ));
+    }
+
+    clusters.join(" • ")
+}
+
+/// The section with the highest non-zero count.
+fn max_section(
+    sections: &[Section],
+    count: impl Fn(&Section) -> usize,
+) -> Option<(usize, &Section)> {
+    sections
+        .iter()
+        .enumerate()
+        .filter(|(_, section)| count(section) > 0)
+        .max_by_key(|(_, section)| count(section))
+}
+
+fn non_greats(section: &Section) -> usize {
+    section.counts[1] + section.counts[2]
+}
+
+fn format_acc(section: &Section) -> String {
+    section
+        .accuracy()
+        .map_or_else(|| "-".to_owned(), |acc| format!("{acc:.2}%"))
+}
+
+fn format_ur(section: &Section) -> String {
+    section
+        .unstable_rate
+        .map_or_else(|| "-".to_owned(), |ur| format!("{ur:.1}"))
+}
@@ -0,0 +1,483 @@
+use std::{collections::HashMap, iter};
+
+use eyre::{Result, WrapErr};
+use plotters::{
+    coord::Shift,
+    prelude::{
+        ChartBuilder, Circle, Cross, DrawingArea, IntoDrawingArea, LineSeries, PathElement,
+        Rectangle, SeriesLabelPosition,
+    },
+    style::{Color, FontDesc, FontFamily, HSLColor, RGBColor, WHITE},
+};
+use plotters_backend::FontStyle;
+use plotters_skia::SkiaBackend;
+use skia_safe::{surfaces, EncodedImageFormat};
+
+use crate::util::replay::{HitResult, Miss, MissCause, ReplayAnalysis, ReplayFrame, Section};
+
+const W: u32 = 1100;
+const H: u32 = 440;
+
+const PLAYFIELD_W: f64 = 512.0;
+const PLAYFIELD_H: f64 = 384.0;
+
+/// Size of a heatmap cell in osu!pixels
+const CELL_SIZE: f64 = 8.0;
+
+/// Approximate amount of histogram buckets on each side of zero
+const BUCKETS_PER_SIDE: f64 = 25.0;
+
+const BACKGROUND: RGBColor = RGBColor(19, 43, 33);
+const GREAT_COLOR: RGBColor = RGBColor(50, 188, 231);
+const OK_COLOR: RGBColor = RGBColor(87, 227, 19);
+const MEH_COLOR: RGBColor = RGBColor(218, 174, 70);
+
+/// Draws a hit error histogram next to a cursor heatmap.
+pub fn analysis_graph(analysis: &ReplayAnalysis, frames: &[ReplayFrame]) -> Result<Vec<u8>> {
+    let mut surface =
+        surfaces::raster_n32_premul((W as i32, H as i32)).wrap_err("Failed to create surface")?;
+
+    {
+        let root = SkiaBackend::new(surface.canvas(), W, H).into_drawing_area();
+
+        root.fill(&BACKGROUND)
+            .wrap_err("Failed to fill background")?;
+
+        let (left, right) = root.split_horizontally(W * 11 / 20);
+
+        draw_histogram(&left, analysis)?;
+        draw_heatmap(&right, frames)?;
+    }
+
+    let png_bytes = surface
+        .image_snapshot()
+        .encode(None, EncodedImageFormat::PNG, None)
+        .wrap_err("Failed to encode image")?
+        .to_vec();
+
+    Ok(png_bytes)
+}
+
+fn draw_histogram(
+    area: &DrawingArea<SkiaBackend<'_>, Shift>,
+    analysis: &ReplayAnalysis,
+) -> Result<()> {
+    let [great, ok, meh] = analysis
+        .hit_windows
+        .map(|window| window / analysis.clock_rate);
+    let bucket_size = (meh / BUCKETS_PER_SIDE).ceil().max(1.0);
+
+    let mut buckets = HashMap::<i32, u32>::new();
+
+    for error in analysis.hit_errors() {
+        *buckets
+            .entry((error / bucket_size).round() as i32)
+            .or_default() += 1;
+    }
+
+    let max_count = buckets.values().copied().max().unwrap_or(0).max(1);
+
+    let caption_style = ("sans-serif", 22_i32, FontStyle::Bold, &WHITE);
+
+    let mut chart = ChartBuilder::on(area)
+        .x_label_area_size(35_i32)
+        .y_label_area_size(45_i32)
+        .margin(10_i32)
+        .caption("Hit errors", caption_style)
+        .build_cartesian_2d(-meh..meh, 0.0..max_count as f64 * 1.1)
+        .wrap_err("Failed to build histogram chart")?;
+
+    chart
+        .configure_mesh()
+        .disable_x_mesh()
+        .x_desc("Early (ms) / Late (ms)")
+        .x_label_formatter(&|ms| format!("{ms:.0}"))
+        .y_label_formatter(&|count| format!("{count:.0}"))
+        .label_style(("sans-serif", 14_i32, &WHITE))
+        .bold_line_style(WHITE.mix(0.2))
+        .light_line_style(WHITE.mix(0.0))
+        .axis_style(WHITE.mix(0.6))
+        .axis_desc_style(("sans-serif", 14_i32, FontStyle::Bold, &WHITE))
+        .draw()
+        .wrap_err("Failed to draw histogram mesh")?;
+
+    let bars = buckets.into_iter().map(|(bucket, count)| {
+        let center = bucket as f64 * bucket_size;
+        let abs = center.abs();
+
+        let color = if abs <= great {
+            GREAT_COLOR
+        } else if abs <= ok {
+            OK_COLOR
+        } else {
+            MEH_COLOR
+        };
+
+        let x0 = center - bucket_size / 2.0;
+        let x1 = center + bucket_size / 2.0;
+
+        Rectangle::new([(x0, 0.0), (x1, count as f64)], color.filled())
+    });
+
+    chart
+        .draw_series(bars)
+        .wrap_err("Failed to draw histogram bars")?;
+
+    // Mark zero
+    let zero = Rectangle::new(
+        [(-0.25, 0.0), (0.25, max_count as f64 * 1.1)],
+        WHITE.mix(0.7).filled(),
+    );
+
+    chart
+        .draw_series(iter::once(zero))
+        .wrap_err("Failed to draw zero line")?;
+
+    Ok(())
+}
+
+fn draw_heatmap(area: &DrawingArea<SkiaBackend<'_>, Shift>, frames: &[ReplayFrame]) -> Result<()> {
+    let cols = (PLAYFIELD_W / CELL_SIZE) as usize;
+    let rows = (PLAYFIELD_H / CELL_SIZE) as usize;
+
+    let mut cells = vec![0_u32; cols * rows];
+
+    for frame in frames {
+        let (x, y) = (frame.x as f64, frame.y as f64);
+
+        if !(0.0..PLAYFIELD_W).contains(&x) || !(0.0..PLAYFIELD_H).contains(&y) {
+            continue;
+        }
+
+        let col = (x / CELL_SIZE) as usize;
+        let row = (y / CELL_SIZE) as usize;
+        cells[row * cols + col] += 1;
+    }
+
+    let max_count = cells.iter().copied().max().unwrap_or(0).max(1);
+
+    let caption_style = ("sans-serif", 22_i32, FontStyle::Bold, &WHITE);
+
+    let mut chart = ChartBuilder::on(area)
+        .margin(20_i32)
+        .caption("Cursor heatmap", caption_style)
+        .build_cartesian_2d(0.0..PLAYFIELD_W, 0.0..PLAYFIELD_H)
+        .wrap_err("Failed to build heatmap chart")?;
+
+    let playfield = Rectangle::new(
+        [(0.0, 0.0), (PLAYFIELD_W, PLAYFIELD_H)],
+        RGBColor(7, 18, 14).filled(),
+    );
+
+    chart
+        .draw_series(iter::once(playfield))
+        .wrap_err("Failed to draw playfield")?;
+
+    // Logarithmic scale so that resting positions don't outshine everything
+    let max_log = (max_count as f64).ln_1p();
+
+    let rects = cells
+        .iter()
+        .enumerate()
+        .filter(|(_, count)| **count > 0)
+        .map(|(i, count)| {
+            let intensity = (*count as f64).ln_1p() / max_log;
+            let color = HSLColor(0.66 * (1.0 - intensity), 1.0, 0.2 + 0.35 * intensity);
+
+            let x = (i % cols) as f64 * CELL_SIZE;
+
+            // Playfield y goes downwards, chart y goes upwards
+            let y = PLAYFIELD_H - (i / cols) as f64 * CELL_SIZE;
+
+            Rectangle::new([(x, y - CELL_SIZE), (x + CELL_SIZE, y)], color.filled())
+        });
+
+    chart
+        .draw_series(rects)
+        .wrap_err("Failed to draw heatmap cells")?;
+
+    Ok(())
+}
+
+/// Amount of misses that are drawn
+const MISS_FRAME_COUNT: usize = 6;
+const MISS_FRAME_COLS: usize = 3;
+const MISS_FRAME_SIZE: u32 = 300;
+
+/// How many milliseconds of cursor movement are drawn before and after a
+/// miss
+const TRAIL_BEFORE: f64 = 300.0;
+const TRAIL_AFTER: f64 = 150.0;
+
+/// How many milliseconds before and after a miss hit objects are drawn
+const OBJECTS_AROUND: f64 = 800.0;
+
+const MISS_COLOR: RGBColor = RGBColor(255, 60, 60);
+const LEFT_KEY_COLOR: RGBColor = RGBColor(255, 214, 10);
+const RIGHT_KEY_COLOR: RGBColor = RGBColor(255, 102, 170);
+
+/// Draws the surroundings of the first few misses.
+///
+/// Returns `None` if there are no misses.
+pub fn miss_frames_graph(
+    analysis: &ReplayAnalysis,
+    frames: &[ReplayFrame],
+    misses: &[Miss],
+) -> Result<Option<Vec<u8>>> {
+    if misses.is_empty() {
+        return Ok(None);
+    }
+
+    let count = misses.len().min(MISS_FRAME_COUNT);
+    let cols = count.min(MISS_FRAME_COLS);
+    let rows = count.div_ceil(MISS_FRAME_COLS);
+
+    let w = cols as u32 * MISS_FRAME_SIZE;
+    let h = rows as u32 * MISS_FRAME_SIZE;
+
+    let mut surface =
+        surfaces::raster_n32_premul((w as i32, h as i32)).wrap_err("Failed to create surface")?;
+
+    {
+        let root = SkiaBackend::new(surface.canvas(), w, h).into_drawing_area();
+
+        root.fill(&BACKGROUND)
+            .wrap_err("Failed to fill background")?;
+
+        let areas = root.split_evenly((rows, cols));
+
+        for (area, miss) in areas.iter().zip(misses) {
+            draw_miss_frame(area, analysis, frames, miss)?;
+        }
+    }
+
+    let png_bytes = surface
+        .image_snapshot()
+        .encode(None, EncodedImageFormat::PNG, None)
+        .wrap_err("Failed to encode image")?
+        .to_vec();
+
+    Ok(Some(png_bytes))
+}
+
+fn draw_miss_frame(
+    area: &DrawingArea<SkiaBackend<'_>, Shift>,
+    analysis: &ReplayAnalysis,
+    frames: &[ReplayFrame],
+    miss: &Miss,
+) -> Result<()> {
+    let judgement = &analysis.judgements[miss.judgement];
+    let (cx, cy) = (judgement.pos.0 as f64, judgement.pos.1 as f64);
+
+    // Show an area of four radii around the object
+    let view = analysis.radius as f64 * 4.0;
+
+    let minutes = (miss.time / 60_000.0).floor();
+    let seconds = (miss.time - minutes * 60_000.0) / 1000.0;
+    let caption = format!("{minutes}:{seconds:0>6.3}");
+    let caption_style = ("sans-serif", 18_i32, FontStyle::Bold, &WHITE);
+
+    // Playfield y goes downwards, chart y goes upwards so y is negated
+    let mut chart = ChartBuilder::on(area)
+        .margin(8_i32)
+        .caption(caption, caption_style)
+        .build_cartesian_2d(cx - view..cx + view, -cy - view..-cy + view)
+        .wrap_err("Failed to build miss chart")?;
+
+    let (x_range, _) = chart.plotting_area().get_pixel_range();
+    let px_per_unit = (x_range.end - x_range.start) as f64 / (2.0 * view);
+    let radius_px = (analysis.radius as f64 * px_per_unit) as i32;
+
+    let contains = |x: f64, y: f64| (x - cx).abs() <= view && (y - cy).abs() <= view;
+
+    let objects = analysis
+        .judgements
+        .iter()
+        .filter(|other| (other.time - miss.time).abs() <= OBJECTS_AROUND)
+        .filter(|other| contains(other.pos.0 as f64, other.pos.1 as f64))
+        .map(|other| {
+            let color = if other.idx == judgement.idx {
+                MISS_COLOR.stroke_width(3)
+            } else {
+                WHITE.mix(0.4).stroke_width(2)
+            };
+
+            let pos = (other.pos.0 as f64, -other.pos.1 as f64);
+
+            Circle::new(pos, radius_px, color)
+        });
+
+    chart
+        .draw_series(objects)
+        .wrap_err("Failed to draw objects")?;
+
+    let trail = frames
+        .iter()
+        .skip_while(|frame| frame.time < miss.time - TRAIL_BEFORE)
+        .take_while(|frame| frame.time <= miss.time + TRAIL_AFTER)
+        .map(|frame| (frame.x as f64, frame.y as f64))
+        .filter(|(x, y)| contains(*x, *y))
+        .map(|(x, y)| (x, -y));
+
+    chart
+        .draw_series(LineSeries::new(trail, WHITE.mix(0.8).stroke_width(2)))
+        .wrap_err("Failed to draw cursor trail")?;
+
+    let presses = analysis
+        .presses
+        .iter()
+        .filter(|press| {
+            press.time >= miss.time - TRAIL_BEFORE && press.time <= miss.time + TRAIL_AFTER
+        })
+        .filter(|press| contains(press.x as f64, press.y as f64))
+        .map(|press| {
+            let color = if press.left {
+                LEFT_KEY_COLOR
+            } else {
+                RIGHT_KEY_COLOR
+            };
+
+            Circle::new((press.x as f64, -press.y as f64), 5_i32, color.filled())
+        });
+
+    chart
+        .draw_series(presses)
+        .wrap_err("Failed to draw keypresses")?;
+
+    let label = match miss.cause {
+        MissCause::Aim { distance } => format!("Aim: {distance:.0}px off"),
+        MissCause::Early { error } => format!("Early: {:.0}ms", error.abs()),
+        MissCause::Late { error } => format!("Late: {error:.0}ms"),
+        MissCause::SliderBreak => "Slider break".to_owned(),
+        MissCause::Notelock => "Notelock".to_owned(),
+        MissCause::NoPress => "No keypress".to_owned(),
+    };
+
+    let label_style = FontDesc::new(FontFamily::SansSerif, 16.0, FontStyle::Bold).color(&WHITE);
+
+    area.draw_text(&label, &label_style, (10, MISS_FRAME_SIZE as i32 - 26))
+        .wrap_err("Failed to draw miss label")?;
+
+    Ok(())
+}
+
+const FIRST_COLOR: RGBColor = RGBColor(50, 188, 231);
+const SECOND_COLOR: RGBColor = RGBColor(255, 102, 170);
+
+/// Draws the cumulative accuracy of both replays over map time.
+///
+/// Section boundaries are marked by vertical lines and misses by crosses.
+pub fn compare_graph(
+    replays: [(&str, &ReplayAnalysis); 2],
+    sections: &[Section],
+) -> Result<Vec<u8>> {
+    let accuracies = replays.map(|(_, analysis)| analysis.cumulative_accuracy());
+
+    let start = sections.first().map_or(0.0, |section| section.start);
+    let end = sections.last().map_or(1.0, |section| section.end);
+
+    // Skip the first few judgements so the start doesn't stretch the y-axis
+    let min_acc = accuracies
+        .iter()
+        .flat_map(|accuracy| accuracy.iter().skip(10))
+        .map(|(_, acc)| *acc)
+        .min_by(f64::total_cmp)
+        .unwrap_or(90.0)
+        .clamp(0.0, 99.0);
+
+    let min_acc = (min_acc - 0.5).floor().max(0.0);
+
+    let mut surface =
+        surfaces::raster_n32_premul((W as i32, H as i32)).wrap_err("Failed to create surface")?;
+
+    {
+        let root = SkiaBackend::new(surface.canvas(), W, H).into_drawing_area();
+
+        root.fill(&BACKGROUND)
+            .wrap_err("Failed to fill background")?;
+
+        let caption_style = ("sans-serif", 22_i32, FontStyle::Bold, &WHITE);
+
+        let mut chart = ChartBuilder::on(&root)
+            .x_label_area_size(35_i32)
+            .y_label_area_size(55_i32)
+            .margin(10_i32)
+            .caption("Cumulative accuracy", caption_style)
+            .build_cartesian_2d(start..end, min_acc..100.0)
+            .wrap_err("Failed to build accuracy chart")?;
+
+        chart
+            .configure_mesh()
+            .disable_x_mesh()
+            .x_label_formatter(&|ms| {
+                let secs = (*ms / 1000.0).max(0.0) as u32;
+
+                format!("{}:{:0>2}", secs / 60, secs % 60)
+            })
+            .y_label_formatter(&|acc| format!("{acc:.1}%"))
+            .label_style(("sans-serif", 14_i32, &WHITE))
+            .bold_line_style(WHITE.mix(0.2))
+            .light_line_style(WHITE.mix(0.0))
+            .axis_style(WHITE.mix(0.6))
+            .draw()
+            .wrap_err("Failed to draw accuracy mesh")?;
+
+        let boundaries = sections.iter().skip(1).map(|section| {
+            let x = section.start;
+
+            PathElement::new(
+                vec![(x, min_acc), (x, 100.0)],
+                WHITE.mix(0.3).stroke_width(1),
+            )
+        });
+
+        chart
+            .draw_series(boundaries)
+            .wrap_err("Failed to draw section boundaries")?;
+
+        let colors = [FIRST_COLOR, SECOND_COLOR];
+
+        for (((name, analysis), accuracy), color) in replays.iter().zip(&accuracies).zip(colors) {
+            let line = accuracy.iter().map(|(time, acc)| (*time, acc.max(min_acc)));
+
+            chart
+                .draw_series(LineSeries::new(line, color.stroke_width(2)))
+                .wrap_err("Failed to draw accuracy line")?
+                .label(*name)
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 20, y)], color.stroke_width(2))
+                });
+
+            let misses = analysis
+                .judgements
+                .iter()
+                .zip(accuracy)
+                .filter(|(judgement, _)| judgement.result == HitResult::Miss)
+                .map(|(_, (time, acc))| {
+                    Cross::new((*time, acc.max(min_acc)), 4_i32, color.stroke_width(2))
+                });
+
+            chart
+                .draw_series(misses)
+                .wrap_err("Failed to draw misses")?;
+        }
+
+        chart
+            .configure_series_labels()
+            .border_style(WHITE.mix(0.6).stroke_width(1))
+            .background_style(RGBColor(7, 23, 17))
+            .position(SeriesLabelPosition::LowerLeft)
+            .legend_area_size(25_i32)
+            .label_font(("sans-serif", 16_i32, FontStyle::Bold, &WHITE))
+            .draw()
+            .wrap_err("Failed to draw legend")?;
+    }
+
+    let png_bytes = surface
+        .image_snapshot()
+        .encode(None, EncodedImageFormat::PNG, None)
+        .wrap_err("Failed to encode image")?
+        .to_vec();
+
+    Ok(png_bytes)
+}
@@ -0,0 +1,139 @@
+use std::fmt::Write;
+
+use bathbot_util::{
+    constants::OSU_BASE, AuthorBuilder, CowUtils, EmbedBuilder, FooterBuilder, MessageBuilder,
+};
+use eyre::Result;
+
+use super::{graph::miss_frames_graph, load_replay, LoadedReplay, ReplayMisses, ReplaySource};
+use crate::{
+    embeds::attachment,
+    manager::OsuMap,
+    util::{
+        interaction::InteractionCommand,
+        replay::{MissCause, Replay as OsuReplay, ReplayAnalysis},
+        InteractionCommandExt,
+    },
+};
+
+/// Maximum amount of listed misses
+const MISS_LIST_LEN: usize = 15;
+
+pub(super) async fn misses(command: InteractionCommand, args: ReplayMisses) -> Result<()> {
+    let Some(source) = ReplaySource::new(args.score_id, args.replay.as_ref()) else {
+        let content = "You must specify either a score id or a replay file";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    let Some(LoadedReplay { replay, map }) = load_replay(&command, source).await? else {
+        return Ok(());
+    };
+
+    let Some(builder) = miss_analysis_message(&replay, &map) else {
+        let content = "Only osu!standard replays on osu!standard maps can be analyzed";
+        command.error(content).await?;
+
+        return Ok(());
+    };
+
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+/// Classifies the misses of the replay and lists them alongside an image of
+/// the first few misses.
+///
+/// Returns `None` if the replay or map is not osu!standard.
+pub fn miss_analysis_message(replay: &OsuReplay, map: &OsuMap) -> Option<MessageBuilder<'static>> {
+    let analysis = ReplayAnalysis::new(replay, &map.pp_map)?;
+    let misses = analysis.misses(&replay.frames, &map.pp_map);
+
+    let mut description = String::with_capacity(512);
+
+    let mut counts = [0; 5];
+
+    for miss in misses.iter() {
+        let idx = match miss.cause {
+            MissCause::Aim { .. } => 0,
+            MissCause::Early { .. } | MissCause::Late { .. } => 1,
+            MissCause::SliderBreak => 2,
+            MissCause::Notelock => 3,
+            MissCause::NoPress => 4,
+        };
+
+        counts[idx] += 1;
+    }
+
+    if misses.is_empty() {
+        description.push_str("No misses or slider breaks, nice :)");
+    } else {
+        let [aim, timing, slider_breaks, notelock, no_press] = counts;
+
+        let _ = writeln!(
+            description,
+            "**Aim:** {aim} • **Timing:** {timing} • **Slider breaks:** {slider_breaks} • \
+            **Notelock:** {notelock} • **No keypress:** {no_press}\n"
+        );
+
+        for miss in misses.iter().take(MISS_LIST_LEN) {
+            let minutes = (miss.time / 60_000.0).floor();
+            let seconds = (miss.time - minutes * 60_000.0) / 1000.0;
+
+            let cause = match miss.cause {
+                MissCause::Aim { distance } => format!("Aimed {distance:.0}px off"),
+                MissCause::Early { error } => format!("Pressed {:.0}ms early", error.abs()),
+                MissCause::Late { error } => format!("Pressed {error:.0}ms late"),
+                MissCause::SliderBreak => "Slider break".to_owned(),
+                MissCause::Notelock => "Notelock".to_owned(),
+                MissCause::NoPress => "No keypress".to_owned(),
+            };
+
+            let _ = writeln!(description, "`{minutes}:{seconds:0>6.3}` {cause}");
+        }
+
+        if misses.len() > MISS_LIST_LEN {
+            let _ = write!(description, "... and {} more", misses.len() - MISS_LIST_LEN);
+        }
+    }
+
+    let graph = match miss_frames_graph(&analysis, &replay.frames, &misses) {
+        Ok(graph) => graph,
+        Err(err) => {
+            warn!(?err, "Failed to create miss frames graph");
+
+            None
+        }
+    };
+
+    let title = format!(
+        "{artist} - {title} [{version}]",
+        artist = map.artist().cow_escape_markdown(),
+        title = map.title().cow_escape_markdown(),
+        version = map.version().cow_escape_markdown(),
+    );
+
+    let footer =
+        FooterBuilder::new("Cursor trail is white, keypresses are yellow (left) and pink (right)");
+
+    let mut embed = EmbedBuilder::new()
+        .author(AuthorBuilder::new(format!(
+            "Miss analysis for {}",
+            replay.username
+        )))
+        .description(description)
+        .footer(footer)
+        .title(title)
+        .url(format!("{OSU_BASE}b/{}", map.map_id()));
+
+    let mut builder = MessageBuilder::new();
+
+    if let Some(graph) = graph {
+        embed = embed.image(attachment("misses.png"));
+        builder = builder.attachment("misses.png", graph);
+    }
+
+    Some(builder.embed(embed))
+}
@@ -0,0 +1,270 @@
+use bathbot_macros::SlashCommand;
+use bathbot_util::constants::{GENERAL_ISSUE, OSU_API_ISSUE};
+use eyre::{Report, Result};
+use rosu_v2::prelude::{GameMode, OsuError};
+use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_model::channel::Attachment;
+
+pub use self::misses::miss_analysis_message;
+use self::{analyze::analyze, compare::compare, misses::misses};
+use crate::{
+    core::Context,
+    manager::{MapError, OsuMap, ReplayScore},
+    util::{interaction::InteractionCommand, replay::Replay as OsuReplay, InteractionCommandExt},
+};
+
+mod analyze;
+mod compare;
+mod graph;
+mod misses;
+
+/// Maximum size of attached replays; regular replays are far smaller
+const MAX_REPLAY_SIZE: u64 = 4 * 1024 * 1024;
+
+#[derive(CommandModel, CreateCommand, SlashCommand)]
+#[command(
+    name = "replay",
+    desc = "Analyze osu!standard replays",
+    help = "Analyze osu!standard replays.\n\
+    Replays can be specified either through a score id, in which case the replay \
+    will be downloaded, or by attaching a `.osr` file.\n\
+    Everything is calculated by the bot itself so results might slightly differ from \
+    what osu! shows."
+)]
+pub enum Replay {
+    #[command(name = "analyze")]
+    Analyze(ReplayAnalyze),
+    #[command(name = "misses")]
+    Misses(ReplayMisses),
+    #[command(name = "compare")]
+    Compare(ReplayCompare),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "analyze",
+    desc = "Show unstable rate, hit errors, and a cursor heatmap of a replay",
+    help = "Show unstable rate, hit errors, and a cursor heatmap of a replay.\n\
+    Also shows the keypress ratio and the tapping BPM i.e. the BPM of 1/4 notes \
+    for the fastest ten consecutive keypresses."
+)]
+pub struct ReplayAnalyze {
+    #[command(desc = "Specify the score through its id")]
+    score_id: Option<u64>,
+    #[command(desc = "Specify the replay through a .osr file")]
+    replay: Option<Attachment>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "misses",
+    desc = "Find out why you missed or broke combo in a replay",
+    help = "Find out why you missed or broke combo in a replay.\n\
+    Each miss is classified as either aim, timing, notelock, or no keypress at all. \
+    Slider breaks are only detected when releasing all keys while a slider tick or repeat passes.\n\
+    The first six misses are shown in an image with the cursor trail and keypresses around them."
+)]
+pub struct ReplayMisses {
+    #[command(desc = "Specify the score through its id")]
+    score_id: Option<u64>,
+    #[command(desc = "Specify the replay through a .osr file")]
+    replay: Option<Attachment>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "compare",
+    desc = "Compare two replays on the same map",
+    help = "Compare two replays on the same map.\n\
+    The map is split into eight equally long sections and for each section the accuracy, \
+    unstable rate, misses, and 100s/50s of both replays are shown.\n\
+    The image shows the accuracy of both replays over the course of the map."
+)]
+pub struct ReplayCompare {
+    #[command(desc = "Specify the first score through its id")]
+    score_id_1: Option<u64>,
+    #[command(desc = "Specify the first replay through a .osr file")]
+    replay_1: Option<Attachment>,
+    #[command(desc = "Specify the second score through its id")]
+    score_id_2: Option<u64>,
+    #[command(desc = "Specify the second replay through a .osr file")]
+    replay_2: Option<Attachment>,
+}
+
+async fn slash_replay(mut command: InteractionCommand) -> Result<()> {
+    match Replay::from_interaction(command.input_data())? {
+        Replay::Analyze(args) => analyze(command, args).await,
+        Replay::Misses(args) => misses(command, args).await,
+        Replay::Compare(args) => compare(command, args).await,
+    }
+}
+
+#[derive(Copy, Clone)]
+pub enum ReplaySource<'a> {
+    ScoreId(u64),
+    Attachment(&'a Attachment),
+}
+
+impl<'a> ReplaySource<'a> {
+    /// Prefers the attachment if both are specified.
+    pub fn new(score_id: Option<u64>, attachment: Option<&'a Attachment>) -> Option<Self> {
+        attachment
+            .map(Self::Attachment)
+            .or(score_id.map(Self::ScoreId))
+    }
+}
+
+pub struct LoadedReplay {
+    pub replay: OsuReplay,
+    pub map: OsuMap,
+}
+
+/// Retrieves and parses the replay and its map.
+///
+/// Responds with an error and returns `None` if something went wrong.
+pub async fn load_replay(
+    command: &InteractionCommand,
+    source: ReplaySource<'_>,
+) -> Result<Option<LoadedReplay>> {
+    let (bytes, map_id) = match source {
+        ReplaySource::ScoreId(score_id) => {
+            let score = match Context::osu().score(score_id).mode(GameMode::Osu).await {
+                Ok(score) => score,
+                Err(OsuError::NotFound) => {
+                    let content = "Found no osu!standard score with that id";
+                    command.error(content).await?;
+
+                    return Ok(None);
+                }
+                Err(err) => {
+                    let _ = command.error(OSU_API_ISSUE).await;
+
+                    return Err(Report::new(err).wrap_err("Failed to get score"));
+                }
+            };
+
+            let Some(replay_score) = ReplayScore::try_from_score(&score) else {
+                let content = "Replays of scores on osu!lazer currently cannot be analyzed :(";
+                command.error(content).await?;
+
+                return Ok(None);
+            };
+
+            let username = score
+                .user
+                .as_ref()
+                .map(|user| user.username.as_str())
+                .unwrap_or_default();
+
+            match Context::replay().get_replay(&replay_score, username).await {
+                Ok(Some(replay)) => (replay.into_vec(), Some(score.map_id)),
+                Ok(None) => {
+                    let content = "Looks like the replay for that score is not available";
+                    command.error(content).await?;
+
+                    return Ok(None);
+                }
+                Err(err) => {
+                    let _ = command.error(GENERAL_ISSUE).await;
+
+                    return Err(err.wrap_err("Failed to get replay"));
+                }
+            }
+        }
+        ReplaySource::Attachment(attachment) => {
+            if !attachment.filename.ends_with(".osr") {
+                let content = "The attached replay must be a .osr file";
+                command.error(content).await?;
+
+                return Ok(None);
+            }
+
+            if attachment.size > MAX_REPLAY_SIZE {
+                let content = "The attached replay is too large, it must be at most 4MB";
+                command.error(content).await?;
+
+                return Ok(None);
+            }
+
+            match Context::client().get_discord_attachment(attachment).await {
+                Ok(bytes) => (bytes.to_vec(), None),
+                Err(err) => {
+                    let _ = command.error(GENERAL_ISSUE).await;
+
+                    return Err(err.wrap_err("Failed to download replay attachment"));
+                }
+            }
+        }
+    };
+
+    let replay = match OsuReplay::parse(&bytes) {
+        Ok(replay) => replay,
+        Err(err) => {
+            debug!(?err, "Failed to parse replay");
+
+            let content = "Failed to parse the replay, be sure it's a valid `.osr` file";
+            command.error(content).await?;
+
+            return Ok(None);
+        }
+    };
+
+    if replay.mode != GameMode::Osu {
+        let content = "Only osu!standard replays can be analyzed";
+        command.error(content).await?;
+
+        return Ok(None);
+    }
+
+    let map_id = match map_id {
+        Some(map_id) => map_id,
+        None => match map_id_by_checksum(&replay.map_checksum).await {
+            Ok(Some(map_id)) => map_id,
+            Ok(None) => {
+                let content = "Could not find the map of the replay";
+                command.error(content).await?;
+
+                return Ok(None);
+            }
+            Err(err) => {
+                let _ = command.error(OSU_API_ISSUE).await;
+
+                return Err(err);
+            }
+        },
+    };
+
+    let checksum = Some(replay.map_checksum.as_str()).filter(|checksum| !checksum.is_empty());
+
+    let map = match Context::osu_map().map(map_id, checksum).await {
+        Ok(map) => map,
+        Err(MapError::NotFound) => {
+            let content = format!("Could not find the map with id `{map_id}`");
+            command.error(content).await?;
+
+            return Ok(None);
+        }
+        Err(MapError::Report(err)) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    Ok(Some(LoadedReplay { replay, map }))
+}
+
+async fn map_id_by_checksum(checksum: &str) -> Result<Option<u32>> {
+    let checksums = [checksum.to_owned()];
+    let stored = Context::osu_map().map_ids_by_checksums(&checksums).await?;
+
+    if let Some((_, map_id)) = stored.first() {
+        return Ok(Some(*map_id));
+    }
+
+    match Context::osu().beatmap().checksum(checksum).await {
+        Ok(map) => Ok(Some(map.map_id)),
+        Err(OsuError::NotFound) => Ok(None),
+        Err(err) => Err(Report::new(err).wrap_err("Failed to get map by checksum")),
+    }
+}
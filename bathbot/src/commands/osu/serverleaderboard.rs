@@ -23,8 +23,8 @@ use crate::{
     Whenever any command is used that requests an osu! user, the retrieved user will be cached.\n\
     The leaderboards will contain all members of this server that are linked to an osu! username \
     which was cached through some command beforehand.\n\
-    Since only the cached data is used, no values are guaranteed to be up-to-date. \
-    They're just snapshots from the last time the user was retrieved through a command.\n\n\
+    After a leaderboard has been shown, cached stats of this server's members will be refreshed \
+    in the background over time so values might lag behind slightly.\n\n\
     There are three reasons why a user might be missing from the leaderboard:\n\
     - They are not linked through the `/link` command\n\
     - Their osu! user stats have not been cached yet. \
@@ -156,6 +156,10 @@ async fn slash_serverleaderboard(mut command: InteractionCommand) -> Result<()>
         }
     };
 
+    Context::leaderboard_refresh()
+        .leaderboard_shown(guild_id)
+        .await;
+
     let guild_icon = cache
         .guild(guild_id)
         .await
@@ -0,0 +1,543 @@
+use std::{borrow::Cow, fmt::Write};
+
+use bathbot_macros::{HasName, SlashCommand};
+use bathbot_model::command_fields::GameModeOption;
+use bathbot_psql::model::configs::ScoreData;
+use bathbot_util::{
+    constants::{GENERAL_ISSUE, OSU_API_ISSUE},
+    matcher, CowUtils,
+};
+use eyre::{Report, Result};
+use rosu_v2::{
+    prelude::{GameMode, OsuError},
+    request::UserId,
+};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
+use twilight_model::id::{marker::UserMarker, Id};
+
+use super::{args::SimulateArg, current_version, SimulateArgs};
+use crate::{
+    active::{
+        impls::{SimulateBatchPagination, SimulateMap},
+        ActiveMessages,
+    },
+    commands::osu::{require_link, user_not_found},
+    core::{commands::CommandOrigin, Context},
+    manager::{redis::osu::UserArgs, MapError, OsuMap},
+    util::{interaction::InteractionCommand, InteractionCommandExt},
+};
+
+/// Maximum amount of maps a score is simulated on
+const MAX_MAPS: usize = 100;
+
+#[derive(CommandModel, CreateCommand, HasName, SlashCommand)]
+#[command(
+    name = "simulatebatch",
+    desc = "Simulate a score on many maps at once",
+    help = "Simulate a score on many maps at once.\n\
+    The maps are either a user's top 100, your bookmarks, or a list of map ids.\n\
+    Maps for which the given mods are invalid are skipped."
+)]
+pub struct SimulateBatch<'a> {
+    #[command(
+        desc = "Specify the score e.g. `acc=98 mods=HDDT miss=0`",
+        help = "Specify the score through arguments separated by spaces.\n\
+        They're the same as for the prefix version of `simulate`:\n\
+        - Accuracy: `acc=[number]` or `[number]%`\n\
+        - Combo: `combo=[integer]` or `[integer]x`\n\
+        - Clock rate: `clockrate=[number]` or `[number]*` or `rate=[number]`\n\
+        - Bpm: `bpm=[number]` (only if clock rate is not specified)\n\
+        - n300: `n300=[integer]` or `[integer]x300`\n\
+        - n100: `n100=[integer]` or `[integer]x100`\n\
+        - n50: `n50=[integer]` or `[integer]x50`\n\
+        - misses: `miss=[integer]` or `[integer]m`\n\
+        - gekis (n320): `gekis=[integer]` or `[integer]xgeki`\n\
+        - katus (n200 / tiny droplet misses): `katus=[integer]` or `[integer]xkatu`\n\
+        - mods: `mods=[mod acronym]` or `+[mod acronym]`\n\
+        - ar, cs, hp, od: e.g. `ar=[number]` or `ar[number]`"
+    )]
+    args: Cow<'a, str>,
+    #[command(
+        desc = "Choose on which maps the score should be simulated",
+        help = "Choose on which maps the score should be simulated.\n\
+        Defaults to the list of map ids if the `maps` option is specified, \
+        and to the top 100 otherwise."
+    )]
+    source: Option<SimulateBatchSource>,
+    #[command(desc = "Specify map urls or map ids separated by spaces or commas")]
+    maps: Option<Cow<'a, str>>,
+    #[command(desc = "Specify one of your bookmark folders")]
+    folder: Option<Cow<'a, str>>,
+    #[command(desc = "Specify a gamemode")]
+    mode: Option<GameModeOption>,
+    #[command(desc = "Specify a username")]
+    name: Option<Cow<'a, str>>,
+    #[command(
+        desc = "Choose how the maps should be ordered",
+        help = "Choose how the maps should be ordered, defaults to `pp`."
+    )]
+    sort: Option<SimulateBatchOrder>,
+    #[command(
+        desc = "Specify a linked discord user",
+        help = "Instead of specifying an osu! username with the `name` option, \
+        you can use this option to choose a discord user.\n\
+        Only works on users who have used the `/link` command."
+    )]
+    discord: Option<Id<UserMarker>>,
+}
+
+#[derive(Copy, Clone, CommandOption, CreateOption, Eq, PartialEq)]
+pub enum SimulateBatchSource {
+    #[option(name = "Top 100", value = "top")]
+    Top,
+    #[option(name = "Bookmarks", value = "bookmarks")]
+    Bookmarks,
+    #[option(name = "Map ids", value = "maps")]
+    Maps,
+}
+
+#[derive(Copy, Clone, Default, CommandOption, CreateOption, Eq, PartialEq)]
+pub enum SimulateBatchOrder {
+    #[default]
+    #[option(name = "PP", value = "pp")]
+    Pp,
+    #[option(name = "Stars", value = "stars")]
+    Stars,
+    #[option(name = "Original order", value = "original")]
+    Original,
+}
+
+async fn slash_simulatebatch(mut command: InteractionCommand) -> Result<()> {
+    let args = SimulateBatch::from_interaction(command.input_data())?;
+
+    simulate_batch((&mut command).into(), args).await
+}
+
+async fn simulate_batch(orig: CommandOrigin<'_>, args: SimulateBatch<'_>) -> Result<()> {
+    let mut simulate = SimulateArgs::default();
+
+    for arg in args.args.split_whitespace() {
+        match SimulateArg::parse(arg) {
+            Ok(arg) => simulate.apply(arg),
+            Err(err) => return orig.error(err.into_str()).await,
+        }
+    }
+
+    let owner = orig.user_id()?;
+    let mode = args.mode.map(GameMode::from);
+
+    let source = args.source.unwrap_or(if args.maps.is_some() {
+        SimulateBatchSource::Maps
+    } else {
+        SimulateBatchSource::Top
+    });
+
+    let batch = match source {
+        SimulateBatchSource::Top => {
+            let config = Context::user_config().with_osu_id(owner).await?;
+
+            let user_id = match user_id!(orig, args) {
+                Some(user_id) => user_id,
+                None => match config.osu {
+                    Some(user_id) => UserId::Id(user_id),
+                    None => return require_link(&orig).await,
+                },
+            };
+
+            let mode = mode.or(config.mode).unwrap_or(GameMode::Osu);
+
+            let legacy_scores = match config.score_data {
+                Some(score_data) => score_data.is_legacy(),
+                None => match orig.guild_id() {
+                    Some(guild_id) => Context::guild_config()
+                        .peek(guild_id, |config| config.score_data)
+                        .await
+                        .map_or(false, ScoreData::is_legacy),
+                    None => false,
+                },
+            };
+
+            top_maps(&orig, user_id, mode, legacy_scores).await?
+        }
+        SimulateBatchSource::Bookmarks => {
+            bookmark_maps(&orig, owner, args.folder.as_deref(), mode).await?
+        }
+        SimulateBatchSource::Maps => match args.maps.as_deref() {
+            Some(maps) => listed_maps(&orig, maps, mode).await?,
+            None => {
+                let content = "You must specify map ids through the `maps` option";
+
+                return orig.error(content).await;
+            }
+        },
+    };
+
+    let Some(BatchMaps {
+        title,
+        maps,
+        mut skipped,
+    }) = batch
+    else {
+        return Ok(());
+    };
+
+    // Maps that were not found or could not be loaded
+    let missing = skipped;
+    let mut entries = Vec::with_capacity(maps.len());
+
+    for BatchMap { idx, map, score_pp } in maps {
+        let mode = map.mode();
+
+        let mods = match simulate.mods.clone().map(|mods| mods.try_with_mode(mode)) {
+            Some(mods @ Some(_)) => mods,
+            None => None,
+            Some(None) => {
+                skipped += 1;
+
+                continue;
+            }
+        };
+
+        let max_combo = Context::pp(&map).difficulty().await.max_combo();
+        let mut data = simulate.data(mods, current_version(mode), max_combo);
+
+        let map_id = map.map_id();
+        let artist = Box::from(map.artist());
+        let map_title = Box::from(map.title());
+        let version = Box::from(map.version());
+
+        // Each map gets its own data because simulating turns a bpm into a
+        // clock rate for that specific map
+        let mut map = SimulateMap::Full(map);
+        map.apply_attrs(data.attrs);
+        let (stars, pp) = data.stars_and_pp(&map);
+
+        entries.push(SimulateBatchEntry {
+            original_idx: idx,
+            map_id,
+            artist,
+            title: map_title,
+            version,
+            stars,
+            pp,
+            score_pp,
+        });
+    }
+
+    if entries.is_empty() {
+        let content = if skipped == missing {
+            "None of the maps could be found"
+        } else if missing > 0 {
+            "None of the maps could be found or they don't support the mods"
+        } else {
+            "The mods are invalid for all of the maps' modes"
+        };
+
+        return orig.error(content).await;
+    }
+
+    let sort = args.sort.unwrap_or_default();
+
+    match sort {
+        SimulateBatchOrder::Pp => entries.sort_unstable_by(|a, b| b.pp.total_cmp(&a.pp)),
+        SimulateBatchOrder::Stars => entries.sort_unstable_by(|a, b| {
+            b.stars
+                .total_cmp(&a.stars)
+                .then_with(|| b.pp.total_cmp(&a.pp))
+        }),
+        SimulateBatchOrder::Original => entries.sort_unstable_by_key(|entry| entry.original_idx),
+    }
+
+    let mut content = format!("Simulating `{}`", args.args.trim());
+
+    if skipped > 0 {
+        let _ = write!(
+            content,
+            " • Skipped {skipped} map{plural} that could not be found or don't support the mods",
+            plural = if skipped == 1 { "" } else { "s" },
+        );
+    }
+
+    let pagination = SimulateBatchPagination::builder()
+        .entries(entries.into_boxed_slice())
+        .title(title.into_boxed_str())
+        .content(content.into_boxed_str())
+        .sort(sort)
+        .msg_owner(owner)
+        .build();
+
+    ActiveMessages::builder(pagination)
+        .start_by_update(true)
+        .begin(orig)
+        .await
+}
+
+pub struct SimulateBatchEntry {
+    /// Position within the top scores, bookmarks, or map list
+    pub original_idx: usize,
+    pub map_id: u32,
+    pub artist: Box<str>,
+    pub title: Box<str>,
+    pub version: Box<str>,
+    pub stars: f32,
+    pub pp: f32,
+    /// The pp of the actual top score on the map
+    pub score_pp: Option<f32>,
+}
+
+struct BatchMaps {
+    title: String,
+    maps: Vec<BatchMap>,
+    skipped: usize,
+}
+
+struct BatchMap {
+    idx: usize,
+    map: OsuMap,
+    score_pp: Option<f32>,
+}
+
+async fn top_maps(
+    orig: &CommandOrigin<'_>,
+    user_id: UserId,
+    mode: GameMode,
+    legacy_scores: bool,
+) -> Result<Option<BatchMaps>> {
+    let user_args = UserArgs::rosu_id(&user_id, mode).await;
+    let scores_fut = Context::osu_scores()
+        .top(legacy_scores)
+        .limit(100)
+        .exec_with_user(user_args);
+
+    let (user, scores) = match scores_fut.await {
+        Ok((user, scores)) => (user, scores),
+        Err(OsuError::NotFound) => {
+            let content = user_not_found(user_id).await;
+
+            return orig.error(content).await.map(|_| None);
+        }
+        Err(err) => {
+            let _ = orig.error(OSU_API_ISSUE).await;
+            let err = Report::new(err).wrap_err("Failed to get user or scores");
+
+            return Err(err);
+        }
+    };
+
+    if scores.is_empty() {
+        let content = format!("`{}` has no top scores", user.username());
+
+        return orig.error(content).await.map(|_| None);
+    }
+
+    let maps_id_checksum = scores
+        .iter()
+        .map(|score| {
+            (
+                score.map_id as i32,
+                score.map.as_ref().and_then(|map| map.checksum.as_deref()),
+            )
+        })
+        .collect();
+
+    let mut maps = match Context::osu_map().maps(&maps_id_checksum).await {
+        Ok(maps) => maps,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let mut skipped = 0;
+
+    let maps = scores
+        .iter()
+        .zip(1..)
+        .filter_map(|(score, idx)| {
+            let Some(map) = maps.remove(&score.map_id) else {
+                skipped += 1;
+
+                return None;
+            };
+
+            Some(BatchMap {
+                idx,
+                map: map.convert(score.mode),
+                score_pp: score.pp,
+            })
+        })
+        .collect();
+
+    Ok(Some(BatchMaps {
+        title: format!("Top 100 of {}", user.username().cow_escape_markdown()),
+        maps,
+        skipped,
+    }))
+}
+
+async fn bookmark_maps(
+    orig: &CommandOrigin<'_>,
+    owner: Id<UserMarker>,
+    folder: Option<&str>,
+    mode: Option<GameMode>,
+) -> Result<Option<BatchMaps>> {
+    let folder = match folder {
+        Some(name) => {
+            let name = name.trim().cow_to_ascii_lowercase();
+
+            match Context::bookmarks().folder(owner, &name).await {
+                Ok(Some(folder)) => Some(folder),
+                Ok(None) => {
+                    let content = format!(
+                        "You have no bookmark folder named `{name}`.\n\
+                        Use `/bookmarkfolder list` to see all of your folders."
+                    );
+
+                    return orig.error(content).await.map(|_| None);
+                }
+                Err(err) => {
+                    let _ = orig.error(GENERAL_ISSUE).await;
+
+                    return Err(err);
+                }
+            }
+        }
+        None => None,
+    };
+
+    let folder_id = folder.as_ref().map(|folder| folder.folder_id);
+
+    let mut bookmarks = match Context::bookmarks().get(owner, folder_id).await {
+        Ok(bookmarks) => bookmarks,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    if bookmarks.is_empty() {
+        let content = match folder {
+            Some(ref folder) => format!("The bookmark folder `{}` is empty", folder.name),
+            None => "You have no bookmarked maps".to_owned(),
+        };
+
+        return orig.error(content).await.map(|_| None);
+    }
+
+    let mut skipped = bookmarks.len().saturating_sub(MAX_MAPS);
+    bookmarks.truncate(MAX_MAPS);
+
+    let maps_id_checksum = bookmarks
+        .iter()
+        .map(|bookmark| (bookmark.map_id as i32, Some(bookmark.checksum.as_ref())))
+        .collect();
+
+    let mut maps = match Context::osu_map().maps(&maps_id_checksum).await {
+        Ok(maps) => maps,
+        Err(err) => {
+            let _ = orig.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let maps = bookmarks
+        .iter()
+        .zip(1..)
+        .filter_map(|(bookmark, idx)| {
+            let Some(map) = maps.remove(&bookmark.map_id) else {
+                skipped += 1;
+
+                return None;
+            };
+
+            Some(BatchMap {
+                idx,
+                map: match mode {
+                    Some(mode) => map.convert(mode),
+                    None => map,
+                },
+                score_pp: None,
+            })
+        })
+        .collect();
+
+    let title = match folder {
+        Some(folder) => format!("Bookmark folder `{}`", folder.name),
+        None => "Bookmarks".to_owned(),
+    };
+
+    Ok(Some(BatchMaps {
+        title,
+        maps,
+        skipped,
+    }))
+}
+
+async fn listed_maps(
+    orig: &CommandOrigin<'_>,
+    maps: &str,
+    mode: Option<GameMode>,
+) -> Result<Option<BatchMaps>> {
+    let mut map_ids = Vec::new();
+    let mut skipped = 0;
+
+    for arg in maps.split([' ', ',', '\n']).filter(|arg| !arg.is_empty()) {
+        match matcher::get_osu_map_id(arg) {
+            Some(map_id) if !map_ids.contains(&map_id) => map_ids.push(map_id),
+            Some(_) => {}
+            None => skipped += 1,
+        }
+    }
+
+    if map_ids.is_empty() {
+        let content = "Failed to parse map ids. \
+            Be sure you specify valid map ids or urls to maps separated by spaces or commas.";
+
+        return orig.error(content).await.map(|_| None);
+    }
+
+    skipped += map_ids.len().saturating_sub(MAX_MAPS);
+    map_ids.truncate(MAX_MAPS);
+
+    let mut maps = Vec::with_capacity(map_ids.len());
+
+    for (map_id, idx) in map_ids.into_iter().zip(1..) {
+        let map = match Context::osu_map().map(map_id, None).await {
+            Ok(map) => map,
+            Err(MapError::NotFound) => {
+                skipped += 1;
+
+                continue;
+            }
+            Err(MapError::Report(err)) => {
+                let _ = orig.error(GENERAL_ISSUE).await;
+
+                return Err(err);
+            }
+        };
+
+        maps.push(BatchMap {
+            idx,
+            map: match mode {
+                Some(mode) => map.convert(mode),
+                None => map,
+            },
+            score_pp: None,
+        });
+    }
+
+    let title = format!(
+        "{count} map{plural}",
+        count = maps.len(),
+        plural = if maps.len() == 1 { "" } else { "s" },
+    );
+
+    Ok(Some(BatchMaps {
+        title,
+        maps,
+        skipped,
+    }))
+}
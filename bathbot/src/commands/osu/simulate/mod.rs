@@ -1,4 +1,5 @@
 pub mod args;
+mod batch;
 pub mod parsed_map;
 
 use std::borrow::Cow;
@@ -7,7 +8,7 @@ use bathbot_macros::{command, HasMods, SlashCommand};
 use bathbot_model::command_fields::GameModeOption;
 use bathbot_util::{constants::GENERAL_ISSUE, matcher, osu::MapIdType};
 use eyre::Result;
-use rosu_v2::prelude::{GameMode, GameModsIntermode};
+use rosu_v2::prelude::{GameMode, GameMods, GameModsIntermode};
 use twilight_interactions::command::{CommandModel, CreateCommand};
 use twilight_model::{
     channel::{message::MessageType, Attachment, Message},
@@ -15,6 +16,7 @@ use twilight_model::{
 };
 
 use self::args::{ParseError, SimulateArg};
+pub use self::batch::{SimulateBatchEntry, SimulateBatchOrder};
 use super::{
     HasMods, ModsResult, TopOldCatchVersion, TopOldManiaVersion, TopOldOsuVersion,
     TopOldTaikoVersion,
@@ -98,20 +100,14 @@ async fn simulate(orig: CommandOrigin<'_>, mut args: SimulateArgs) -> Result<()>
     };
 
     let mode = map.mode();
-
-    let version = match mode {
-        GameMode::Osu => TopOldVersion::Osu(TopOldOsuVersion::September22Now),
-        GameMode::Taiko => TopOldVersion::Taiko(TopOldTaikoVersion::September22Now),
-        GameMode::Catch => TopOldVersion::Catch(TopOldCatchVersion::May20Now),
-        GameMode::Mania => TopOldVersion::Mania(TopOldManiaVersion::October22Now),
-    };
+    let version = current_version(mode);
 
     let max_combo = match map {
         SimulateMap::Full(ref map) => Context::pp(map).difficulty().await.max_combo(),
         SimulateMap::Attached(ref map) => map.max_combo,
     };
 
-    let mods = match args.mods.map(|mods| mods.try_with_mode(mode)) {
+    let mods = match args.mods.take().map(|mods| mods.try_with_mode(mode)) {
         Some(mods @ Some(_)) => mods,
         None => None,
         Some(None) => {
@@ -121,28 +117,7 @@ async fn simulate(orig: CommandOrigin<'_>, mut args: SimulateArgs) -> Result<()>
         }
     };
 
-    let simulate_data = SimulateData {
-        mods,
-        acc: args.acc,
-        n_geki: args.geki,
-        n_katu: args.katu,
-        n300: args.n300,
-        n100: args.n100,
-        n50: args.n50,
-        n_miss: args.misses,
-        combo: args.combo,
-        clock_rate: args.clock_rate,
-        bpm: args.bpm,
-        attrs: SimulateAttributes {
-            ar: args.ar,
-            cs: args.cs,
-            hp: args.hp,
-            od: args.od,
-        },
-        score: None,
-        version,
-        max_combo,
-    };
+    let simulate_data = args.data(mods, version, max_combo);
 
     let active = SimulateComponents::new(map, simulate_data, orig.user_id()?);
 
@@ -152,6 +127,16 @@ async fn simulate(orig: CommandOrigin<'_>, mut args: SimulateArgs) -> Result<()>
         .await
 }
 
+/// The latest pp version of the mode.
+fn current_version(mode: GameMode) -> TopOldVersion {
+    match mode {
+        GameMode::Osu => TopOldVersion::Osu(TopOldOsuVersion::September22Now),
+        GameMode::Taiko => TopOldVersion::Taiko(TopOldTaikoVersion::September22Now),
+        GameMode::Catch => TopOldVersion::Catch(TopOldCatchVersion::May20Now),
+        GameMode::Mania => TopOldVersion::Mania(TopOldManiaVersion::October22Now),
+    }
+}
+
 #[command]
 #[desc("Simulate a score on a map")]
 #[help(
@@ -447,28 +432,57 @@ impl SimulateArgs {
                 continue;
             }
 
-            match SimulateArg::parse(arg).map_err(ParseError::into_str)? {
-                SimulateArg::Acc(val) => simulate.acc = Some(val.clamp(0.0, 100.0)),
-                SimulateArg::Bpm(val) => simulate.bpm = Some(val),
-                SimulateArg::Combo(val) => simulate.combo = Some(val),
-                SimulateArg::ClockRate(val) => simulate.clock_rate = Some(val),
-                SimulateArg::N300(val) => simulate.n300 = Some(val),
-                SimulateArg::N100(val) => simulate.n100 = Some(val),
-                SimulateArg::N50(val) => simulate.n50 = Some(val),
-                SimulateArg::Geki(val) => simulate.geki = Some(val),
-                SimulateArg::Katu(val) => simulate.katu = Some(val),
-                SimulateArg::Miss(val) => simulate.misses = Some(val),
-                SimulateArg::Mods(val) => simulate.mods = Some(val),
-                SimulateArg::Ar(val) => simulate.ar = Some(val),
-                SimulateArg::Cs(val) => simulate.cs = Some(val),
-                SimulateArg::Hp(val) => simulate.hp = Some(val),
-                SimulateArg::Od(val) => simulate.od = Some(val),
-            }
+            simulate.apply(SimulateArg::parse(arg).map_err(ParseError::into_str)?);
         }
 
         Ok(simulate)
     }
 
+    fn apply(&mut self, arg: SimulateArg) {
+        match arg {
+            SimulateArg::Acc(val) => self.acc = Some(val.clamp(0.0, 100.0)),
+            SimulateArg::Bpm(val) => self.bpm = Some(val),
+            SimulateArg::Combo(val) => self.combo = Some(val),
+            SimulateArg::ClockRate(val) => self.clock_rate = Some(val),
+            SimulateArg::N300(val) => self.n300 = Some(val),
+            SimulateArg::N100(val) => self.n100 = Some(val),
+            SimulateArg::N50(val) => self.n50 = Some(val),
+            SimulateArg::Geki(val) => self.geki = Some(val),
+            SimulateArg::Katu(val) => self.katu = Some(val),
+            SimulateArg::Miss(val) => self.misses = Some(val),
+            SimulateArg::Mods(val) => self.mods = Some(val),
+            SimulateArg::Ar(val) => self.ar = Some(val),
+            SimulateArg::Cs(val) => self.cs = Some(val),
+            SimulateArg::Hp(val) => self.hp = Some(val),
+            SimulateArg::Od(val) => self.od = Some(val),
+        }
+    }
+
+    fn data(&self, mods: Option<GameMods>, version: TopOldVersion, max_combo: u32) -> SimulateData {
+        SimulateData {
+            mods,
+            acc: self.acc,
+            n_geki: self.geki,
+            n_katu: self.katu,
+            n300: self.n300,
+            n100: self.n100,
+            n50: self.n50,
+            n_miss: self.misses,
+            combo: self.combo,
+            clock_rate: self.clock_rate,
+            bpm: self.bpm,
+            attrs: SimulateAttributes {
+                ar: self.ar,
+                cs: self.cs,
+                hp: self.hp,
+                od: self.od,
+            },
+            score: None,
+            version,
+            max_combo,
+        }
+    }
+
     fn from_simulate(simulate: Simulate<'_>) -> Result<Self, &'static str> {
         let mods = match simulate.mods() {
             ModsResult::Mods(mods) => Some(mods.into_mods()),
@@ -344,6 +344,10 @@ impl<'q> Searchable<TopCriteria<'q>> for TopIfEntry {
         matches &= criteria.pp.contains(self.score.pp);
         matches &= criteria.acc.contains(self.score.accuracy);
 
+        if let Some(ref mods) = criteria.mods {
+            matches &= mods.filter_mods(&self.score.mods);
+        }
+
         if !criteria.ranked_date.is_empty() {
             let Some(datetime) = self.map.ranked_date() else {
                 return false;
@@ -15,6 +15,7 @@ use bathbot_util::{
 };
 use eyre::{Report, Result};
 use rand::{thread_rng, Rng};
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_v2::{
     prelude::{GameMode, Grade, OsuError, Score},
     request::UserId,
@@ -32,9 +33,7 @@ use crate::{
         impls::{SingleScoreContent, SingleScorePagination, TopPagination},
         ActiveMessages,
     },
-    commands::utility::{
-        MissAnalyzerCheck, ScoreEmbedDataHalf, ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap,
-    },
+    commands::utility::{ScoreEmbedDataHalf, ScoreEmbedDataPersonalBest, ScoreEmbedDataWrap},
     core::commands::{prefix::Args, CommandOrigin},
     manager::redis::osu::UserArgs,
     util::{
@@ -104,7 +103,18 @@ pub struct Top {
     size: Option<ListSize>,
 }
 
-#[derive(Copy, Clone, Default, CommandOption, CreateOption, Eq, PartialEq)]
+#[derive(
+    Archive,
+    Copy,
+    Clone,
+    Default,
+    CommandOption,
+    CreateOption,
+    Deserialize,
+    Eq,
+    PartialEq,
+    Serialize,
+)]
 pub enum TopScoreOrder {
     #[option(name = "Accuracy", value = "acc")]
     Acc,
@@ -1012,7 +1022,7 @@ async fn process_scores(
             pb_idx,
             legacy_scores,
             with_render,
-            MissAnalyzerCheck::without(),
+            false,
         )
         .await;
 
@@ -10,6 +10,7 @@ use bathbot_util::{
     osu::ModSelection,
 };
 use eyre::{Report, Result};
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_pp_older::*;
 use rosu_v2::{
     prelude::{GameMod, GameMode, OsuError, Score},
@@ -108,7 +109,9 @@ pub struct TopOldOsu<'a> {
     reverse: Option<bool>,
 }
 
-#[derive(Copy, Clone, CommandOption, CreateOption, Debug, PartialEq)]
+#[derive(
+    Archive, Copy, Clone, CommandOption, CreateOption, Debug, Deserialize, PartialEq, Serialize,
+)]
 pub enum TopOldOsuVersion {
     #[option(name = "May 2014 - July 2014", value = "may14_july14")]
     May14July14,
@@ -214,7 +217,9 @@ pub struct TopOldTaiko<'a> {
     reverse: Option<bool>,
 }
 
-#[derive(Copy, Clone, CommandOption, CreateOption, Debug, PartialEq)]
+#[derive(
+    Archive, Copy, Clone, CommandOption, CreateOption, Debug, Deserialize, PartialEq, Serialize,
+)]
 pub enum TopOldTaikoVersion {
     #[option(name = "March 2014 - September 2020", value = "march14_september20")]
     March14September20,
@@ -290,7 +295,9 @@ pub struct TopOldCatch<'a> {
     reverse: Option<bool>,
 }
 
-#[derive(Copy, Clone, CommandOption, CreateOption, Debug, PartialEq)]
+#[derive(
+    Archive, Copy, Clone, CommandOption, CreateOption, Debug, Deserialize, PartialEq, Serialize,
+)]
 pub enum TopOldCatchVersion {
     #[option(name = "March 2014 - May 2020", value = "march14_may20")]
     March14May20,
@@ -361,7 +368,9 @@ pub struct TopOldMania<'a> {
     reverse: Option<bool>,
 }
 
-#[derive(Copy, Clone, CommandOption, CreateOption, Debug, PartialEq)]
+#[derive(
+    Archive, Copy, Clone, CommandOption, CreateOption, Debug, Deserialize, PartialEq, Serialize,
+)]
 pub enum TopOldManiaVersion {
     #[option(name = "March 2014 - May 2018", value = "march14_may18")]
     March14May18,
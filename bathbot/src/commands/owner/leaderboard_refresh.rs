@@ -0,0 +1,45 @@
+use bathbot_util::{EmbedBuilder, FooterBuilder, MessageBuilder};
+use eyre::Result;
+use twilight_model::channel::message::embed::EmbedField;
+
+use crate::{
+    util::{interaction::InteractionCommand, InteractionCommandExt},
+    Context,
+};
+
+pub async fn leaderboard_refresh(command: InteractionCommand) -> Result<()> {
+    let stats = Context::leaderboard_refresh().stats();
+
+    let fields = vec![
+        EmbedField {
+            name: "Budget".to_owned(),
+            value: format!("{} requests/min", stats.budget),
+            inline: true,
+        },
+        EmbedField {
+            name: "Refreshed".to_owned(),
+            value: stats.refreshed.to_string(),
+            inline: true,
+        },
+        EmbedField {
+            name: "Failed".to_owned(),
+            value: stats.failed.to_string(),
+            inline: true,
+        },
+    ];
+
+    let title = format!("Active guilds: {} | queue: {}", stats.guilds, stats.queue);
+
+    let mut embed = EmbedBuilder::new().title(title).fields(fields);
+
+    if let Some(last_refresh) = stats.last_refresh {
+        embed = embed
+            .footer(FooterBuilder::new("Last refresh"))
+            .timestamp(last_refresh);
+    }
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.callback(builder, false).await?;
+
+    Ok(())
+}
@@ -2,15 +2,17 @@ use std::{borrow::Cow, collections::HashMap};
 
 use bathbot_macros::SlashCommand;
 use bathbot_model::command_fields::GameModeOption;
+use bathbot_psql::model::osu::TrackKind;
 use bathbot_util::CowUtils;
 use eyre::Result;
 use rosu_v2::prelude::{GameMode, OsuError, Username};
-use twilight_interactions::command::{CommandModel, CreateCommand};
+use twilight_interactions::command::{CommandModel, CommandOption, CreateCommand, CreateOption};
 
 pub use self::{track::*, track_list::*, untrack::*, untrack_all::*};
 use crate::{
     core::commands::prefix::{Args, ArgsNum},
     manager::redis::osu::UserArgs,
+    tracking::RecentTrackConditions,
     util::{interaction::InteractionCommand, InteractionCommandExt},
     Context,
 };
@@ -41,13 +43,23 @@ pub enum Track {
     name = "add",
     desc = "Track top scores of a player",
     help = "Add users to the tracking list for this channel.\n\
-    If a tracked user gets a new top score, this channel will be notified about it."
+    If a tracked user gets a new top score, this channel will be notified about it.\n\
+    Alternatively, recent scores can be tracked instead so that the channel is notified about \
+    new personal bests, new #1 scores, or SS scores."
 )]
 pub struct TrackAdd {
     #[command(desc = "Choose a username to be tracked")]
     name: String,
     #[command(desc = "Specify a mode for the tracked users")]
     mode: GameModeOption,
+    #[command(
+        desc = "Specify whether top scores or recent scores should be tracked",
+        help = "With `Top scores`, new scores in the user's top scores will be notified about.\n\
+        With `Recent scores`, the user's recent passes are checked for the `pb`, `first`, \
+        and `ss` conditions instead. If none of them are specified, all of them apply.\n\
+        To only get notified about SS scores on harder maps, specify `ss_stars`."
+    )]
+    kind: Option<TrackKindOption>,
     #[command(
         min_value = 1,
         max_value = 100,
@@ -57,6 +69,59 @@ pub struct TrackAdd {
         the top scores.\nThe value must be between 1 and 100."
     )]
     limit: Option<u8>,
+    #[command(
+        desc = "Only notify on scores matching this filter e.g. `pp>=400 mods=+hd stars>7`",
+        help = "Only scores that match the filter will trigger notification messages.\n\
+        The filter uses the same syntax as the `query` option of `/top`, e.g. \
+        `pp>=400 mods=+hd stars>7 acc>98`.\n\
+        Specifying a filter for an already tracked user will replace the previous filter."
+    )]
+    filter: Option<String>,
+    #[command(
+        desc = "Notify on milestones: comma-separated `rank`, `country`, `pp`, `medals`, or `all`",
+        help = "In addition to scores, the channel can be notified when the user reaches \
+        milestones.\n\
+        - `rank`: Reaching a global rank threshold like top 10,000 or top 1,000\n\
+        - `country`: Reaching a country rank threshold like top 100 or top 10\n\
+        - `pp`: Crossing a multiple of 1,000pp\n\
+        - `medals`: Unlocking new medals\n\
+        Specifying milestones for an already tracked user will replace the previous ones."
+    )]
+    milestones: Option<String>,
+    #[command(
+        min_value = 5,
+        max_value = 720,
+        desc = "Edit one summary message per play session that ends after this many idle minutes",
+        help = "Instead of sending a new message for each score, the channel gets one summary \
+        message per play session of the user.\n\
+        The message is edited whenever a new score comes in and shows the latest plays \
+        as well as the pp and rank changes throughout the session.\n\
+        If the user did not set a score for the given amount of minutes, the session ends and \
+        the next score will start a new session with a new message."
+    )]
+    session: Option<u32>,
+    #[command(
+        desc = "Post notifications as JSON to this http(s) url instead of the channel",
+        help = "Instead of sending messages to the channel, notifications will be posted as         JSON payload to the given url.
+        The payload contains the `type` of notification, the `channel_id`, and the score,         user, and map data in `data`.
+        Specifying a webhook for an already tracked user will replace the previous one."
+    )]
+    webhook: Option<String>,
+    #[command(desc = "For recent tracking: notify on new personal bests on a map")]
+    pb: Option<bool>,
+    #[command(desc = "For recent tracking: notify on new #1 scores on a map")]
+    first: Option<bool>,
+    #[command(desc = "For recent tracking: notify on SS scores")]
+    ss: Option<bool>,
+    #[command(
+        min_value = 1,
+        max_value = 20,
+        desc = "For recent tracking: only notify on SS scores on maps with at least this many stars",
+        help = "SS scores only trigger a notification if the map's star rating, including \
+        mods, is at least this value.\n\
+        Other conditions such as `pb` or `first` are not affected by it."
+    )]
+    ss_stars: Option<u8>,
     #[command(desc = "Specify a second username")]
     name2: Option<String>,
     #[command(desc = "Specify a third username")]
@@ -67,6 +132,23 @@ pub struct TrackAdd {
     name5: Option<String>,
 }
 
+#[derive(Copy, Clone, CommandOption, CreateOption)]
+pub enum TrackKindOption {
+    #[option(name = "Top scores", value = "top")]
+    Top,
+    #[option(name = "Recent scores", value = "recent")]
+    Recent,
+}
+
+impl From<TrackKindOption> for TrackKind {
+    fn from(kind: TrackKindOption) -> Self {
+        match kind {
+            TrackKindOption::Top => Self::Top,
+            TrackKindOption::Recent => Self::Recent,
+        }
+    }
+}
+
 #[derive(CommandModel, CreateCommand)]
 #[command(
     name = "remove",
@@ -149,6 +231,13 @@ struct TrackArgs {
     mode: Option<GameMode>,
     name: String,
     limit: Option<u8>,
+    filter: Option<String>,
+    milestones: Option<String>,
+    session: Option<u32>,
+    webhook: Option<String>,
+    kind: TrackKind,
+    conditions: RecentTrackConditions,
+    ss_stars: Option<u8>,
     more_names: Vec<String>,
 }
 
@@ -156,6 +245,12 @@ impl TrackArgs {
     async fn args(mode: Option<GameMode>, args: Args<'_>) -> Result<Self, Cow<'static, str>> {
         let mut name = None;
         let mut more_names = Vec::new();
+        let mut filter = None;
+        let mut milestones = None;
+        let mut session = None;
+        let mut kind = TrackKind::Top;
+        let mut conditions = RecentTrackConditions::empty();
+        let mut ss_stars = None;
 
         let mut limit = match args.num {
             ArgsNum::Value(n) => Some(n.min(100) as u8),
@@ -176,9 +271,54 @@ impl TrackArgs {
                             return Err(content.into());
                         }
                     },
+                    "filter" | "f" => filter = Some(value.to_owned()),
+                    "milestones" | "m" => milestones = Some(value.to_owned()),
+                    "session" | "s" => match value.parse() {
+                        Ok(minutes) => session = Some(minutes),
+                        Err(_) => {
+                            let content = "Failed to parse `session`. Must be an integer.";
+
+                            return Err(content.into());
+                        }
+                    },
+                    "kind" | "k" => match value {
+                        "top" => kind = TrackKind::Top,
+                        "recent" => kind = TrackKind::Recent,
+                        _ => {
+                            let content =
+                                "Failed to parse `kind`. Must be either `top` or `recent`.";
+
+                            return Err(content.into());
+                        }
+                    },
+                    "notify" | "n" => {
+                        for condition in value.split(',') {
+                            conditions |= match condition.trim() {
+                                "pb" => RecentTrackConditions::PERSONAL_BEST,
+                                "first" | "#1" => RecentTrackConditions::GLOBAL_FIRST,
+                                "ss" => RecentTrackConditions::SS,
+                                _ => {
+                                    let content = "Failed to parse `notify`. Must be a \
+                                        comma-separated list of `pb`, `first`, or `ss`.";
+
+                                    return Err(content.into());
+                                }
+                            };
+                        }
+                    }
+                    "ssstars" => match value.parse() {
+                        Ok(stars) => ss_stars = Some(stars),
+                        Err(_) => {
+                            let content = "Failed to parse `ssstars`. Must be an integer.";
+
+                            return Err(content.into());
+                        }
+                    },
                     _ => {
                         let content = format!(
-                            "Unrecognized option `{key}`.\nAvailable options are: `limit`."
+                            "Unrecognized option `{key}`.\n\
+                            Available options are: `limit`, `filter`, `kind`, `notify`, \
+                            `ssstars`, `milestones`, or `session`."
                         );
 
                         return Err(content.into());
@@ -199,6 +339,15 @@ impl TrackArgs {
         let args = Self {
             name,
             limit,
+            filter,
+            milestones,
+            session,
+            // Prefix arguments are lowercased so urls are only supported as
+            // slash command option
+            webhook: None,
+            kind,
+            conditions,
+            ss_stars,
             more_names,
             mode,
         };
@@ -212,7 +361,16 @@ impl From<TrackAdd> for TrackArgs {
         let TrackAdd {
             name,
             mode,
+            kind,
             limit,
+            filter,
+            milestones,
+            session,
+            webhook,
+            pb,
+            first,
+            ss,
+            ss_stars,
             name2,
             name3,
             name4,
@@ -237,10 +395,22 @@ impl From<TrackAdd> for TrackArgs {
             more_names.push(name);
         }
 
+        let mut conditions = RecentTrackConditions::empty();
+        conditions.set(RecentTrackConditions::PERSONAL_BEST, pb == Some(true));
+        conditions.set(RecentTrackConditions::GLOBAL_FIRST, first == Some(true));
+        conditions.set(RecentTrackConditions::SS, ss == Some(true));
+
         Self {
             mode: Some(mode.into()),
             name,
             limit,
+            filter,
+            milestones,
+            session,
+            webhook,
+            kind: kind.map_or(TrackKind::Top, TrackKind::from),
+            conditions,
+            ss_stars,
             more_names,
         }
     }
@@ -254,6 +424,13 @@ impl From<TrackRemoveUser> for TrackArgs {
             mode: mode.map(GameMode::from),
             name,
             limit: None,
+            filter: None,
+            milestones: None,
+            session: None,
+            webhook: None,
+            kind: TrackKind::Top,
+            conditions: RecentTrackConditions::empty(),
+            ss_stars: None,
             more_names: Vec::new(),
         }
     }
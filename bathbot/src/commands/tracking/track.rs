@@ -1,4 +1,5 @@
 use bathbot_macros::command;
+use bathbot_psql::model::osu::{TrackKind, TrackedOsuUserKey};
 use bathbot_util::{constants::OSU_API_ISSUE, MessageBuilder};
 use eyre::{Report, Result};
 use rosu_v2::prelude::{GameMode, OsuError};
@@ -8,7 +9,11 @@ use super::TrackArgs;
 use crate::{
     core::commands::CommandOrigin,
     embeds::{EmbedData, TrackEmbed},
-    util::ChannelExt,
+    tracking::{check_webhook_url, MilestoneKinds, RecentTrackConditions, TrackEntryOptions},
+    util::{
+        query::{IFilterCriteria, TopCriteria},
+        ChannelExt,
+    },
     Context,
 };
 
@@ -17,6 +22,13 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
         name,
         mode,
         limit,
+        filter,
+        milestones,
+        session,
+        webhook,
+        kind,
+        conditions,
+        ss_stars,
         mut more_names,
     } = args;
 
@@ -28,14 +40,32 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
         return orig.error(content).await;
     }
 
-    let limit = match limit {
-        Some(limit @ 1..=100) => limit,
+    let ss_stars = match ss_stars {
+        Some(stars @ 1..=RecentTrackConditions::MAX_SS_STARS) => stars,
         Some(_) => {
+            let content = format!(
+                "The minimum stars for SS scores must be between 1 and {}",
+                RecentTrackConditions::MAX_SS_STARS
+            );
+
+            return orig.error(content).await;
+        }
+        None => 0,
+    };
+
+    // For recent scores, the channel value holds the conditions instead
+    let limit = match (kind, limit) {
+        (TrackKind::Recent, _) if conditions.is_empty() => {
+            RecentTrackConditions::all().value(ss_stars)
+        }
+        (TrackKind::Recent, _) => conditions.value(ss_stars),
+        (TrackKind::Top, Some(limit @ 1..=100)) => limit,
+        (TrackKind::Top, Some(_)) => {
             let content = "The given limit must be between 1 and 100";
 
             return orig.error(content).await;
         }
-        None => {
+        (TrackKind::Top, None) => {
             let guild = orig.guild_id().unwrap();
 
             Context::guild_config()
@@ -45,6 +75,56 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
         }
     };
 
+    let filter = filter
+        .as_deref()
+        .map(str::trim)
+        .filter(|filter| !filter.is_empty());
+
+    if filter.is_some_and(|filter| filter.len() > 256) {
+        let content = "The filter must not be longer than 256 characters";
+
+        return orig.error(content).await;
+    }
+
+    let milestones = match milestones.as_deref().map(MilestoneKinds::parse) {
+        Some(Some(milestones)) => milestones,
+        Some(None) => {
+            let content = "Failed to parse milestones. Must be a comma-separated list of \
+                `rank`, `country`, `pp`, `medals`, or `all`.";
+
+            return orig.error(content).await;
+        }
+        None => MilestoneKinds::empty(),
+    };
+
+    let session = match session {
+        Some(minutes @ 5..=720) => minutes as u16,
+        Some(_) => {
+            let content = "The session timeout must be between 5 and 720 minutes";
+
+            return orig.error(content).await;
+        }
+        None => 0,
+    };
+
+    let webhook = webhook
+        .as_deref()
+        .map(str::trim)
+        .filter(|url| !url.is_empty());
+
+    if let Some(Err(content)) = webhook.map(check_webhook_url) {
+        return orig.error(content).await;
+    }
+
+    let options = TrackEntryOptions {
+        limit,
+        filter,
+        milestones,
+        session,
+        webhook,
+    };
+
+    let criteria = filter.map(TopCriteria::create);
     let mode = mode.unwrap_or(GameMode::Osu);
 
     let users = match super::get_names(&more_names, mode).await {
@@ -68,7 +148,14 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
     let tracking = Context::tracking();
 
     for (username, user_id) in users {
-        let add_fut = tracking.add(user_id, mode, OffsetDateTime::now_utc(), channel, limit);
+        let key = TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        };
+
+        let now = OffsetDateTime::now_utc();
+        let add_fut = tracking.add(key, now, channel, options);
 
         match add_fut.await {
             Ok(true) => success.push(username),
@@ -76,7 +163,16 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
             Err(err) => {
                 warn!(?err, "Failed to add tracked entry");
 
-                let embed = TrackEmbed::new(mode, success, failure, Some(username), limit).build();
+                let embed = TrackEmbed::new(
+                    mode,
+                    success,
+                    failure,
+                    Some(username),
+                    kind,
+                    options,
+                    criteria.as_ref(),
+                )
+                .build();
 
                 let builder = MessageBuilder::new().embed(embed);
                 orig.create_message(builder).await?;
@@ -86,7 +182,15 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
         }
     }
 
-    let embed = TrackEmbed::new(mode, success, failure, None, limit);
+    let embed = TrackEmbed::new(
+        mode,
+        success,
+        failure,
+        None,
+        kind,
+        options,
+        criteria.as_ref(),
+    );
     let builder = MessageBuilder::new().embed(embed.build());
     orig.create_message(builder).await?;
 
@@ -104,9 +208,25 @@ pub(super) async fn track(orig: CommandOrigin<'_>, args: TrackArgs) -> Result<()
     a new score in his top 42.\n\
     Alternatively, you can provide a limit by specifying `limit=number`, \
     e.g. `track limit=42 badewanne3`.\n\
-    The limit must be between 1 and 100, **defaults to 50** if none is given."
+    The limit must be between 1 and 100, **defaults to 50** if none is given.\n\
+    To only get notified about some scores, provide a filter via `filter=...` \
+    which uses the same syntax as the `query` option of `/top`. \
+    Wrap it in quotes if it contains spaces, e.g. `\"filter=pp>400 mods=+hd\"`.\n\
+    To track recent scores instead of top scores, specify `kind=recent`. \
+    You can then choose when to get notified with `notify=pb,first,ss` \
+    i.e. on new personal bests, new #1 scores, or SS scores. Defaults to all of them.\n\
+    To only get notified about SS scores on maps with at least a certain star rating, \
+    specify e.g. `ssstars=7`.\n\
+    To also get notified when users reach milestones, specify \
+    `milestones=rank,country,pp,medals` or `milestones=all`.\n\
+    To reduce spam, specify `session=minutes` so that each play session of a user \
+    gets a single summary message that is edited on new scores. \
+    A session ends after the given amount of minutes without new scores."
+)]
+#[usage(
+    "[limit=number] [filter=query] [kind=top/recent] [notify=pb,first,ss] [ssstars=number] \
+    [milestones=rank,country,pp,medals] [session=minutes] [username1] [username2] ..."
 )]
-#[usage("[limit=number] [username1] [username2] ...")]
 #[examples(
     "badewanne3 \"freddie benson\" peppy limit=23",
     "limit=45 cookiezi whitecat",
@@ -136,9 +256,25 @@ async fn prefix_track(msg: &Message, args: Args<'_>) -> Result<()> {
     a new score in his top 42.\n\
     Alternatively, you can provide a limit by specifying `limit=number`, \
     e.g. `trackmania limit=42 badewanne3`.\n\
-    The limit must be between 1 and 100, **defaults to 50** if none is given."
+    The limit must be between 1 and 100, **defaults to 50** if none is given.\n\
+    To only get notified about some scores, provide a filter via `filter=...` \
+    which uses the same syntax as the `query` option of `/top`. \
+    Wrap it in quotes if it contains spaces, e.g. `\"filter=pp>400 mods=+hd\"`.\n\
+    To track recent scores instead of top scores, specify `kind=recent`. \
+    You can then choose when to get notified with `notify=pb,first,ss` \
+    i.e. on new personal bests, new #1 scores, or SS scores. Defaults to all of them.\n\
+    To only get notified about SS scores on maps with at least a certain star rating, \
+    specify e.g. `ssstars=7`.\n\
+    To also get notified when users reach milestones, specify \
+    `milestones=rank,country,pp,medals` or `milestones=all`.\n\
+    To reduce spam, specify `session=minutes` so that each play session of a user \
+    gets a single summary message that is edited on new scores. \
+    A session ends after the given amount of minutes without new scores."
+)]
+#[usage(
+    "[limit=number] [filter=query] [kind=top/recent] [notify=pb,first,ss] [ssstars=number] \
+    [milestones=rank,country,pp,medals] [session=minutes] [username1] [username2] ..."
 )]
-#[usage("[limit=number] [username1] [username2] ...")]
 #[examples(
     "badewanne3 \"freddie benson\" peppy limit=23",
     "limit=45 cookiezi whitecat",
@@ -168,9 +304,25 @@ pub async fn prefix_trackmania(msg: &Message, args: Args<'_>) -> Result<()> {
     a new score in his top 42.\n\
     Alternatively, you can provide a limit by specifying `limit=number`, \
     e.g. `tracktaiko limit=42 badewanne3`.\n\
-    The limit must be between 1 and 100, **defaults to 50** if none is given."
+    The limit must be between 1 and 100, **defaults to 50** if none is given.\n\
+    To only get notified about some scores, provide a filter via `filter=...` \
+    which uses the same syntax as the `query` option of `/top`. \
+    Wrap it in quotes if it contains spaces, e.g. `\"filter=pp>400 mods=+hd\"`.\n\
+    To track recent scores instead of top scores, specify `kind=recent`. \
+    You can then choose when to get notified with `notify=pb,first,ss` \
+    i.e. on new personal bests, new #1 scores, or SS scores. Defaults to all of them.\n\
+    To only get notified about SS scores on maps with at least a certain star rating, \
+    specify e.g. `ssstars=7`.\n\
+    To also get notified when users reach milestones, specify \
+    `milestones=rank,country,pp,medals` or `milestones=all`.\n\
+    To reduce spam, specify `session=minutes` so that each play session of a user \
+    gets a single summary message that is edited on new scores. \
+    A session ends after the given amount of minutes without new scores."
+)]
+#[usage(
+    "[limit=number] [filter=query] [kind=top/recent] [notify=pb,first,ss] [ssstars=number] \
+    [milestones=rank,country,pp,medals] [session=minutes] [username1] [username2] ..."
 )]
-#[usage("[limit=number] [username1] [username2] ...")]
 #[examples(
     "badewanne3 \"freddie benson\" peppy limit=23",
     "limit=45 cookiezi whitecat",
@@ -200,9 +352,25 @@ pub async fn prefix_tracktaiko(msg: &Message, args: Args<'_>) -> Result<()> {
     a new score in his top 42.\n\
     Alternatively, you can provide a limit by specifying `limit=number`, \
     e.g. `trackctb limit=42 badewanne3`.\n\
-    The limit must be between 1 and 100, **defaults to 50** if none is given."
+    The limit must be between 1 and 100, **defaults to 50** if none is given.\n\
+    To only get notified about some scores, provide a filter via `filter=...` \
+    which uses the same syntax as the `query` option of `/top`. \
+    Wrap it in quotes if it contains spaces, e.g. `\"filter=pp>400 mods=+hd\"`.\n\
+    To track recent scores instead of top scores, specify `kind=recent`. \
+    You can then choose when to get notified with `notify=pb,first,ss` \
+    i.e. on new personal bests, new #1 scores, or SS scores. Defaults to all of them.\n\
+    To only get notified about SS scores on maps with at least a certain star rating, \
+    specify e.g. `ssstars=7`.\n\
+    To also get notified when users reach milestones, specify \
+    `milestones=rank,country,pp,medals` or `milestones=all`.\n\
+    To reduce spam, specify `session=minutes` so that each play session of a user \
+    gets a single summary message that is edited on new scores. \
+    A session ends after the given amount of minutes without new scores."
+)]
+#[usage(
+    "[limit=number] [filter=query] [kind=top/recent] [notify=pb,first,ss] [ssstars=number] \
+    [milestones=rank,country,pp,medals] [session=minutes] [username1] [username2] ..."
 )]
-#[usage("[limit=number] [username1] [username2] ...")]
 #[examples(
     "badewanne3 \"freddie benson\" peppy limit=23",
     "limit=45 cookiezi whitecat",
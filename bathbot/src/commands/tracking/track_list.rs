@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use bathbot_macros::command;
-use bathbot_psql::model::osu::TrackedOsuUserKey;
+use bathbot_psql::model::osu::{TrackKind, TrackedOsuUserKey};
 use bathbot_util::{constants::OSU_API_ISSUE, MessageBuilder};
 use eyre::{Report, Result};
 use rosu_v2::{
@@ -14,13 +14,19 @@ use crate::{
     core::commands::CommandOrigin,
     embeds::{EmbedData, TrackListEmbed},
     manager::redis::osu::UserArgs,
+    tracking::{MilestoneKinds, TrackListEntry},
     Context,
 };
 
 pub struct TracklistUserEntry {
     pub name: Username,
     pub mode: GameMode,
+    pub kind: TrackKind,
     pub limit: u8,
+    pub filter: Option<Box<str>>,
+    pub milestones: MilestoneKinds,
+    pub session: u16,
+    pub webhook: bool,
 }
 
 #[command]
@@ -70,9 +76,12 @@ pub async fn tracklist(orig: CommandOrigin<'_>) -> Result<()> {
 
 async fn get_users(
     channel: Id<ChannelMarker>,
-    tracked: Vec<(TrackedOsuUserKey, u8)>,
+    tracked: Vec<TrackListEntry>,
 ) -> OsuResult<Vec<TracklistUserEntry>> {
-    let user_ids: Vec<_> = tracked.iter().map(|(key, ..)| key.user_id as i32).collect();
+    let user_ids: Vec<_> = tracked
+        .iter()
+        .map(|entry| entry.key.user_id as i32)
+        .collect();
 
     // Get all names that are stored in the DB
     let stored_names = match Context::osu_user().names(&user_ids).await {
@@ -87,12 +96,31 @@ async fn get_users(
     let mut users = Vec::with_capacity(tracked.len());
 
     // Get all missing names from the api
-    for (TrackedOsuUserKey { user_id, mode }, limit) in tracked {
+    for TrackListEntry {
+        key,
+        limit,
+        filter,
+        milestones,
+        session,
+        webhook,
+    } in tracked
+    {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
         let entry = match stored_names.get(&user_id) {
             Some(name) => TracklistUserEntry {
                 name: name.to_owned(),
                 mode,
+                kind,
                 limit,
+                filter,
+                milestones,
+                session,
+                webhook,
             },
             None => {
                 let user_args = UserArgs::user_id(user_id, mode);
@@ -101,7 +129,12 @@ async fn get_users(
                     Ok(user) => TracklistUserEntry {
                         name: user.username().into(),
                         mode,
+                        kind,
                         limit,
+                        filter,
+                        milestones,
+                        session,
+                        webhook,
                     },
                     Err(OsuError::NotFound) => {
                         let remove_fut = Context::tracking().remove_user(user_id, None, channel);
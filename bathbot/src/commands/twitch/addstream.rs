@@ -5,7 +5,9 @@ use bathbot_util::{
 };
 use eyre::Result;
 
-use crate::{core::commands::CommandOrigin, util::ChannelExt, Context};
+use crate::{
+    core::commands::CommandOrigin, tracking::check_webhook_url, util::ChannelExt, Context,
+};
 
 #[command]
 #[flags(AUTHORITY, ONLY_GUILDS)]
@@ -25,10 +27,21 @@ async fn prefix_addstream(msg: &Message, mut args: Args<'_>) -> Result<()> {
         }
     };
 
-    addstream(msg.into(), name.as_ref()).await
+    addstream(msg.into(), name.as_ref(), None, false).await
 }
 
-pub async fn addstream(orig: CommandOrigin<'_>, name: &'_ str) -> Result<()> {
+pub async fn addstream(
+    orig: CommandOrigin<'_>,
+    name: &'_ str,
+    webhook: Option<&'_ str>,
+    followup: bool,
+) -> Result<()> {
+    let webhook = webhook.map(str::trim).filter(|url| !url.is_empty());
+
+    if let Some(Err(content)) = webhook.map(check_webhook_url) {
+        return orig.error(content).await;
+    }
+
     let twitch_id = match Context::client().get_twitch_user(name).await {
         Ok(Some(user)) => user.user_id,
         Ok(None) => {
@@ -46,7 +59,10 @@ pub async fn addstream(orig: CommandOrigin<'_>, name: &'_ str) -> Result<()> {
     let channel = orig.channel_id();
     Context::add_tracking(twitch_id, channel);
 
-    match Context::twitch().track(channel, twitch_id).await {
+    match Context::twitch()
+        .track(channel, twitch_id, webhook, followup)
+        .await
+    {
         Ok(true) => {
             let content = format!("I'm now tracking `{name}`'s twitch stream in this channel");
             let builder = MessageBuilder::new().embed(content);
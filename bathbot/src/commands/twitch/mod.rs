@@ -37,6 +37,21 @@ pub enum TrackStream {
 pub struct TrackStreamAdd {
     #[command(desc = "Name of the twitch channel")]
     name: String,
+    #[command(
+        desc = "Post notifications as JSON to this http(s) url instead of the channel",
+        help = "Instead of sending messages to the channel, notifications will be posted as \
+        JSON payload to the given url.\n\
+        Specifying a webhook for an already tracked stream will replace the previous one."
+    )]
+    webhook: Option<String>,
+    #[command(
+        desc = "Edit the notification with duration and VOD once the stream ends",
+        help = "Once the stream ends, the notification will be edited to show the stream's \
+        duration as well as the title and game at its viewer peak.\n\
+        As soon as the VOD is available, it will be linked too.\n\
+        Has no effect if a webhook is specified."
+    )]
+    followup: Option<bool>,
 }
 
 #[derive(CommandModel, CreateCommand)]
@@ -55,7 +70,15 @@ pub struct TrackStreamList;
 
 pub async fn slash_trackstream(mut command: InteractionCommand) -> Result<()> {
     match TrackStream::from_interaction(command.input_data())? {
-        TrackStream::Add(add) => addstream((&mut command).into(), add.name.as_ref()).await,
+        TrackStream::Add(add) => {
+            addstream(
+                (&mut command).into(),
+                add.name.as_ref(),
+                add.webhook.as_deref(),
+                add.followup.unwrap_or(false),
+            )
+            .await
+        }
         TrackStream::Remove(remove) => {
             removestream((&mut command).into(), remove.name.as_ref()).await
         }
@@ -5,6 +5,7 @@ use bathbot_model::{embed_builder::ScoreEmbedSettings, ScoreSlim};
 use bathbot_psql::model::configs::ScoreData;
 use bathbot_util::{constants::GENERAL_ISSUE, CowUtils, MessageOrigin};
 use eyre::{Report, Result};
+use rkyv::{Archive, Deserialize, Serialize};
 use rosu_pp::model::beatmap::BeatmapAttributes;
 use rosu_v2::{
     model::{GameMode, Grade},
@@ -12,10 +13,7 @@ use rosu_v2::{
 };
 use time::OffsetDateTime;
 use twilight_interactions::command::{CommandModel, CreateCommand};
-use twilight_model::id::{
-    marker::{GuildMarker, UserMarker},
-    Id,
-};
+use twilight_model::id::{marker::UserMarker, Id};
 
 use crate::{
     active::{impls::ScoreEmbedBuilderActive, ActiveMessages},
@@ -231,7 +229,7 @@ impl ScoreEmbedDataWrap {
         score: Score,
         legacy_scores: bool,
         with_render: bool,
-        miss_analyzer: MissAnalyzerCheck,
+        with_miss_analyzer: bool,
         top100: Option<Arc<[Score]>>,
         #[cfg(feature = "twitch")] twitch_data: Option<Arc<TwitchData>>,
         origin: MessageOrigin,
@@ -241,7 +239,7 @@ impl ScoreEmbedDataWrap {
                 score,
                 legacy_scores,
                 with_render,
-                miss_analyzer,
+                with_miss_analyzer,
                 top100,
                 #[cfg(feature = "twitch")]
                 twitch_data,
@@ -259,7 +257,7 @@ impl ScoreEmbedDataWrap {
         pb_idx: Option<ScoreEmbedDataPersonalBest>,
         legacy_scores: bool,
         with_render: bool,
-        miss_analyzer: MissAnalyzerCheck,
+        with_miss_analyzer: bool,
     ) -> Self {
         Self {
             inner: ScoreEmbedDataStatus::Half(Some(
@@ -270,7 +268,7 @@ impl ScoreEmbedDataWrap {
                     pb_idx,
                     legacy_scores,
                     with_render,
-                    miss_analyzer,
+                    with_miss_analyzer,
                 )
                 .await,
             )),
@@ -372,6 +370,14 @@ impl ScoreEmbedDataWrap {
     pub fn get_half(&self) -> &ScoreEmbedDataHalf {
         self.try_get_half().unwrap()
     }
+
+    pub fn into_half(self) -> Option<ScoreEmbedDataHalf> {
+        if let ScoreEmbedDataStatus::Half(half) = self.inner {
+            half
+        } else {
+            None
+        }
+    }
 }
 
 impl From<ScoreEmbedDataHalf> for ScoreEmbedDataWrap {
@@ -409,7 +415,7 @@ pub struct ScoreEmbedDataHalf {
     pub pb_idx: Option<ScoreEmbedDataPersonalBest>,
     pub legacy_scores: bool,
     pub with_render: bool,
-    pub miss_analyzer_check: MissAnalyzerCheck,
+    pub with_miss_analyzer: bool,
     pub original_idx: Option<usize>,
 }
 
@@ -421,7 +427,7 @@ impl ScoreEmbedDataHalf {
         pb_idx: Option<ScoreEmbedDataPersonalBest>,
         legacy_scores: bool,
         with_render: bool,
-        miss_analyzer_check: MissAnalyzerCheck,
+        with_miss_analyzer: bool,
     ) -> Self {
         let user_id = score.user_id;
 
@@ -450,7 +456,7 @@ impl ScoreEmbedDataHalf {
             pb_idx,
             legacy_scores,
             with_render,
-            miss_analyzer_check,
+            with_miss_analyzer,
             original_idx: None,
         }
     }
@@ -491,43 +497,26 @@ impl ScoreEmbedDataHalf {
                 .map(|idx| idx + 1)
         };
 
-        let miss_analyzer_fut = async {
-            let (score_id, guild_id) = self
-                .score
-                .legacy_id
-                .zip(self.miss_analyzer_check.guild_id)?;
-
-            debug!(score_id, "Sending score id to miss analyzer");
-
-            match Context::client()
-                .miss_analyzer_score_request(guild_id.get(), score_id)
-                .await
-            {
-                Ok(wants_button) => wants_button.then_some(MissAnalyzerData { score_id }),
-                Err(err) => {
-                    warn!(?err, "Failed to send score id to miss analyzer");
-
-                    None
-                }
-            }
-        };
-
         let if_fc_fut = IfFc::new(&self.score, &self.map);
 
-        let (global_idx, if_fc, miss_analyzer) =
-            tokio::join!(global_idx_fut, if_fc_fut, miss_analyzer_fut);
+        let (global_idx, if_fc) = tokio::join!(global_idx_fut, if_fc_fut);
 
         let if_fc_pp = if_fc.map(|if_fc| if_fc.pp);
 
-        let replay = if self.with_render {
-            self.checksum
-                .map(String::into_boxed_str)
-                .and_then(|checksum| {
-                    OwnedReplayScore::try_from_slim(&self.score, self.max_combo, checksum)
-                })
-        } else {
-            None
-        };
+        let replay_score =
+            |checksum: &str| OwnedReplayScore::try_from_slim(&self.score, self.max_combo, checksum);
+
+        let replay = self
+            .checksum
+            .as_deref()
+            .filter(|_| self.with_render)
+            .and_then(replay_score);
+
+        let miss_analyzer = self
+            .checksum
+            .as_deref()
+            .filter(|_| self.with_miss_analyzer)
+            .and_then(replay_score);
 
         ScoreEmbedData {
             score: self.score,
@@ -573,7 +562,8 @@ pub struct ScoreEmbedData {
     pub max_combo: u32,
     pub max_pp: f32,
     pub replay: Option<OwnedReplayScore>,
-    pub miss_analyzer: Option<MissAnalyzerData>,
+    /// Replay to analyze misses of through a button
+    pub miss_analyzer: Option<OwnedReplayScore>,
     pub pb_idx: Option<ScoreEmbedDataPersonalBest>,
     pub global_idx: Option<usize>,
     pub if_fc_pp: Option<f32>,
@@ -695,7 +685,7 @@ pub struct ScoreEmbedDataRaw {
     pub checksum: Option<String>,
     pub legacy_scores: bool,
     pub with_render: bool,
-    pub miss_analyzer_check: MissAnalyzerCheck,
+    pub with_miss_analyzer: bool,
     pub top100: Option<Arc<[Score]>>,
     #[cfg(feature = "twitch")]
     pub twitch: Option<Arc<TwitchData>>,
@@ -720,7 +710,7 @@ impl ScoreEmbedDataRaw {
         score: Score,
         legacy_scores: bool,
         with_render: bool,
-        miss_analyzer_check: MissAnalyzerCheck,
+        with_miss_analyzer: bool,
         top100: Option<Arc<[Score]>>,
         #[cfg(feature = "twitch")] twitch_data: Option<Arc<TwitchData>>,
         origin: MessageOrigin,
@@ -731,7 +721,7 @@ impl ScoreEmbedDataRaw {
             checksum: score.map.and_then(|map| map.checksum),
             legacy_scores,
             with_render,
-            miss_analyzer_check,
+            with_miss_analyzer,
             top100,
             #[cfg(feature = "twitch")]
             twitch: twitch_data,
@@ -826,41 +816,26 @@ impl ScoreEmbedDataRaw {
                 .map(|idx| idx + 1)
         };
 
-        let miss_analyzer_fut = async {
-            let (score_id, guild_id) = score
-                .legacy_id
-                .zip(self.miss_analyzer_check.guild_id)
-                .filter(|_| self.has_replay)?;
-
-            debug!(score_id, "Sending score id to miss analyzer");
-
-            match Context::client()
-                .miss_analyzer_score_request(guild_id.get(), score_id)
-                .await
-            {
-                Ok(wants_button) => wants_button.then_some(MissAnalyzerData { score_id }),
-                Err(err) => {
-                    warn!(?err, "Failed to send score id to miss analyzer");
-
-                    None
-                }
-            }
-        };
-
         let if_fc_fut = IfFc::new(&score, &map);
 
-        let (global_idx, if_fc, miss_analyzer) =
-            tokio::join!(global_idx_fut, if_fc_fut, miss_analyzer_fut);
+        let (global_idx, if_fc) = tokio::join!(global_idx_fut, if_fc_fut);
 
         let if_fc_pp = if_fc.map(|if_fc| if_fc.pp);
 
-        let replay = if self.with_render && self.has_replay {
-            self.checksum
-                .map(String::into_boxed_str)
-                .and_then(|checksum| OwnedReplayScore::try_from_slim(&score, max_combo, checksum))
-        } else {
-            None
-        };
+        let replay_score =
+            |checksum: &str| OwnedReplayScore::try_from_slim(&score, max_combo, checksum);
+
+        let replay = self
+            .checksum
+            .as_deref()
+            .filter(|_| self.with_render && self.has_replay)
+            .and_then(replay_score);
+
+        let miss_analyzer = self
+            .checksum
+            .as_deref()
+            .filter(|_| self.with_miss_analyzer && self.has_replay)
+            .and_then(replay_score);
 
         let pb_idx = self
             .top100
@@ -919,33 +894,13 @@ impl<'m> PpAttrs<'m> {
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct MissAnalyzerCheck {
-    guild_id: Option<Id<GuildMarker>>,
-}
-
-impl MissAnalyzerCheck {
-    pub fn new(guild_id: Option<Id<GuildMarker>>, with_miss_analyzer: bool) -> Self {
-        let guild_id = if with_miss_analyzer { guild_id } else { None };
-
-        Self { guild_id }
-    }
-
-    pub fn without() -> Self {
-        Self { guild_id: None }
-    }
-}
-
+#[derive(Archive, Deserialize, Serialize)]
 pub struct ScoreEmbedDataPersonalBest {
     /// Note that `idx` is 0-indexed.
     pub idx: Option<usize>,
     pub formatted: String,
 }
 
-pub struct MissAnalyzerData {
-    pub score_id: u64,
-}
-
 impl ScoreEmbedDataPersonalBest {
     pub fn try_new(pb_idx: PersonalBestIndex, origin: &MessageOrigin) -> Option<Self> {
         let idx = match &pb_idx {
@@ -981,96 +936,115 @@ impl ScoreEmbedDataPersonalBest {
 
 impl<'q> Searchable<TopCriteria<'q>> for ScoreEmbedDataHalf {
     fn matches(&self, criteria: &FilterCriteria<TopCriteria<'q>>) -> bool {
-        let mut matches = true;
-
-        matches &= criteria.combo.contains(self.score.max_combo);
-        matches &= criteria.miss.contains(self.score.statistics.count_miss);
-        matches &= criteria.score.contains(self.score.score);
-        matches &= criteria.date.contains(self.score.ended_at.date());
-        matches &= criteria.stars.contains(self.stars);
-        matches &= criteria.pp.contains(self.score.pp);
-        matches &= criteria.acc.contains(self.score.accuracy);
-
-        if !criteria.ranked_date.is_empty() {
-            let Some(datetime) = self.map.ranked_date() else {
-                return false;
-            };
-            matches &= criteria.ranked_date.contains(datetime.date());
-        }
+        matches_top_criteria(&self.score, &self.map, self.stars, criteria)
+    }
+}
 
-        let attrs = self.map.attributes().mods(self.score.mods.clone()).build();
-
-        matches &= criteria.ar.contains(attrs.ar as f32);
-        matches &= criteria.cs.contains(attrs.cs as f32);
-        matches &= criteria.hp.contains(attrs.hp as f32);
-        matches &= criteria.od.contains(attrs.od as f32);
-
-        let keys = [
-            (GameModIntermode::OneKey, 1.0),
-            (GameModIntermode::TwoKeys, 2.0),
-            (GameModIntermode::ThreeKeys, 3.0),
-            (GameModIntermode::FourKeys, 4.0),
-            (GameModIntermode::FiveKeys, 5.0),
-            (GameModIntermode::SixKeys, 6.0),
-            (GameModIntermode::SevenKeys, 7.0),
-            (GameModIntermode::EightKeys, 8.0),
-            (GameModIntermode::NineKeys, 9.0),
-            (GameModIntermode::TenKeys, 10.0),
-        ]
-        .into_iter()
-        .find_map(|(gamemod, keys)| self.score.mods.contains_intermode(gamemod).then_some(keys))
-        .unwrap_or(attrs.cs as f32);
-
-        matches &= self.map.mode() != GameMode::Mania || criteria.keys.contains(keys);
-
-        if !matches
-            || (criteria.length.is_empty()
-                && criteria.bpm.is_empty()
-                && criteria.artist.is_empty()
-                && criteria.creator.is_empty()
-                && criteria.version.is_empty()
-                && criteria.title.is_empty()
-                && !criteria.has_search_terms())
-        {
-            return matches;
-        }
+impl<'q> Searchable<TopCriteria<'q>> for ScoreEmbedData {
+    fn matches(&self, criteria: &FilterCriteria<TopCriteria<'q>>) -> bool {
+        matches_top_criteria(&self.score, &self.map, self.stars, criteria)
+    }
+}
 
-        let clock_rate = attrs.clock_rate as f32;
-        matches &= criteria
-            .length
-            .contains(self.map.seconds_drain() as f32 / clock_rate);
-        matches &= criteria.bpm.contains(self.map.bpm() * clock_rate);
-
-        if !matches
-            || (criteria.artist.is_empty()
-                && criteria.creator.is_empty()
-                && criteria.title.is_empty()
-                && criteria.version.is_empty()
-                && !criteria.has_search_terms())
-        {
-            return matches;
-        }
+fn matches_top_criteria(
+    score: &ScoreSlim,
+    map: &OsuMap,
+    stars: f32,
+    criteria: &FilterCriteria<TopCriteria<'_>>,
+) -> bool {
+    let mut matches = true;
+
+    matches &= criteria.combo.contains(score.max_combo);
+    matches &= criteria.miss.contains(score.statistics.count_miss);
+    matches &= criteria.score.contains(score.score);
+    matches &= criteria.date.contains(score.ended_at.date());
+    matches &= criteria.stars.contains(stars);
+    matches &= criteria.pp.contains(score.pp);
+    matches &= criteria.acc.contains(score.accuracy);
+
+    if let Some(ref mods) = criteria.mods {
+        matches &= mods.filter_mods(&score.mods);
+    }
 
-        let artist = self.map.artist().cow_to_ascii_lowercase();
-        matches &= criteria.artist.matches(&artist);
+    if !criteria.ranked_date.is_empty() {
+        let Some(datetime) = map.ranked_date() else {
+            return false;
+        };
+        matches &= criteria.ranked_date.contains(datetime.date());
+    }
 
-        let creator = self.map.creator().cow_to_ascii_lowercase();
-        matches &= criteria.creator.matches(&creator);
+    let attrs = map.attributes().mods(score.mods.clone()).build();
+
+    matches &= criteria.ar.contains(attrs.ar as f32);
+    matches &= criteria.cs.contains(attrs.cs as f32);
+    matches &= criteria.hp.contains(attrs.hp as f32);
+    matches &= criteria.od.contains(attrs.od as f32);
+
+    let keys = [
+        (GameModIntermode::OneKey, 1.0),
+        (GameModIntermode::TwoKeys, 2.0),
+        (GameModIntermode::ThreeKeys, 3.0),
+        (GameModIntermode::FourKeys, 4.0),
+        (GameModIntermode::FiveKeys, 5.0),
+        (GameModIntermode::SixKeys, 6.0),
+        (GameModIntermode::SevenKeys, 7.0),
+        (GameModIntermode::EightKeys, 8.0),
+        (GameModIntermode::NineKeys, 9.0),
+        (GameModIntermode::TenKeys, 10.0),
+    ]
+    .into_iter()
+    .find_map(|(gamemod, keys)| score.mods.contains_intermode(gamemod).then_some(keys))
+    .unwrap_or(attrs.cs as f32);
+
+    matches &= map.mode() != GameMode::Mania || criteria.keys.contains(keys);
+
+    if !matches
+        || (criteria.length.is_empty()
+            && criteria.bpm.is_empty()
+            && criteria.artist.is_empty()
+            && criteria.creator.is_empty()
+            && criteria.version.is_empty()
+            && criteria.title.is_empty()
+            && !criteria.has_search_terms())
+    {
+        return matches;
+    }
 
-        let version = self.map.version().cow_to_ascii_lowercase();
-        matches &= criteria.version.matches(&version);
+    let clock_rate = attrs.clock_rate as f32;
+    matches &= criteria
+        .length
+        .contains(map.seconds_drain() as f32 / clock_rate);
+    matches &= criteria.bpm.contains(map.bpm() * clock_rate);
+
+    if !matches
+        || (criteria.artist.is_empty()
+            && criteria.creator.is_empty()
+            && criteria.title.is_empty()
+            && criteria.version.is_empty()
+            && !criteria.has_search_terms())
+    {
+        return matches;
+    }
 
-        let title = self.map.title().cow_to_ascii_lowercase();
-        matches &= criteria.title.matches(&title);
+    let artist = map.artist().cow_to_ascii_lowercase();
+    matches &= criteria.artist.matches(&artist);
 
-        if matches && criteria.has_search_terms() {
-            let terms = [artist, creator, version, title];
+    let creator = map.creator().cow_to_ascii_lowercase();
+    matches &= criteria.creator.matches(&creator);
 
-            matches &= criteria
-                .search_terms()
-                .all(|term| terms.iter().any(|searchable| searchable.contains(term)))
-        }
+    let version = map.version().cow_to_ascii_lowercase();
+    matches &= criteria.version.matches(&version);
 
-        matches
+    let title = map.title().cow_to_ascii_lowercase();
+    matches &= criteria.title.matches(&title);
+
+    if matches && criteria.has_search_terms() {
+        let terms = [artist, creator, version, title];
+
+        matches &= criteria
+            .search_terms()
+            .all(|term| terms.iter().any(|searchable| searchable.contains(term)))
     }
+
+    matches
 }
@@ -0,0 +1,469 @@
+use std::fmt::Write;
+
+use bathbot_psql::model::configs::{GuildSkin, NewGuildSkin};
+use bathbot_util::{
+    constants::GENERAL_ISSUE, CowUtils, EmbedBuilder, FooterBuilder, MessageBuilder,
+};
+use eyre::Result;
+use twilight_interactions::command::{CommandModel, CreateCommand};
+
+use super::{SkinValidation, ValidationStatus};
+use crate::{
+    active::{impls::GuildSkinsPagination, ActiveMessages},
+    core::commands::checks::check_authority,
+    util::{
+        interaction::InteractionCommand,
+        query::{FilterCriteria, IFilterCriteria, SkinCriteria},
+        Authored, InteractionCommandExt,
+    },
+    Context,
+};
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "guild",
+    desc = "Manage the skin collection of this server",
+    help = "Manage the skin collection of this server.\n\
+    Each skin has a name, a download link, and optionally an author, \
+    a preview image, and the id of the skin if it's uploaded as custom skin to o!rdr.\n\
+    Server authorities can choose one skin with an o!rdr id as default skin \
+    which will be used for renders of users that did not pick a skin themselves."
+)]
+pub enum SkinGuild {
+    #[command(name = "add")]
+    Add(SkinGuildAdd),
+    #[command(name = "remove")]
+    Remove(SkinGuildRemove),
+    #[command(name = "show")]
+    Show(SkinGuildShow),
+    #[command(name = "list")]
+    List(SkinGuildList),
+    #[command(name = "default")]
+    Default(SkinGuildDefault),
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "add", desc = "Add a skin to the server's collection")]
+pub struct SkinGuildAdd {
+    #[command(max_length = 64, desc = "Name of the skin")]
+    name: String,
+    #[command(
+        desc = "Specify a download link for the skin",
+        help = "Specify a download link for the skin.\n\
+        Must be a URL to a direct-download of an .osk file or of one of the approved sites, \
+        see `/help skin set` for the list of sites."
+    )]
+    url: String,
+    #[command(max_length = 64, desc = "Author of the skin")]
+    author: Option<String>,
+    #[command(
+        min_value = 1,
+        desc = "Id of the skin if it's uploaded as custom skin to o!rdr",
+        help = "Id of the skin if it's uploaded as custom skin to o!rdr.\n\
+        Only skins with an o!rdr id can be used as default render skin of the server."
+    )]
+    ordr_id: Option<i64>,
+    #[command(desc = "Link to a preview image of the skin")]
+    preview: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "remove",
+    desc = "Remove a skin from the server's collection",
+    help = "Remove a skin from the server's collection.\n\
+    Only the user that added the skin or server authorities can remove it."
+)]
+pub struct SkinGuildRemove {
+    #[command(desc = "Name of the skin")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "show", desc = "Show a skin of the server's collection")]
+pub struct SkinGuildShow {
+    #[command(desc = "Name of the skin")]
+    name: String,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(name = "list", desc = "List all skins of the server's collection")]
+pub struct SkinGuildList {
+    #[command(
+        desc = "Specify a search query containing name, author, or added date",
+        help = "Filter out skins similarly as you filter maps in osu! itself.\n\
+        You can specify the name, author, or limit values for the added date.\n\
+        Example: `author=rafis added>2024-01-01 whitecat`"
+    )]
+    query: Option<String>,
+}
+
+#[derive(CommandModel, CreateCommand)]
+#[command(
+    name = "default",
+    desc = "Set or unset the server's default render skin",
+    help = "Set or unset the server's default render skin.\n\
+    The skin must have an o!rdr id. Renders of users that neither set a custom skin \
+    nor chose an official skin other than the default one will use this skin.\n\
+    Only server authorities can use this command."
+)]
+pub struct SkinGuildDefault {
+    #[command(desc = "Name of the skin, leave empty to unset the default skin")]
+    name: Option<String>,
+}
+
+pub(super) async fn process(mut command: InteractionCommand, args: SkinGuild) -> Result<()> {
+    if command.guild_id.is_none() {
+        command
+            .error("Skin collections are only available in servers")
+            .await?;
+
+        return Ok(());
+    }
+
+    match args {
+        SkinGuild::Add(args) => add(&command, args).await,
+        SkinGuild::Remove(args) => remove(&command, args).await,
+        SkinGuild::Show(args) => show(&command, args).await,
+        SkinGuild::List(args) => list(&mut command, args).await,
+        SkinGuild::Default(args) => default(&command, args).await,
+    }
+}
+
+async fn add(command: &InteractionCommand, args: SkinGuildAdd) -> Result<()> {
+    let SkinGuildAdd {
+        name,
+        url,
+        author,
+        ordr_id,
+        preview,
+    } = args;
+
+    let owner = command.user_id()?;
+    let guild = command.guild_id.expect("checked before");
+
+    let name = name.trim();
+
+    if name.is_empty() {
+        command.error("The name must not be empty").await?;
+
+        return Ok(());
+    }
+
+    let preview = preview.as_deref().map(str::trim);
+
+    if preview.is_some_and(|url| !url.starts_with("https://") || url.len() > 256) {
+        let content = "The preview must be a https link of at most 256 characters";
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    match SkinValidation::check(command, &url).await? {
+        ValidationStatus::Continue => {}
+        ValidationStatus::Handled => return Ok(()),
+    }
+
+    let ordr_skin_id = ordr_id.map(|id| id as u32);
+
+    if let (Some(id), Some(ordr)) = (ordr_skin_id, Context::ordr()) {
+        if let Err(err) = ordr.client().custom_skin_info(id).await {
+            debug!(?err, "Failed to get custom skin info for id {id}");
+
+            let content = format!("Could not find a custom o!rdr skin with id `{id}`");
+            command.error(content).await?;
+
+            return Ok(());
+        }
+    }
+
+    let skin = NewGuildSkin {
+        name,
+        author: author.as_deref().map(str::trim),
+        url: &url,
+        ordr_skin_id,
+        preview_url: preview,
+    };
+
+    match Context::guild_skins().add(guild, &skin, owner).await {
+        Ok(true) => {
+            let content = format!("Added skin `{name}` to the server's collection");
+            let builder = MessageBuilder::new().embed(content);
+            command.update(builder).await?;
+
+            Ok(())
+        }
+        Ok(false) => {
+            let content = format!("There already is a skin named `{name}` in this server");
+            command.error(content).await?;
+
+            Ok(())
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+async fn remove(command: &InteractionCommand, args: SkinGuildRemove) -> Result<()> {
+    let owner = command.user_id()?;
+
+    let Some(skin) = get_skin(command, &args.name).await? else {
+        return Ok(());
+    };
+
+    if skin.added_by != owner {
+        match check_authority(owner, command.guild_id).await {
+            Ok(None) => {}
+            Ok(Some(_)) => {
+                let content = "Only the user that added the skin or server authorities can do that";
+                command.error(content).await?;
+
+                return Ok(());
+            }
+            Err(err) => {
+                let _ = command.error(GENERAL_ISSUE).await;
+
+                return Err(err.wrap_err("Failed to check authority status"));
+            }
+        }
+    }
+
+    if let Err(err) = Context::guild_skins().remove(skin.skin_id).await {
+        let _ = command.error(GENERAL_ISSUE).await;
+
+        return Err(err);
+    }
+
+    let mut content = format!("Removed skin `{}` from the server's collection", skin.name);
+
+    if skin.is_default {
+        content.push_str(", the server no longer has a default render skin");
+    }
+
+    let builder = MessageBuilder::new().embed(content);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+async fn show(command: &InteractionCommand, args: SkinGuildShow) -> Result<()> {
+    let Some(skin) = get_skin(command, &args.name).await? else {
+        return Ok(());
+    };
+
+    let mut description = format!("**Download:** {}", skin.url);
+
+    if let Some(ref author) = skin.author {
+        let _ = write!(description, "\n**Author:** {author}");
+    }
+
+    if let Some(id) = skin.ordr_skin_id {
+        let _ = write!(description, "\n**o!rdr id:** `{id}`");
+    }
+
+    if skin.is_default {
+        description.push_str("\n*Default render skin of this server*");
+    }
+
+    let _ = write!(
+        description,
+        "\nAdded by <@{user}> <t:{timestamp}:R>",
+        user = skin.added_by,
+        timestamp = skin.added_at.unix_timestamp(),
+    );
+
+    let mut embed = EmbedBuilder::new()
+        .description(description)
+        .title(skin.name.as_ref())
+        .url(skin.url.as_ref());
+
+    if let Some(preview) = skin.preview_url {
+        embed = embed.image(preview.into_string());
+    } else {
+        embed = embed.footer(FooterBuilder::new("No preview available"));
+    }
+
+    let builder = MessageBuilder::new().embed(embed);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+async fn list(command: &mut InteractionCommand, args: SkinGuildList) -> Result<()> {
+    let guild = command.guild_id.expect("checked before");
+
+    let mut skins = match Context::guild_skins().list(guild).await {
+        Ok(skins) => skins,
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err);
+        }
+    };
+
+    let criteria = args.query.as_deref().map(SkinCriteria::create);
+
+    if let Some(ref criteria) = criteria {
+        filter_skins(&mut skins, criteria);
+    }
+
+    let mut content = String::new();
+
+    if let Some(ref criteria) = criteria {
+        criteria.display(&mut content);
+    }
+
+    if skins.is_empty() {
+        let description = if criteria.is_some() {
+            "No skin of this server matches the query"
+        } else {
+            "This server has no skins yet, you can add some with `/skin guild add`"
+        };
+
+        let builder = MessageBuilder::new().embed(description).content(content);
+        command.update(builder).await?;
+
+        return Ok(());
+    }
+
+    let pagination = GuildSkinsPagination::builder()
+        .entries(skins.into_boxed_slice())
+        .content(content.into_boxed_str())
+        .msg_owner(command.user_id()?)
+        .build();
+
+    ActiveMessages::builder(pagination)
+        .start_by_update(true)
+        .begin(command)
+        .await
+}
+
+async fn default(command: &InteractionCommand, args: SkinGuildDefault) -> Result<()> {
+    let owner = command.user_id()?;
+    let guild = command.guild_id.expect("checked before");
+
+    match check_authority(owner, Some(guild)).await {
+        Ok(None) => {}
+        Ok(Some(content)) => {
+            command.error(content).await?;
+
+            return Ok(());
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            return Err(err.wrap_err("Failed to check authority status"));
+        }
+    }
+
+    let Some(name) = args.name else {
+        return match Context::guild_skins().unset_default(guild).await {
+            Ok(true) => {
+                let content = "Unset the server's default render skin";
+                let builder = MessageBuilder::new().embed(content);
+                command.update(builder).await?;
+
+                Ok(())
+            }
+            Ok(false) => {
+                command
+                    .error("The server has no default render skin")
+                    .await?;
+
+                Ok(())
+            }
+            Err(err) => {
+                let _ = command.error(GENERAL_ISSUE).await;
+
+                Err(err)
+            }
+        };
+    };
+
+    let Some(skin) = get_skin(command, &name).await? else {
+        return Ok(());
+    };
+
+    if skin.ordr_skin_id.is_none() {
+        let content = format!(
+            "The skin `{}` has no o!rdr id so it can't be used for renders",
+            skin.name
+        );
+
+        command.error(content).await?;
+
+        return Ok(());
+    }
+
+    if let Err(err) = Context::guild_skins()
+        .set_default(guild, skin.skin_id)
+        .await
+    {
+        let _ = command.error(GENERAL_ISSUE).await;
+
+        return Err(err);
+    }
+
+    let content = format!("The server's default render skin is now `{}`", skin.name);
+    let builder = MessageBuilder::new().embed(content);
+    command.update(builder).await?;
+
+    Ok(())
+}
+
+/// Responds with an error if the skin could not be found.
+async fn get_skin(command: &InteractionCommand, name: &str) -> Result<Option<GuildSkin>> {
+    let guild = command.guild_id.expect("checked before");
+    let name = name.trim();
+
+    match Context::guild_skins().get(guild, name).await {
+        Ok(Some(skin)) => Ok(Some(skin)),
+        Ok(None) => {
+            let content = format!(
+                "There is no skin named `{name}` in this server.\n\
+                Use `/skin guild list` to see all skins."
+            );
+
+            command.error(content).await?;
+
+            Ok(None)
+        }
+        Err(err) => {
+            let _ = command.error(GENERAL_ISSUE).await;
+
+            Err(err)
+        }
+    }
+}
+
+fn filter_skins(skins: &mut Vec<GuildSkin>, criteria: &FilterCriteria<SkinCriteria<'_>>) {
+    skins.retain(|skin| {
+        let mut matches = true;
+
+        matches &= criteria.added_date.contains(skin.added_at.date());
+
+        let name = skin.name.cow_to_ascii_lowercase();
+        matches &= criteria.name.matches(&name);
+
+        let author = skin
+            .author
+            .as_deref()
+            .map(|author| author.cow_to_ascii_lowercase())
+            .unwrap_or_default();
+
+        matches &= criteria.author.matches(&author);
+
+        if matches && criteria.has_search_terms() {
+            let terms = [name.as_ref(), author.as_ref()];
+
+            matches &= criteria
+                .search_terms()
+                .all(|term| terms.iter().any(|searchable| searchable.contains(term)))
+        }
+
+        matches
+    });
+}
@@ -3,6 +3,7 @@ mod changelog;
 mod commands;
 mod config;
 mod embed_builder;
+mod guild_skin;
 mod invite;
 mod ping;
 mod prefix;
@@ -10,6 +10,7 @@ use twilight_interactions::command::{CommandModel, CreateCommand};
 use twilight_model::id::{marker::UserMarker, Id};
 use url::{SyntaxViolation, Url};
 
+use super::guild_skin::{self, SkinGuild};
 use crate::{
     active::{self, ActiveMessages},
     core::{commands::CommandOrigin, Context},
@@ -17,7 +18,12 @@ use crate::{
 };
 
 #[derive(CommandModel, CreateCommand, SlashCommand)]
-#[command(name = "skin", desc = "Set your own skin or check someone else's")]
+#[command(
+    name = "skin",
+    desc = "Set your own skin or check someone else's",
+    help = "Set your own skin or check someone else's.\n\
+    Servers can also collect skins with `/skin guild`."
+)]
 pub enum Skin {
     #[command(name = "check")]
     Check(CheckSkin),
@@ -27,6 +33,8 @@ pub enum Skin {
     Set(SetSkin),
     #[command(name = "unset")]
     Unset(UnsetSkin),
+    #[command(name = "guild")]
+    Guild(SkinGuild),
 }
 
 pub async fn slash_skin(mut command: InteractionCommand) -> Result<()> {
@@ -35,6 +43,7 @@ pub async fn slash_skin(mut command: InteractionCommand) -> Result<()> {
         Skin::All(args) => args.process(&mut command).await,
         Skin::Set(args) => args.process(&command).await,
         Skin::Unset(args) => args.process(&command).await,
+        Skin::Guild(args) => guild_skin::process(command, args).await,
     }
 }
 
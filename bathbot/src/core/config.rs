@@ -27,6 +27,8 @@ pub struct BotConfig {
     pub owner: Id<UserMarker>,
     pub dev_guild: Id<GuildMarker>,
     pub hl_channel: Id<ChannelMarker>,
+    /// osu!api requests per minute for refreshing server leaderboard stats
+    pub leaderboard_refresh_budget: u16,
 }
 
 #[derive(Debug)]
@@ -43,6 +45,7 @@ pub struct Paths {
 pub struct Server {
     pub port: u16,
     pub public_url: Box<str>,
+    pub twitch_eventsub_secret: Box<str>,
 }
 
 #[derive(Debug)]
@@ -118,6 +121,7 @@ impl BotConfig {
             server: Server {
                 port: env_var("SERVER_PORT")?,
                 public_url: env_var("PUBLIC_URL")?,
+                twitch_eventsub_secret: env_var("TWITCH_EVENTSUB_SECRET")?,
             },
             grades,
             emotes,
@@ -127,6 +131,7 @@ impl BotConfig {
             owner: env_var("OWNER_USER_ID")?,
             dev_guild: env_var("DEV_GUILD_ID")?,
             hl_channel: env_var("HL_IMAGE_CHANNEL")?,
+            leaderboard_refresh_budget: env_var_or("LEADERBOARD_REFRESH_BUDGET", 10)?,
         };
 
         if CONFIG.set(config).is_err() {
@@ -222,6 +227,15 @@ fn env_var<T: EnvKind>(name: &str) -> Result<T> {
     })
 }
 
+/// Same as [`env_var`] but uses the default if the variable is missing.
+fn env_var_or<T: EnvKind>(name: &str, default: T) -> Result<T> {
+    if env::var_os(name).is_none() {
+        return Ok(default);
+    }
+
+    env_var(name)
+}
+
 trait AsUsize {
     fn to_usize(self) -> usize;
 }
@@ -4,8 +4,9 @@ use rosu_v2::prelude::GameMode;
 use super::Context;
 use crate::manager::{
     redis::RedisManager, ApproxManager, BookmarkManager, GameManager, GithubManager,
-    GuildConfigManager, HuismetbenenCountryManager, MapManager, OsuMap, OsuUserManager, PpManager,
-    ReplayManager, ScoresManager, UserConfigManager,
+    GuildConfigManager, GuildSkinManager, HuismetbenenCountryManager, MapManager, MappoolManager,
+    MatchCostSeriesManager, OsuMap, OsuUserManager, PpManager, ReplayManager, ScoresManager,
+    UserConfigManager,
 };
 
 impl Context {
@@ -53,6 +54,18 @@ impl Context {
         BookmarkManager::new()
     }
 
+    pub fn guild_skins() -> GuildSkinManager {
+        GuildSkinManager::new()
+    }
+
+    pub fn mappools() -> MappoolManager {
+        MappoolManager::new()
+    }
+
+    pub fn match_cost_series() -> MatchCostSeriesManager {
+        MatchCostSeriesManager::new()
+    }
+
     pub fn replay() -> ReplayManager {
         let ctx = Self::get();
 
@@ -1,15 +1,21 @@
-use std::slice;
-
-use hashbrown::hash_map::Entry;
-use rosu_v2::prelude::{MatchEvent, OsuError};
+use std::{mem, slice};
+
+use bathbot_psql::model::osu::{Mappool, TrackedMatchChannel};
+use bathbot_util::IntHasher;
+use eyre::{Result, WrapErr};
+use hashbrown::{hash_map::Entry, HashMap};
+use rosu_v2::prelude::{MatchEvent, OsuError, OsuMatch, OsuResult};
+use smallvec::SmallVec;
 use tokio::time::{interval, Duration};
-use twilight_model::id::{marker::ChannelMarker, Id};
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
 
 use super::Context;
 use crate::{
     embeds::MatchLiveEmbed,
     matchlive::{send_match_messages, Channel, MatchEntry, MatchTrackResult, TrackedMatch},
-    util::ChannelExt,
 };
 
 impl Context {
@@ -31,7 +37,13 @@ impl Context {
             .map(|(key, _)| *key)
     }
 
-    pub async fn add_match_track(channel: Id<ChannelMarker>, match_id: u32) -> MatchTrackResult {
+    /// The mappool is only used if the match is not already tracked with a
+    /// mappool in another channel.
+    pub async fn add_match_track(
+        channel: Id<ChannelMarker>,
+        match_id: u32,
+        mappool: Option<Mappool>,
+    ) -> MatchTrackResult {
         let mut match_live = Context::get().data.matchlive.inner.lock().await;
 
         // Increment the track counter for the channel
@@ -55,6 +67,10 @@ impl Context {
                     return MatchTrackResult::Duplicate;
                 }
 
+                if entry.tracked.mappool.is_none() {
+                    entry.tracked.mappool = mappool;
+                }
+
                 let embeds = &entry.tracked.embeds;
 
                 let channel = match send_match_messages(channel, embeds).await {
@@ -66,6 +82,10 @@ impl Context {
                     }
                 };
 
+                if let Err(err) = store_match_channel(&entry.tracked, &channel).await {
+                    warn!(?err, "Failed to store tracked match");
+                }
+
                 let id = channel.id;
                 entry.channels.push(channel);
                 *match_live.channel_count.entry(id).or_insert(0) += 1;
@@ -75,7 +95,7 @@ impl Context {
             // The match is not yet tracked -> request and store it
             Entry::Vacant(e) => match Context::osu().osu_match(match_id).await {
                 Ok(osu_match) => {
-                    let embeds = MatchLiveEmbed::new(&osu_match);
+                    let embeds = MatchLiveEmbed::new(&osu_match, mappool.as_ref());
 
                     let channel = match send_match_messages(channel, &embeds).await {
                         Ok(msg) => Channel::new(channel, msg),
@@ -88,7 +108,12 @@ impl Context {
 
                     // Only add to tracking if it's not already disbanded
                     if !matches!(osu_match.events.last(), Some(MatchEvent::Disbanded { .. })) {
-                        let tracked_match = TrackedMatch::new(osu_match, embeds);
+                        let tracked_match = TrackedMatch::new(osu_match, embeds, mappool);
+
+                        if let Err(err) = store_match_channel(&tracked_match, &channel).await {
+                            warn!(?err, "Failed to store tracked match");
+                        }
+
                         let id = channel.id;
                         e.insert(MatchEntry::new(tracked_match, channel));
                         *match_live.channel_count.entry(id).or_insert(0) += 1;
@@ -125,6 +150,12 @@ impl Context {
                     e.remove();
                 }
 
+                let delete_fut = Context::psql().delete_tracked_match_channel(match_id, channel);
+
+                if let Err(err) = delete_fut.await {
+                    warn!(?err, "Failed to remove tracked match from DB");
+                }
+
                 // Decrement the counter for the channel
                 match_live
                     .channel_count
@@ -142,6 +173,10 @@ impl Context {
     async fn remove_all_match_tracks(&self, match_id: u32) -> usize {
         let mut match_live = self.data.matchlive.inner.lock().await;
 
+        if let Err(err) = Context::psql().delete_tracked_match(match_id).await {
+            warn!(match_id, ?err, "Failed to remove tracked match from DB");
+        }
+
         if let Some(entry) = match_live.match_channels.remove(&match_id) {
             for Channel { id, .. } in &entry.channels {
                 match_live
@@ -157,6 +192,15 @@ impl Context {
     }
 
     pub async fn match_live_loop() {
+        let ctx = Context::get();
+
+        // Pick up all matches that were tracked before the last shutdown
+        let count = ctx.resume_match_tracks().await;
+
+        if count > 0 {
+            info!("Resumed tracking {count} matches");
+        }
+
         // Update all matches every 10 seconds
         let mut interval = interval(Duration::from_secs(10));
         interval.tick().await;
@@ -164,9 +208,6 @@ impl Context {
         // Match ids of matches that finished this iteration
         let mut remove = Vec::new();
 
-        let ctx = Context::get();
-        let http = Context::http();
-
         loop {
             interval.tick().await;
 
@@ -193,13 +234,14 @@ impl Context {
                         .embeds
                         .last_mut()
                         .expect("no last live embed")
-                        .update(&next_match);
+                        .update(&next_match, tracked_match.mappool.as_ref());
 
                     if next_match.end_time.is_some() {
                         remove.push(next_match.match_id);
                     }
 
-                    tracked_match.osu_match = next_match;
+                    let prev_match = mem::replace(&mut tracked_match.osu_match, next_match);
+                    let osu_match = &tracked_match.osu_match;
 
                     // If there was an update for the last embed
                     if update {
@@ -207,23 +249,7 @@ impl Context {
 
                         // For every channel that's tracking the match
                         for Channel { id, msg_id } in entry.channels.iter() {
-                            let embed = Some(data.as_embed());
-
-                            // Update the last message
-                            let update_result = http
-                                .update_message(*id, *msg_id)
-                                .embeds(embed.as_ref().map(slice::from_ref));
-
-                            let update_fut = match update_result {
-                                Ok(update_fut) => update_fut,
-                                Err(err) => {
-                                    warn!(?err, "Failed to build msg update");
-
-                                    continue;
-                                }
-                            };
-
-                            if let Err(err) = update_fut.await {
+                            if let Err(err) = update_match_message(*id, *msg_id, data).await {
                                 warn!(?err, "Failed to update msg");
                             }
                         }
@@ -233,7 +259,19 @@ impl Context {
                     if let Some(embeds) = new_embeds {
                         for Channel { id, msg_id } in entry.channels.iter_mut() {
                             match send_match_messages(*id, &embeds).await {
-                                Ok(msg) => *msg_id = msg,
+                                Ok(msg) => {
+                                    *msg_id = msg;
+
+                                    let store_fut = Context::psql().update_tracked_match_msg(
+                                        osu_match.match_id,
+                                        *id,
+                                        msg,
+                                    );
+
+                                    if let Err(err) = store_fut.await {
+                                        warn!(?err, "Failed to store tracked match msg");
+                                    }
+                                }
                                 Err(err) => {
                                     error!(channel = id.get(), ?err, "Failed to send last msg")
                                 }
@@ -242,6 +280,21 @@ impl Context {
 
                         tracked_match.embeds.extend(embeds);
                     }
+
+                    let progressed = prev_match.latest_event_id != osu_match.latest_event_id
+                        || prev_match.current_game_id != osu_match.current_game_id;
+
+                    if progressed {
+                        let store_fut = Context::psql().update_tracked_match_progress(
+                            osu_match.match_id,
+                            osu_match.latest_event_id,
+                            osu_match.current_game_id,
+                        );
+
+                        if let Err(err) = store_fut.await {
+                            warn!(?err, "Failed to store tracked match progress");
+                        }
+                    }
                 }
             }
 
@@ -254,24 +307,205 @@ impl Context {
         }
     }
 
-    pub async fn notify_match_live_shutdown(&self) -> usize {
+    /// Restores all match tracks that were stored in the database and
+    /// returns for how many matches the tracking was resumed.
+    ///
+    /// Messages are not sent again, instead the tracking continues from the
+    /// last processed event so that the next iteration of the loop sends
+    /// only what was missed in the meantime.
+    async fn resume_match_tracks(&self) -> usize {
+        let tracks = match Context::psql().select_tracked_matches().await {
+            Ok(tracks) => tracks,
+            Err(err) => {
+                error!(?err, "Failed to get tracked matches");
+
+                return 0;
+            }
+        };
+
+        let mut tracks_by_match: HashMap<u32, Vec<TrackedMatchChannel>, IntHasher> =
+            HashMap::default();
+
+        for track in tracks {
+            tracks_by_match
+                .entry(track.match_id)
+                .or_default()
+                .push(track);
+        }
+
         let mut match_live = self.data.matchlive.inner.lock().await;
-        match_live.match_channels.clear();
 
-        let content = "I'm about to reboot so the match tracking will be aborted, \
-            you can restart it in just a moment...";
+        for (match_id, tracks) in tracks_by_match {
+            // All channels share the same progress but just in case they
+            // don't, take the earliest one so that no event is skipped
+            let last_event_id = tracks
+                .iter()
+                .map(|track| track.last_event_id)
+                .min()
+                .unwrap_or(0);
+
+            let osu_match = match request_match_until(match_id, last_event_id).await {
+                Ok(osu_match) => osu_match,
+                Err(err) => {
+                    warn!(match_id, ?err, "Failed to request match to resume tracking");
+
+                    if let Err(err) = Context::psql().delete_tracked_match(match_id).await {
+                        warn!(match_id, ?err, "Failed to remove tracked match from DB");
+                    }
+
+                    continue;
+                }
+            };
+
+            let mappool = match tracks.iter().find_map(|track| track.mappool_id) {
+                Some(pool_id) => match Context::psql().select_mappool_by_id(pool_id).await {
+                    Ok(mappool) => mappool,
+                    Err(err) => {
+                        warn!(match_id, ?err, "Failed to get mappool of tracked match");
+
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            let embeds = MatchLiveEmbed::new(&osu_match, mappool.as_ref());
+
+            let Some(last) = embeds.last() else {
+                warn!(match_id, "No events left to resume match tracking");
+
+                if let Err(err) = Context::psql().delete_tracked_match(match_id).await {
+                    warn!(match_id, ?err, "Failed to remove tracked match from DB");
+                }
 
-        let mut notified = 0;
+                continue;
+            };
 
-        for (channel, count) in match_live.channel_count.iter() {
-            if *count > 0 {
-                let _ = channel.plain_message(content).await;
-                notified += 1;
+            let mut channels = SmallVec::with_capacity(tracks.len());
+
+            for track in tracks {
+                // The last message showed a game that was still in progress
+                // so its content may have changed since then
+                if track.last_game_id.is_some() {
+                    if let Err(err) =
+                        update_match_message(track.channel_id, track.msg_id, last).await
+                    {
+                        warn!(?err, "Failed to update msg of resumed match");
+                    }
+                }
+
+                *match_live
+                    .channel_count
+                    .entry(track.channel_id)
+                    .or_insert(0) += 1;
+
+                channels.push(Channel::new(track.channel_id, track.msg_id));
             }
+
+            let entry = MatchEntry {
+                tracked: TrackedMatch::new(osu_match, embeds, mappool),
+                channels,
+            };
+
+            match_live.match_channels.insert(match_id, entry);
         }
 
+        match_live.match_channels.len()
+    }
+
+    /// Stores the current state of all tracked matches so that tracking can be
+    /// resumed after the reboot. Returns for how many channels the state was
+    /// stored.
+    pub async fn store_match_live_tracks(&self) -> usize {
+        let mut match_live = self.data.matchlive.inner.lock().await;
+        let mut stored = 0;
+
+        for entry in match_live.match_channels.values() {
+            for channel in entry.channels.iter() {
+                if let Err(err) = store_match_channel(&entry.tracked, channel).await {
+                    warn!(?err, "Failed to store tracked match");
+                } else {
+                    stored += 1;
+                }
+            }
+        }
+
+        match_live.match_channels.clear();
         match_live.channel_count.clear();
 
-        notified
+        stored
+    }
+}
+
+/// Requests the match and discards all events that happened after the given
+/// event id.
+///
+/// The latest event id will be set to the given id so that
+/// [`OsuMatch::get_next`] retrieves the remaining events afterwards.
+async fn request_match_until(match_id: u32, last_event_id: u64) -> OsuResult<OsuMatch> {
+    let osu = Context::osu();
+    let mut osu_match = osu.osu_match(match_id).await?;
+
+    // Retrieve at most 500 previous events in case
+    // the match progressed a lot in the meantime
+    for _ in 0..5 {
+        let reached_last = osu_match
+            .events
+            .first()
+            .map_or(true, |event| event.event_id() <= last_event_id);
+
+        if reached_last {
+            break;
+        }
+
+        match osu_match.get_previous(osu).await {
+            Some(Ok(mut prev)) => {
+                prev.events.append(&mut osu_match.events);
+                mem::swap(&mut prev.events, &mut osu_match.events);
+                osu_match.users.extend(prev.users);
+            }
+            Some(Err(err)) => return Err(err),
+            None => break,
+        }
     }
+
+    osu_match
+        .events
+        .retain(|event| event.event_id() <= last_event_id);
+
+    osu_match.latest_event_id = last_event_id;
+
+    Ok(osu_match)
+}
+
+async fn update_match_message(
+    channel: Id<ChannelMarker>,
+    msg: Id<MessageMarker>,
+    data: &MatchLiveEmbed,
+) -> Result<()> {
+    let embed = data.as_embed();
+
+    Context::http()
+        .update_message(channel, msg)
+        .embeds(Some(slice::from_ref(&embed)))
+        .wrap_err("Failed to build msg update")?
+        .await
+        .wrap_err("Failed to update msg")?;
+
+    Ok(())
+}
+
+async fn store_match_channel(tracked: &TrackedMatch, channel: &Channel) -> Result<()> {
+    let osu_match = &tracked.osu_match;
+
+    Context::psql()
+        .upsert_tracked_match(
+            osu_match.match_id,
+            channel.id,
+            channel.msg_id,
+            osu_match.latest_event_id,
+            osu_match.current_game_id,
+            tracked.mappool.as_ref().map(|pool| pool.pool_id),
+        )
+        .await
 }
@@ -44,7 +44,7 @@ use super::{
 };
 use crate::{
     active::{impls::BackgroundGame, ActiveMessages},
-    tracking::Ordr,
+    tracking::{LeaderboardRefresh, Ordr},
 };
 
 mod games;
@@ -60,10 +60,8 @@ mod matchlive;
 #[cfg(feature = "twitchtracking")]
 mod twitch;
 
-type PapayaSet<K, S> = PapayaMap<K, (), S>; // TODO: await native support for sets
 type GuildShards = PapayaMap<Id<GuildMarker>, u64>;
 type GuildConfigs = PapayaMap<Id<GuildMarker>, GuildConfig, IntHasher>;
-type MissAnalyzerGuilds = PapayaSet<Id<GuildMarker>, IntHasher>;
 
 #[cfg(feature = "twitchtracking")]
 type TrackedStreams = PapayaMap<u64, Vec<Id<ChannelMarker>>, IntHasher>;
@@ -125,6 +123,10 @@ impl Context {
         &Self::get().clients.psql
     }
 
+    pub fn leaderboard_refresh() -> &'static LeaderboardRefresh {
+        &Self::get().data.leaderboard_refresh
+    }
+
     #[cfg(feature = "osutracking")]
     pub fn tracking() -> &'static crate::tracking::OsuTracking {
         &Self::get().data.osu_tracking
@@ -139,14 +141,6 @@ impl Context {
         &self.data.guild_shards
     }
 
-    pub fn miss_analyzer_guilds() -> &'static MissAnalyzerGuilds {
-        &Self::get().data.miss_analyzer_guilds
-    }
-
-    pub fn has_miss_analyzer(guild: &Id<GuildMarker>) -> bool {
-        Self::miss_analyzer_guilds().pin().contains_key(guild)
-    }
-
     #[cfg(feature = "twitch")]
     pub fn online_twitch_streams() -> &'static crate::tracking::OnlineTwitchStreams {
         &Self::get().data.online_twitch_streams
@@ -252,9 +246,10 @@ impl Context {
         let shard_senders = RwLock::new(shard_senders);
 
         #[cfg(feature = "server")]
-        let (auth_standby, server_tx) = bathbot_server(config, _prometheus, reader.clone())
-            .await
-            .wrap_err("Failed to create server")?;
+        let (auth_standby, server_tx, twitch_events) =
+            bathbot_server(config, _prometheus, reader.clone())
+                .await
+                .wrap_err("Failed to create server")?;
 
         let clients = Clients {
             http,
@@ -287,6 +282,8 @@ impl Context {
             shards,
             #[cfg(feature = "server")]
             server_tx,
+            #[cfg(feature = "server")]
+            twitch_events,
         ))
     }
 
@@ -350,7 +347,11 @@ impl Context {
 pub type ContextResult = (Vec<Shard>,);
 
 #[cfg(feature = "server")]
-pub type ContextResult = (Vec<Shard>, tokio::sync::oneshot::Sender<()>);
+pub type ContextResult = (
+    Vec<Shard>,
+    tokio::sync::oneshot::Sender<()>,
+    tokio::sync::mpsc::UnboundedReceiver<bathbot_model::TwitchStreamEvent>,
+);
 
 pub struct MemberRequests {
     pub tx: UnboundedSender<(Id<GuildMarker>, u64)>,
@@ -387,10 +388,10 @@ struct ContextData {
     osu_tracking: crate::tracking::OsuTracking,
     #[cfg(feature = "twitchtracking")]
     tracked_streams: TrackedStreams, // read-heavy
-    guild_configs: GuildConfigs,              // read-heavy
-    guild_shards: GuildShards,                // necessary to request members for a guild
-    miss_analyzer_guilds: MissAnalyzerGuilds, // read-heavy
+    guild_configs: GuildConfigs, // read-heavy
+    guild_shards: GuildShards,   // necessary to request members for a guild
     osutrack_user_notif_timestamps: OsuTrackUserNotifTimestamps,
+    leaderboard_refresh: LeaderboardRefresh,
     #[cfg(feature = "twitch")]
     online_twitch_streams: crate::tracking::OnlineTwitchStreams,
 }
@@ -402,18 +403,16 @@ impl ContextData {
         application_id: Id<ApplicationMarker>,
     ) -> Result<Self> {
         #[cfg(feature = "twitchtracking")]
-        let (guild_configs_res, tracked_streams_res, guild_shards, miss_analyzer_guilds) = tokio::join!(
+        let (guild_configs_res, tracked_streams_res, guild_shards) = tokio::join!(
             psql.select_guild_configs::<IntHasher>(),
             psql.select_tracked_twitch_streams::<IntHasher>(),
             Self::fetch_guild_shards(&cache),
-            Self::fetch_miss_analyzer_guilds(&cache),
         );
 
         #[cfg(not(feature = "twitchtracking"))]
-        let (guild_configs_res, guild_shards, miss_analyzer_guilds) = tokio::join!(
+        let (guild_configs_res, guild_shards) = tokio::join!(
             psql.select_guild_configs::<IntHasher>(),
             Self::fetch_guild_shards(&cache),
-            Self::fetch_miss_analyzer_guilds(&cache),
         );
 
         Ok(Self {
@@ -436,8 +435,8 @@ impl ContextData {
             osu_tracking: crate::tracking::OsuTracking::new(psql)
                 .await
                 .wrap_err("Failed to create osu tracking")?,
-            miss_analyzer_guilds,
             osutrack_user_notif_timestamps: OsuTrackUserNotifTimestamps::default(),
+            leaderboard_refresh: LeaderboardRefresh::default(),
             #[cfg(feature = "twitch")]
             online_twitch_streams: crate::tracking::OnlineTwitchStreams::default(),
         })
@@ -456,26 +455,6 @@ impl ContextData {
             }
         }
     }
-
-    async fn fetch_miss_analyzer_guilds(cache: &Cache) -> MissAnalyzerGuilds {
-        let fetch_fut =
-            cache.fetch::<_, Vec<With<Id<GuildMarker>, IdRkyv>>>("miss_analyzer_guilds");
-
-        match fetch_fut.await {
-            Ok(Ok(miss_analyzer_guilds)) => {
-                miss_analyzer_guilds.iter().map(|id| (*id, ())).collect()
-            }
-            Ok(Err(_)) => MissAnalyzerGuilds::default(),
-            Err(err) => {
-                warn!(
-                    ?err,
-                    "Failed to fetch miss analyzer guilds, creating default..."
-                );
-
-                MissAnalyzerGuilds::default()
-            }
-        }
-    }
 }
 
 struct Games {
@@ -595,6 +574,7 @@ async fn bathbot_server(
 ) -> Result<(
     Arc<bathbot_server::AuthenticationStandby>,
     tokio::sync::oneshot::Sender<()>,
+    tokio::sync::mpsc::UnboundedReceiver<bathbot_model::TwitchStreamEvent>,
 )> {
     let builder = bathbot_server::AppStateBuilder {
         website_path: config.paths.website.clone(),
@@ -604,12 +584,13 @@ async fn bathbot_server(
         osu_client_secret: config.tokens.osu_client_secret.to_string(),
         twitch_client_id: config.tokens.twitch_client_id.to_string(),
         twitch_token: config.tokens.twitch_token.to_string(),
+        twitch_eventsub_secret: config.server.twitch_eventsub_secret.to_string(),
         redirect_base: config.server.public_url.to_string(),
     };
 
-    let (server, standby, tx) = bathbot_server::Server::new(builder)?;
+    let (server, standby, tx, twitch_rx) = bathbot_server::Server::new(builder)?;
 
     tokio::spawn(server.run(config.server.port));
 
-    Ok((standby, tx))
+    Ok((standby, tx, twitch_rx))
 }
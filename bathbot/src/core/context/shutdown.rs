@@ -21,16 +21,18 @@ impl Context {
         #[cfg(feature = "osutracking")]
         Context::tracking().set_stop_tracking(true);
 
-        // Prevent non-minimized msgs from getting minimized
-        this.active_msgs.clear().await;
+        // Prevent non-minimized msgs from getting minimized and store the
+        // ones that can be restored after the restart
+        let count = this.active_msgs.persist().await;
+        info!("Persisted {count} active messages");
 
         let count = Context::stop_all_games().await;
         info!("Stopped {count} bg games");
 
         #[cfg(feature = "matchlive")]
         {
-            let count = this.notify_match_live_shutdown().await;
-            info!("Stopped match tracking in {count} channels");
+            let count = this.store_match_live_tracks().await;
+            info!("Stored match tracking for {count} channels");
         }
 
         if let Some(ordr) = Context::ordr() {
@@ -49,11 +51,6 @@ impl Context {
             Ok(len) => info!("Stored {len} guild shards"),
             Err(err) => error!(?err, "Failed to store guild shards"),
         }
-
-        match Context::store_miss_analyzer_guilds(STORE_DURATION).await {
-            Ok(len) => info!("Stored {len} miss analyzer guilds"),
-            Err(err) => error!(?err, "Failed to store miss analyzer guilds"),
-        }
     }
 
     /// Notify all active bg games that they'll be aborted due to a bot restart
@@ -122,31 +119,6 @@ impl Context {
         Self::finalize_store_as_vec(serializer, len, "guild_shards", store_duration).await
     }
 
-    #[cold]
-    async fn store_miss_analyzer_guilds(store_duration: u64) -> Result<usize> {
-        let mut serializer = AllocSerializer::<0>::default();
-
-        // Will be serialized as ArchivedVec
-        let miss_analyzer_guilds = Self::miss_analyzer_guilds().pin();
-        let len = miss_analyzer_guilds.len();
-
-        // Serialize data
-        for guild in miss_analyzer_guilds.keys() {
-            serializer
-                .serialize_value(With::<_, IdRkyv>::cast(guild))
-                .wrap_err("Failed to serialize guild")?;
-        }
-
-        type ArchivedData = ArchivedVec<<With<Id<GuildMarker>, IdRkyv> as Archive>::Archived>;
-
-        // Align buffer
-        serializer
-            .align_for::<ArchivedData>()
-            .wrap_err("Failed to align serializer")?;
-
-        Self::finalize_store_as_vec(serializer, len, "miss_analyzer_guilds", store_duration).await
-    }
-
     // Does not include serializer alignment to avoid generics
     async fn finalize_store_as_vec<const N: usize>(
         mut serializer: AllocSerializer<N>,
@@ -2,7 +2,6 @@ use std::fmt::{Display, Formatter, Result as FmtResult};
 
 use bathbot_cache::model::CachedArchive;
 use bathbot_model::twilight_model::{channel::Channel, guild::Guild};
-use bathbot_util::constants::MISS_ANALYZER_ID;
 use eyre::Result;
 use futures::StreamExt;
 use tokio::sync::mpsc::Receiver;
@@ -223,20 +222,6 @@ async fn handle_event(event: Event, shard_id: u64) -> Result<()> {
             }
         }
         Event::InteractionCreate(e) => handle_interaction(e.0).await,
-        Event::MemberAdd(e) if e.member.user.id == MISS_ANALYZER_ID => {
-            Context::miss_analyzer_guilds().pin().insert(e.guild_id, ());
-        }
-        Event::MemberChunk(e) => {
-            if e.members
-                .iter()
-                .any(|member| member.user.id == MISS_ANALYZER_ID)
-            {
-                Context::miss_analyzer_guilds().pin().insert(e.guild_id, ());
-            }
-        }
-        Event::MemberRemove(e) if e.user.id == MISS_ANALYZER_ID => {
-            Context::miss_analyzer_guilds().pin().remove(&e.guild_id);
-        }
         Event::MessageCreate(msg) => handle_message(msg.0).await,
         Event::MessageDelete(e) => {
             Context::get().active_msgs.remove(e.id).await;
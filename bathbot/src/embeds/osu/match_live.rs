@@ -6,6 +6,7 @@ use std::{
     mem,
 };
 
+use bathbot_psql::model::osu::Mappool;
 use bathbot_util::{
     constants::{DESCRIPTION_SIZE, OSU_BASE},
     datetime::SecToMinSec,
@@ -88,7 +89,7 @@ macro_rules! team {
 }
 
 impl MatchLiveEmbed {
-    pub fn new(lobby: &OsuMatch) -> MatchLiveEmbeds {
+    pub fn new(lobby: &OsuMatch, mappool: Option<&Mappool>) -> MatchLiveEmbeds {
         let mut embeds = MatchLiveEmbeds::new();
 
         if lobby.events.is_empty() {
@@ -148,7 +149,7 @@ impl MatchLiveEmbed {
                         }
                     }
 
-                    let (description, image, footer) = game_content(lobby, game);
+                    let (description, image, footer) = game_content(lobby, game, mappool);
                     state = Some(next_state);
 
                     let embed = Self {
@@ -208,7 +209,11 @@ impl MatchLiveEmbed {
         embeds
     }
 
-    pub fn update(&mut self, lobby: &OsuMatch) -> (bool, Option<MatchLiveEmbeds>) {
+    pub fn update(
+        &mut self,
+        lobby: &OsuMatch,
+        mappool: Option<&Mappool>,
+    ) -> (bool, Option<MatchLiveEmbeds>) {
         if lobby.events.is_empty() {
             return (false, None);
         }
@@ -277,7 +282,7 @@ impl MatchLiveEmbed {
                             continue;
                         }
 
-                        let (description, image, footer) = game_content(lobby, game);
+                        let (description, image, footer) = game_content(lobby, game, mappool);
 
                         // Previous game not yet finished but next one already there => override
                         if !state.finished {
@@ -367,7 +372,7 @@ impl MatchLiveEmbed {
                         embed.description.push_str("• **Lobby was closed**")
                     }
                     MatchEvent::Game { game, .. } => {
-                        let (description, image, footer) = game_content(lobby, game);
+                        let (description, image, footer) = game_content(lobby, game, mappool);
 
                         let state = GameState {
                             game_id: game.game_id,
@@ -453,12 +458,25 @@ impl MatchLiveEmbed {
 }
 
 /// Return the description and image for a either in-progress or finished games
+///
+/// If a mappool is given, the game is labeled with the map's slot or flagged
+/// if the map is not part of the pool.
 fn game_content(
     lobby: &OsuMatch,
     game: &MatchGame,
+    mappool: Option<&Mappool>,
 ) -> (String, Option<String>, Option<FooterBuilder>) {
     let mut description = String::with_capacity(128);
 
+    if let Some(mappool) = mappool {
+        match mappool.slot(game.map_id) {
+            Some(slot) => {
+                let _ = write!(description, "`{slot}` ");
+            }
+            None => description.push_str(":warning: `Not in mappool` "),
+        }
+    }
+
     match game.end_time {
         Some(_) => {
             let image = match game.map {
@@ -1,10 +1,11 @@
 use std::fmt::Write;
 
 use bathbot_macros::EmbedData;
+use bathbot_psql::model::osu::TrackKind;
 use bathbot_util::constants::DESCRIPTION_SIZE;
 use rosu_v2::model::GameMode;
 
-use crate::commands::tracking::TracklistUserEntry;
+use crate::{commands::tracking::TracklistUserEntry, tracking::RecentTrackConditions};
 
 #[derive(EmbedData)]
 pub struct TrackListEmbed {
@@ -33,9 +34,13 @@ impl TrackListEmbed {
         }
 
         for (mode, group) in MODES.into_iter().zip(groups) {
-            let mut names = group.into_iter().map(|entry| (entry.name, entry.limit));
+            let mut names = group.into_iter().map(|entry| {
+                let value = entry_value(&entry);
 
-            let Some((first_name, first_limit)) = names.next() else {
+                (entry.name, value, entry.filter)
+            });
+
+            let Some((first_name, first_limit, first_filter)) = names.next() else {
                 continue;
             };
 
@@ -47,7 +52,12 @@ impl TrackListEmbed {
             };
 
             description.reserve(256);
-            let len = description.chars().count() + mode.len() + first_name.chars().count() + 7;
+            let len = description.chars().count()
+                + mode.len()
+                + first_name.chars().count()
+                + first_limit.len()
+                + first_filter.as_deref().map_or(0, filter_len)
+                + 5;
 
             if len > DESCRIPTION_SIZE {
                 embeds.push(Self {
@@ -59,11 +69,16 @@ impl TrackListEmbed {
             }
 
             let _ = writeln!(description, "__**{mode}**__");
-            let _ = write!(description, "`{first_name}` ({first_limit})");
+            let _ = write!(description, "`{first_name}` ({first_limit}");
+            write_filter(&mut description, first_filter.as_deref());
             let mut with_comma = true;
 
-            for (name, limit) in names {
-                let len = description.chars().count() + name.chars().count() + 9;
+            for (name, limit, filter) in names {
+                let len = description.chars().count()
+                    + name.chars().count()
+                    + limit.len()
+                    + filter.as_deref().map_or(0, filter_len)
+                    + 7;
 
                 if len > DESCRIPTION_SIZE {
                     embeds.push(Self {
@@ -78,10 +93,12 @@ impl TrackListEmbed {
 
                 let _ = write!(
                     description,
-                    "{}`{name}` ({limit})",
+                    "{}`{name}` ({limit}",
                     if with_comma { ", " } else { "" },
                 );
 
+                write_filter(&mut description, filter.as_deref());
+
                 with_comma = true;
             }
 
@@ -95,3 +112,42 @@ impl TrackListEmbed {
         embeds
     }
 }
+
+/// The limit for top score tracking or the conditions for recent score tracking,
+/// followed by the milestones, session timeout, and webhook if there are any
+fn entry_value(entry: &TracklistUserEntry) -> String {
+    let mut value = match entry.kind {
+        TrackKind::Top => entry.limit.to_string(),
+        TrackKind::Recent => {
+            format!("recent: {}", RecentTrackConditions::names(entry.limit))
+        }
+    };
+
+    for (i, name) in entry.milestones.names().enumerate() {
+        value.push_str(if i == 0 { ", milestones: " } else { "," });
+        value.push_str(name);
+    }
+
+    if entry.session > 0 {
+        let _ = write!(value, ", session: {}m", entry.session);
+    }
+
+    if entry.webhook {
+        value.push_str(", webhook");
+    }
+
+    value
+}
+
+fn filter_len(filter: &str) -> usize {
+    filter.chars().count() + 4
+}
+
+/// Writes the filter, if any, and closes the parenthesis of the entry
+fn write_filter(description: &mut String, filter: Option<&str>) {
+    if let Some(filter) = filter {
+        let _ = write!(description, ", `{filter}`");
+    }
+
+    description.push(')');
+}
@@ -1,10 +1,16 @@
 use std::fmt::Write;
 
 use bathbot_macros::EmbedData;
+use bathbot_psql::model::osu::TrackKind;
 use bathbot_util::fields;
 use rosu_v2::{model::GameMode, prelude::Username};
 use twilight_model::channel::message::embed::EmbedField;
 
+use crate::{
+    tracking::{RecentTrackConditions, TrackEntryOptions},
+    util::query::{FilterCriteria, TopCriteria},
+};
+
 #[derive(EmbedData)]
 pub struct TrackEmbed {
     fields: Vec<EmbedField>,
@@ -17,10 +23,28 @@ impl TrackEmbed {
         success: Vec<Username>,
         failure: Vec<Username>,
         failed: Option<Username>,
-        limit: u8,
+        kind: TrackKind,
+        options: TrackEntryOptions<'_>,
+        criteria: Option<&FilterCriteria<TopCriteria<'_>>>,
     ) -> Self {
-        let title = format!("Top score tracking | mode={mode} | limit={limit}");
-        let mut fields = Vec::with_capacity(3);
+        let TrackEntryOptions {
+            limit,
+            milestones,
+            session,
+            webhook,
+            ..
+        } = options;
+
+        let title = match kind {
+            TrackKind::Top => format!("Top score tracking | mode={mode} | limit={limit}"),
+            TrackKind::Recent => {
+                let names = RecentTrackConditions::names(limit);
+
+                format!("Recent score tracking | mode={mode} | notify={names}")
+            }
+        };
+
+        let mut fields = Vec::with_capacity(4);
         let mut iter = success.iter();
 
         if let Some(first) = iter.next() {
@@ -53,6 +77,34 @@ impl TrackEmbed {
             fields![fields { "Failed to track:".to_owned(), format!("`{failed}`"), false }];
         }
 
+        if let Some(criteria) = criteria {
+            let mut value = String::new();
+            criteria.display(&mut value);
+            fields![fields { "Filter:".to_owned(), value, false }];
+        }
+
+        let mut names = milestones.names();
+
+        if let Some(first) = names.next() {
+            let mut value = format!("`{first}`");
+
+            for name in names {
+                let _ = write!(value, ", `{name}`");
+            }
+
+            fields![fields { "Milestones:".to_owned(), value, false }];
+        }
+
+        if session > 0 {
+            let value = format!("Summarized per session, ends after {session} idle minutes");
+            fields![fields { "Session:".to_owned(), value, false }];
+        }
+
+        if webhook.is_some() {
+            let value = "Notifications are posted to the webhook instead".to_owned();
+            fields![fields { "Webhook:".to_owned(), value, false }];
+        }
+
         Self { fields, title }
     }
 }
@@ -17,6 +17,7 @@ mod matchlive;
 
 use std::time::Duration;
 
+use ::time::OffsetDateTime;
 use bathbot_model::Countries;
 use eyre::{Report, Result, WrapErr};
 use tokio::{
@@ -65,7 +66,8 @@ async fn async_main() -> Result<()> {
     let (mut shards,) = res;
 
     #[cfg(feature = "server")]
-    let (mut shards, server_tx) = res;
+    #[cfg_attr(not(feature = "twitchtracking"), allow(unused_variables))]
+    let (mut shards, server_tx, twitch_events) = res;
 
     // Initialize commands
     let slash_commands = InteractionCommands::get().collect();
@@ -93,8 +95,14 @@ async fn async_main() -> Result<()> {
 
     #[cfg(feature = "twitchtracking")]
     {
+        #[cfg(feature = "server")]
+        let twitch_events = Some(twitch_events);
+
+        #[cfg(not(feature = "server"))]
+        let twitch_events = None;
+
         // Spawn twitch worker
-        tokio::spawn(tracking::twitch_tracking_loop());
+        tokio::spawn(tracking::twitch_tracking_loop(twitch_events));
     }
 
     #[cfg(feature = "osutracking")]
@@ -109,6 +117,28 @@ async fn async_main() -> Result<()> {
         tokio::spawn(Context::match_live_loop());
     }
 
+    // Spawn server leaderboard refresh worker
+    tokio::spawn(tracking::leaderboard_refresh_loop());
+
+    // Downsample osu! user stats history once a day
+    tokio::spawn(async {
+        let mut interval = time::interval(Duration::from_secs(60 * 60 * 24));
+        let mut last_run = None;
+
+        loop {
+            interval.tick().await;
+            let now = OffsetDateTime::now_utc();
+
+            match Context::osu_user().downsample_stats_history(last_run).await {
+                Ok(deleted) => {
+                    debug!(deleted, "Downsampled osu! user stats history");
+                    last_run = Some(now);
+                }
+                Err(err) => warn!(?err, "Failed to downsample osu! user stats history"),
+            }
+        }
+    });
+
     // Request members
     tokio::spawn(async move {
         let ctx = Context::get();
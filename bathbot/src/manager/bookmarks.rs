@@ -1,4 +1,7 @@
-use bathbot_psql::{model::osu::MapBookmark, Database};
+use bathbot_psql::{
+    model::osu::{BookmarkFolder, MapBookmark},
+    Database,
+};
 use eyre::{Result, WrapErr};
 use twilight_model::id::{marker::UserMarker, Id};
 
@@ -16,9 +19,10 @@ impl BookmarkManager {
         }
     }
 
-    pub async fn get(self, user: Id<UserMarker>) -> Result<Vec<MapBookmark>> {
+    /// If a folder is specified, only bookmarks of that folder are returned.
+    pub async fn get(self, user: Id<UserMarker>, folder: Option<u32>) -> Result<Vec<MapBookmark>> {
         self.psql
-            .select_user_bookmarks(user)
+            .select_user_bookmarks(user, folder)
             .await
             .wrap_err("Failed to get bookmarks")
     }
@@ -30,10 +34,69 @@ impl BookmarkManager {
             .wrap_err("Failed to insert user bookmark")
     }
 
+    /// Bookmarks the maps and adds them to the folder.
+    ///
+    /// Returns the amount of maps that were newly added to the folder.
+    pub async fn add_many(self, user: Id<UserMarker>, map_ids: &[i32], folder: u32) -> Result<u64> {
+        self.psql
+            .insert_user_bookmarks(user, map_ids, folder)
+            .await
+            .wrap_err("Failed to insert user bookmarks")
+    }
+
     pub async fn remove(self, user: Id<UserMarker>, map_id: u32) -> Result<()> {
         self.psql
             .delete_user_bookmark(user, map_id)
             .await
             .wrap_err("Failed to delete user bookmark")
     }
+
+    pub async fn set_note(
+        self,
+        user: Id<UserMarker>,
+        map_id: u32,
+        note: Option<&str>,
+    ) -> Result<()> {
+        self.psql
+            .update_user_bookmark_note(user, map_id, note)
+            .await
+            .wrap_err("Failed to update bookmark note")
+    }
+
+    pub async fn folders(self, user: Id<UserMarker>) -> Result<Vec<BookmarkFolder>> {
+        self.psql
+            .select_bookmark_folders(user)
+            .await
+            .wrap_err("Failed to get bookmark folders")
+    }
+
+    pub async fn folder(self, user: Id<UserMarker>, name: &str) -> Result<Option<BookmarkFolder>> {
+        self.psql
+            .select_bookmark_folder(user, name)
+            .await
+            .wrap_err("Failed to get bookmark folder")
+    }
+
+    /// Returns whether the folder was created i.e. it did not exist yet
+    pub async fn create_folder(self, user: Id<UserMarker>, name: &str) -> Result<bool> {
+        self.psql
+            .insert_bookmark_folder(user, name)
+            .await
+            .wrap_err("Failed to insert bookmark folder")
+    }
+
+    /// Returns whether the folder was deleted
+    pub async fn delete_folder(self, user: Id<UserMarker>, name: &str) -> Result<bool> {
+        self.psql
+            .delete_bookmark_folder(user, name)
+            .await
+            .wrap_err("Failed to delete bookmark folder")
+    }
+
+    pub async fn set_folder_shared(self, folder_id: u32, shared: bool) -> Result<()> {
+        self.psql
+            .update_bookmark_folder_shared(folder_id, shared)
+            .await
+            .wrap_err("Failed to update bookmark folder")
+    }
 }
@@ -1,14 +1,21 @@
 use std::collections::HashMap;
 
-use bathbot_model::{BgGameScore, HlGameScore, HlVersion};
+use bathbot_model::{
+    BgGameScore, BgGameSeasonScore, DailyChallengeStats, DailyGuess, HlGameScore, HlVersion,
+    MapsetTags,
+};
 use bathbot_psql::{
-    model::games::{DbMapTagsParams, MapsetTagsEntries},
+    model::games::{DbMapTag, DbMapTagsParams, MapsetTagsEntries},
     Database,
 };
 use bathbot_util::IntHasher;
 use eyre::{Result, WrapErr};
 use rosu_v2::prelude::GameMode;
-use twilight_model::id::{marker::UserMarker, Id};
+use time::{Date, Duration, OffsetDateTime};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
 
 use crate::core::Context;
 
@@ -63,6 +70,66 @@ impl GameManager {
             .wrap_err("failed to get bggame leaderboard")
     }
 
+    pub async fn bggame_season(
+        self,
+        guild: Id<GuildMarker>,
+    ) -> Result<(i32, Option<OffsetDateTime>)> {
+        self.psql
+            .select_bggame_season(guild)
+            .await
+            .wrap_err("Failed to get bggame season")
+    }
+
+    pub async fn bggame_new_season(self, guild: Id<GuildMarker>) -> Result<i32> {
+        self.psql
+            .insert_bggame_season(guild)
+            .await
+            .wrap_err("Failed to start new bggame season")
+    }
+
+    pub async fn bggame_season_leaderboard(
+        self,
+        guild: Id<GuildMarker>,
+        season: i32,
+    ) -> Result<Vec<BgGameSeasonScore>> {
+        self.psql
+            .select_bggame_guild_scores(guild, season)
+            .await
+            .wrap_err("Failed to get bggame season leaderboard")
+    }
+
+    pub async fn bggame_increment_guild_scores(
+        self,
+        guild: Id<GuildMarker>,
+        scores: &[BgGameSeasonScore],
+    ) -> Result<()> {
+        let mut user_ids = Vec::with_capacity(scores.len());
+        let mut guesses = Vec::with_capacity(scores.len());
+        let mut guess_times = Vec::with_capacity(scores.len());
+        let mut hints = Vec::with_capacity(scores.len());
+        let mut streaks = Vec::with_capacity(scores.len());
+
+        for score in scores {
+            user_ids.push(score.discord_id);
+            guesses.push(score.guesses);
+            guess_times.push(score.guess_time_ms);
+            hints.push(score.hints);
+            streaks.push(score.best_streak);
+        }
+
+        self.psql
+            .increment_bggame_guild_scores(
+                guild,
+                &user_ids,
+                &guesses,
+                &guess_times,
+                &hints,
+                &streaks,
+            )
+            .await
+            .wrap_err("Failed to increment guild scores")
+    }
+
     pub async fn bggame_tags(self, params: DbMapTagsParams) -> Result<MapsetTagsEntries> {
         let mode = params.mode;
 
@@ -104,4 +171,230 @@ impl GameManager {
             .await
             .wrap_err("failed to upsert mapset")
     }
+
+    pub async fn bggame_mapset(self, mapset_id: u32) -> Result<Option<DbMapTag>> {
+        self.psql
+            .select_map_tag(mapset_id)
+            .await
+            .wrap_err("Failed to get mapset tags")
+    }
+
+    pub async fn bggame_mapsets(self, mode: GameMode) -> Result<Vec<DbMapTag>> {
+        self.psql
+            .select_map_tags_by_mode(mode)
+            .await
+            .wrap_err("Failed to get mapsets tags")
+    }
+
+    pub async fn bggame_set_tags(
+        self,
+        changed_by: Id<UserMarker>,
+        entries: &[(u32, MapsetTags)],
+    ) -> Result<u64> {
+        self.psql
+            .update_map_tags(changed_by, entries)
+            .await
+            .wrap_err("Failed to update mapset tags")
+    }
+
+    pub async fn bggame_undo_tags(self, changed_by: Id<UserMarker>) -> Result<Vec<u32>> {
+        self.psql
+            .revert_map_tags(changed_by)
+            .await
+            .wrap_err("Failed to revert mapset tags")
+    }
+
+    pub async fn daily_challenge_mapset(self, day: Date) -> Result<Option<u32>> {
+        self.psql
+            .select_daily_challenge_mapset(day)
+            .await
+            .map(|mapset_id| mapset_id.map(|mapset_id| mapset_id as u32))
+            .wrap_err("Failed to get daily challenge mapset")
+    }
+
+    /// Returns the mapset id that is stored for the day which might differ
+    /// from the given one if it has been set concurrently.
+    pub async fn daily_challenge_set_mapset(self, day: Date, mapset_id: u32) -> Result<u32> {
+        self.psql
+            .insert_daily_challenge_mapset(day, mapset_id as i32)
+            .await
+            .map(|mapset_id| mapset_id as u32)
+            .wrap_err("Failed to store daily challenge mapset")
+    }
+
+    /// Returns the guesses of the user for the day and whether the attempt is
+    /// finished.
+    pub async fn daily_challenge_attempt(
+        self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<Option<(Vec<DailyGuess>, bool)>> {
+        let attempt_opt = self
+            .psql
+            .select_daily_challenge_attempt(user_id, day)
+            .await
+            .wrap_err("Failed to get daily challenge attempt")?;
+
+        let attempt = attempt_opt.map(|attempt| {
+            let guesses = attempt.outcomes.into_iter().map(DailyGuess::from).collect();
+
+            (guesses, attempt.finished)
+        });
+
+        Ok(attempt)
+    }
+
+    /// Stores the guess on top of the `prev_count` guesses that the attempt
+    /// is expected to contain.
+    ///
+    /// Returns `None` if the attempt progressed elsewhere in the meanwhile.
+    pub async fn daily_challenge_store_guess(
+        self,
+        user_id: Id<UserMarker>,
+        day: Date,
+        guess: DailyGuess,
+        prev_count: usize,
+        finished: bool,
+    ) -> Result<Option<(Vec<DailyGuess>, bool)>> {
+        let attempt_opt = self
+            .psql
+            .append_daily_challenge_outcome(user_id, day, guess as i16, prev_count as i32, finished)
+            .await
+            .wrap_err("Failed to store daily challenge guess")?;
+
+        let attempt = attempt_opt.map(|attempt| {
+            let guesses = attempt.outcomes.into_iter().map(DailyGuess::from).collect();
+
+            (guesses, attempt.finished)
+        });
+
+        Ok(attempt)
+    }
+
+    /// Finishes the attempt and returns its guesses.
+    pub async fn daily_challenge_give_up(
+        self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<Vec<DailyGuess>> {
+        self.psql
+            .finish_daily_challenge_attempt(user_id, day)
+            .await
+            .map(|attempt| attempt.outcomes.into_iter().map(DailyGuess::from).collect())
+            .wrap_err("Failed to finish daily challenge attempt")
+    }
+
+    /// Returns `false` if the user already started today's higherlower
+    /// attempt.
+    pub async fn daily_higherlower_start(self, user_id: Id<UserMarker>, day: Date) -> Result<bool> {
+        self.psql
+            .insert_daily_higherlower_attempt(user_id, day)
+            .await
+            .wrap_err("Failed to start daily higherlower attempt")
+    }
+
+    pub async fn daily_higherlower_score(
+        self,
+        user_id: Id<UserMarker>,
+        day: Date,
+    ) -> Result<Option<u32>> {
+        self.psql
+            .select_daily_higherlower_attempt(user_id, day)
+            .await
+            .map(|attempt| attempt.map(|attempt| attempt.score as u32))
+            .wrap_err("Failed to get daily higherlower attempt")
+    }
+
+    pub async fn daily_higherlower_store(
+        self,
+        user_id: Id<UserMarker>,
+        day: Date,
+        score: u32,
+        finished: bool,
+    ) -> Result<()> {
+        self.psql
+            .update_daily_higherlower_attempt(user_id, day, score as i32, finished)
+            .await
+            .wrap_err("Failed to store daily higherlower attempt")
+    }
+
+    /// Returns the user id and score id at the index of the day's
+    /// higherlower chain, if it has been picked already.
+    pub async fn daily_higherlower_entry(self, day: Date, idx: u32) -> Result<Option<(u32, u64)>> {
+        self.psql
+            .select_daily_higherlower_score(day, idx as i16)
+            .await
+            .map(|score| score.map(|score| (score.user_id as u32, score.score_id as u64)))
+            .wrap_err("Failed to get daily higherlower entry")
+    }
+
+    /// Stores the entry at the index of the day's higherlower chain unless
+    /// another one was stored first. Returns the stored user id and score id.
+    pub async fn daily_higherlower_set_entry(
+        self,
+        day: Date,
+        idx: u32,
+        user_id: u32,
+        score_id: u64,
+    ) -> Result<(u32, u64)> {
+        self.psql
+            .insert_daily_higherlower_score(day, idx as i16, user_id as i32, score_id as i64)
+            .await
+            .map(|score| (score.user_id as u32, score.score_id as u64))
+            .wrap_err("Failed to store daily higherlower entry")
+    }
+
+    pub async fn daily_challenge_stats(
+        self,
+        user_id: Id<UserMarker>,
+        today: Date,
+    ) -> Result<DailyChallengeStats> {
+        let attempts = self
+            .psql
+            .select_daily_challenge_attempts(user_id)
+            .await
+            .wrap_err("Failed to get daily challenge attempts")?;
+
+        let mut stats = DailyChallengeStats {
+            played: attempts.len() as u32,
+            solved: 0,
+            current_streak: 0,
+            best_streak: 0,
+        };
+
+        let mut streak = 0;
+        let mut last_solved: Option<Date> = None;
+
+        for attempt in attempts {
+            let solved = attempt
+                .outcomes
+                .last()
+                .is_some_and(|outcome| DailyGuess::from(*outcome) == DailyGuess::Title);
+
+            if !solved {
+                streak = 0;
+
+                continue;
+            }
+
+            stats.solved += 1;
+
+            streak = match last_solved {
+                Some(day) if day + Duration::DAY == attempt.day => streak + 1,
+                _ => 1,
+            };
+
+            last_solved = Some(attempt.day);
+            stats.best_streak = stats.best_streak.max(streak);
+        }
+
+        // The streak is only ongoing if it hasn't been interrupted since
+        let yesterday = today - Duration::DAY;
+
+        if last_solved.is_some_and(|day| day >= yesterday) {
+            stats.current_streak = streak;
+        }
+
+        Ok(stats)
+    }
 }
@@ -0,0 +1,81 @@
+use bathbot_psql::{
+    model::configs::{GuildSkin, NewGuildSkin},
+    Database,
+};
+use eyre::{Result, WrapErr};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::core::Context;
+
+#[derive(Copy, Clone)]
+pub struct GuildSkinManager {
+    psql: &'static Database,
+}
+
+impl GuildSkinManager {
+    pub fn new() -> Self {
+        Self {
+            psql: Context::psql(),
+        }
+    }
+
+    pub async fn list(self, guild: Id<GuildMarker>) -> Result<Vec<GuildSkin>> {
+        self.psql
+            .select_guild_skins(guild)
+            .await
+            .wrap_err("Failed to get skins of guild")
+    }
+
+    pub async fn get(self, guild: Id<GuildMarker>, name: &str) -> Result<Option<GuildSkin>> {
+        self.psql
+            .select_guild_skin(guild, name)
+            .await
+            .wrap_err("Failed to get guild skin")
+    }
+
+    pub async fn default_skin(self, guild: Id<GuildMarker>) -> Result<Option<GuildSkin>> {
+        self.psql
+            .select_guild_default_skin(guild)
+            .await
+            .wrap_err("Failed to get default skin of guild")
+    }
+
+    /// Returns whether the skin was added i.e. its name was not taken yet
+    pub async fn add(
+        self,
+        guild: Id<GuildMarker>,
+        skin: &NewGuildSkin<'_>,
+        user: Id<UserMarker>,
+    ) -> Result<bool> {
+        self.psql
+            .insert_guild_skin(guild, skin, user)
+            .await
+            .wrap_err("Failed to insert guild skin")
+    }
+
+    /// Returns whether the skin was removed
+    pub async fn remove(self, skin_id: u32) -> Result<bool> {
+        self.psql
+            .delete_guild_skin(skin_id)
+            .await
+            .wrap_err("Failed to delete guild skin")
+    }
+
+    pub async fn set_default(self, guild: Id<GuildMarker>, skin_id: u32) -> Result<()> {
+        self.psql
+            .upsert_guild_default_skin(guild, skin_id)
+            .await
+            .wrap_err("Failed to upsert default skin of guild")
+    }
+
+    /// Returns whether the guild had a default skin
+    pub async fn unset_default(self, guild: Id<GuildMarker>) -> Result<bool> {
+        self.psql
+            .delete_guild_default_skin(guild)
+            .await
+            .wrap_err("Failed to delete default skin of guild")
+    }
+}
@@ -0,0 +1,74 @@
+use bathbot_psql::{
+    model::osu::{Mappool, MappoolEntry},
+    Database,
+};
+use eyre::{Result, WrapErr};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::core::Context;
+
+#[derive(Copy, Clone)]
+pub struct MappoolManager {
+    psql: &'static Database,
+}
+
+impl MappoolManager {
+    pub fn new() -> Self {
+        Self {
+            psql: Context::psql(),
+        }
+    }
+
+    pub async fn get(self, guild: Id<GuildMarker>, name: &str) -> Result<Option<Mappool>> {
+        self.psql
+            .select_mappool(guild, name)
+            .await
+            .wrap_err("Failed to get mappool")
+    }
+
+    pub async fn list(self, guild: Id<GuildMarker>) -> Result<Vec<MappoolEntry>> {
+        self.psql
+            .select_guild_mappools(guild)
+            .await
+            .wrap_err("Failed to get mappools of guild")
+    }
+
+    /// Returns whether the pool was created i.e. it did not exist yet
+    pub async fn create(
+        self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        user: Id<UserMarker>,
+    ) -> Result<bool> {
+        self.psql
+            .insert_mappool(guild, name, user)
+            .await
+            .wrap_err("Failed to insert mappool")
+    }
+
+    /// Returns whether the pool was deleted
+    pub async fn delete(self, guild: Id<GuildMarker>, name: &str) -> Result<bool> {
+        self.psql
+            .delete_mappool(guild, name)
+            .await
+            .wrap_err("Failed to delete mappool")
+    }
+
+    pub async fn set_slot(self, pool_id: u32, slot: &str, map_id: u32) -> Result<()> {
+        self.psql
+            .upsert_mappool_slot(pool_id, slot, map_id)
+            .await
+            .wrap_err("Failed to upsert mappool slot")
+    }
+
+    /// Returns whether the slot was removed
+    pub async fn remove_slot(self, pool_id: u32, slot: &str) -> Result<bool> {
+        self.psql
+            .delete_mappool_slot(pool_id, slot)
+            .await
+            .wrap_err("Failed to delete mappool slot")
+    }
+}
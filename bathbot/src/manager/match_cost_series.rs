@@ -0,0 +1,85 @@
+use bathbot_psql::{
+    model::osu::{MatchCostSeries, MatchCostSeriesEntry, MatchCostSeriesMatch},
+    Database,
+};
+use eyre::{Result, WrapErr};
+use twilight_model::id::{
+    marker::{GuildMarker, UserMarker},
+    Id,
+};
+
+use crate::core::Context;
+
+#[derive(Copy, Clone)]
+pub struct MatchCostSeriesManager {
+    psql: &'static Database,
+}
+
+impl MatchCostSeriesManager {
+    pub fn new() -> Self {
+        Self {
+            psql: Context::psql(),
+        }
+    }
+
+    pub async fn get(self, guild: Id<GuildMarker>, name: &str) -> Result<Option<MatchCostSeries>> {
+        self.psql
+            .select_match_cost_series(guild, name)
+            .await
+            .wrap_err("Failed to get match cost series")
+    }
+
+    pub async fn list(self, guild: Id<GuildMarker>) -> Result<Vec<MatchCostSeriesEntry>> {
+        self.psql
+            .select_guild_match_cost_series(guild)
+            .await
+            .wrap_err("Failed to get match cost series of guild")
+    }
+
+    /// Returns whether the series was created i.e. it did not exist yet
+    pub async fn create(
+        self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        user: Id<UserMarker>,
+    ) -> Result<bool> {
+        self.psql
+            .insert_match_cost_series(guild, name, user)
+            .await
+            .wrap_err("Failed to insert match cost series")
+    }
+
+    /// Returns whether the series was deleted
+    pub async fn delete(self, guild: Id<GuildMarker>, name: &str) -> Result<bool> {
+        self.psql
+            .delete_match_cost_series(guild, name)
+            .await
+            .wrap_err("Failed to delete match cost series")
+    }
+
+    pub async fn add_match(
+        self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        series_match: MatchCostSeriesMatch,
+        user: Id<UserMarker>,
+    ) -> Result<()> {
+        self.psql
+            .upsert_match_cost_series_match(guild, name, series_match, user)
+            .await
+            .wrap_err("Failed to upsert match cost series match")
+    }
+
+    /// Returns whether the match was removed
+    pub async fn remove_match(
+        self,
+        guild: Id<GuildMarker>,
+        name: &str,
+        match_id: u32,
+    ) -> Result<bool> {
+        self.psql
+            .delete_match_cost_series_match(guild, name, match_id)
+            .await
+            .wrap_err("Failed to delete match cost series match")
+    }
+}
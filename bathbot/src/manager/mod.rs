@@ -7,13 +7,16 @@ pub use self::{
     games::GameManager,
     github::GithubManager,
     guild_config::GuildConfigManager,
+    guild_skins::GuildSkinManager,
     huismetbenen_country::HuismetbenenCountryManager,
+    mappool::MappoolManager,
+    match_cost_series::MatchCostSeriesManager,
     osu_map::{MapError, MapManager, OsuMap, OsuMapSlim},
     osu_scores::ScoresManager,
     osu_user::OsuUserManager,
     pp::{Mods, PpManager},
     rank_pp_approx::ApproxManager,
-    replay::{OwnedReplayScore, ReplayManager, ReplayScore, ReplaySettings},
+    replay::{OwnedReplayScore, ReplayManager, ReplayScore, ReplaySettings, ReplaySkin},
     user_config::UserConfigManager,
 };
 
@@ -23,7 +26,10 @@ mod bookmarks;
 mod games;
 mod github;
 mod guild_config;
+mod guild_skins;
 mod huismetbenen_country;
+mod mappool;
+mod match_cost_series;
 mod osu_map;
 mod osu_scores;
 mod osu_user;
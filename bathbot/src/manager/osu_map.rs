@@ -243,6 +243,26 @@ impl MapManager {
         Ok(versions)
     }
 
+    /// Returns checksum-map id pairs for all stored maps of the given
+    /// checksums.
+    pub async fn map_ids_by_checksums(
+        self,
+        checksums: &[String],
+    ) -> eyre::Result<Vec<(Box<str>, u32)>> {
+        Context::psql()
+            .select_map_ids_by_checksums(checksums)
+            .await
+            .wrap_err("Failed to get map ids by checksums")
+    }
+
+    /// Returns the subset of the given map ids that are stored.
+    pub async fn stored_map_ids(self, map_ids: &[i32]) -> eyre::Result<Vec<u32>> {
+        Context::psql()
+            .select_stored_map_ids(map_ids)
+            .await
+            .wrap_err("Failed to get stored map ids")
+    }
+
     pub async fn store(&self, mapset: &BeatmapsetExtended) {
         if let Err(err) = Context::psql().upsert_beatmapset(mapset).await {
             warn!(?err, "Failed to store mapset");
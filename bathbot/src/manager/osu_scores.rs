@@ -311,7 +311,7 @@ impl ScoreArgs {
             // Pass scores to tracking check
             #[cfg(feature = "osutracking")]
             if let ScoreKind::Top { .. } = self.kind {
-                crate::tracking::process_osu_tracking(&scores_clone, None).await
+                crate::tracking::process_osu_tracking(&scores_clone, None, None).await
             }
         });
 
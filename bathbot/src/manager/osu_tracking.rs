@@ -1,12 +1,15 @@
 use std::{collections::HashMap, num::NonZeroU64};
 
 use bathbot_psql::{
-    model::osu::{TrackedOsuUserKey, TrackedOsuUserValue},
+    model::osu::{TrackedOsuUserKey, TrackedOsuUserSession, TrackedOsuUserValue},
     Database,
 };
 use bathbot_util::IntHasher;
 use eyre::{Result, WrapErr};
-use twilight_model::id::{marker::ChannelMarker, Id};
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
 
 #[derive(Copy, Clone)]
 pub struct OsuTrackingManager<'d> {
@@ -30,32 +33,225 @@ impl<'d> OsuTrackingManager<'d> {
     }
 
     pub async fn update_date(self, key: TrackedOsuUserKey) -> Result<()> {
-        let TrackedOsuUserKey { user_id, mode } = key;
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
 
         self.psql
-            .update_tracked_osu_user_date(user_id, mode)
+            .update_tracked_osu_user_date(user_id, mode, kind)
             .await
             .wrap_err("failed to update date for tracking")
     }
 
+    pub async fn update_last_score(self, key: TrackedOsuUserKey, score_id: u64) -> Result<()> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
+        self.psql
+            .update_tracked_osu_user_last_score(user_id, mode, kind, score_id)
+            .await
+            .wrap_err("failed to update last score for tracking")
+    }
+
     pub async fn update_channels(
         self,
         key: TrackedOsuUserKey,
         channels: &HashMap<NonZeroU64, u8, IntHasher>,
     ) -> Result<()> {
-        let TrackedOsuUserKey { user_id, mode } = key;
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
 
         self.psql
-            .update_tracked_osu_user_channels(user_id, mode, channels)
+            .update_tracked_osu_user_channels(user_id, mode, kind, channels)
             .await
             .wrap_err("failed to update channels for user in osu tracking")
     }
 
+    pub async fn update_filter(
+        self,
+        key: TrackedOsuUserKey,
+        channel: Id<ChannelMarker>,
+        filter: Option<&str>,
+    ) -> Result<()> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+        let channel = channel.into_nonzero();
+
+        match filter {
+            Some(filter) => self
+                .psql
+                .upsert_tracked_osu_user_filter(user_id, mode, kind, channel, filter)
+                .await
+                .wrap_err("failed to upsert filter for user in osu tracking"),
+            None => self
+                .psql
+                .delete_tracked_osu_user_filter(user_id, mode, kind, channel)
+                .await
+                .wrap_err("failed to delete filter for user in osu tracking"),
+        }
+    }
+
+    pub async fn update_milestones(
+        self,
+        key: TrackedOsuUserKey,
+        channel: Id<ChannelMarker>,
+        milestones: u8,
+    ) -> Result<()> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
+        let channel = channel.into_nonzero();
+
+        if milestones == 0 {
+            self.psql
+                .delete_tracked_osu_user_milestones(user_id, mode, kind, channel)
+                .await
+                .wrap_err("failed to delete milestones for user in osu tracking")
+        } else {
+            self.psql
+                .upsert_tracked_osu_user_milestones(user_id, mode, kind, channel, milestones)
+                .await
+                .wrap_err("failed to upsert milestones for user in osu tracking")
+        }
+    }
+
+    pub async fn update_webhook(
+        self,
+        key: TrackedOsuUserKey,
+        channel: Id<ChannelMarker>,
+        webhook: Option<&str>,
+    ) -> Result<()> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
+        let channel = channel.into_nonzero();
+
+        match webhook {
+            Some(url) => self
+                .psql
+                .upsert_tracked_osu_user_webhook(user_id, mode, kind, channel, url)
+                .await
+                .wrap_err("failed to upsert webhook for user in osu tracking"),
+            None => self
+                .psql
+                .delete_tracked_osu_user_webhook(user_id, mode, kind, channel)
+                .await
+                .wrap_err("failed to delete webhook for user in osu tracking"),
+        }
+    }
+
+    /// Sets the session inactivity timeout in minutes, a timeout of `0`
+    /// disables sessions for the channel.
+    pub async fn update_session_timeout(
+        self,
+        key: TrackedOsuUserKey,
+        channel: Id<ChannelMarker>,
+        timeout: u16,
+    ) -> Result<()> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
+        let channel = channel.into_nonzero();
+
+        if timeout == 0 {
+            self.psql
+                .delete_tracked_osu_user_session(user_id, mode, kind, channel)
+                .await
+                .wrap_err("failed to delete session for user in osu tracking")
+        } else {
+            self.psql
+                .upsert_tracked_osu_user_session_timeout(user_id, mode, kind, channel, timeout)
+                .await
+                .wrap_err("failed to upsert session timeout for user in osu tracking")
+        }
+    }
+
+    pub async fn session(
+        self,
+        key: TrackedOsuUserKey,
+        channel: NonZeroU64,
+    ) -> Result<Option<TrackedOsuUserSession>> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
+        self.psql
+            .select_tracked_osu_user_session(user_id, mode, kind, channel)
+            .await
+            .wrap_err("failed to get session for user in osu tracking")
+    }
+
+    pub async fn store_session(
+        self,
+        key: TrackedOsuUserKey,
+        channel: NonZeroU64,
+        session: &TrackedOsuUserSession,
+    ) -> Result<()> {
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
+
+        self.psql
+            .update_tracked_osu_user_session(user_id, mode, kind, channel, session)
+            .await
+            .wrap_err("failed to store session for user in osu tracking")
+    }
+
+    /// Closes all sessions that have been inactive for longer than their
+    /// timeout and returns the summary message of each.
+    pub async fn close_stale_sessions(self) -> Result<Vec<(Id<ChannelMarker>, Id<MessageMarker>)>> {
+        let sessions = self
+            .psql
+            .close_stale_tracked_osu_user_sessions()
+            .await
+            .wrap_err("failed to close stale sessions in osu tracking")?;
+
+        let sessions = sessions
+            .into_iter()
+            .filter_map(|(channel, msg)| {
+                Some((
+                    Id::new_checked(channel as u64)?,
+                    Id::new_checked(msg as u64)?,
+                ))
+            })
+            .collect();
+
+        Ok(sessions)
+    }
+
     pub async fn remove_user(self, key: TrackedOsuUserKey) -> Result<()> {
-        let TrackedOsuUserKey { user_id, mode } = key;
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
 
         self.psql
-            .delete_tracked_osu_user_by_mode(user_id, mode)
+            .delete_tracked_osu_user_by_mode(user_id, mode, kind)
             .await
             .wrap_err("failed to remove tracked user by mode")
     }
@@ -66,10 +262,14 @@ impl<'d> OsuTrackingManager<'d> {
         channel: Id<ChannelMarker>,
         limit: u8,
     ) -> Result<()> {
-        let TrackedOsuUserKey { user_id, mode } = key;
+        let TrackedOsuUserKey {
+            user_id,
+            mode,
+            kind,
+        } = key;
 
         self.psql
-            .insert_osu_tracking::<IntHasher>(user_id, mode, channel.into_nonzero(), limit)
+            .insert_osu_tracking::<IntHasher>(user_id, mode, kind, channel.into_nonzero(), limit)
             .await
             .wrap_err("failed to insert tracked user")
     }
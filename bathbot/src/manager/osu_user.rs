@@ -1,10 +1,14 @@
 use std::collections::HashMap;
 
 use bathbot_model::{RankingEntries, UserModeStatsColumn, UserStatsColumn};
-use bathbot_psql::Database;
+use bathbot_psql::{
+    model::osu::{DbUserMilestoneStats, DbUserStatsHistoryEntry},
+    Database,
+};
 use bathbot_util::{CowUtils, IntHasher};
 use eyre::{Result, WrapErr};
 use rosu_v2::prelude::{GameMode, UserExtended, Username};
+use time::{Duration, OffsetDateTime};
 
 use crate::core::Context;
 
@@ -86,6 +90,64 @@ impl OsuUserManager {
             .wrap_err("Failed to get user mode stats")
     }
 
+    pub async fn milestone_stats(
+        self,
+        user_id: u32,
+        mode: GameMode,
+    ) -> Result<Option<DbUserMilestoneStats>> {
+        self.psql
+            .select_osu_user_milestone_stats(user_id, mode)
+            .await
+            .wrap_err("Failed to get milestone stats")
+    }
+
+    pub async fn stale_modes(
+        self,
+        discord_ids: &[i64],
+        stale_before: OffsetDateTime,
+        limit: usize,
+    ) -> Result<Vec<(u32, GameMode)>> {
+        self.psql
+            .select_stale_osu_user_modes(discord_ids, stale_before, limit)
+            .await
+            .wrap_err("Failed to get stale user modes")
+    }
+
+    pub async fn stats_history(
+        self,
+        user_id: u32,
+        mode: GameMode,
+        from: OffsetDateTime,
+        until: OffsetDateTime,
+    ) -> Result<Vec<DbUserStatsHistoryEntry>> {
+        self.psql
+            .select_osu_user_stats_history(user_id, mode, from, until)
+            .await
+            .wrap_err("Failed to get user stats history")
+    }
+
+    /// Keeps one snapshot per day after 30 days and one per week after a year.
+    ///
+    /// If `last_run` is specified, only snapshots that passed one of these
+    /// thresholds since then are considered.
+    pub async fn downsample_stats_history(self, last_run: Option<OffsetDateTime>) -> Result<u64> {
+        const DAILY_AFTER: Duration = Duration::days(30);
+        const WEEKLY_AFTER: Duration = Duration::days(365);
+
+        let now = OffsetDateTime::now_utc();
+        let last_run = last_run.unwrap_or(OffsetDateTime::UNIX_EPOCH + WEEKLY_AFTER);
+
+        self.psql
+            .delete_osu_user_stats_history_downsampled(
+                now - DAILY_AFTER,
+                now - WEEKLY_AFTER,
+                last_run - DAILY_AFTER,
+                last_run - WEEKLY_AFTER,
+            )
+            .await
+            .wrap_err("Failed to downsample user stats history")
+    }
+
     pub async fn store(self, user: &UserExtended, mode: GameMode) {
         if let Err(err) = self.psql.upsert_osu_user(user, mode).await {
             warn!(?err, "Failed to upsert osu user");
@@ -6,7 +6,7 @@ use std::{
 use bathbot_model::{OsuStatsScore, ScoreSlim};
 use eyre::Result;
 use rosu_pp::{
-    any::{DifficultyAttributes, PerformanceAttributes, ScoreState},
+    any::{DifficultyAttributes, PerformanceAttributes, ScoreState, Strains},
     model::mode::GameMode as Mode,
     Beatmap, Difficulty,
 };
@@ -183,6 +183,17 @@ impl<'m> PpManager<'m> {
 
         calc.calculate()
     }
+
+    /// Calculate the strain values of each skill
+    pub fn strains(&self) -> Strains {
+        let mut calc = Difficulty::new().mods(self.mods.bits);
+
+        if let Some(clock_rate) = self.mods.clock_rate {
+            calc = calc.clock_rate(f64::from(clock_rate));
+        }
+
+        calc.strains(&self.map)
+    }
 }
 
 pub struct ScoreData {
@@ -145,6 +145,12 @@ pub struct ReplaySkin {
     pub display_name: Box<str>,
 }
 
+impl ReplaySkin {
+    fn is_default(&self) -> bool {
+        matches!(self.skin, RenderSkinOption::Official { ref name } if name == "default")
+    }
+}
+
 impl Default for ReplaySkin {
     fn default() -> Self {
         Self {
@@ -195,6 +201,26 @@ impl ReplaySettings {
         }
     }
 
+    /// Same as [`ReplaySettings::skin`] but uses the fallback skin if there
+    /// is no usable custom skin and the official skin was not changed from
+    /// its default.
+    pub fn skin_or<'a>(
+        &'a self,
+        allow_custom_skin: bool,
+        fallback: Option<&'a ReplaySkin>,
+    ) -> &'a ReplaySkin {
+        if allow_custom_skin {
+            if let Some(ref skin) = self.custom_skin {
+                return skin;
+            }
+        }
+
+        match fallback {
+            Some(fallback) if self.official_skin.is_default() => fallback,
+            _ => &self.official_skin,
+        }
+    }
+
     pub fn official_skin(&mut self, skin: Skin) {
         self.official_skin = ReplaySkin {
             skin: RenderSkinOption::Official {
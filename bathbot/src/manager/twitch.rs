@@ -1,8 +1,16 @@
-use bathbot_psql::Database;
+use bathbot_model::TwitchStream;
+use bathbot_psql::{
+    model::twitch::{DbTrackedTwitchStreamTarget, DbTwitchStreamMessage},
+    Database,
+};
 use bathbot_util::CowUtils;
 use eyre::{Result, WrapErr};
 use rosu_v2::request::UserId;
-use twilight_model::id::{marker::ChannelMarker, Id};
+use time::OffsetDateTime;
+use twilight_model::id::{
+    marker::{ChannelMarker, MessageMarker},
+    Id,
+};
 
 use crate::core::Context;
 
@@ -36,14 +44,85 @@ impl TwitchManager {
         }
     }
 
-    /// Returns whether a new entry was inserted
-    pub async fn track(self, channel: Id<ChannelMarker>, twitch_id: u64) -> Result<bool> {
+    /// Returns whether a new entry was inserted or its options changed
+    pub async fn track(
+        self,
+        channel: Id<ChannelMarker>,
+        twitch_id: u64,
+        webhook: Option<&str>,
+        followup: bool,
+    ) -> Result<bool> {
         self.psql
-            .insert_tracked_twitch_stream(channel, twitch_id)
+            .insert_tracked_twitch_stream(channel, twitch_id, webhook, followup)
             .await
             .wrap_err("failed to insert twitch stream for tracking")
     }
 
+    pub async fn targets(self, twitch_id: u64) -> Result<Vec<DbTrackedTwitchStreamTarget>> {
+        self.psql
+            .select_tracked_twitch_stream_targets(twitch_id)
+            .await
+            .wrap_err("failed to get targets of tracked twitch stream")
+    }
+
+    pub async fn store_message(
+        self,
+        channel: Id<ChannelMarker>,
+        msg: Id<MessageMarker>,
+        stream: &TwitchStream,
+    ) -> Result<()> {
+        self.psql
+            .upsert_twitch_stream_message(
+                channel,
+                stream.user_id,
+                msg,
+                stream.stream_id,
+                stream.started_at,
+                stream.viewer_count,
+                &stream.title,
+                &stream.game_name,
+            )
+            .await
+            .wrap_err("failed to store twitch stream message")
+    }
+
+    pub async fn update_peak(self, stream: &TwitchStream) -> Result<()> {
+        self.psql
+            .update_twitch_stream_message_peak(
+                stream.user_id,
+                stream.viewer_count,
+                &stream.title,
+                &stream.game_name,
+            )
+            .await
+            .wrap_err("failed to update twitch stream peak")
+    }
+
+    pub async fn end_messages(
+        self,
+        twitch_id: u64,
+        ended_at: OffsetDateTime,
+    ) -> Result<Vec<DbTwitchStreamMessage>> {
+        self.psql
+            .end_twitch_stream_messages(twitch_id, ended_at)
+            .await
+            .wrap_err("failed to end twitch stream messages")
+    }
+
+    pub async fn ended_messages(self) -> Result<Vec<DbTwitchStreamMessage>> {
+        self.psql
+            .select_ended_twitch_stream_messages()
+            .await
+            .wrap_err("failed to get ended twitch stream messages")
+    }
+
+    pub async fn remove_ended_messages(self, twitch_id: u64) -> Result<()> {
+        self.psql
+            .delete_ended_twitch_stream_messages(twitch_id)
+            .await
+            .wrap_err("failed to delete ended twitch stream messages")
+    }
+
     /// Returns whether an entry was deleted
     pub async fn untrack(self, channel: Id<ChannelMarker>, twitch_id: u64) -> Result<bool> {
         self.psql
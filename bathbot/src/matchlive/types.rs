@@ -1,3 +1,4 @@
+use bathbot_psql::model::osu::Mappool;
 use bathbot_util::IntHasher;
 use hashbrown::HashMap;
 use rosu_v2::prelude::OsuMatch;
@@ -80,13 +81,16 @@ pub struct TrackedMatch {
     pub osu_match: OsuMatch,
     /// All embeds of the match
     pub embeds: Vec<MatchLiveEmbed>,
+    /// Mappool to label the games with
+    pub mappool: Option<Mappool>,
 }
 
 impl TrackedMatch {
-    pub fn new(osu_match: OsuMatch, embeds: MatchLiveEmbeds) -> Self {
+    pub fn new(osu_match: OsuMatch, embeds: MatchLiveEmbeds, mappool: Option<Mappool>) -> Self {
         Self {
             osu_match,
             embeds: embeds.into_vec(),
+            mappool,
         }
     }
 }
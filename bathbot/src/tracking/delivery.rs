@@ -0,0 +1,226 @@
+use std::slice;
+
+use bathbot_client::{is_public_ip, ClientError};
+use bathbot_util::{constants::UNKNOWN_CHANNEL, ExponentialBackoff};
+use eyre::{Report, Result, WrapErr};
+use serde::Serialize;
+use tokio::time::sleep;
+use twilight_http::{
+    api_error::{ApiError, GeneralApiError},
+    error::ErrorType,
+};
+use twilight_model::{
+    channel::message::Embed,
+    id::{
+        marker::{ChannelMarker, MessageMarker},
+        Id,
+    },
+};
+use url::{Host, Url};
+
+use crate::Context;
+
+/// How often a webhook request is attempted before giving up
+const WEBHOOK_ATTEMPTS: usize = 5;
+
+/// Check whether the given url is allowed to be used as a webhook.
+///
+/// Only `https` urls are allowed and they must not point to local or private
+/// network hosts. Since a domain may still resolve to a private address, the
+/// resolved address is checked again whenever a webhook is posted.
+pub fn check_webhook_url(url: &str) -> Result<(), &'static str> {
+    if url.len() > 512 {
+        return Err("The webhook url must not be longer than 512 characters");
+    }
+
+    let Ok(url) = Url::parse(url) else {
+        return Err("The webhook must be a valid url");
+    };
+
+    if url.scheme() != "https" {
+        return Err("The webhook must be an url starting with `https://`");
+    }
+
+    let is_public = match url.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+
+            !(domain == "localhost"
+                || domain.ends_with(".localhost")
+                || domain.ends_with(".local")
+                || domain.ends_with(".internal"))
+        }
+        Some(Host::Ipv4(addr)) => is_public_ip(addr.into()),
+        Some(Host::Ipv6(addr)) => is_public_ip(addr.into()),
+        None => false,
+    };
+
+    if is_public {
+        Ok(())
+    } else {
+        Err("The webhook must not point to a local or private network")
+    }
+}
+
+/// Where a tracking notification is delivered to
+#[derive(Copy, Clone)]
+pub struct DeliveryTarget<'w> {
+    pub channel: Id<ChannelMarker>,
+    /// If specified, the notification is posted to this url instead of being
+    /// sent to the channel
+    pub webhook: Option<&'w str>,
+}
+
+/// A tracking notification consisting of an embed for Discord channels and a
+/// JSON payload for webhooks
+pub struct Notification<'n, P> {
+    /// Notification type that webhook receivers can dispatch on
+    pub kind: &'static str,
+    pub embed: &'n Embed,
+    pub payload: &'n P,
+}
+
+/// Outcome of delivering a notification
+#[derive(Default)]
+pub struct DeliveryReport {
+    /// Channels that turned out to be unknown so that the caller can remove
+    /// their tracking
+    pub unknown_channels: Vec<Id<ChannelMarker>>,
+    /// Messages that were sent to channels
+    pub sent: Vec<(Id<ChannelMarker>, Id<MessageMarker>)>,
+}
+
+enum SendOutcome {
+    Sent(Id<MessageMarker>),
+    UnknownChannel,
+    Failed,
+}
+
+#[derive(Serialize)]
+struct WebhookBody<'n, P> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    channel_id: Id<ChannelMarker>,
+    data: &'n P,
+}
+
+impl<P: Serialize> Notification<'_, P> {
+    /// Deliver the notification to each target.
+    pub async fn deliver(&self, targets: &[DeliveryTarget<'_>]) -> DeliveryReport {
+        let mut report = DeliveryReport::default();
+
+        for target in targets {
+            let channel = target.channel;
+
+            match target.webhook {
+                Some(url) => self.post_webhook(channel, url),
+                None => match self.send_msg(channel).await {
+                    SendOutcome::Sent(msg) => report.sent.push((channel, msg)),
+                    SendOutcome::UnknownChannel => report.unknown_channels.push(channel),
+                    SendOutcome::Failed => {}
+                },
+            }
+        }
+
+        report
+    }
+
+    async fn send_msg(&self, channel: Id<ChannelMarker>) -> SendOutcome {
+        let msg_fut = match Context::http()
+            .create_message(channel)
+            .embeds(slice::from_ref(self.embed))
+        {
+            Ok(msg_fut) => msg_fut,
+            Err(err) => {
+                warn!(?err, kind = self.kind, "Invalid embed for notif");
+
+                return SendOutcome::Failed;
+            }
+        };
+
+        let err = match msg_fut.await {
+            Ok(response) => match response.model().await {
+                Ok(msg) => return SendOutcome::Sent(msg.id),
+                Err(err) => {
+                    warn!(%channel, ?err, kind = self.kind, "Failed to deserialize notif");
+
+                    return SendOutcome::Failed;
+                }
+            },
+            Err(err) => err,
+        };
+
+        if let ErrorType::Response { error, .. } = err.kind() {
+            if let ApiError::General(GeneralApiError {
+                code: UNKNOWN_CHANNEL,
+                ..
+            }) = error
+            {
+                return SendOutcome::UnknownChannel;
+            }
+
+            warn!(%channel, ?error, kind = self.kind, "Error from API while sending notif");
+        } else {
+            warn!(%channel, ?err, kind = self.kind, "Error while sending notif");
+        }
+
+        SendOutcome::Failed
+    }
+
+    /// Post the payload to the webhook in a separate task so that retries
+    /// don't hold up the delivery to other targets
+    fn post_webhook(&self, channel: Id<ChannelMarker>, url: &str) {
+        let body = WebhookBody {
+            kind: self.kind,
+            channel_id: channel,
+            data: self.payload,
+        };
+
+        let json = match serde_json::to_vec(&body) {
+            Ok(json) => json,
+            Err(err) => {
+                warn!(?err, kind = self.kind, "Failed to serialize webhook body");
+
+                return;
+            }
+        };
+
+        let url = url.to_owned();
+        let kind = self.kind;
+
+        tokio::spawn(async move {
+            if let Err(err) = post_webhook(&url, json).await {
+                warn!(%channel, ?err, kind, "Failed to deliver webhook notif");
+            }
+        });
+    }
+}
+
+/// Post the payload to the webhook and retry if it failed
+async fn post_webhook(url: &str, json: Vec<u8>) -> Result<()> {
+    let backoff = ExponentialBackoff::new(2).factor(500).max_delay(10_000);
+
+    for (duration, i) in backoff.take(WEBHOOK_ATTEMPTS - 1).zip(2..) {
+        match Context::client().post_webhook(url, json.clone()).await {
+            Ok(_) => return Ok(()),
+            // The receiver rejected the payload so retrying won't help
+            Err(err @ (ClientError::BadRequest | ClientError::NotFound)) => {
+                return Err(Report::new(err).wrap_err("Webhook rejected payload"))
+            }
+            Err(err @ ClientError::NonPublicAddress) => {
+                return Err(Report::new(err).wrap_err("Webhook does not point to a public host"))
+            }
+            Err(err) => warn!(
+                ?err,
+                "Failed webhook request; backoff {duration:?} and then retry attempt #{i}"
+            ),
+        }
+
+        sleep(duration).await;
+    }
+
+    Context::client()
+        .post_webhook(url, json)
+        .await
+        .wrap_err("Reached retry limit and still failed to post webhook")
+}
@@ -0,0 +1,196 @@
+use std::sync::Mutex;
+
+use eyre::{Result, WrapErr};
+use rosu_v2::prelude::{GameMode, OsuError};
+use time::{Duration, OffsetDateTime};
+use tokio::time::{self as tokio_time, MissedTickBehavior};
+use twilight_model::id::{marker::GuildMarker, Id};
+
+use crate::core::{BotConfig, Context};
+
+/// Guilds whose leaderboard has not been shown for this long are no longer
+/// considered.
+const ACTIVE_DURATION: Duration = Duration::days(7);
+
+/// Stats that were stored longer ago than this will be refreshed.
+const STALE_AFTER: Duration = Duration::hours(12);
+
+/// How long to wait until checking again when nothing is stale.
+const IDLE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// Keeps stored osu! user stats of linked guild members up-to-date so that
+/// `/serverleaderboard` doesn't depend on whenever someone last requested a
+/// user.
+///
+/// Users with milestone tracking are not refreshed. Milestones are detected
+/// by comparing new stats against the stored ones so refreshing them in the
+/// meantime would swallow milestones. Their stats are stored by the tracking
+/// loop anyway whenever they set a new score.
+#[derive(Default)]
+pub struct LeaderboardRefresh {
+    inner: Mutex<LeaderboardRefreshInner>,
+}
+
+#[derive(Default)]
+struct LeaderboardRefreshInner {
+    /// Amount of guilds whose leaderboard was shown recently
+    guilds: usize,
+    queue: usize,
+    refreshed: usize,
+    failed: usize,
+    last_refresh: Option<OffsetDateTime>,
+}
+
+pub struct LeaderboardRefreshStats {
+    pub budget: u16,
+    pub guilds: usize,
+    pub queue: usize,
+    pub refreshed: usize,
+    pub failed: usize,
+    pub last_refresh: Option<OffsetDateTime>,
+}
+
+impl LeaderboardRefresh {
+    /// Marks the guild as active so that its members' stats are refreshed,
+    /// prioritizing guilds that showed their leaderboard most recently.
+    pub async fn leaderboard_shown(&self, guild: Id<GuildMarker>) {
+        if let Err(err) = Context::psql()
+            .upsert_leaderboard_refresh_guild(guild)
+            .await
+        {
+            warn!(?err, "Failed to mark leaderboard guild as active");
+        }
+    }
+
+    pub fn stats(&self) -> LeaderboardRefreshStats {
+        let inner = self.inner.lock().unwrap();
+
+        LeaderboardRefreshStats {
+            budget: BotConfig::get().leaderboard_refresh_budget,
+            guilds: inner.guilds,
+            queue: inner.queue,
+            refreshed: inner.refreshed,
+            failed: inner.failed,
+            last_refresh: inner.last_refresh,
+        }
+    }
+
+    /// Collects up to `limit` stale users, starting with members of the guild
+    /// that showed its leaderboard most recently.
+    async fn next_batch(&self, limit: usize) -> Result<Vec<(u32, GameMode)>> {
+        let now = OffsetDateTime::now_utc();
+
+        let guilds = Context::psql()
+            .select_leaderboard_refresh_guilds(now - ACTIVE_DURATION)
+            .await
+            .wrap_err("Failed to get active leaderboard guilds")?;
+
+        self.inner.lock().unwrap().guilds = guilds.len();
+
+        let stale_before = now - STALE_AFTER;
+        let mut batch = Vec::with_capacity(limit);
+
+        for guild in guilds {
+            let members: Vec<_> = Context::cache()
+                .members(guild)
+                .await
+                .wrap_err("Failed to get guild members")?
+                .into_iter()
+                .map(|id| id as i64)
+                .collect();
+
+            let users = Context::osu_user()
+                .stale_modes(&members, stale_before, limit - batch.len())
+                .await?;
+
+            for user in users {
+                if !batch.contains(&user) {
+                    batch.push(user);
+                }
+            }
+
+            if batch.len() >= limit {
+                break;
+            }
+        }
+
+        Ok(batch)
+    }
+
+    fn finish_refresh(&self, success: bool) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.queue = inner.queue.saturating_sub(1);
+        inner.last_refresh = Some(OffsetDateTime::now_utc());
+
+        if success {
+            inner.refreshed += 1;
+        } else {
+            inner.failed += 1;
+        }
+    }
+}
+
+#[cold]
+pub async fn leaderboard_refresh_loop() {
+    let budget = BotConfig::get().leaderboard_refresh_budget;
+
+    if budget == 0 {
+        info!("Leaderboard refresh budget is 0, not refreshing stats");
+
+        return;
+    }
+
+    let refresh = Context::leaderboard_refresh();
+
+    // Spread the requests evenly across each minute
+    let period = std::time::Duration::from_secs(60) / u32::from(budget);
+    let mut interval = tokio_time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        let batch = match refresh.next_batch(usize::from(budget)).await {
+            Ok(batch) => batch,
+            Err(err) => {
+                warn!(?err, "Failed to collect stale leaderboard users");
+
+                Vec::new()
+            }
+        };
+
+        if batch.is_empty() {
+            tokio_time::sleep(IDLE_INTERVAL).await;
+
+            continue;
+        }
+
+        refresh.inner.lock().unwrap().queue = batch.len();
+
+        for (user_id, mode) in batch {
+            interval.tick().await;
+
+            let success = match Context::osu().user(user_id).mode(mode).await {
+                Ok(user) => {
+                    Context::osu_user().store(&user, mode).await;
+
+                    true
+                }
+                Err(OsuError::NotFound) => {
+                    // Remove stats of unknown/restricted users so they don't appear in the
+                    // leaderboard
+                    if let Err(err) = Context::osu_user().remove_stats_and_scores(user_id).await {
+                        warn!(?err, "Failed to remove stats of unknown user");
+                    }
+
+                    false
+                }
+                Err(err) => {
+                    warn!(user_id, ?mode, ?err, "Failed to refresh leaderboard user");
+
+                    false
+                }
+            };
+
+            refresh.finish_refresh(success);
+        }
+    }
+}
@@ -1,6 +1,8 @@
-pub use self::ordr::{Ordr, OrdrReceivers};
+#[cfg(any(feature = "osutracking", feature = "twitchtracking"))]
+pub use self::delivery::check_webhook_url;
 #[cfg(feature = "osutracking")]
 pub use self::osu::{
+    milestones::MilestoneKinds,
     osu_loop::{osu_tracking_loop, process_osu_tracking},
     osu_queue::*,
 };
@@ -8,9 +10,17 @@ pub use self::osu::{
 pub use self::twitch::online_streams::OnlineTwitchStreams;
 #[cfg(feature = "twitchtracking")]
 pub use self::twitch::twitch_loop::twitch_tracking_loop;
+pub use self::{
+    leaderboard_refresh::{leaderboard_refresh_loop, LeaderboardRefresh},
+    ordr::{Ordr, OrdrReceivers},
+};
 
+mod leaderboard_refresh;
 mod ordr;
 
+#[cfg(any(feature = "osutracking", feature = "twitchtracking"))]
+mod delivery;
+
 #[cfg(feature = "osutracking")]
 mod osu;
 #[cfg(feature = "twitch")]
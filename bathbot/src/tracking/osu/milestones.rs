@@ -0,0 +1,347 @@
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+
+use bathbot_model::rosu_v2::user::User;
+use bathbot_psql::model::osu::{DbUserMilestoneStats, TrackedOsuUserKey};
+use bathbot_util::{numbers::WithComma, EmbedBuilder};
+use rosu_v2::prelude::{MedalCompact, UserExtended};
+use serde::Serialize;
+use twilight_model::id::Id;
+
+use super::{osu_loop::send_notifs, payload::MilestonePayload};
+use crate::{
+    manager::redis::RedisData,
+    tracking::delivery::{DeliveryTarget, Notification},
+    Context,
+};
+
+bitflags::bitflags! {
+    /// Types of milestones that a channel can opt into for a tracked user
+    pub struct MilestoneKinds: u8 {
+        const GLOBAL_RANK  = 1 << 0;
+        const COUNTRY_RANK = 1 << 1;
+        const PP           = 1 << 2;
+        const MEDALS       = 1 << 3;
+    }
+}
+
+impl MilestoneKinds {
+    const NAMES: [(Self, &'static str); 4] = [
+        (Self::GLOBAL_RANK, "rank"),
+        (Self::COUNTRY_RANK, "country"),
+        (Self::PP, "pp"),
+        (Self::MEDALS, "medals"),
+    ];
+
+    /// Parses a comma-separated list of milestone names.
+    ///
+    /// Returns `None` if any of the names is unknown.
+    pub fn parse(list: &str) -> Option<Self> {
+        let mut kinds = Self::empty();
+
+        for name in list
+            .split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            if name == "all" {
+                kinds |= Self::all();
+
+                continue;
+            }
+
+            let (kind, _) = Self::NAMES
+                .iter()
+                .find(|(_, n)| n.eq_ignore_ascii_case(name))?;
+            kinds |= *kind;
+        }
+
+        Some(kinds)
+    }
+
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        Self::NAMES
+            .into_iter()
+            .filter_map(move |(kind, name)| self.contains(kind).then_some(name))
+    }
+}
+
+const GLOBAL_RANK_THRESHOLDS: [u32; 16] = [
+    1_000_000, 500_000, 250_000, 100_000, 50_000, 25_000, 10_000, 5000, 2500, 1000, 500, 250, 100,
+    50, 10, 1,
+];
+
+const COUNTRY_RANK_THRESHOLDS: [u32; 7] = [1000, 500, 100, 50, 10, 5, 1];
+
+const PP_STEP: f32 = 1000.0;
+
+/// Upper bound on new medals to list in case the stored count is off
+const MAX_NEW_MEDALS: usize = 10;
+
+/// Compares the stored stats of a tracked user with their current stats and
+/// notifies all channels that opted into the milestones that were reached.
+pub(super) async fn process_milestones(
+    key: TrackedOsuUserKey,
+    old_stats: Option<&DbUserMilestoneStats>,
+) {
+    let Some(value) = Context::tracking().get_tracked(key).await else {
+        return;
+    };
+
+    let wanted = value
+        .milestones
+        .values()
+        .fold(MilestoneKinds::empty(), |wanted, &bits| {
+            wanted | MilestoneKinds::from_bits_truncate(bits)
+        });
+
+    if wanted.is_empty() {
+        return;
+    }
+
+    let TrackedOsuUserKey { user_id, mode, .. } = key;
+
+    let user = match Context::osu().user(user_id).mode(mode).await {
+        Ok(user) => user,
+        Err(err) => {
+            warn!(user_id, ?mode, ?err, "Failed to get user for milestones");
+
+            return;
+        }
+    };
+
+    Context::osu_user().store(&user, mode).await;
+
+    // Without previous stats there is nothing to compare against
+    let Some(old_stats) = old_stats else {
+        return;
+    };
+
+    let milestones = Milestone::collect(old_stats, &user, wanted).await;
+
+    if milestones.is_empty() {
+        return;
+    }
+
+    let thumbnail = user.avatar_url.clone();
+    let user = RedisData::new(User::from(user));
+
+    for (channel, bits) in value.milestones {
+        let kinds = MilestoneKinds::from_bits_truncate(bits);
+
+        let reached: Vec<_> = milestones
+            .iter()
+            .filter(|m| kinds.contains(m.kind()))
+            .collect();
+
+        if reached.is_empty() {
+            continue;
+        }
+
+        let mut description = String::new();
+
+        for milestone in reached.iter() {
+            let _ = writeln!(description, "{milestone}");
+        }
+
+        let embed = EmbedBuilder::new()
+            .author(user.author_builder())
+            .description(description)
+            .thumbnail(thumbnail.as_str())
+            .title("New milestones reached!")
+            .build();
+
+        let payload = MilestonePayload::new(&user, reached);
+
+        let notif = Notification {
+            kind: "milestones",
+            embed: &embed,
+            payload: &payload,
+        };
+
+        let target = DeliveryTarget {
+            channel: Id::from(channel),
+            webhook: value.webhooks.get(&channel).map(AsRef::as_ref),
+        };
+
+        send_notifs(notif, &[target]).await;
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(super) enum Milestone {
+    GlobalRank { threshold: u32, rank: u32 },
+    CountryRank { threshold: u32, rank: u32 },
+    Pp { threshold: u32, pp: f32 },
+    Medal { name: Box<str> },
+}
+
+impl Milestone {
+    async fn collect(
+        old: &DbUserMilestoneStats,
+        user: &UserExtended,
+        wanted: MilestoneKinds,
+    ) -> Vec<Self> {
+        let mut milestones = Vec::new();
+
+        let Some(ref stats) = user.statistics else {
+            return milestones;
+        };
+
+        if wanted.contains(MilestoneKinds::GLOBAL_RANK) {
+            let rank = stats.global_rank.unwrap_or(0);
+
+            if let Some(threshold) =
+                passed_rank(&GLOBAL_RANK_THRESHOLDS, old.global_rank as u32, rank)
+            {
+                milestones.push(Self::GlobalRank { threshold, rank });
+            }
+        }
+
+        if wanted.contains(MilestoneKinds::COUNTRY_RANK) {
+            let rank = stats.country_rank.unwrap_or(0);
+
+            if let Some(threshold) =
+                passed_rank(&COUNTRY_RANK_THRESHOLDS, old.country_rank as u32, rank)
+            {
+                milestones.push(Self::CountryRank { threshold, rank });
+            }
+        }
+
+        if wanted.contains(MilestoneKinds::PP) {
+            let threshold = (stats.pp / PP_STEP).floor() * PP_STEP;
+
+            if threshold > 0.0 && old.pp < threshold {
+                milestones.push(Self::Pp {
+                    threshold: threshold as u32,
+                    pp: stats.pp,
+                });
+            }
+        }
+
+        if wanted.contains(MilestoneKinds::MEDALS) {
+            let medals = user.medals.as_deref().unwrap_or_default();
+
+            // A stored count of zero likely means the medals were never stored
+            let new_count = match old.medals {
+                0 => 0,
+                old_count => medals.len().saturating_sub(old_count as usize),
+            };
+
+            if new_count > 0 {
+                Self::collect_medals(medals, new_count, &mut milestones).await;
+            }
+        }
+
+        milestones
+    }
+
+    async fn collect_medals(medals: &[MedalCompact], new_count: usize, milestones: &mut Vec<Self>) {
+        let all_medals = match Context::redis().medals().await {
+            Ok(medals) => medals.into_original(),
+            Err(err) => {
+                warn!(?err, "Failed to get medals for milestones");
+
+                return;
+            }
+        };
+
+        let mut newest: Vec<_> = medals.iter().collect();
+        newest.sort_unstable_by(|a, b| b.achieved_at.cmp(&a.achieved_at));
+
+        let new_medals = newest
+            .into_iter()
+            .take(new_count.min(MAX_NEW_MEDALS))
+            .filter_map(|compact| {
+                all_medals
+                    .iter()
+                    .find(|medal| medal.medal_id == compact.medal_id)
+            })
+            .map(|medal| Self::Medal {
+                name: medal.name.clone(),
+            });
+
+        milestones.extend(new_medals);
+    }
+
+    fn kind(&self) -> MilestoneKinds {
+        match self {
+            Self::GlobalRank { .. } => MilestoneKinds::GLOBAL_RANK,
+            Self::CountryRank { .. } => MilestoneKinds::COUNTRY_RANK,
+            Self::Pp { .. } => MilestoneKinds::PP,
+            Self::Medal { .. } => MilestoneKinds::MEDALS,
+        }
+    }
+}
+
+impl Display for Milestone {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::GlobalRank { threshold: 1, .. } => f.write_str("Reached global rank **#1**"),
+            Self::GlobalRank { threshold, rank } => write!(
+                f,
+                "Reached the global top **{}** (#{})",
+                WithComma::new(*threshold),
+                WithComma::new(*rank)
+            ),
+            Self::CountryRank { threshold: 1, .. } => f.write_str("Reached country rank **#1**"),
+            Self::CountryRank { threshold, rank } => write!(
+                f,
+                "Reached the country top **{}** (#{})",
+                WithComma::new(*threshold),
+                WithComma::new(*rank)
+            ),
+            Self::Pp { threshold, pp } => write!(
+                f,
+                "Crossed **{}pp** ({}pp)",
+                WithComma::new(*threshold),
+                WithComma::new(*pp)
+            ),
+            Self::Medal { name } => write!(f, "Unlocked the medal **{name}**"),
+        }
+    }
+}
+
+/// Returns the smallest threshold that was passed when going from `old` to
+/// `new` rank.
+///
+/// A rank of 0 means that there was no rank in which case no threshold is
+/// considered to be passed.
+fn passed_rank(thresholds: &[u32], old: u32, new: u32) -> Option<u32> {
+    if old == 0 || new == 0 {
+        return None;
+    }
+
+    thresholds
+        .iter()
+        .rev()
+        .copied()
+        .find(|&threshold| new <= threshold && threshold < old)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn passed_rank_thresholds() {
+        assert_eq!(
+            passed_rank(&GLOBAL_RANK_THRESHOLDS, 10_234, 9_876),
+            Some(10_000)
+        );
+        assert_eq!(passed_rank(&GLOBAL_RANK_THRESHOLDS, 1_200, 480), Some(500));
+        assert_eq!(passed_rank(&GLOBAL_RANK_THRESHOLDS, 9_000, 8_000), None);
+        assert_eq!(passed_rank(&GLOBAL_RANK_THRESHOLDS, 9_000, 9_500), None);
+        assert_eq!(passed_rank(&GLOBAL_RANK_THRESHOLDS, 0, 500), None);
+    }
+
+    #[test]
+    fn parse_milestone_kinds() {
+        assert_eq!(
+            MilestoneKinds::parse("rank, pp"),
+            Some(MilestoneKinds::GLOBAL_RANK | MilestoneKinds::PP)
+        );
+        assert_eq!(MilestoneKinds::parse("all"), Some(MilestoneKinds::all()));
+        assert_eq!(MilestoneKinds::parse("rank,foo"), None);
+    }
+}
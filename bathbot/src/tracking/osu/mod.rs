@@ -1,2 +1,5 @@
+pub mod milestones;
 pub mod osu_loop;
 pub mod osu_queue;
+pub mod payload;
+pub mod session;
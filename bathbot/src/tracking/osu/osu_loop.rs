@@ -1,4 +1,9 @@
-use std::{borrow::Cow, collections::HashMap, num::NonZeroU64, slice};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    num::NonZeroU64,
+    time::{Duration, Instant},
+};
 
 use bathbot_model::{
     embed_builder::{
@@ -9,52 +14,100 @@ use bathbot_model::{
 };
 use bathbot_psql::model::{
     configs::ScoreData,
-    osu::{TrackedOsuUserKey, TrackedOsuUserValue},
+    osu::{
+        ChannelFilters, ChannelSessions, ChannelWebhooks, DbUserMilestoneStats, TrackKind,
+        TrackedOsuUserKey, TrackedOsuUserValue,
+    },
 };
-use bathbot_util::{constants::UNKNOWN_CHANNEL, EmbedBuilder, IntHasher};
+use bathbot_util::{EmbedBuilder, IntHasher};
 use eyre::Report;
 use rosu_v2::{
-    prelude::{GameMode, OsuError, Score},
+    prelude::{GameMode, Grade, OsuError, Score},
     OsuResult,
 };
+use serde::Serialize;
 use time::OffsetDateTime;
-use twilight_http::{
-    api_error::{ApiError, GeneralApiError},
-    error::ErrorType as TwilightErrorType,
-};
 use twilight_model::id::Id;
 
+use super::{
+    milestones::process_milestones,
+    payload::ScorePayload,
+    session::{close_stale_sessions, update_sessions},
+};
 use crate::{
     active::impls::{MarkIndex, SingleScoreContent, SingleScorePagination},
     commands::utility::ScoreEmbedDataWrap,
-    manager::{
-        redis::{osu::UserArgs, RedisData},
-        OsuMap,
+    manager::redis::{osu::UserArgs, RedisData},
+    tracking::{
+        delivery::{DeliveryTarget, Notification},
+        RecentTrackConditions,
     },
+    util::query::{FilterCriteria, IFilterCriteria, Searchable, TopCriteria},
     Context,
 };
 
+/// Amount of recent scores to request per poll of a [`TrackKind::Recent`]
+/// entry
+const RECENT_SCORES_LIMIT: usize = 100;
+
+/// Interval in which sessions are checked for inactivity
+const SESSION_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
 #[cold]
 pub async fn osu_tracking_loop() {
     let osu = Context::osu();
     let tracking = Context::tracking();
 
+    let mut last_session_check = Instant::now();
+
     loop {
-        if let Some((key, amount)) = tracking.pop().await {
-            let TrackedOsuUserKey { user_id, mode } = key;
+        if last_session_check.elapsed() >= SESSION_CHECK_INTERVAL {
+            close_stale_sessions().await;
+            last_session_check = Instant::now();
+        }
 
-            let scores_fut = osu
-                .user_scores(user_id)
-                .best()
-                .mode(mode)
-                .limit(amount as usize);
+        if let Some((key, amount)) = tracking.pop().await {
+            let TrackedOsuUserKey {
+                user_id,
+                mode,
+                kind,
+            } = key;
+
+            let scores_fut = osu.user_scores(user_id).mode(mode);
+
+            let scores_fut = match kind {
+                TrackKind::Top => scores_fut.best().limit(amount as usize),
+                TrackKind::Recent => scores_fut
+                    .recent()
+                    .include_fails(false)
+                    .limit(RECENT_SCORES_LIMIT),
+            };
 
             match scores_fut.await {
                 Ok(scores) => {
-                    // * Note: If scores are empty, (user_id, mode) will not be reset into the
-                    //   tracking queue
-                    if !scores.is_empty() {
-                        process_osu_tracking(&scores, None).await
+                    // Must be retrieved before processing milestones or scores
+                    // stores the new stats
+                    let old_stats = match Context::osu_user().milestone_stats(user_id, mode).await {
+                        Ok(stats) => stats,
+                        Err(err) => {
+                            warn!(?err, "Failed to get previous user stats");
+
+                            None
+                        }
+                    };
+
+                    process_milestones(key, old_stats.as_ref()).await;
+
+                    match kind {
+                        // * Note: If scores are empty, (user_id, mode) will not be reset into the
+                        //   tracking queue
+                        TrackKind::Top if !scores.is_empty() => {
+                            process_osu_tracking(&scores, None, old_stats.as_ref()).await
+                        }
+                        TrackKind::Top => {}
+                        TrackKind::Recent => {
+                            process_recent_tracking(key, &scores, old_stats.as_ref()).await
+                        }
                     }
                 }
                 Err(OsuError::NotFound) => {
@@ -83,7 +136,12 @@ pub async fn osu_tracking_loop() {
     }
 }
 
-pub async fn process_osu_tracking(scores: &[Score], user: Option<&RedisData<User>>) {
+/// `old_stats` are the stored stats of the user before the scores were set.
+pub async fn process_osu_tracking(
+    scores: &[Score],
+    user: Option<&RedisData<User>>,
+    old_stats: Option<&DbUserMilestoneStats>,
+) {
     let tracking = Context::tracking();
 
     // Make sure scores is not empty
@@ -92,6 +150,7 @@ pub async fn process_osu_tracking(scores: &[Score], user: Option<&RedisData<User
             let key = TrackedOsuUserKey {
                 user_id: score.user_id,
                 mode: score.mode,
+                kind: TrackKind::Top,
             };
 
             (key, score.ended_at)
@@ -100,11 +159,16 @@ pub async fn process_osu_tracking(scores: &[Score], user: Option<&RedisData<User
     };
 
     // Make sure the user is being tracked in general
-    let (channels, last) = match tracking.get_tracked(key).await {
+    let (channels, filters, sessions, webhooks, last) = match tracking.get_tracked(key).await {
         Some(TrackedOsuUserValue {
             channels,
+            filters,
+            milestones: _,
+            sessions,
+            webhooks,
             last_update,
-        }) => (channels, last_update),
+            last_score_id: _,
+        }) => (channels, filters, sessions, webhooks, last_update),
         None => return,
     };
 
@@ -125,10 +189,14 @@ pub async fn process_osu_tracking(scores: &[Score], user: Option<&RedisData<User
 
     tracking.reset(key).await;
 
-    let mut user = TrackUser::new(key, user);
+    let mut user = TrackUser::new(key, user, old_stats);
 
     // Process scores
-    match score_loop(&mut user, max, last, scores, &channels).await {
+    let score_fut = score_loop(
+        &mut user, max, last, scores, &channels, &filters, &sessions, &webhooks,
+    );
+
+    match score_fut.await {
         Ok(_) => {}
         Err(OsuError::NotFound) => {
             if let Err(err) = tracking.remove_user_all(key.user_id).await {
@@ -142,13 +210,19 @@ pub async fn process_osu_tracking(scores: &[Score], user: Option<&RedisData<User
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn score_loop(
     user: &mut TrackUser<'_>,
     max: u8,
     last: OffsetDateTime,
     scores: &[Score],
     channels: &HashMap<NonZeroU64, u8, IntHasher>,
+    filters: &ChannelFilters<IntHasher>,
+    sessions: &ChannelSessions<IntHasher>,
+    webhooks: &ChannelWebhooks<IntHasher>,
 ) -> OsuResult<()> {
+    let criteria = parse_filters(filters);
+
     for (idx, score) in (1..).zip(scores.iter()).take(max as usize) {
         // Skip if its an older score
         if score.ended_at <= last {
@@ -166,75 +240,376 @@ async fn score_loop(
             }
         };
 
-        let embed = user.embed(score, map, idx).await?.build();
-        let http = Context::http();
-        let tracking = Context::tracking();
+        let entry =
+            ScoreEmbedDataWrap::new_custom(score.clone(), map, idx as usize - 1, None).await;
+
+        // Only consider channels whose limit and filter apply to the score
+        let targets: Vec<_> = channels
+            .iter()
+            .filter(|&(_, &limit)| idx <= limit)
+            .map(|(&channel, _)| channel)
+            .filter(|channel| matches_filter(&criteria, channel, &entry))
+            .collect();
+
+        if targets.is_empty() {
+            continue;
+        }
+
+        let (embed, payload) = user.embed(score, entry).await?;
+        notify(user, score, embed, payload, targets, sessions, webhooks).await;
+    }
+
+    Ok(())
+}
+
+async fn process_recent_tracking(
+    key: TrackedOsuUserKey,
+    scores: &[Score],
+    old_stats: Option<&DbUserMilestoneStats>,
+) {
+    let tracking = Context::tracking();
+    tracking.reset(key).await;
+
+    let (channels, filters, sessions, webhooks, last_update, last_score_id) =
+        match tracking.get_tracked(key).await {
+            Some(TrackedOsuUserValue {
+                channels,
+                filters,
+                milestones: _,
+                sessions,
+                webhooks,
+                last_update,
+                last_score_id,
+            }) => (
+                channels,
+                filters,
+                sessions,
+                webhooks,
+                last_update,
+                last_score_id,
+            ),
+            None => return,
+        };
+
+    // Make sure scores is not empty
+    let Some(newest_id) = scores.iter().map(|score| score.id).max() else {
+        return;
+    };
+
+    // Update the latest score id first so that a restart won't process the
+    // same scores again
+    if let Err(err) = tracking.update_last_score(key, newest_id).await {
+        warn!(?err, "Failed to update last score id for user");
+    }
+
+    let mut user = TrackUser::new(key, None, old_stats);
+    let last = LastProcessed::new(last_score_id, last_update);
+
+    let recent_fut = recent_loop(
+        &mut user, last, scores, &channels, &filters, &sessions, &webhooks,
+    );
+
+    match recent_fut.await {
+        Ok(_) => {}
+        Err(OsuError::NotFound) => {
+            if let Err(err) = tracking.remove_user_all(key.user_id).await {
+                warn!(?err, "Failed to remove unknown user from tracking");
+            }
+        }
+        Err(err) => warn!(?err, "osu!api error while tracking recent scores"),
+    }
+}
+
+/// The most recent point up to which scores have been processed.
+enum LastProcessed {
+    ScoreId(u64),
+    /// No score has been processed yet so only scores after the entry was
+    /// added are considered.
+    Date(OffsetDateTime),
+}
+
+impl LastProcessed {
+    fn new(last_score_id: Option<u64>, last_update: OffsetDateTime) -> Self {
+        match last_score_id {
+            Some(score_id) => Self::ScoreId(score_id),
+            None => Self::Date(last_update),
+        }
+    }
+
+    fn is_before(&self, score: &Score) -> bool {
+        match self {
+            Self::ScoreId(score_id) => score.id > *score_id,
+            Self::Date(date) => score.ended_at > *date,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn recent_loop(
+    user: &mut TrackUser<'_>,
+    last: LastProcessed,
+    scores: &[Score],
+    channels: &HashMap<NonZeroU64, u8, IntHasher>,
+    filters: &ChannelFilters<IntHasher>,
+    sessions: &ChannelSessions<IntHasher>,
+    webhooks: &ChannelWebhooks<IntHasher>,
+) -> OsuResult<()> {
+    let wanted = channels
+        .values()
+        .fold(RecentTrackConditions::empty(), |wanted, &bits| {
+            wanted | RecentTrackConditions::from_bits_truncate(bits)
+        });
+
+    let criteria = parse_filters(filters);
+
+    // Recent scores are sorted from newest to oldest
+    for score in scores.iter().rev() {
+        if !last.is_before(score) {
+            continue;
+        }
+
+        let mut fulfilled = RecentTrackConditions::empty();
+
+        if matches!(score.grade, Grade::X | Grade::XH) {
+            fulfilled |= RecentTrackConditions::SS;
+        }
+
+        if wanted.contains(RecentTrackConditions::PERSONAL_BEST) && is_personal_best(score).await {
+            fulfilled |= RecentTrackConditions::PERSONAL_BEST;
+        }
+
+        // Whether the global leaderboard position is still worth checking
+        let possible = fulfilled | (wanted & RecentTrackConditions::GLOBAL_FIRST);
+
+        if !wanted.intersects(possible) {
+            continue;
+        }
+
+        let checksum = score.map.as_ref().and_then(|map| map.checksum.as_deref());
+
+        let map = match Context::osu_map().map(score.map_id, checksum).await {
+            Ok(map) => map,
+            Err(err) => {
+                warn!("{:?}", Report::new(err));
 
-        // Send the embed to each tracking channel
-        for (&channel, &limit) in channels.iter() {
-            if idx > limit {
                 continue;
             }
+        };
 
-            let channel = Id::new(channel.get());
-            let embeds = slice::from_ref(&embed);
-
-            // Try to build and send the message
-            match http.create_message(channel).embeds(embeds) {
-                Ok(msg_fut) => {
-                    if let Err(err) = msg_fut.await {
-                        if let TwilightErrorType::Response { error, .. } = err.kind() {
-                            if let ApiError::General(GeneralApiError {
-                                code: UNKNOWN_CHANNEL,
-                                ..
-                            }) = error
-                            {
-                                let remove_fut = tracking.remove_channel(channel, None);
-
-                                if let Err(err) = remove_fut.await {
-                                    warn!(
-                                        ?channel,
-                                        ?err,
-                                        "Failed to remove osu tracks from unknown channel"
-                                    );
-                                }
-                            } else {
-                                warn!(%channel, ?error, "Error from API while sending osu notif")
-                            }
-                        } else {
-                            warn!(%channel, ?err, "Error while sending osu notif");
-                        }
-                    }
-                }
-                Err(err) => {
-                    warn!(?err, "Invalid embed for osu!tracking notification");
-                }
+        let mut entry =
+            ScoreEmbedDataWrap::new_half(score.clone(), map, None, None, false, false, false).await;
+
+        // Converting to full data also retrieves the global leaderboard position
+        match entry.get_mut().await {
+            Ok(data) if data.global_idx == Some(1) => {
+                fulfilled |= RecentTrackConditions::GLOBAL_FIRST;
+            }
+            Ok(_) => {}
+            Err(err) => {
+                warn!(?err, "Failed to get full score data for recent tracking");
+
+                continue;
             }
         }
+
+        let stars = entry.try_get().map_or(0.0, |data| data.stars);
+
+        let targets: Vec<_> = channels
+            .iter()
+            .filter(|&(_, &value)| {
+                let mut matching = RecentTrackConditions::from_bits_truncate(value) & fulfilled;
+
+                // SS scores only count on maps with the channel's minimum stars
+                if stars < f32::from(RecentTrackConditions::ss_stars(value)) {
+                    matching.remove(RecentTrackConditions::SS);
+                }
+
+                !matching.is_empty()
+            })
+            .map(|(&channel, _)| channel)
+            .filter(|channel| matches_filter(&criteria, channel, &entry))
+            .collect();
+
+        if targets.is_empty() {
+            continue;
+        }
+
+        let (embed, payload) = user.embed(score, entry).await?;
+        notify(user, score, embed, payload, targets, sessions, webhooks).await;
     }
 
     Ok(())
 }
 
+/// Whether the score is the user's best score on its map
+async fn is_personal_best(score: &Score) -> bool {
+    let best_fut = Context::osu_scores().user_on_map_single(
+        score.user_id,
+        score.map_id,
+        score.mode,
+        None,
+        false,
+    );
+
+    match best_fut.await {
+        Ok(best) => best.score.id == score.id,
+        Err(OsuError::NotFound) => false,
+        Err(err) => {
+            warn!(?err, "Failed to get user's best score on map");
+
+            false
+        }
+    }
+}
+
+type ChannelCriteria<'f> = HashMap<NonZeroU64, FilterCriteria<TopCriteria<'f>>, IntHasher>;
+
+/// Parse each channel's filter once so that it can be checked for all scores
+fn parse_filters(filters: &ChannelFilters<IntHasher>) -> ChannelCriteria<'_> {
+    filters
+        .iter()
+        .map(|(&channel, filter)| (channel, TopCriteria::create(filter)))
+        .collect()
+}
+
+/// Whether the channel has no filter or its filter matches the entry.
+///
+/// Entries whose data failed to load never match a filter.
+fn matches_filter(
+    criteria: &ChannelCriteria<'_>,
+    channel: &NonZeroU64,
+    entry: &ScoreEmbedDataWrap,
+) -> bool {
+    let Some(criteria) = criteria.get(channel) else {
+        return true;
+    };
+
+    entry.try_get().map_or(false, |data| data.matches(criteria))
+}
+
+/// Post the payload to channels with a webhook, update the session summary
+/// of channels with sessions enabled, and send the embed to all other channels
+#[allow(clippy::too_many_arguments)]
+async fn notify(
+    user: &TrackUser<'_>,
+    score: &Score,
+    embed: EmbedBuilder,
+    payload: Option<ScorePayload>,
+    mut targets: Vec<NonZeroU64>,
+    sessions: &ChannelSessions<IntHasher>,
+    webhooks: &ChannelWebhooks<IntHasher>,
+) {
+    let mut delivery = Vec::new();
+
+    // Channels with a webhook receive the payload instead of a message
+    if payload.is_some() {
+        targets.retain(|channel| match webhooks.get(channel) {
+            Some(url) => {
+                delivery.push(DeliveryTarget {
+                    channel: Id::from(*channel),
+                    webhook: Some(url.as_ref()),
+                });
+
+                false
+            }
+            None => true,
+        });
+    }
+
+    if let Some(redis_user) = user.user.as_deref() {
+        let session_targets: Vec<_> = targets
+            .iter()
+            .filter_map(|channel| Some((*channel, *sessions.get(channel)?)))
+            .collect();
+
+        if !session_targets.is_empty() {
+            targets.retain(|channel| !sessions.contains_key(channel));
+            let update_fut = update_sessions(
+                user.key,
+                redis_user,
+                user.old_stats,
+                score,
+                &embed,
+                session_targets,
+            );
+
+            update_fut.await;
+        }
+    }
+
+    delivery.extend(targets.into_iter().map(|channel| DeliveryTarget {
+        channel: Id::from(channel),
+        webhook: None,
+    }));
+
+    if delivery.is_empty() {
+        return;
+    }
+
+    let kind = match user.key.kind {
+        TrackKind::Top => "top_score",
+        TrackKind::Recent => "recent_score",
+    };
+
+    let embed = embed.build();
+
+    let notif = Notification {
+        kind,
+        embed: &embed,
+        payload: &payload,
+    };
+
+    send_notifs(notif, &delivery).await;
+}
+
+/// Deliver the notification to each target and remove the tracking of
+/// unknown channels
+pub(super) async fn send_notifs<P: Serialize>(
+    notif: Notification<'_, P>,
+    targets: &[DeliveryTarget<'_>],
+) {
+    let tracking = Context::tracking();
+
+    for channel in notif.deliver(targets).await.unknown_channels {
+        if let Err(err) = tracking.remove_channel(channel, None).await {
+            warn!(
+                ?channel,
+                ?err,
+                "Failed to remove osu tracks from unknown channel"
+            );
+        }
+    }
+}
+
 struct TrackUser<'u> {
     key: TrackedOsuUserKey,
     user: Option<Cow<'u, RedisData<User>>>,
+    old_stats: Option<&'u DbUserMilestoneStats>,
 }
 
 impl<'u> TrackUser<'u> {
     #[inline]
-    fn new(key: TrackedOsuUserKey, user: Option<&'u RedisData<User>>) -> Self {
+    fn new(
+        key: TrackedOsuUserKey,
+        user: Option<&'u RedisData<User>>,
+        old_stats: Option<&'u DbUserMilestoneStats>,
+    ) -> Self {
         Self {
             key,
             user: user.map(Cow::Borrowed),
+            old_stats,
         }
     }
 
-    async fn embed(&mut self, score: &Score, map: OsuMap, idx: u8) -> OsuResult<EmbedBuilder> {
+    async fn embed(
+        &mut self,
+        score: &Score,
+        entry: ScoreEmbedDataWrap,
+    ) -> OsuResult<(EmbedBuilder, Option<ScorePayload>)> {
         let user = match self.user.as_deref() {
             Some(user) => user,
             None => {
-                let TrackedOsuUserKey { user_id, mode } = self.key;
+                let TrackedOsuUserKey { user_id, mode, .. } = self.key;
                 let args = UserArgs::user_id(user_id, mode);
                 let user = Context::redis().osu_user(args).await?;
 
@@ -245,6 +620,8 @@ impl<'u> TrackUser<'u> {
             }
         };
 
+        let payload = entry.try_get().map(|data| ScorePayload::new(user, data));
+
         let settings = match score.mode {
             GameMode::Mania => create_mania_settings(),
             _ => create_settings(),
@@ -253,10 +630,6 @@ impl<'u> TrackUser<'u> {
         let score_data = ScoreData::Lazer;
         let msg_owner = Id::new(1);
         let content = SingleScoreContent::None;
-
-        let entry =
-            ScoreEmbedDataWrap::new_custom(score.clone(), map, idx as usize - 1, None).await;
-
         let entries = Box::<[_]>::from([entry]);
 
         let mut pagination =
@@ -266,12 +639,12 @@ impl<'u> TrackUser<'u> {
             .async_build_page(Box::default(), MarkIndex::Skip)
             .await
         {
-            Ok(data) => Ok(data.into_embed()),
+            Ok(data) => Ok((data.into_embed(), payload)),
             Err(_) => {
                 // Unreachable because `async_build_page` can only fail while
                 // converting to full score data but it already starts off as
                 // full.
-                Ok(Default::default())
+                Ok((Default::default(), payload))
             }
         }
     }
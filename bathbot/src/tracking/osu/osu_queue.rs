@@ -1,6 +1,7 @@
 use std::{
     cmp::Reverse,
     collections::HashMap as StdHashMap,
+    fmt::Write,
     sync::{
         atomic::{AtomicBool, Ordering},
         RwLock,
@@ -24,6 +25,7 @@ use rosu_v2::model::GameMode;
 use tokio::{sync::Mutex, time};
 use twilight_model::id::{marker::ChannelMarker, Id};
 
+use super::milestones::MilestoneKinds;
 use crate::{core::Context, manager::OsuTrackingManager};
 
 static OSU_TRACKING_INTERVAL: OnceCell<Duration> = OnceCell::with_value(Duration::minutes(210));
@@ -96,6 +98,16 @@ impl OsuTracking {
         Ok(())
     }
 
+    pub async fn update_last_score(&self, key: TrackedOsuUserKey, score_id: u64) -> Result<()> {
+        if self.queue.update_last_score(key, score_id).await {
+            Context::osu_tracking()
+                .update_last_score(key, score_id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
     pub async fn get_tracked(
         &self,
         key: TrackedOsuUserKey,
@@ -110,8 +122,7 @@ impl OsuTracking {
     pub async fn remove_user_all(&self, user_id: u32) -> Result<()> {
         let manager = Context::osu_tracking();
 
-        for mode in self.queue.remove_user_all(user_id).await {
-            let key = TrackedOsuUserKey { user_id, mode };
+        for key in self.queue.remove_user_all(user_id).await {
             manager.remove_user(key).await?;
         }
 
@@ -125,7 +136,7 @@ impl OsuTracking {
         channel: Id<ChannelMarker>,
     ) -> Result<()> {
         let remove_entries = self.queue.remove_user(user_id, mode, channel).await;
-        self.remove(remove_entries).await?;
+        self.remove(remove_entries, channel).await?;
 
         Ok(())
     }
@@ -137,12 +148,12 @@ impl OsuTracking {
     ) -> Result<usize> {
         let remove_entries = self.queue.remove_channel(channel, mode).await;
         let len = remove_entries.len();
-        self.remove(remove_entries).await?;
+        self.remove(remove_entries, channel).await?;
 
         Ok(len)
     }
 
-    async fn remove(&self, remove: Vec<RemoveEntry>) -> Result<()> {
+    async fn remove(&self, remove: Vec<RemoveEntry>, channel: Id<ChannelMarker>) -> Result<()> {
         let manager = Context::osu_tracking();
 
         for remove_entry in remove {
@@ -156,6 +167,22 @@ impl OsuTracking {
                         .update_channels(remove_entry.key, &user.channels)
                         .await?;
                 }
+
+                manager
+                    .update_filter(remove_entry.key, channel, None)
+                    .await?;
+
+                manager
+                    .update_milestones(remove_entry.key, channel, 0)
+                    .await?;
+
+                manager
+                    .update_session_timeout(remove_entry.key, channel, 0)
+                    .await?;
+
+                manager
+                    .update_webhook(remove_entry.key, channel, None)
+                    .await?;
             }
         }
 
@@ -164,20 +191,48 @@ impl OsuTracking {
 
     pub async fn add(
         &self,
-        user_id: u32,
-        mode: GameMode,
+        key: TrackedOsuUserKey,
         last_top_score: OffsetDateTime,
         channel: Id<ChannelMarker>,
-        limit: u8,
+        options: TrackEntryOptions<'_>,
     ) -> Result<bool> {
+        let TrackEntryOptions {
+            limit,
+            filter,
+            milestones,
+            session,
+            webhook,
+        } = options;
+
         let manager = Context::osu_tracking();
-        let key = TrackedOsuUserKey { user_id, mode };
-        let added = self.queue.add(key, last_top_score, channel, limit).await;
+        let added = self.queue.add(key, last_top_score, channel, options).await;
 
         match added {
-            AddEntry::AddedNew => manager.insert_user(key, channel, limit).await?,
+            AddEntry::AddedNew => {
+                manager.insert_user(key, channel, limit).await?;
+
+                if filter.is_some() {
+                    manager.update_filter(key, channel, filter).await?;
+                }
+
+                if !milestones.is_empty() {
+                    manager
+                        .update_milestones(key, channel, milestones.bits())
+                        .await?;
+                }
+
+                if session > 0 {
+                    manager
+                        .update_session_timeout(key, channel, session)
+                        .await?;
+                }
+
+                if webhook.is_some() {
+                    manager.update_webhook(key, channel, webhook).await?;
+                }
+            }
             AddEntry::NotAdded => return Ok(false),
-            AddEntry::Added | AddEntry::UpdatedLimit => {
+            AddEntry::Added | AddEntry::Updated => {
                 let guard = self.queue.users.lock(&key).await;
 
                 if let Some(user) = guard.get() {
@@ -185,13 +240,25 @@ impl OsuTracking {
                 } else {
                     return Ok(false);
                 }
+
+                manager.update_filter(key, channel, filter).await?;
+
+                manager
+                    .update_milestones(key, channel, milestones.bits())
+                    .await?;
+
+                manager
+                    .update_session_timeout(key, channel, session)
+                    .await?;
+
+                manager.update_webhook(key, channel, webhook).await?;
             }
         }
 
         Ok(true)
     }
 
-    pub async fn list(&self, channel: Id<ChannelMarker>) -> Vec<(TrackedOsuUserKey, u8)> {
+    pub async fn list(&self, channel: Id<ChannelMarker>) -> Vec<TrackListEntry> {
         self.queue.list(channel).await
     }
 
@@ -254,6 +321,21 @@ impl OsuTrackingQueue {
             })
     }
 
+    /// Returns whether the entry was updated
+    /// i.e. if `score_id` is newer than the latest processed score of the user
+    async fn update_last_score(&self, key: TrackedOsuUserKey, score_id: u64) -> bool {
+        self.users
+            .lock(&key)
+            .await
+            .get_mut()
+            .filter(|value| value.last_score_id.map_or(true, |last| score_id > last))
+            .map_or(false, |value| {
+                value.last_score_id = Some(score_id);
+
+                true
+            })
+    }
+
     /// Returns all channels in which a user is tracked for a mode
     /// and also the date time of the user's last top score
     async fn get_tracked(&self, key: TrackedOsuUserKey) -> Option<TrackedOsuUserValue<IntHasher>> {
@@ -290,22 +372,20 @@ impl OsuTrackingQueue {
         }
     }
 
-    /// Returns all game modes for which the user was tracked in some channel
-    async fn remove_user_all(&self, user_id: u32) -> Vec<GameMode> {
+    /// Returns all keys for which the user was tracked in some channel
+    async fn remove_user_all(&self, user_id: u32) -> Vec<TrackedOsuUserKey> {
         let mut to_remove = Vec::with_capacity(2);
         let mut stream = self.users.iter();
 
         while let Some(guard) = stream.next().await {
             if guard.key().user_id == user_id {
-                to_remove.push(guard.key().mode);
+                to_remove.push(*guard.key());
             }
         }
 
-        for &mode in to_remove.iter() {
-            let key = TrackedOsuUserKey { user_id, mode };
-
-            self.queue.lock().await.remove(&key);
-            self.users.lock(&key).await.remove();
+        for key in to_remove.iter() {
+            self.queue.lock().await.remove(key);
+            self.users.lock(key).await.remove();
         }
 
         to_remove
@@ -330,6 +410,10 @@ impl OsuTrackingQueue {
                     .remove(&channel.into_nonzero())
                     .is_some()
             {
+                guard.value_mut().filters.remove(&channel.into_nonzero());
+                guard.value_mut().milestones.remove(&channel.into_nonzero());
+                guard.value_mut().sessions.remove(&channel.into_nonzero());
+                guard.value_mut().webhooks.remove(&channel.into_nonzero());
                 removed.push(RemoveEntry::from(guard.key()));
             }
         }
@@ -367,6 +451,10 @@ impl OsuTrackingQueue {
                     .remove(&channel.into_nonzero())
                     .is_some()
             {
+                guard.value_mut().filters.remove(&channel.into_nonzero());
+                guard.value_mut().milestones.remove(&channel.into_nonzero());
+                guard.value_mut().sessions.remove(&channel.into_nonzero());
+                guard.value_mut().webhooks.remove(&channel.into_nonzero());
                 removed.push(RemoveEntry::from(guard.key()));
             }
         }
@@ -393,34 +481,106 @@ impl OsuTrackingQueue {
         key: TrackedOsuUserKey,
         last_top_score: OffsetDateTime,
         channel: Id<ChannelMarker>,
-        limit: u8,
+        options: TrackEntryOptions<'_>,
     ) -> AddEntry {
+        let TrackEntryOptions {
+            limit,
+            filter,
+            milestones,
+            session,
+            webhook,
+        } = options;
+
         let channel = channel.into_nonzero();
         let mut guard = self.users.own(key).await;
 
         match guard.entry() {
-            Entry::Occupied(mut entry) => match entry.get().channels.get(&channel) {
-                Some(old_limit) => match *old_limit == limit {
-                    true => AddEntry::NotAdded,
-                    false => {
-                        entry.get_mut().channels.insert(channel, limit);
-
-                        AddEntry::UpdatedLimit
+            Entry::Occupied(mut entry) => {
+                let value = entry.get_mut();
+
+                let added = match value.channels.get(&channel) {
+                    Some(old_limit) => {
+                        let old_filter = value.filters.get(&channel).map(|filter| &**filter);
+                        let old_milestones = value.milestones.get(&channel).copied();
+                        let old_session = value.sessions.get(&channel).copied();
+                        let old_webhook = value.webhooks.get(&channel).map(|url| &**url);
+
+                        if *old_limit == limit
+                            && old_filter == filter
+                            && old_milestones.unwrap_or(0) == milestones.bits()
+                            && old_session.unwrap_or(0) == session
+                            && old_webhook == webhook
+                        {
+                            return AddEntry::NotAdded;
+                        }
+
+                        AddEntry::Updated
                     }
-                },
-                None => {
-                    entry.get_mut().channels.insert(channel, limit);
+                    None => AddEntry::Added,
+                };
+
+                value.channels.insert(channel, limit);
 
-                    AddEntry::Added
+                match filter {
+                    Some(filter) => value.filters.insert(channel, Box::from(filter)),
+                    None => value.filters.remove(&channel),
+                };
+
+                if milestones.is_empty() {
+                    value.milestones.remove(&channel);
+                } else {
+                    value.milestones.insert(channel, milestones.bits());
                 }
-            },
+
+                if session == 0 {
+                    value.sessions.remove(&channel);
+                } else {
+                    value.sessions.insert(channel, session);
+                }
+
+                match webhook {
+                    Some(url) => value.webhooks.insert(channel, Box::from(url)),
+                    None => value.webhooks.remove(&channel),
+                };
+
+                added
+            }
             Entry::Vacant(entry) => {
                 let mut channels = StdHashMap::default();
                 channels.insert(channel, limit);
 
+                let mut filters = StdHashMap::default();
+
+                if let Some(filter) = filter {
+                    filters.insert(channel, Box::from(filter));
+                }
+
+                let mut channel_milestones = StdHashMap::default();
+
+                if !milestones.is_empty() {
+                    channel_milestones.insert(channel, milestones.bits());
+                }
+
+                let mut sessions = StdHashMap::default();
+
+                if session > 0 {
+                    sessions.insert(channel, session);
+                }
+
+                let mut webhooks = StdHashMap::default();
+
+                if let Some(url) = webhook {
+                    webhooks.insert(channel, Box::from(url));
+                }
+
                 let value = TrackedOsuUserValue {
                     channels,
+                    filters,
+                    milestones: channel_milestones,
+                    sessions,
+                    webhooks,
                     last_update: last_top_score,
+                    last_score_id: None,
                 };
 
                 entry.insert(value);
@@ -435,15 +595,30 @@ impl OsuTrackingQueue {
     }
 
     /// Returns all entries that are tracked in the channel
-    async fn list(&self, channel: Id<ChannelMarker>) -> Vec<(TrackedOsuUserKey, u8)> {
+    async fn list(&self, channel: Id<ChannelMarker>) -> Vec<TrackListEntry> {
+        let channel = channel.into_nonzero();
+
         self.users
             .iter()
-            .filter_map(
-                |guard| match guard.value().channels.get(&channel.into_nonzero()) {
-                    Some(limit) => future::ready(Some((*guard.key(), *limit))),
-                    None => future::ready(None),
-                },
-            )
+            .filter_map(|guard| {
+                let value = guard.value();
+
+                let entry = value.channels.get(&channel).map(|limit| TrackListEntry {
+                    key: *guard.key(),
+                    limit: *limit,
+                    filter: value.filters.get(&channel).cloned(),
+                    milestones: value
+                        .milestones
+                        .get(&channel)
+                        .map_or_else(MilestoneKinds::empty, |bits| {
+                            MilestoneKinds::from_bits_truncate(*bits)
+                        }),
+                    session: value.sessions.get(&channel).copied().unwrap_or(0),
+                    webhook: value.webhooks.contains_key(&channel),
+                });
+
+                future::ready(entry)
+            })
             .collect()
             .await
     }
@@ -495,5 +670,94 @@ pub enum AddEntry {
     AddedNew,
     Added,
     NotAdded,
-    UpdatedLimit,
+    /// The options of the channel changed
+    Updated,
+}
+
+/// Per-channel options of a tracked user
+#[derive(Copy, Clone)]
+pub struct TrackEntryOptions<'f> {
+    /// Top score limit or bits of [`RecentTrackConditions`]
+    pub limit: u8,
+    pub filter: Option<&'f str>,
+    pub milestones: MilestoneKinds,
+    /// Inactivity timeout in minutes of session summaries, `0` if disabled
+    pub session: u16,
+    /// Url that notifications are posted to instead of the channel
+    pub webhook: Option<&'f str>,
+}
+
+pub struct TrackListEntry {
+    pub key: TrackedOsuUserKey,
+    pub limit: u8,
+    pub filter: Option<Box<str>>,
+    pub milestones: MilestoneKinds,
+    /// Inactivity timeout in minutes of session summaries, `0` if disabled
+    pub session: u16,
+    /// Whether notifications are delivered to a webhook
+    pub webhook: bool,
+}
+
+bitflags::bitflags! {
+    /// Conditions for which a recent score of a user with
+    /// [`TrackKind::Recent`] will be notified about.
+    ///
+    /// Stored as the channel value in place of the top score limit. The bits
+    /// above the conditions hold the minimum star rating for SS scores, see
+    /// [`RecentTrackConditions::value`].
+    ///
+    /// [`TrackKind::Recent`]: bathbot_psql::model::osu::TrackKind::Recent
+    pub struct RecentTrackConditions: u8 {
+        const PERSONAL_BEST = 1 << 0;
+        const GLOBAL_FIRST  = 1 << 1;
+        const SS            = 1 << 2;
+    }
+}
+
+impl RecentTrackConditions {
+    /// Highest configurable minimum star rating for SS scores
+    pub const MAX_SS_STARS: u8 = 20;
+
+    const SS_STARS_SHIFT: u32 = 3;
+
+    /// Channel value of the conditions with the given minimum star rating
+    /// for SS scores.
+    pub fn value(self, ss_stars: u8) -> u8 {
+        self.bits() | (ss_stars.min(Self::MAX_SS_STARS) << Self::SS_STARS_SHIFT)
+    }
+
+    /// Minimum star rating for SS scores stored in the channel value.
+    pub fn ss_stars(value: u8) -> u8 {
+        value >> Self::SS_STARS_SHIFT
+    }
+
+    /// Comma-separated names of the conditions stored in the channel value
+    pub fn names(value: u8) -> String {
+        let conditions = Self::from_bits_truncate(value);
+        let mut names = String::new();
+
+        for (flag, name) in [
+            (Self::PERSONAL_BEST, "pb"),
+            (Self::GLOBAL_FIRST, "#1"),
+            (Self::SS, "SS"),
+        ] {
+            if !conditions.contains(flag) {
+                continue;
+            }
+
+            if !names.is_empty() {
+                names.push(',');
+            }
+
+            names.push_str(name);
+        }
+
+        let ss_stars = Self::ss_stars(value);
+
+        if conditions.contains(Self::SS) && ss_stars > 0 {
+            let _ = write!(names, " ({ss_stars}★+)");
+        }
+
+        names
+    }
 }
@@ -0,0 +1,152 @@
+use bathbot_model::rosu_v2::user::User;
+use bathbot_util::constants::OSU_BASE;
+use serde::Serialize;
+
+use super::milestones::Milestone;
+use crate::{commands::utility::ScoreEmbedData, manager::redis::RedisData};
+
+/// Webhook payload of a tracked score
+#[derive(Serialize)]
+pub(super) struct ScorePayload {
+    user: UserPayload,
+    score: ScoreFields,
+    map: MapFields,
+}
+
+impl ScorePayload {
+    pub(super) fn new(user: &RedisData<User>, data: &ScoreEmbedData) -> Self {
+        let ScoreEmbedData {
+            score,
+            map,
+            stars,
+            max_combo,
+            max_pp,
+            pb_idx,
+            global_idx,
+            if_fc_pp,
+            ..
+        } = data;
+
+        let score = ScoreFields {
+            score_id: score.score_id,
+            legacy_score_id: score.legacy_id,
+            mode: score.mode as u8,
+            mods: score.mods.to_string().into_boxed_str(),
+            grade: format!("{:?}", score.grade).into_boxed_str(),
+            score: score.score,
+            accuracy: score.accuracy,
+            max_combo: score.max_combo,
+            pp: score.pp,
+            if_fc_pp: *if_fc_pp,
+            count_300: score.statistics.count_300,
+            count_100: score.statistics.count_100,
+            count_50: score.statistics.count_50,
+            count_miss: score.statistics.count_miss,
+            personal_best_idx: pb_idx.as_ref().and_then(|pb| pb.idx).map(|idx| idx + 1),
+            global_idx: *global_idx,
+            ended_at: score.ended_at.unix_timestamp(),
+            url: format!("{OSU_BASE}scores/{}", score.score_id).into_boxed_str(),
+        };
+
+        let map = MapFields {
+            map_id: map.map_id(),
+            mapset_id: map.mapset_id(),
+            artist: Box::from(map.artist()),
+            title: Box::from(map.title()),
+            version: Box::from(map.version()),
+            creator: Box::from(map.creator()),
+            stars: *stars,
+            max_combo: *max_combo,
+            max_pp: *max_pp,
+            url: format!("{OSU_BASE}b/{}", map.map_id()).into_boxed_str(),
+        };
+
+        Self {
+            user: UserPayload::new(user),
+            score,
+            map,
+        }
+    }
+}
+
+/// Webhook payload of reached milestones
+#[derive(Serialize)]
+pub(super) struct MilestonePayload<'m> {
+    user: UserPayload,
+    milestones: Vec<&'m Milestone>,
+}
+
+impl<'m> MilestonePayload<'m> {
+    pub(super) fn new(user: &RedisData<User>, milestones: Vec<&'m Milestone>) -> Self {
+        Self {
+            user: UserPayload::new(user),
+            milestones,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct UserPayload {
+    user_id: u32,
+    username: Box<str>,
+    country_code: Box<str>,
+    avatar_url: Box<str>,
+    pp: f32,
+    global_rank: u32,
+    country_rank: u32,
+}
+
+impl UserPayload {
+    fn new(user: &RedisData<User>) -> Self {
+        let stats = user.stats();
+
+        Self {
+            user_id: user.user_id(),
+            username: Box::from(user.username()),
+            country_code: Box::from(user.country_code()),
+            avatar_url: Box::from(user.avatar_url()),
+            pp: stats.pp(),
+            global_rank: stats.global_rank(),
+            country_rank: stats.country_rank(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ScoreFields {
+    score_id: u64,
+    legacy_score_id: Option<u64>,
+    mode: u8,
+    mods: Box<str>,
+    grade: Box<str>,
+    score: u32,
+    accuracy: f32,
+    max_combo: u32,
+    pp: f32,
+    if_fc_pp: Option<f32>,
+    count_300: u32,
+    count_100: u32,
+    count_50: u32,
+    count_miss: u32,
+    /// 1-indexed position in the user's top scores
+    personal_best_idx: Option<usize>,
+    /// 1-indexed position on the map leaderboard
+    global_idx: Option<usize>,
+    /// Unix timestamp
+    ended_at: i64,
+    url: Box<str>,
+}
+
+#[derive(Serialize)]
+struct MapFields {
+    map_id: u32,
+    mapset_id: u32,
+    artist: Box<str>,
+    title: Box<str>,
+    version: Box<str>,
+    creator: Box<str>,
+    stars: f32,
+    max_combo: u32,
+    max_pp: f32,
+    url: Box<str>,
+}
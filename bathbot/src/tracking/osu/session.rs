@@ -0,0 +1,273 @@
+use std::{fmt::Write, num::NonZeroU64, slice};
+
+use bathbot_model::rosu_v2::user::User;
+use bathbot_psql::model::osu::{DbUserMilestoneStats, TrackedOsuUserKey, TrackedOsuUserSession};
+use bathbot_util::{numbers::WithComma, CowUtils, EmbedBuilder};
+use eyre::{Result, WrapErr};
+use rosu_v2::prelude::Score;
+use time::Duration;
+use twilight_model::{
+    channel::message::{embed::EmbedField, Embed},
+    id::{
+        marker::{ChannelMarker, MessageMarker},
+        Id,
+    },
+};
+
+use crate::{manager::redis::RedisData, Context};
+
+/// Amount of plays that are listed in a session summary
+const SESSION_PLAYS: usize = 5;
+
+/// Edits the session summary message of each channel to include the score.
+///
+/// If the channel's previous session has been inactive for longer than its
+/// timeout, a new session with a new message is started instead.
+///
+/// New sessions start from the stored stats before the score, if available.
+pub(super) async fn update_sessions(
+    key: TrackedOsuUserKey,
+    user: &RedisData<User>,
+    old_stats: Option<&DbUserMilestoneStats>,
+    score: &Score,
+    embed: &EmbedBuilder,
+    channels: Vec<(NonZeroU64, u16)>,
+) {
+    let play = play_line(score);
+
+    for (channel, timeout) in channels {
+        let update_fut =
+            update_session(key, user, old_stats, score, &play, embed, channel, timeout);
+
+        if let Err(err) = update_fut.await {
+            warn!(%channel, ?err, "Failed to update tracking session");
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn update_session(
+    key: TrackedOsuUserKey,
+    user: &RedisData<User>,
+    old_stats: Option<&DbUserMilestoneStats>,
+    score: &Score,
+    play: &str,
+    embed: &EmbedBuilder,
+    channel: NonZeroU64,
+    timeout: u16,
+) -> Result<()> {
+    let manager = Context::osu_tracking();
+
+    // Sessions were disabled in the meanwhile
+    let Some(mut session) = manager.session(key, channel).await? else {
+        return Ok(());
+    };
+
+    let timeout = Duration::minutes(i64::from(timeout));
+
+    if session.msg_id.is_none() || score.ended_at - session.last_activity > timeout {
+        // The current stats already include the score so they're only a
+        // fallback if there are no stored stats from before the score
+        let (start_pp, start_rank) = match old_stats {
+            Some(stats) => (stats.pp, stats.global_rank.max(0) as u32),
+            None => {
+                let stats = user.stats();
+
+                (stats.pp(), stats.global_rank())
+            }
+        };
+
+        session = TrackedOsuUserSession {
+            msg_id: None,
+            started_at: score.ended_at,
+            last_activity: score.ended_at,
+            start_pp,
+            start_rank,
+            score_count: 0,
+            plays: Vec::with_capacity(1),
+        };
+    }
+
+    // Top scores are not necessarily processed in chronological order
+    session.last_activity = session.last_activity.max(score.ended_at);
+    session.score_count += 1;
+    session.plays.insert(0, play.to_owned());
+    session.plays.truncate(SESSION_PLAYS);
+
+    let embed = summary_embed(embed.clone(), &session, user).build();
+    let channel_id = Id::new(channel.get());
+
+    let msg = match session.msg_id {
+        Some(msg) => match update_msg(channel_id, Id::from(msg), &embed).await {
+            Ok(_) => msg,
+            Err(err) => {
+                // The message was most likely deleted so we send a new one
+                debug!(?err, "Failed to edit session summary");
+
+                create_msg(channel_id, &embed).await?.into_nonzero()
+            }
+        },
+        None => create_msg(channel_id, &embed).await?.into_nonzero(),
+    };
+
+    session.msg_id = Some(msg);
+
+    manager.store_session(key, channel, &session).await
+}
+
+/// Closes sessions that have been inactive for longer than their timeout and
+/// marks their summary message as ended.
+pub(super) async fn close_stale_sessions() {
+    let sessions = match Context::osu_tracking().close_stale_sessions().await {
+        Ok(sessions) => sessions,
+        Err(err) => {
+            warn!(?err, "Failed to close stale tracking sessions");
+
+            return;
+        }
+    };
+
+    for (channel, msg) in sessions {
+        if let Err(err) = mark_ended(channel, msg).await {
+            // The message was most likely deleted
+            debug!(%channel, ?err, "Failed to mark session summary as ended");
+        }
+    }
+}
+
+async fn mark_ended(channel: Id<ChannelMarker>, msg: Id<MessageMarker>) -> Result<()> {
+    let msg = Context::http()
+        .message(channel, msg)
+        .await
+        .wrap_err("Failed to get msg")?
+        .model()
+        .await
+        .wrap_err("Failed to deserialize msg")?;
+
+    let Some(mut embed) = msg.embeds.into_iter().next() else {
+        return Ok(());
+    };
+
+    let Some(field) = embed
+        .fields
+        .iter_mut()
+        .find(|field| field.name == "Session")
+    else {
+        return Ok(());
+    };
+
+    field.name = "Session (ended)".to_owned();
+
+    update_msg(channel, msg.id, &embed).await
+}
+
+async fn update_msg(
+    channel: Id<ChannelMarker>,
+    msg: Id<MessageMarker>,
+    embed: &Embed,
+) -> Result<()> {
+    Context::http()
+        .update_message(channel, msg)
+        .embeds(Some(slice::from_ref(embed)))
+        .wrap_err("Failed to build msg update")?
+        .await
+        .wrap_err("Failed to update msg")?;
+
+    Ok(())
+}
+
+async fn create_msg(channel: Id<ChannelMarker>, embed: &Embed) -> Result<Id<MessageMarker>> {
+    let msg = Context::http()
+        .create_message(channel)
+        .embeds(slice::from_ref(embed))
+        .wrap_err("Failed to build msg")?
+        .await
+        .wrap_err("Failed to send msg")?
+        .model()
+        .await
+        .wrap_err("Failed to deserialize msg")?;
+
+    Ok(msg.id)
+}
+
+/// Extends the embed of the latest score by the session progress
+fn summary_embed(
+    mut embed: EmbedBuilder,
+    session: &TrackedOsuUserSession,
+    user: &RedisData<User>,
+) -> EmbedBuilder {
+    let stats = user.stats();
+    let pp = stats.pp();
+    let rank = stats.global_rank();
+
+    let mut value = format!(
+        "**pp:** {start} → {pp} ({sign}{diff})",
+        start = WithComma::new(session.start_pp),
+        pp = WithComma::new(pp),
+        sign = if pp >= session.start_pp { "+" } else { "-" },
+        diff = WithComma::new((pp - session.start_pp).abs()),
+    );
+
+    if session.start_rank > 0 && rank > 0 {
+        let _ = write!(
+            value,
+            "\n**Rank:** #{start} → #{rank} ({sign}{diff})",
+            start = WithComma::new(session.start_rank),
+            rank = WithComma::new(rank),
+            sign = if rank <= session.start_rank { "+" } else { "-" },
+            diff = WithComma::new(session.start_rank.abs_diff(rank)),
+        );
+    }
+
+    let _ = write!(
+        value,
+        "\n**Started:** <t:{}:t> • **Last play:** <t:{}:R>",
+        session.started_at.unix_timestamp(),
+        session.last_activity.unix_timestamp(),
+    );
+
+    embed.push_field(EmbedField {
+        inline: false,
+        name: "Session".to_owned(),
+        value,
+    });
+
+    let name = if session.score_count as usize > session.plays.len() {
+        format!(
+            "Latest plays ({} of {})",
+            session.plays.len(),
+            session.score_count
+        )
+    } else {
+        "Plays".to_owned()
+    };
+
+    embed.push_field(EmbedField {
+        inline: false,
+        name,
+        value: session.plays.join("\n"),
+    });
+
+    embed
+}
+
+fn play_line(score: &Score) -> String {
+    let title = score
+        .mapset
+        .as_ref()
+        .map_or("<unknown title>", |mapset| mapset.title.as_str());
+
+    let version = score
+        .map
+        .as_ref()
+        .map_or("<unknown version>", |map| map.version.as_str());
+
+    format!(
+        "**{title} [{version}]** +{mods} • {acc}% • **{pp}pp**",
+        title = title.cow_escape_markdown(),
+        version = version.cow_escape_markdown(),
+        mods = score.mods,
+        acc = WithComma::new(score.accuracy),
+        pp = WithComma::new(score.pp.unwrap_or(0.0)),
+    )
+}
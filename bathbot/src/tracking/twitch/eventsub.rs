@@ -0,0 +1,76 @@
+use bathbot_util::IntHasher;
+use hashbrown::HashSet;
+
+use crate::{core::BotConfig, Context};
+
+const STREAM_ONLINE: &str = "stream.online";
+const STREAM_OFFLINE: &str = "stream.offline";
+
+/// Subscriptions that are still active or about to be
+const ACTIVE_STATUS: [&str; 2] = ["enabled", "webhook_callback_verification_pending"];
+
+/// Subscribes to online and offline events of all tracked streams and removes
+/// subscriptions of streams that are no longer tracked.
+pub(super) async fn sync_subscriptions(user_ids: &[u64]) {
+    let config = BotConfig::get();
+    let callback = format!("{}/twitch/eventsub", config.server.public_url);
+    let client = Context::client();
+
+    let subscriptions = match client.get_twitch_eventsub_subscriptions().await {
+        Ok(subscriptions) => subscriptions,
+        Err(err) => {
+            warn!(?err, "Failed to get eventsub subscriptions");
+
+            return;
+        }
+    };
+
+    let tracked: HashSet<_, IntHasher> = user_ids.iter().copied().collect();
+    let mut subscribed = HashSet::new();
+
+    for subscription in subscriptions.iter() {
+        // Subscriptions of other deployments
+        if subscription.transport.callback.as_deref() != Some(callback.as_str()) {
+            continue;
+        }
+
+        let user_id = subscription.condition.broadcaster_user_id;
+
+        if tracked.contains(&user_id) && ACTIVE_STATUS.contains(&&*subscription.status) {
+            subscribed.insert((user_id, &*subscription.kind));
+
+            continue;
+        }
+
+        // Subscriptions of untracked streams are removed, as are failed ones
+        // so that they can be created anew
+        if let Err(err) = client
+            .delete_twitch_eventsub_subscription(&subscription.id)
+            .await
+        {
+            warn!(user_id, ?err, "Failed to delete eventsub subscription");
+        }
+    }
+
+    let secret = config.server.twitch_eventsub_secret.as_ref();
+
+    for &user_id in user_ids {
+        for kind in [STREAM_ONLINE, STREAM_OFFLINE] {
+            if subscribed.contains(&(user_id, kind)) {
+                continue;
+            }
+
+            let create_fut =
+                client.create_twitch_eventsub_subscription(kind, user_id, &callback, secret);
+
+            if let Err(err) = create_fut.await {
+                warn!(
+                    user_id,
+                    kind,
+                    ?err,
+                    "Failed to create eventsub subscription"
+                );
+            }
+        }
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(all(feature = "twitchtracking", feature = "server"))]
+mod eventsub;
+
 #[cfg(feature = "twitch")]
 pub mod online_streams;
 
@@ -1,183 +1,527 @@
-use std::fmt::Write;
+use std::{fmt::Write, slice};
 
-use bathbot_model::TwitchUser;
+use bathbot_model::{TwitchStream, TwitchStreamEvent, TwitchUser, TwitchVideo};
+use bathbot_psql::model::twitch::DbTwitchStreamMessage;
 use bathbot_util::{
-    constants::{TWITCH_BASE, UNKNOWN_CHANNEL},
-    AuthorBuilder, EmbedBuilder, IntHasher,
+    constants::TWITCH_BASE, fields, AuthorBuilder, EmbedBuilder, ExponentialBackoff, IntHasher,
 };
+use eyre::{Result, WrapErr};
+use futures::future;
 use hashbrown::{HashMap, HashSet};
 use rand::Rng;
-use tokio::time::{interval, Duration};
-use twilight_http::{
-    api_error::{ApiError, GeneralApiError},
-    error::ErrorType,
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::{
+    sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
+    time::{interval, sleep, Duration},
 };
-use twilight_model::id::{marker::ChannelMarker, Id};
+use twilight_model::{
+    channel::message::Embed,
+    id::{
+        marker::{ChannelMarker, MessageMarker},
+        Id,
+    },
+};
+
+use crate::{
+    tracking::delivery::{DeliveryTarget, Notification},
+    Context,
+};
+
+/// Amount of attempts to retrieve a stream after receiving its online event
+const ONLINE_EVENT_ATTEMPTS: usize = 4;
 
-use crate::Context;
+/// How long after a stream ended its VOD is still being looked for
+const VOD_TIMEOUT: time::Duration = time::Duration::days(1);
 
+/// Notifies channels about tracked streams that went online.
+///
+/// Streams are polled periodically. If `events` is specified, stream status
+/// changes are received from twitch directly and polling only serves as
+/// fallback in case events get lost.
 #[cold]
-pub async fn twitch_tracking_loop() {
+pub async fn twitch_tracking_loop(mut events: Option<UnboundedReceiver<TwitchStreamEvent>>) {
     let mut online_streams = HashSet::with_hasher(IntHasher);
     let mut interval = interval(Duration::from_secs(10 * 60));
     interval.tick().await;
 
+    // Online events are handled in separate tasks which report back whether
+    // they notified the channels
+    let (online_tx, mut online_rx) = mpsc::unbounded_channel();
+    let mut pending = HashSet::with_hasher(IntHasher);
+
+    #[cfg(feature = "server")]
+    super::eventsub::sync_subscriptions(&Context::tracked_users()).await;
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => poll_streams(&mut online_streams, &pending).await,
+            event = next_event(&mut events) => match event {
+                Some(event) => {
+                    process_event(event, &mut online_streams, &mut pending, &online_tx).await
+                }
+                None => {
+                    warn!("Twitch event sender was dropped, only polling from now on");
+                    events = None;
+                }
+            },
+            Some((user_id, notified)) = online_rx.recv() => {
+                pending.remove(&user_id);
+
+                if notified {
+                    online_streams.insert(user_id);
+                }
+            }
+        }
+    }
+}
+
+async fn next_event(
+    events: &mut Option<UnboundedReceiver<TwitchStreamEvent>>,
+) -> Option<TwitchStreamEvent> {
+    match events {
+        Some(events) => events.recv().await,
+        None => future::pending().await,
+    }
+}
+
+async fn poll_streams(
+    online_streams: &mut HashSet<u64, IntHasher>,
+    pending: &HashSet<u64, IntHasher>,
+) {
     let client = Context::client();
     let online_twitch_streams = Context::online_twitch_streams();
 
-    loop {
-        interval.tick().await;
+    // Get data about what needs to be tracked for which channel
+    let user_ids = Context::tracked_users();
 
-        // Get data about what needs to be tracked for which channel
-        let user_ids = Context::tracked_users();
+    #[cfg(feature = "server")]
+    super::eventsub::sync_subscriptions(&user_ids).await;
 
-        // Get stream data about all streams that need to be tracked
-        let mut streams = match client.get_twitch_streams(&user_ids).await {
-            Ok(streams) => streams,
-            Err(err) => {
-                warn!(?err, "Failed to retrieve streams");
+    link_vods().await;
 
-                continue;
+    // Get stream data about all streams that need to be tracked
+    let mut streams = match client.get_twitch_streams(&user_ids).await {
+        Ok(streams) => streams,
+        Err(err) => {
+            warn!(?err, "Failed to retrieve streams");
+
+            return;
+        }
+    };
+
+    // Filter streams whether they're live
+    {
+        let guard = online_twitch_streams.guard();
+
+        streams.retain(|stream| {
+            if stream.live {
+                online_twitch_streams.set_online(stream, &guard);
+            } else {
+                online_twitch_streams.set_offline(stream, &guard);
             }
-        };
 
-        // Filter streams whether they're live
-        {
-            let guard = online_twitch_streams.guard();
+            stream.live
+        });
+    }
 
-            streams.retain(|stream| {
-                if stream.live {
-                    online_twitch_streams.set_online(stream, &guard);
-                } else {
-                    online_twitch_streams.set_offline(stream, &guard);
-                }
+    // Streams of pending online events are handled by their own task
+    streams.retain(|stream| !pending.contains(&stream.user_id));
 
-                stream.live
+    let now_online: HashSet<_, IntHasher> = streams.iter().map(|stream| stream.user_id).collect();
+
+    for stream in &streams {
+        if online_streams.contains(&stream.user_id) {
+            if let Err(err) = Context::twitch().update_peak(stream).await {
+                warn!(?err, "Failed to update stream peak");
+            }
+        }
+    }
+
+    // If there was no activity change since last time, don't do anything
+    if now_online == *online_streams {
+        return;
+    }
+
+    for &user_id in online_streams.difference(&now_online) {
+        end_stream(user_id).await;
+    }
+
+    // Filter streams whether its already known they're live
+    streams.retain(|stream| !online_streams.contains(&stream.user_id));
+
+    // Nothing to do if streams is empty
+    // (i.e. the change was that streamers went offline)
+    if streams.is_empty() || notify_streams(streams).await {
+        *online_streams = now_online;
+    }
+}
+
+async fn process_event(
+    event: TwitchStreamEvent,
+    online_streams: &mut HashSet<u64, IntHasher>,
+    pending: &mut HashSet<u64, IntHasher>,
+    online_tx: &UnboundedSender<(u64, bool)>,
+) {
+    match event {
+        TwitchStreamEvent::Online { user_id } => {
+            // Twitch may send the same event multiple times
+            if online_streams.contains(&user_id) || !pending.insert(user_id) {
+                return;
+            }
+
+            let online_tx = online_tx.clone();
+
+            tokio::spawn(async move {
+                let notified = notify_online_event(user_id).await;
+                let _ = online_tx.send((user_id, notified));
             });
         }
+        TwitchStreamEvent::Offline { user_id } => {
+            Context::online_twitch_streams().set_offline_by_user(user_id);
 
-        let now_online: HashSet<_, IntHasher> =
-            streams.iter().map(|stream| stream.user_id).collect();
+            if online_streams.remove(&user_id) {
+                end_stream(user_id).await;
+            }
+        }
+    }
+}
 
-        // If there was no activity change since last time, don't do anything
-        if now_online == online_streams {
-            continue;
+/// Retrieves the stream of an online event and notifies its channels.
+///
+/// Returns whether the channels were notified.
+async fn notify_online_event(user_id: u64) -> bool {
+    // The stream might not be available through the api right away
+    let backoff = ExponentialBackoff::new(2).factor(5000).max_delay(30_000);
+
+    for (duration, i) in backoff.take(ONLINE_EVENT_ATTEMPTS).zip(1..) {
+        match Context::client().get_twitch_stream(user_id).await {
+            Ok(Some(stream)) if stream.live => {
+                let online_twitch_streams = Context::online_twitch_streams();
+                online_twitch_streams.set_online(&stream, &online_twitch_streams.guard());
+
+                return notify_streams(vec![stream]).await;
+            }
+            Ok(_) => {}
+            Err(err) => warn!(user_id, ?err, "Failed to retrieve stream of online event"),
         }
 
-        // Filter streams whether its already known they're live
-        streams.retain(|stream| !online_streams.contains(&stream.user_id));
+        if i < ONLINE_EVENT_ATTEMPTS {
+            sleep(duration).await;
+        }
+    }
 
-        // Nothing to do if streams is empty
-        // (i.e. the change was that streamers went offline)
-        if streams.is_empty() {
-            online_streams = now_online;
+    debug!(
+        user_id,
+        "Stream of online event not live, leaving it to polling"
+    );
 
-            continue;
+    false
+}
+
+/// Notifies all channels tracking the streams.
+///
+/// Returns `false` if the twitch users could not be retrieved.
+async fn notify_streams(streams: Vec<TwitchStream>) -> bool {
+    let ids: Vec<_> = streams.iter().map(|s| s.user_id).collect();
+
+    let users: HashMap<_, _, IntHasher> = match Context::client().get_twitch_users(&ids).await {
+        Ok(users) => users
+            .into_iter()
+            .map(|u| (u.user_id, TwitchUserCompact::from(u)))
+            .collect(),
+        Err(err) => {
+            warn!(?err, "Failed to retrieve twitch users");
+
+            return false;
         }
+    };
 
-        let ids: Vec<_> = streams.iter().map(|s| s.user_id).collect();
+    // Generate random width and height to avoid discord caching the thumbnail url
+    let (width, height) = {
+        let mut rng = rand::thread_rng();
 
-        let users: HashMap<_, _, IntHasher> = match client.get_twitch_users(&ids).await {
-            Ok(users) => users
-                .into_iter()
-                .map(|u| (u.user_id, TwitchUserCompact::from(u)))
-                .collect(),
-            Err(err) => {
-                warn!(?err, "Failed to retrieve twitch users");
+        let width: u32 = rng.gen_range(350..=370);
+        let height: u32 = rng.gen_range(175..=185);
 
-                continue;
-            }
-        };
+        (width, height)
+    };
 
-        // Generate random width and height to avoid discord caching the thumbnail url
-        let (width, height) = {
-            let mut rng = rand::thread_rng();
+    // Process each stream by notifying all corresponding channels
+    for mut stream in streams {
+        let Some(channels) = Context::tracked_channels_for(stream.user_id) else {
+            continue;
+        };
 
-            let width: u32 = rng.gen_range(350..=370);
-            let height: u32 = rng.gen_range(175..=185);
+        // Adjust streams' thumbnail url
+        let url_len = stream.thumbnail_url.len();
+        stream.thumbnail_url.truncate(url_len - 20); // cut off "{width}x{height}.jpg"
+        let _ = write!(stream.thumbnail_url, "{width}x{height}.jpg");
+
+        let user = &users[&stream.user_id];
+        let url = format!("{TWITCH_BASE}{}", user.display_name);
+
+        let embed = EmbedBuilder::new()
+            .author(AuthorBuilder::new("Now live on twitch:"))
+            .description(stream.title.as_ref())
+            .image(&stream.thumbnail_url)
+            .thumbnail(user.image_url.as_ref())
+            .title(stream.username.as_ref())
+            .url(url.as_str())
+            .build();
+
+        let options = match Context::twitch().targets(stream.user_id).await {
+            Ok(options) => options,
+            Err(err) => {
+                warn!(?err, "Failed to get twitch targets");
 
-            (width, height)
+                Vec::new()
+            }
         };
 
-        // Process each stream by notifying all corresponding channels
-        for mut stream in streams {
-            let Some(channels) = Context::tracked_channels_for(stream.user_id) else {
-                continue;
-            };
+        let targets: Vec<_> = channels
+            .into_iter()
+            .map(|channel| DeliveryTarget {
+                channel,
+                webhook: options
+                    .iter()
+                    .find(|target| target.channel_id == channel)
+                    .and_then(|target| target.webhook_url.as_deref()),
+            })
+            .collect();
+
+        let payload = StreamPayload {
+            user_id: stream.user_id,
+            login: stream.login.as_ref(),
+            username: stream.username.as_ref(),
+            title: stream.title.as_ref(),
+            game_id: stream.game_id,
+            game_name: stream.game_name.as_ref(),
+            viewer_count: stream.viewer_count,
+            thumbnail_url: stream.thumbnail_url.as_str(),
+            image_url: user.image_url.as_ref(),
+            url: url.as_str(),
+        };
 
-            // Adjust streams' thumbnail url
-            let url_len = stream.thumbnail_url.len();
-            stream.thumbnail_url.truncate(url_len - 20); // cut off "{width}x{height}.jpg"
-            let _ = write!(stream.thumbnail_url, "{width}x{height}.jpg");
+        let notif = Notification {
+            kind: "stream_online",
+            embed: &embed,
+            payload: &payload,
+        };
 
-            let user = &users[&stream.user_id];
+        let report = notif.deliver(&targets).await;
 
-            let embed = EmbedBuilder::new()
-                .author(AuthorBuilder::new("Now live on twitch:"))
-                .description(stream.title.as_ref())
-                .image(&stream.thumbnail_url)
-                .thumbnail(user.image_url.as_ref())
-                .title(stream.username.as_ref())
-                .url(format!("{TWITCH_BASE}{}", user.display_name));
+        for (channel, msg) in report.sent {
+            let followup = options
+                .iter()
+                .any(|target| target.channel_id == channel && target.followup);
 
-            let mut channels = channels.into_iter();
-            let last = channels.next_back();
+            if !followup {
+                continue;
+            }
 
-            for channel in channels {
-                send_notif(embed.clone(), channel).await;
+            if let Err(err) = Context::twitch().store_message(channel, msg, &stream).await {
+                warn!(%channel, ?err, "Failed to store stream message");
             }
+        }
 
-            // doing last one separately so we don't clone embed
-            if let Some(channel) = last {
-                send_notif(embed, channel).await;
+        for channel in report.unknown_channels {
+            if let Err(err) = Context::twitch().untrack_all(channel).await {
+                warn!(
+                    %channel,
+                    ?err,
+                    "Failed to remove stream tracks from unknown channel"
+                );
+            } else {
+                debug!("Removed twitch tracking of unknown channel {channel}");
             }
         }
+    }
+
+    true
+}
 
-        online_streams = now_online;
+/// Edits the notifications of the user's stream that just ended
+async fn end_stream(user_id: u64) {
+    let msgs = match Context::twitch()
+        .end_messages(user_id, OffsetDateTime::now_utc())
+        .await
+    {
+        Ok(msgs) => msgs,
+        Err(err) => return warn!(user_id, ?err, "Failed to end stream messages"),
+    };
+
+    if !msgs.is_empty() {
+        followup_stream(user_id, &msgs, false).await;
     }
 }
 
-async fn send_notif(embed: EmbedBuilder, channel: Id<ChannelMarker>) {
-    let embed = embed.build();
-
-    match Context::http().create_message(channel).embeds(&[embed]) {
-        Ok(msg_fut) => {
-            if let Err(err) = msg_fut.await {
-                if let ErrorType::Response { error, .. } = err.kind() {
-                    match error {
-                        ApiError::General(GeneralApiError {
-                            code: UNKNOWN_CHANNEL,
-                            ..
-                        }) => {
-                            if let Err(err) = Context::twitch().untrack_all(channel).await {
-                                warn!(
-                                    %channel,
-                                    ?err,
-                                    "Failed to remove stream tracks from unknown channel"
-                                );
-                            } else {
-                                debug!("Removed twitch tracking of unknown channel {channel}");
-                            }
-                        }
-                        err => warn!(
-                            %channel,
-                            ?err,
-                            "Error from API while sending twitch notif"
-                        ),
-                    }
-                } else {
-                    warn!(
-                        %channel,
-                        ?err,
-                        "Error while sending twitch notif"
-                    );
-                }
+/// Links the VOD in notifications of ended streams once it's available
+async fn link_vods() {
+    let msgs = match Context::twitch().ended_messages().await {
+        Ok(msgs) => msgs,
+        Err(err) => return warn!(?err, "Failed to get ended stream messages"),
+    };
+
+    let mut msgs_by_user: HashMap<_, Vec<_>, IntHasher> = HashMap::default();
+
+    for msg in msgs {
+        msgs_by_user
+            .entry(msg.user_id as u64)
+            .or_default()
+            .push(msg);
+    }
+
+    let now = OffsetDateTime::now_utc();
+
+    for (user_id, msgs) in msgs_by_user {
+        if followup_stream(user_id, &msgs, true).await {
+            continue;
+        }
+
+        let timed_out = msgs
+            .iter()
+            .filter_map(|msg| msg.ended_at)
+            .all(|ended_at| ended_at + VOD_TIMEOUT < now);
+
+        if timed_out {
+            if let Err(err) = Context::twitch().remove_ended_messages(user_id).await {
+                warn!(user_id, ?err, "Failed to remove ended stream messages");
             }
         }
+    }
+}
+
+/// Edits the notifications of an ended stream to show its duration, peak
+/// title and game, and, if available, its VOD.
+///
+/// If `vod_only` is set, messages are only edited if the VOD is available.
+///
+/// Returns whether the VOD was linked.
+async fn followup_stream(user_id: u64, msgs: &[DbTwitchStreamMessage], vod_only: bool) -> bool {
+    let client = Context::client();
+
+    let vod = match client.get_last_twitch_vod(user_id).await {
+        // The VOD is created when the stream starts
+        Ok(vod) => vod.filter(|vod| {
+            msgs.iter()
+                .all(|msg| (vod.created_at - msg.started_at).abs() < time::Duration::minutes(15))
+        }),
         Err(err) => {
-            warn!(?err, "Invalid embed for twitch notif");
+            warn!(user_id, ?err, "Failed to get last twitch vod");
+
+            None
         }
+    };
+
+    if vod_only && vod.is_none() {
+        return false;
     }
+
+    let user = match client.get_twitch_user_by_id(user_id).await {
+        Ok(Some(user)) => TwitchUserCompact::from(user),
+        Ok(None) => {
+            warn!(user_id, "Missing twitch user of ended stream");
+
+            return false;
+        }
+        Err(err) => {
+            warn!(user_id, ?err, "Failed to get twitch user of ended stream");
+
+            return false;
+        }
+    };
+
+    for msg in msgs {
+        let embed = followup_embed(msg, &user, vod.as_ref());
+        let channel = Id::new(msg.channel_id as u64);
+        let msg_id = Id::new(msg.msg_id as u64);
+
+        if let Err(err) = update_msg(channel, msg_id, &embed).await {
+            debug!(%channel, ?err, "Failed to follow up stream notif");
+        }
+    }
+
+    if vod.is_none() {
+        return false;
+    }
+
+    if let Err(err) = Context::twitch().remove_ended_messages(user_id).await {
+        warn!(user_id, ?err, "Failed to remove ended stream messages");
+    }
+
+    true
+}
+
+fn followup_embed(
+    msg: &DbTwitchStreamMessage,
+    user: &TwitchUserCompact,
+    vod: Option<&TwitchVideo>,
+) -> Embed {
+    let ended_at = msg.ended_at.unwrap_or_else(OffsetDateTime::now_utc);
+    let secs = (ended_at - msg.started_at).whole_seconds().max(0);
+
+    let duration = if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}m", secs / 60)
+    };
+
+    let game = if msg.game_name.is_empty() {
+        "-".to_owned()
+    } else {
+        msg.game_name.clone()
+    };
+
+    let mut fields = fields![
+        "Duration", duration, true;
+        "Game", game, true;
+        "Peak viewers", msg.peak_viewers.to_string(), true;
+    ];
+
+    if let Some(vod) = vod {
+        fields![fields { "VOD", format!("[Watch the VOD]({})", vod.url), false }];
+    }
+
+    EmbedBuilder::new()
+        .author(AuthorBuilder::new("Stream ended:"))
+        .description(msg.title.as_str())
+        .fields(fields)
+        .thumbnail(user.image_url.as_ref())
+        .title(user.display_name.as_ref())
+        .url(format!("{TWITCH_BASE}{}", user.display_name))
+        .timestamp(ended_at)
+        .build()
+}
+
+async fn update_msg(
+    channel: Id<ChannelMarker>,
+    msg: Id<MessageMarker>,
+    embed: &Embed,
+) -> Result<()> {
+    Context::http()
+        .update_message(channel, msg)
+        .embeds(Some(slice::from_ref(embed)))
+        .wrap_err("Failed to build msg update")?
+        .await
+        .wrap_err("Failed to update msg")?;
+
+    Ok(())
+}
+
+/// Webhook payload of a stream that went online
+#[derive(Serialize)]
+struct StreamPayload<'s> {
+    user_id: u64,
+    login: &'s str,
+    username: &'s str,
+    title: &'s str,
+    game_id: Option<u64>,
+    game_name: &'s str,
+    viewer_count: u32,
+    thumbnail_url: &'s str,
+    image_url: &'s str,
+    url: &'s str,
 }
 
 struct TwitchUserCompact {
@@ -7,7 +7,9 @@ pub use self::{
 
 pub mod interaction;
 pub mod osu;
+pub mod osu_binary;
 pub mod query;
+pub mod replay;
 
 mod check_permissions;
 mod emote;
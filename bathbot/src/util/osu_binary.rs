@@ -0,0 +1,166 @@
+use eyre::{ContextCompat, Result};
+
+/// Reads values in the binary format of osu!stable files such as `.osr` or
+/// `collection.db`.
+pub struct Reader<'b> {
+    bytes: &'b [u8],
+}
+
+impl<'b> Reader<'b> {
+    pub fn new(bytes: &'b [u8]) -> Self {
+        Self { bytes }
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        let (&byte, rest) = self
+            .bytes
+            .split_first()
+            .wrap_err("Unexpected end of file")?;
+        self.bytes = rest;
+
+        Ok(byte)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16> {
+        let bytes = self.take(2)?;
+
+        Ok(u16::from_le_bytes(
+            bytes.try_into().expect("took two bytes"),
+        ))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32> {
+        let bytes = self.take(4)?;
+
+        Ok(i32::from_le_bytes(
+            bytes.try_into().expect("took four bytes"),
+        ))
+    }
+
+    pub fn read_i64(&mut self) -> Result<i64> {
+        let bytes = self.take(8)?;
+
+        Ok(i64::from_le_bytes(
+            bytes.try_into().expect("took eight bytes"),
+        ))
+    }
+
+    pub fn read_uleb128(&mut self) -> Result<usize> {
+        let mut value = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.read_u8()?;
+            value |= ((byte & 0x7F) as usize) << shift;
+
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+
+            shift += 7;
+
+            if shift > 28 {
+                bail!("Invalid ULEB128 length");
+            }
+        }
+    }
+
+    pub fn read_string(&mut self) -> Result<String> {
+        match self.read_u8()? {
+            0x00 => Ok(String::new()),
+            0x0B => {
+                let len = self.read_uleb128()?;
+                let bytes = self.take(len)?;
+
+                Ok(String::from_utf8_lossy(bytes).into_owned())
+            }
+            other => bail!("Invalid string indicator {other:#04x}"),
+        }
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'b [u8]> {
+        if self.bytes.len() < len {
+            bail!("Unexpected end of file");
+        }
+
+        let (bytes, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+
+        Ok(bytes)
+    }
+}
+
+/// Writes a string in the format that [`Reader::read_string`] expects.
+pub fn write_string(bytes: &mut Vec<u8>, s: &str) {
+    if s.is_empty() {
+        bytes.push(0x00);
+
+        return;
+    }
+
+    bytes.push(0x0B);
+
+    let mut len = s.len();
+
+    loop {
+        let mut byte = (len & 0x7F) as u8;
+        len >>= 7;
+
+        if len != 0 {
+            byte |= 0x80;
+        }
+
+        bytes.push(byte);
+
+        if len == 0 {
+            break;
+        }
+    }
+
+    bytes.extend_from_slice(s.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers() {
+        let mut bytes = vec![7];
+        bytes.extend_from_slice(&513_u16.to_le_bytes());
+        bytes.extend_from_slice(&(-2_i32).to_le_bytes());
+        bytes.extend_from_slice(&(1_i64 << 40).to_le_bytes());
+
+        let mut reader = Reader::new(&bytes);
+
+        assert_eq!(reader.read_u8().unwrap(), 7);
+        assert_eq!(reader.read_u16().unwrap(), 513);
+        assert_eq!(reader.read_i32().unwrap(), -2);
+        assert_eq!(reader.read_i64().unwrap(), 1 << 40);
+        assert!(reader.read_u8().is_err());
+    }
+
+    #[test]
+    fn string_roundtrip() {
+        let long = "a".repeat(300);
+        let mut bytes = Vec::new();
+
+        for s in ["", "abc", long.as_str()] {
+            write_string(&mut bytes, s);
+        }
+
+        let mut reader = Reader::new(&bytes);
+
+        assert_eq!(reader.read_string().unwrap(), "");
+        assert_eq!(reader.read_string().unwrap(), "abc");
+        assert_eq!(reader.read_string().unwrap(), long);
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(Reader::new(&[0x01]).read_string().is_err());
+        assert!(Reader::new(&[0x0B, 5, b'a']).read_string().is_err());
+        assert!(Reader::new(&[0xFF; 8]).read_uleb128().is_err());
+        assert!(Reader::new(&[1, 2, 3]).read_i32().is_err());
+    }
+}
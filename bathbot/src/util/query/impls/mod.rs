@@ -1,7 +1,10 @@
 use std::fmt::{Debug, Write};
 
+use bathbot_util::osu::ModSelection;
+
 pub use self::{
-    bookmark::BookmarkCriteria, regular::RegularCriteria, scores::ScoresCriteria, top::TopCriteria,
+    bookmark::BookmarkCriteria, regular::RegularCriteria, scores::ScoresCriteria,
+    skin::SkinCriteria, top::TopCriteria,
 };
 use super::{
     operator::Operator,
@@ -12,6 +15,7 @@ use super::{
 mod bookmark;
 mod regular;
 mod scores;
+mod skin;
 mod top;
 
 fn try_update_len(length: &mut OptionalRange<f32>, op: Operator, value: &str) -> bool {
@@ -50,3 +54,17 @@ fn display_text(content: &mut String, name: &str, text: &OptionalText<'_>) {
         let _ = write!(content, "`{name}: {text:?}`");
     }
 }
+
+fn display_mods(content: &mut String, mods: Option<&ModSelection>) {
+    let Some(mods) = mods else { return };
+
+    separate_content(content);
+
+    let (pre, mods) = match mods {
+        ModSelection::Include(mods) => ("Include ", mods),
+        ModSelection::Exclude(mods) => ("Exclude ", mods),
+        ModSelection::Exact(mods) => ("", mods),
+    };
+
+    let _ = write!(content, "`Mods: {pre}{mods}`");
+}
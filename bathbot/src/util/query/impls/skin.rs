@@ -0,0 +1,58 @@
+use std::borrow::Cow;
+
+use time::Date;
+
+use super::{display_range, display_text};
+use crate::util::query::{
+    operator::Operator,
+    optional::{OptionalRange, OptionalText},
+    IFilterCriteria,
+};
+
+#[derive(Default)]
+pub struct SkinCriteria<'q> {
+    pub added_date: OptionalRange<Date>,
+
+    pub name: OptionalText<'q>,
+    pub author: OptionalText<'q>,
+}
+
+impl<'q> IFilterCriteria<'q> for SkinCriteria<'q> {
+    fn try_parse_key_value(
+        &mut self,
+        key: Cow<'q, str>,
+        value: Cow<'q, str>,
+        op: Operator,
+    ) -> bool {
+        match key.as_ref() {
+            "added" | "date" | "addeddate" | "added_date" => {
+                self.added_date.try_update_date(op, &value)
+            }
+            "name" | "skin" => self.name.try_update(op, value),
+            "author" | "creator" => self.author.try_update(op, value),
+            _ => false,
+        }
+    }
+
+    fn any_field(&self) -> bool {
+        let Self {
+            added_date,
+            name,
+            author,
+        } = self;
+
+        !(added_date.is_empty() && name.is_empty() && author.is_empty())
+    }
+
+    fn display(&self, content: &mut String) {
+        let Self {
+            added_date,
+            name,
+            author,
+        } = self;
+
+        display_text(content, "Name", name);
+        display_text(content, "Author", author);
+        display_range(content, "Added", added_date);
+    }
+}
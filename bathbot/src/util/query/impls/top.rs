@@ -1,8 +1,9 @@
 use std::borrow::Cow;
 
+use bathbot_util::{matcher, osu::ModSelection};
 use time::Date;
 
-use super::{display_range, display_text};
+use super::{display_mods, display_range, display_text};
 use crate::util::query::{
     operator::Operator,
     optional::{OptionalRange, OptionalText},
@@ -24,6 +25,7 @@ pub struct TopCriteria<'q> {
     pub combo: OptionalRange<u32>,
     pub miss: OptionalRange<u32>,
     pub keys: OptionalRange<f32>,
+    pub mods: Option<ModSelection>,
 
     pub date: OptionalRange<Date>,
     pub ranked_date: OptionalRange<Date>,
@@ -57,6 +59,14 @@ impl<'q> IFilterCriteria<'q> for TopCriteria<'q> {
                 self.miss.try_update(op, &value, 0)
             }
             "key" | "keys" => self.keys.try_update(op, &value, 0.5),
+            "mod" | "mods" => match (op, matcher::get_mods(&value)) {
+                (Operator::Equal, Some(mods)) => {
+                    self.mods = Some(mods);
+
+                    true
+                }
+                _ => false,
+            },
 
             "date" | "scoredate" | "ended_at" => self.date.try_update_date(op, &value),
             "ranked" | "rankeddate" | "ranked_date" => self.ranked_date.try_update_date(op, &value),
@@ -84,6 +94,7 @@ impl<'q> IFilterCriteria<'q> for TopCriteria<'q> {
             combo,
             miss,
             keys,
+            mods,
             date,
             ranked_date,
             artist,
@@ -105,6 +116,7 @@ impl<'q> IFilterCriteria<'q> for TopCriteria<'q> {
             && combo.is_empty()
             && miss.is_empty()
             && keys.is_empty()
+            && mods.is_none()
             && date.is_empty()
             && ranked_date.is_empty()
             && artist.is_empty()
@@ -128,6 +140,7 @@ impl<'q> IFilterCriteria<'q> for TopCriteria<'q> {
             combo,
             miss,
             keys,
+            mods,
             date,
             ranked_date,
             artist,
@@ -149,6 +162,7 @@ impl<'q> IFilterCriteria<'q> for TopCriteria<'q> {
         display_range(content, "Misses", miss);
         display_range(content, "Score", score);
         display_range(content, "Keys", keys);
+        display_mods(content, mods.as_ref());
 
         display_text(content, "Artist", artist);
         display_text(content, "Title", title);
@@ -0,0 +1,457 @@
+use rosu_pp::{
+    model::{hit_object::HitObjectKind, mode::GameMode as Mode},
+    Beatmap,
+};
+use rosu_v2::prelude::GameMode;
+
+use super::{Replay, ReplayFrame};
+
+/// Mods bit for HardRock
+const HARD_ROCK: u32 = 1 << 4;
+
+/// Clicks that are earlier than this amount of milliseconds before a hit
+/// object still count as a miss instead of being ignored.
+pub(super) const EARLY_MISS_WINDOW: f64 = 400.0;
+
+/// Amount of consecutive keypresses that determine the tapping BPM
+const STREAM_LEN: usize = 10;
+
+const PLAYFIELD_HEIGHT: f32 = 384.0;
+
+/// Judgements of a [`Replay`] on an osu!standard map.
+///
+/// Hits are matched similar to osu!stable, including notelock, but stacking
+/// is not considered and sliders are judged only by their head.
+pub struct ReplayAnalysis {
+    pub judgements: Vec<Judgement>,
+    pub presses: Vec<KeyPress>,
+    /// Hit windows for great, ok, and meh in map time
+    pub hit_windows: [f64; 3],
+    pub radius: f32,
+    pub clock_rate: f64,
+}
+
+pub struct Judgement {
+    /// Index of the hit object within the map
+    pub idx: usize,
+    /// Start time of the hit object in map time
+    pub time: f64,
+    /// Position of the hit object, flipped if HardRock is enabled
+    pub pos: (f32, f32),
+    pub kind: JudgedKind,
+    pub result: HitResult,
+    pub hit: Option<ObjectHit>,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum JudgedKind {
+    Circle,
+    Slider,
+    Spinner,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HitResult {
+    Great,
+    Ok,
+    Meh,
+    Miss,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ObjectHit {
+    /// Negative if early, positive if late; in map time
+    pub error: f64,
+    /// Cursor position minus the object position
+    pub offset: (f32, f32),
+    /// Index of the corresponding [`KeyPress`]
+    pub press: usize,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct KeyPress {
+    pub time: f64,
+    pub x: f32,
+    pub y: f32,
+    /// `true` for the left key or mouse button, `false` for the right one
+    pub left: bool,
+    /// Index of the [`Judgement`] that this press was used for
+    pub judgement: Option<usize>,
+}
+
+impl ReplayAnalysis {
+    /// Returns `None` if the replay or the map is not osu!standard.
+    pub fn new(replay: &Replay, map: &Beatmap) -> Option<Self> {
+        if replay.mode != GameMode::Osu || map.mode != Mode::Osu {
+            return None;
+        }
+
+        let attrs = map.attributes().mods(replay.mods).build();
+        let clock_rate = attrs.clock_rate;
+
+        // OD without clock rate adjustments to get hit windows in map time
+        let od = map
+            .attributes()
+            .mods(replay.mods)
+            .clock_rate(1.0)
+            .build()
+            .od;
+
+        let hit_windows = [80.0 - 6.0 * od, 140.0 - 8.0 * od, 200.0 - 10.0 * od];
+        let radius = (54.4 - 4.48 * attrs.cs) as f32;
+        let hard_rock = replay.mods & HARD_ROCK > 0;
+
+        let mut judgements: Vec<_> = map
+            .hit_objects
+            .iter()
+            .enumerate()
+            .map(|(idx, h)| {
+                let (kind, result) = match h.kind {
+                    HitObjectKind::Circle => (JudgedKind::Circle, HitResult::Miss),
+                    HitObjectKind::Slider(_) => (JudgedKind::Slider, HitResult::Miss),
+                    // Spinners can't reasonably be judged so they're
+                    // considered as cleared
+                    _ => (JudgedKind::Spinner, HitResult::Great),
+                };
+
+                let y = if hard_rock {
+                    PLAYFIELD_HEIGHT - h.pos.y
+                } else {
+                    h.pos.y
+                };
+
+                Judgement {
+                    idx,
+                    time: h.start_time,
+                    pos: (h.pos.x, y),
+                    kind,
+                    result,
+                    hit: None,
+                }
+            })
+            .collect();
+
+        let mut presses = key_presses(&replay.frames);
+        let [great, ok, meh] = hit_windows;
+
+        let clickable: Vec<_> = judgements
+            .iter()
+            .enumerate()
+            .filter(|(_, judgement)| judgement.kind != JudgedKind::Spinner)
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut next = 0;
+
+        for (press_idx, press) in presses.iter_mut().enumerate() {
+            // Skip objects whose hit window already passed; they're misses
+            while next < clickable.len() && judgements[clickable[next]].time + meh < press.time {
+                next += 1;
+            }
+
+            let Some(&i) = clickable.get(next) else { break };
+            let judgement = &mut judgements[i];
+
+            let offset = (press.x - judgement.pos.0, press.y - judgement.pos.1);
+            let hovered = offset.0 * offset.0 + offset.1 * offset.1 <= radius * radius;
+            let error = press.time - judgement.time;
+
+            // Without hovering the next object, the press is ignored.
+            // Hovering later objects instead does not help either due to
+            // notelock.
+            if !hovered || error < -EARLY_MISS_WINDOW {
+                continue;
+            }
+
+            let result = match error.abs() {
+                _ if error < -meh => HitResult::Miss,
+                abs if judgement.kind == JudgedKind::Slider && abs <= meh => HitResult::Great,
+                abs if abs <= great => HitResult::Great,
+                abs if abs <= ok => HitResult::Ok,
+                _ => HitResult::Meh,
+            };
+
+            judgement.result = result;
+            judgement.hit = Some(ObjectHit {
+                error,
+                offset,
+                press: press_idx,
+            });
+
+            press.judgement = Some(i);
+            next += 1;
+        }
+
+        Some(Self {
+            judgements,
+            presses,
+            hit_windows,
+            radius,
+            clock_rate,
+        })
+    }
+
+    /// Hit errors in real time i.e. adjusted to the clock rate.
+    ///
+    /// Only includes hits within the hit windows.
+    pub fn hit_errors(&self) -> impl Iterator<Item = f64> + '_ {
+        self.judgements
+            .iter()
+            .filter(|judgement| judgement.result != HitResult::Miss)
+            .filter_map(|judgement| judgement.hit)
+            .map(|hit| hit.error / self.clock_rate)
+    }
+
+    pub fn unstable_rate(&self) -> Option<f64> {
+        let errors: Vec<_> = self.hit_errors().collect();
+
+        unstable_rate(&errors)
+    }
+
+    pub fn mean_error(&self) -> Option<f64> {
+        let (sum, count) = self
+            .hit_errors()
+            .fold((0.0, 0), |(sum, count), error| (sum + error, count + 1));
+
+        (count > 0).then(|| sum / count as f64)
+    }
+
+    /// Amount of judgements for great, ok, meh, and miss.
+    pub fn counts(&self) -> [usize; 4] {
+        self.judgements
+            .iter()
+            .fold([0; 4], |mut counts, judgement| {
+                let idx = match judgement.result {
+                    HitResult::Great => 0,
+                    HitResult::Ok => 1,
+                    HitResult::Meh => 2,
+                    HitResult::Miss => 3,
+                };
+
+                counts[idx] += 1;
+
+                counts
+            })
+    }
+
+    /// Amount of presses with the left and the right key.
+    pub fn key_counts(&self) -> (usize, usize) {
+        let left = self.presses.iter().filter(|press| press.left).count();
+
+        (left, self.presses.len() - left)
+    }
+
+    /// The BPM of 1/4 notes for the fastest sequence of presses, adjusted to
+    /// the clock rate.
+    pub fn max_tapping_bpm(&self) -> Option<f64> {
+        let interval = self
+            .presses
+            .windows(STREAM_LEN)
+            .map(|window| (window[STREAM_LEN - 1].time - window[0].time) / (STREAM_LEN - 1) as f64)
+            .filter(|interval| *interval > 0.0)
+            .min_by(f64::total_cmp)?;
+
+        Some(15_000.0 * self.clock_rate / interval)
+    }
+}
+
+/// Unstable rate of the given hit errors i.e. ten times their standard
+/// deviation.
+pub fn unstable_rate(errors: &[f64]) -> Option<f64> {
+    if errors.is_empty() {
+        return None;
+    }
+
+    let len = errors.len() as f64;
+    let mean = errors.iter().sum::<f64>() / len;
+    let variance = errors
+        .iter()
+        .map(|error| (error - mean).powi(2))
+        .sum::<f64>()
+        / len;
+
+    Some(variance.sqrt() * 10.0)
+}
+
+fn key_presses(frames: &[ReplayFrame]) -> Vec<KeyPress> {
+    let mut presses = Vec::new();
+    let mut prev_left = false;
+    let mut prev_right = false;
+
+    for frame in frames {
+        let left = frame.keys.left();
+        let right = frame.keys.right();
+
+        let mut push = |left| {
+            presses.push(KeyPress {
+                time: frame.time,
+                x: frame.x,
+                y: frame.y,
+                left,
+                judgement: None,
+            })
+        };
+
+        if left && !prev_left {
+            push(true);
+        }
+
+        if right && !prev_right {
+            push(false);
+        }
+
+        prev_left = left;
+        prev_right = right;
+    }
+
+    presses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::replay::ReplayKeys;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:5
+ApproachRate:9
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,1000,1,0,0:0:0:0:
+200,200,2000,1,0,0:0:0:0:
+300,300,3000,1,0,0:0:0:0:
+400,300,4000,1,0,0:0:0:0:
+";
+
+    fn replay(mods: u32, frames: &[(f64, f32, f32, ReplayKeys)]) -> Replay {
+        let frames = frames
+            .iter()
+            .map(|&(time, x, y, keys)| ReplayFrame { time, x, y, keys })
+            .collect();
+
+        Replay {
+            mode: GameMode::Osu,
+            map_checksum: String::new(),
+            username: String::new(),
+            count_300: 0,
+            count_100: 0,
+            count_50: 0,
+            count_geki: 0,
+            count_katu: 0,
+            count_miss: 0,
+            score: 0,
+            max_combo: 0,
+            mods,
+            score_id: 0,
+            frames,
+        }
+    }
+
+    fn map() -> Beatmap {
+        Beatmap::from_bytes(MAP.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn judgements() {
+        let none = ReplayKeys::empty();
+        let left = ReplayKeys::M1 | ReplayKeys::K1;
+        let right = ReplayKeys::M2 | ReplayKeys::K2;
+
+        let replay = replay(
+            0,
+            &[
+                (1010.0, 100.0, 100.0, left),
+                (1050.0, 100.0, 100.0, none),
+                (2080.0, 200.0, 200.0, right),
+                (2100.0, 200.0, 200.0, none),
+                // Not hovering the next object so it's ignored
+                (2500.0, 0.0, 0.0, left),
+                (2550.0, 0.0, 0.0, none),
+                (3120.0, 300.0, 300.0, left),
+                (3150.0, 300.0, 300.0, none),
+                (4000.0, 300.0, 300.0, right),
+            ],
+        );
+
+        let analysis = ReplayAnalysis::new(&replay, &map()).unwrap();
+
+        assert_eq!(analysis.hit_windows, [50.0, 100.0, 150.0]);
+        assert_eq!(analysis.counts(), [1, 1, 1, 1]);
+        assert_eq!(analysis.key_counts(), (3, 2));
+
+        let results: Vec<_> = analysis
+            .judgements
+            .iter()
+            .map(|judgement| judgement.result)
+            .collect();
+
+        assert_eq!(
+            results,
+            [
+                HitResult::Great,
+                HitResult::Ok,
+                HitResult::Meh,
+                HitResult::Miss
+            ]
+        );
+
+        let errors: Vec<_> = analysis.hit_errors().collect();
+        assert_eq!(errors, [10.0, 80.0, 120.0]);
+
+        assert_eq!(analysis.judgements[2].hit.unwrap().press, 3);
+        assert_eq!(analysis.presses[2].judgement, None);
+        assert!(analysis.judgements[3].hit.is_none());
+    }
+
+    #[test]
+    fn hard_rock_flips_positions() {
+        let replay = replay(
+            HARD_ROCK,
+            &[(1000.0, 100.0, PLAYFIELD_HEIGHT - 100.0, ReplayKeys::M1)],
+        );
+
+        let analysis = ReplayAnalysis::new(&replay, &map()).unwrap();
+        let judgement = &analysis.judgements[0];
+
+        assert_eq!(judgement.pos, (100.0, PLAYFIELD_HEIGHT - 100.0));
+        assert_eq!(judgement.result, HitResult::Great);
+    }
+
+    #[test]
+    fn clock_rate_adjusts_errors() {
+        // DoubleTime
+        let replay = replay(1 << 6, &[(1030.0, 100.0, 100.0, ReplayKeys::M1)]);
+
+        let analysis = ReplayAnalysis::new(&replay, &map()).unwrap();
+
+        assert_eq!(analysis.clock_rate, 1.5);
+        assert_eq!(analysis.hit_errors().collect::<Vec<_>>(), [20.0]);
+    }
+
+    #[test]
+    fn non_standard_replay() {
+        let mut replay = replay(0, &[]);
+        replay.mode = GameMode::Taiko;
+
+        assert!(ReplayAnalysis::new(&replay, &map()).is_none());
+    }
+
+    #[test]
+    fn unstable_rate_of_errors() {
+        assert_eq!(unstable_rate(&[]), None);
+        assert_eq!(unstable_rate(&[5.0, 5.0]), Some(0.0));
+        assert_eq!(unstable_rate(&[-10.0, 10.0]), Some(100.0));
+    }
+}
@@ -0,0 +1,332 @@
+use rosu_pp::{model::hit_object::HitObjectKind, Beatmap};
+
+use super::{
+    analyze::EARLY_MISS_WINDOW, HitResult, JudgedKind, Judgement, KeyPress, ReplayAnalysis,
+    ReplayFrame,
+};
+
+/// Ticks that are closer than this many milliseconds to the end of their
+/// span are skipped, same as osu!stable
+const TICK_END_DISTANCE: f64 = 10.0;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Miss {
+    /// Index of the [`Judgement`]
+    pub judgement: usize,
+    /// Map time of the miss
+    pub time: f64,
+    pub cause: MissCause,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub enum MissCause {
+    /// Pressed within the hit window but outside of the circle.
+    ///
+    /// The distance is measured from the circle's edge in osu!pixels.
+    Aim { distance: f32 },
+    /// Pressed on the circle but too early; in real time
+    Early { error: f64 },
+    /// Pressed on the circle but too late; in real time
+    Late { error: f64 },
+    /// Released all keys while a slider tick or repeat passed after hitting
+    /// the slider's head
+    SliderBreak,
+    /// Pressed on the circle within its hit window but the previous object
+    /// was not judged yet
+    Notelock,
+    /// No keypress around the object at all
+    NoPress,
+}
+
+impl ReplayAnalysis {
+    /// Classifies all misses and slider breaks.
+    ///
+    /// Slider breaks are only detected by releasing all keys while a tick or
+    /// repeat passes, leaving the follow circle is not considered.
+    pub fn misses(&self, frames: &[ReplayFrame], map: &Beatmap) -> Vec<Miss> {
+        let mut misses = Vec::new();
+
+        for (i, judgement) in self.judgements.iter().enumerate() {
+            match (judgement.kind, judgement.result) {
+                (JudgedKind::Spinner, _) => {}
+                (_, HitResult::Miss) => misses.push(Miss {
+                    judgement: i,
+                    time: judgement.time,
+                    cause: self.miss_cause(judgement),
+                }),
+                (JudgedKind::Slider, _) => {
+                    let Some(hit) = judgement.hit else { continue };
+
+                    let Some(checkpoints) = slider_checkpoints(map, judgement.idx) else {
+                        continue;
+                    };
+
+                    let start = judgement.time + hit.error;
+
+                    if let Some(time) = slider_break(frames, start, &checkpoints) {
+                        misses.push(Miss {
+                            judgement: i,
+                            time,
+                            cause: MissCause::SliderBreak,
+                        });
+                    }
+                }
+                (JudgedKind::Circle, _) => {}
+            }
+        }
+
+        misses
+    }
+
+    fn miss_cause(&self, judgement: &Judgement) -> MissCause {
+        if let Some(hit) = judgement.hit {
+            return MissCause::Early {
+                error: hit.error / self.clock_rate,
+            };
+        }
+
+        let [_, _, meh] = self.hit_windows;
+
+        let distance = |press: &KeyPress| {
+            let x = press.x - judgement.pos.0;
+            let y = press.y - judgement.pos.1;
+
+            (x * x + y * y).sqrt() - self.radius
+        };
+
+        let unused = |press: &&KeyPress| press.judgement.is_none();
+
+        let in_window = self
+            .presses
+            .iter()
+            .filter(|press| (press.time - judgement.time).abs() <= meh)
+            .filter(unused);
+
+        let mut closest: Option<f32> = None;
+
+        for press in in_window {
+            let distance = distance(press);
+
+            if distance <= 0.0 {
+                return MissCause::Notelock;
+            }
+
+            closest = Some(closest.map_or(distance, |closest| closest.min(distance)));
+        }
+
+        if let Some(distance) = closest {
+            return MissCause::Aim { distance };
+        }
+
+        let late = self
+            .presses
+            .iter()
+            .filter(unused)
+            .filter(|press| {
+                let error = press.time - judgement.time;
+
+                error > meh && error <= EARLY_MISS_WINDOW
+            })
+            .find(|press| distance(press) <= 0.0);
+
+        match late {
+            Some(press) => MissCause::Late {
+                error: (press.time - judgement.time) / self.clock_rate,
+            },
+            None => MissCause::NoPress,
+        }
+    }
+}
+
+/// Map times of the ticks and repeats of the slider at the given index.
+///
+/// Returns `None` if the object is not a slider or if its length is unknown.
+fn slider_checkpoints(map: &Beatmap, idx: usize) -> Option<Vec<f64>> {
+    let h = map.hit_objects.get(idx)?;
+
+    let HitObjectKind::Slider(ref slider) = h.kind else {
+        return None;
+    };
+
+    let dist = slider.expected_dist?;
+
+    let beat_len = map
+        .timing_points
+        .iter()
+        .take_while(|point| point.time <= h.start_time)
+        .last()
+        .or_else(|| map.timing_points.first())
+        .map_or(1000.0, |point| point.beat_len);
+
+    let slider_velocity = map
+        .difficulty_points
+        .iter()
+        .take_while(|point| point.time <= h.start_time)
+        .last()
+        .map_or(1.0, |point| point.slider_velocity);
+
+    let velocity = 100.0 * map.slider_multiplier * slider_velocity / beat_len;
+
+    if velocity <= 0.0 {
+        return None;
+    }
+
+    let span_count = slider.repeats + 1;
+    let span_duration = dist / velocity;
+
+    // Ticks are equally spaced in time regardless of the slider velocity.
+    // Absurdly small intervals are ignored to keep the amount of ticks sane.
+    let tick_interval = beat_len / map.slider_tick_rate;
+    let with_ticks = tick_interval >= 1.0;
+
+    let mut checkpoints = Vec::new();
+
+    for span in 0..span_count {
+        let span_start = h.start_time + span as f64 * span_duration;
+        let mut tick = tick_interval;
+
+        while with_ticks && tick < span_duration - TICK_END_DISTANCE {
+            // Ticks of reversed spans are traversed backwards
+            let offset = if span % 2 == 1 {
+                span_duration - tick
+            } else {
+                tick
+            };
+
+            checkpoints.push(span_start + offset);
+            tick += tick_interval;
+        }
+
+        if span + 1 < span_count {
+            checkpoints.push(span_start + span_duration);
+        }
+    }
+
+    checkpoints.sort_unstable_by(f64::total_cmp);
+
+    Some(checkpoints)
+}
+
+/// Map time of the first checkpoint that passes after `start` while no key is
+/// held.
+fn slider_break(frames: &[ReplayFrame], start: f64, checkpoints: &[f64]) -> Option<f64> {
+    let last = *checkpoints.last()?;
+    let mut released_since = None;
+
+    for frame in frames.iter().skip_while(|frame| frame.time <= start) {
+        let held = frame.keys.left() || frame.keys.right();
+
+        match released_since {
+            None if !held => released_since = Some(frame.time),
+            Some(since) if held => {
+                let missed = checkpoints
+                    .iter()
+                    .find(|&&time| since <= time && time < frame.time);
+
+                if missed.is_some() {
+                    return missed.copied();
+                }
+
+                released_since = None;
+            }
+            _ => {}
+        }
+
+        if frame.time > last {
+            break;
+        }
+    }
+
+    let since = released_since?;
+
+    checkpoints.iter().copied().find(|&time| time >= since)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::replay::ReplayKeys;
+
+    const MAP: &str = "osu file format v14
+
+[General]
+Mode: 0
+
+[Difficulty]
+HPDrainRate:5
+CircleSize:4
+OverallDifficulty:5
+ApproachRate:9
+SliderMultiplier:1.4
+SliderTickRate:1
+
+[TimingPoints]
+0,500,4,2,0,100,1,0
+
+[HitObjects]
+100,100,1000,2,0,L|380:100,2,280
+100,100,4000,1,0,0:0:0:0:
+";
+
+    fn frames(keys: &[(f64, bool)]) -> Vec<ReplayFrame> {
+        keys.iter()
+            .map(|&(time, held)| ReplayFrame {
+                time,
+                x: 0.0,
+                y: 0.0,
+                keys: if held {
+                    ReplayKeys::M1
+                } else {
+                    ReplayKeys::empty()
+                },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn checkpoints() {
+        let map = Beatmap::from_bytes(MAP.as_bytes()).unwrap();
+
+        assert_eq!(
+            slider_checkpoints(&map, 0).unwrap(),
+            [1500.0, 2000.0, 2500.0]
+        );
+        assert!(slider_checkpoints(&map, 1).is_none());
+    }
+
+    #[test]
+    fn release_between_checkpoints() {
+        let frames = frames(&[
+            (1000.0, true),
+            (1400.0, false),
+            (1450.0, true),
+            (2900.0, false),
+            (3000.0, false),
+        ]);
+
+        assert_eq!(
+            slider_break(&frames, 1000.0, &[1500.0, 2000.0, 2500.0]),
+            None
+        );
+    }
+
+    #[test]
+    fn release_over_checkpoint() {
+        let frames = frames(&[(1000.0, true), (1490.0, false), (1600.0, true)]);
+
+        assert_eq!(
+            slider_break(&frames, 1000.0, &[1500.0, 2000.0, 2500.0]),
+            Some(1500.0)
+        );
+    }
+
+    #[test]
+    fn release_until_end() {
+        let frames = frames(&[(1000.0, true), (2400.0, false)]);
+
+        assert_eq!(
+            slider_break(&frames, 1000.0, &[1500.0, 2000.0, 2500.0]),
+            Some(2500.0)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+pub use self::{
+    analyze::{
+        unstable_rate, HitResult, JudgedKind, Judgement, KeyPress, ObjectHit, ReplayAnalysis,
+    },
+    misses::{Miss, MissCause},
+    parse::{Replay, ReplayFrame, ReplayKeys},
+    sections::{accuracy, Section},
+};
+
+mod analyze;
+mod misses;
+mod parse;
+mod sections;
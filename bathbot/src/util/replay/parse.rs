@@ -0,0 +1,316 @@
+use std::io::{Result as IoResult, Write};
+
+use eyre::{ContextCompat, Result, WrapErr};
+use lzma_rs::decompress::Options;
+use rosu_v2::prelude::GameMode;
+
+use crate::util::osu_binary::Reader;
+
+/// Frame delta that marks the frame containing the RNG seed
+const SEED_FRAME_DELTA: i64 = -12345;
+
+/// Maximum amount of bytes that the LZMA decoder may allocate for its
+/// dictionary
+const DECOMPRESS_MEMLIMIT: usize = 16 * 1024 * 1024;
+
+/// Maximum size of the decompressed frames. Even replays of hour-long maps
+/// stay well below this.
+const MAX_UNPACKED_SIZE: usize = 32 * 1024 * 1024;
+
+/// An osu! replay, parsed from the contents of a `.osr` file.
+pub struct Replay {
+    pub mode: GameMode,
+    pub map_checksum: String,
+    pub username: String,
+    pub count_300: u16,
+    pub count_100: u16,
+    pub count_50: u16,
+    pub count_geki: u16,
+    pub count_katu: u16,
+    pub count_miss: u16,
+    pub score: u32,
+    pub max_combo: u16,
+    pub mods: u32,
+    pub score_id: u64,
+    /// Frames sorted by time, with skipped and negative-time frames removed.
+    pub frames: Vec<ReplayFrame>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct ReplayFrame {
+    /// Absolute map time in milliseconds
+    pub time: f64,
+    pub x: f32,
+    pub y: f32,
+    pub keys: ReplayKeys,
+}
+
+bitflags::bitflags! {
+    /// Keys that are held down during a [`ReplayFrame`]
+    #[derive(Default)]
+    pub struct ReplayKeys: u32 {
+        const M1    = 1 << 0;
+        const M2    = 1 << 1;
+        const K1    = 1 << 2;
+        const K2    = 1 << 3;
+        const SMOKE = 1 << 4;
+    }
+}
+
+impl ReplayKeys {
+    /// Whether the left key or mouse button is held down.
+    ///
+    /// Note that `K1` always comes with `M1` so checking for `M1` suffices.
+    pub fn left(self) -> bool {
+        self.contains(Self::M1)
+    }
+
+    /// Whether the right key or mouse button is held down.
+    pub fn right(self) -> bool {
+        self.contains(Self::M2)
+    }
+}
+
+impl Replay {
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+
+        let mode = match reader.read_u8()? {
+            0 => GameMode::Osu,
+            1 => GameMode::Taiko,
+            2 => GameMode::Catch,
+            3 => GameMode::Mania,
+            other => bail!("Invalid mode {other}"),
+        };
+
+        let _version = reader.read_i32()?;
+        let map_checksum = reader.read_string()?;
+        let username = reader.read_string()?;
+        let _replay_checksum = reader.read_string()?;
+
+        let count_300 = reader.read_u16()?;
+        let count_100 = reader.read_u16()?;
+        let count_50 = reader.read_u16()?;
+        let count_geki = reader.read_u16()?;
+        let count_katu = reader.read_u16()?;
+        let count_miss = reader.read_u16()?;
+
+        let score = reader.read_i32()? as u32;
+        let max_combo = reader.read_u16()?;
+        let _perfect = reader.read_u8()?;
+        let mods = reader.read_i32()? as u32;
+        let _lifebar = reader.read_string()?;
+        let _timestamp = reader.read_i64()?;
+
+        let compressed_len = reader.read_i32()?;
+        let compressed = reader.take(compressed_len.max(0) as usize)?;
+        let frames = parse_frames(compressed)?;
+
+        let score_id = reader.read_i64().map_or(0, |score_id| score_id as u64);
+
+        Ok(Self {
+            mode,
+            map_checksum,
+            username,
+            count_300,
+            count_100,
+            count_50,
+            count_geki,
+            count_katu,
+            count_miss,
+            score,
+            max_combo,
+            mods,
+            score_id,
+            frames,
+        })
+    }
+}
+
+fn parse_frames(compressed: &[u8]) -> Result<Vec<ReplayFrame>> {
+    let decompressed = decompress(compressed, MAX_UNPACKED_SIZE)?;
+
+    let decompressed =
+        std::str::from_utf8(&decompressed).wrap_err("Replay frames are not valid UTF-8")?;
+
+    parse_frame_data(decompressed)
+}
+
+/// Decompresses LZMA data, failing if it exceeds `limit` bytes.
+fn decompress(mut compressed: &[u8], limit: usize) -> Result<Vec<u8>> {
+    let options = Options {
+        memlimit: Some(DECOMPRESS_MEMLIMIT),
+        ..Default::default()
+    };
+
+    let mut writer = LimitedWriter {
+        bytes: Vec::with_capacity((compressed.len() * 4).min(limit)),
+        limit,
+    };
+
+    lzma_rs::lzma_decompress_with_options(&mut compressed, &mut writer, &options)
+        .wrap_err("Failed to decompress replay frames")?;
+
+    Ok(writer.bytes)
+}
+
+/// Parses the decompressed frames of the form `w|x|y|z,w|x|y|z,...`
+fn parse_frame_data(decompressed: &str) -> Result<Vec<ReplayFrame>> {
+    let mut frames = Vec::with_capacity(decompressed.len() / 16);
+    let mut time = 0;
+
+    for (i, frame) in decompressed.split(',').enumerate() {
+        if frame.is_empty() {
+            continue;
+        }
+
+        let mut split = frame.split('|');
+
+        let mut next = || split.next().wrap_err("Missing frame value");
+        let delta: i64 = next()?.parse().wrap_err("Invalid frame delta")?;
+        let x: f32 = next()?.parse().wrap_err("Invalid frame x")?;
+        let y: f32 = next()?.parse().wrap_err("Invalid frame y")?;
+        let keys: u32 = next()?.parse().wrap_err("Invalid frame keys")?;
+
+        if delta == SEED_FRAME_DELTA {
+            continue;
+        }
+
+        time += delta;
+
+        // The first two frames are sometimes placeholders
+        if i < 2 && x == 256.0 && y == -500.0 {
+            continue;
+        }
+
+        // Same as osu!stable, negative frames are skipped
+        if delta < 0 {
+            continue;
+        }
+
+        frames.push(ReplayFrame {
+            time: time as f64,
+            x,
+            y,
+            keys: ReplayKeys::from_bits_truncate(keys),
+        });
+    }
+
+    Ok(frames)
+}
+
+/// Collects bytes but fails once more than `limit` bytes are written.
+struct LimitedWriter {
+    bytes: Vec<u8>,
+    limit: usize,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        if self.bytes.len() + buf.len() > self.limit {
+            return Err(std::io::Error::other("Exceeded the unpacked size limit"));
+        }
+
+        self.bytes.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::osu_binary::write_string;
+
+    fn compress(data: &str) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        lzma_rs::lzma_compress(&mut data.as_bytes(), &mut compressed).unwrap();
+
+        compressed
+    }
+
+    fn replay_bytes(frames: &str) -> Vec<u8> {
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&20240101_i32.to_le_bytes());
+        write_string(&mut bytes, "0123456789abcdef0123456789abcdef");
+        write_string(&mut bytes, "player");
+        write_string(&mut bytes, "");
+
+        for count in [100_u16, 20, 3, 40, 5, 6] {
+            bytes.extend_from_slice(&count.to_le_bytes());
+        }
+
+        bytes.extend_from_slice(&1_000_000_i32.to_le_bytes());
+        bytes.extend_from_slice(&321_u16.to_le_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&(1_i32 << 6).to_le_bytes());
+        write_string(&mut bytes, "");
+        bytes.extend_from_slice(&0_i64.to_le_bytes());
+
+        let compressed = compress(frames);
+        bytes.extend_from_slice(&(compressed.len() as i32).to_le_bytes());
+        bytes.extend_from_slice(&compressed);
+        bytes.extend_from_slice(&42_i64.to_le_bytes());
+
+        bytes
+    }
+
+    #[test]
+    fn frame_data() {
+        let data = "0|256|-500|0,-1|256|-500|0,10|1|2|5,20|3|4|10,-5|5|6|0,\
+            -12345|0|0|1234,15|7|8|0,";
+
+        let frames = parse_frame_data(data).unwrap();
+        let times: Vec<_> = frames.iter().map(|frame| frame.time).collect();
+
+        assert_eq!(times, [9.0, 29.0, 39.0]);
+        assert_eq!((frames[0].x, frames[0].y), (1.0, 2.0));
+        assert_eq!(frames[0].keys, ReplayKeys::M1 | ReplayKeys::K1);
+        assert!(frames[0].keys.left() && !frames[0].keys.right());
+        assert!(frames[1].keys.right());
+        assert!(frames[2].keys.is_empty());
+    }
+
+    #[test]
+    fn invalid_frame_data() {
+        assert!(parse_frame_data("10|1|2").is_err());
+        assert!(parse_frame_data("10|x|2|0").is_err());
+    }
+
+    #[test]
+    fn full_replay() {
+        let replay = Replay::parse(&replay_bytes("0|256|-500|0,16|100|200|1,")).unwrap();
+
+        assert_eq!(replay.mode, GameMode::Osu);
+        assert_eq!(replay.map_checksum, "0123456789abcdef0123456789abcdef");
+        assert_eq!(replay.username, "player");
+        assert_eq!(replay.count_300, 100);
+        assert_eq!(replay.count_miss, 6);
+        assert_eq!(replay.score, 1_000_000);
+        assert_eq!(replay.max_combo, 321);
+        assert_eq!(replay.mods, 1 << 6);
+        assert_eq!(replay.score_id, 42);
+        assert_eq!(replay.frames.len(), 1);
+        assert_eq!(replay.frames[0].time, 16.0);
+    }
+
+    #[test]
+    fn truncated_replay() {
+        let bytes = replay_bytes("16|100|200|1,");
+
+        assert!(Replay::parse(&bytes[..bytes.len() / 2]).is_err());
+        assert!(Replay::parse(&[4]).is_err());
+    }
+
+    #[test]
+    fn unpacked_size_limit() {
+        let compressed = compress(&"16|100|200|1,".repeat(100));
+
+        assert!(decompress(&compressed, 1300).is_ok());
+        assert!(decompress(&compressed, 1299).is_err());
+    }
+}
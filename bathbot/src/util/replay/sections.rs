@@ -0,0 +1,99 @@
+use super::{unstable_rate, HitResult, ReplayAnalysis};
+
+/// Judgements within a time span of the map.
+pub struct Section {
+    /// Map time of the section start
+    pub start: f64,
+    /// Map time of the section end
+    pub end: f64,
+    /// Amount of judgements for great, ok, meh, and miss.
+    pub counts: [usize; 4],
+    pub unstable_rate: Option<f64>,
+}
+
+impl Section {
+    /// Accuracy in percent or `None` if the section contains no judgements.
+    pub fn accuracy(&self) -> Option<f64> {
+        accuracy(self.counts)
+    }
+}
+
+impl ReplayAnalysis {
+    /// Splits the judgements into `count` sections of equal length between
+    /// `start` and `end` in map time.
+    ///
+    /// Judgements outside of the bounds are put into the first or last
+    /// section.
+    pub fn sections(&self, start: f64, end: f64, count: usize) -> Vec<Section> {
+        let count = count.max(1);
+        let len = (end - start).max(1.0) / count as f64;
+
+        let mut sections: Vec<_> = (0..count)
+            .map(|i| Section {
+                start: start + i as f64 * len,
+                end: start + (i + 1) as f64 * len,
+                counts: [0; 4],
+                unstable_rate: None,
+            })
+            .collect();
+
+        let mut errors = vec![Vec::new(); count];
+
+        for judgement in self.judgements.iter() {
+            let idx = (((judgement.time - start) / len).max(0.0) as usize).min(count - 1);
+            sections[idx].counts[result_idx(judgement.result)] += 1;
+
+            if judgement.result != HitResult::Miss {
+                if let Some(hit) = judgement.hit {
+                    errors[idx].push(hit.error / self.clock_rate);
+                }
+            }
+        }
+
+        for (section, errors) in sections.iter_mut().zip(errors) {
+            section.unstable_rate = unstable_rate(&errors);
+        }
+
+        sections
+    }
+
+    /// Accuracy in percent after each judgement alongside the judgement's
+    /// map time.
+    pub fn cumulative_accuracy(&self) -> Vec<(f64, f64)> {
+        let mut counts = [0; 4];
+
+        self.judgements
+            .iter()
+            .filter_map(|judgement| {
+                counts[result_idx(judgement.result)] += 1;
+
+                accuracy(counts).map(|acc| (judgement.time, acc))
+            })
+            .collect()
+    }
+}
+
+/// osu!standard accuracy in percent for the amount of great, ok, meh, and miss
+/// judgements.
+///
+/// Returns `None` if there are no judgements.
+pub fn accuracy([great, ok, meh, miss]: [usize; 4]) -> Option<f64> {
+    let total = great + ok + meh + miss;
+
+    if total == 0 {
+        return None;
+    }
+
+    let numerator = 300 * great + 100 * ok + 50 * meh;
+
+    Some(100.0 * numerator as f64 / (300 * total) as f64)
+}
+
+fn result_idx(result: HitResult) -> usize {
+    match result {
+        HitResult::Great => 0,
+        HitResult::Ok => 1,
+        HitResult::Meh => 2,
+        HitResult::Miss => 3,
+    }
+}